@@ -0,0 +1,57 @@
+//! Memory retained by deserialized chart responses, with and without the feature `intern`.
+//!
+//! Run with `cargo bench --bench intern` and `cargo bench --bench intern --features intern`.
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Instant,
+};
+
+use yahoo_finance_api::YResponse;
+
+/// Allocator keeping track of the number of allocated bytes
+struct CountingAllocator;
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATED.fetch_add(layout.size(), Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        ALLOCATED.fetch_sub(layout.size(), Ordering::Relaxed);
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+/// Number of responses held at once, e.g. of the symbols of a screener
+const RESPONSES: usize = 1000;
+
+fn main() {
+    let json = include_str!("../src/testing/fixtures/equity_msft_1m_prepost.json");
+
+    let before = ALLOCATED.load(Ordering::Relaxed);
+    let start = Instant::now();
+    let responses: Vec<YResponse> = (0..RESPONSES)
+        .map(|_| serde_json::from_str(json).unwrap())
+        .collect();
+    let elapsed = start.elapsed();
+    let retained = ALLOCATED.load(Ordering::Relaxed).saturating_sub(before);
+
+    println!(
+        "intern {}: {} responses, {} bytes retained per response, {:?} per response",
+        if cfg!(feature = "intern") {
+            "on"
+        } else {
+            "off"
+        },
+        responses.len(),
+        retained / RESPONSES,
+        elapsed / RESPONSES as u32
+    );
+}
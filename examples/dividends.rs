@@ -23,7 +23,7 @@ async fn main() {
     // Display dividends paid during the requested period
     println!("DIVIDENDS");
     for dividend in hist.dividends().unwrap() {
-        let date = OffsetDateTime::from(UNIX_EPOCH + Duration::from_secs(dividend.date));
+        let date = dividend.date.to_datetime().unwrap();
         println!("{} | {:.3}", date, dividend.amount);
     }
 }
@@ -46,7 +46,7 @@ fn main() {
     // Display dividends paid during the requested period
     println!("DIVIDENDS");
     for dividend in hist.dividends().unwrap() {
-        let date = OffsetDateTime::from(UNIX_EPOCH + Duration::from_secs(dividend.date));
+        let date = dividend.date.to_datetime().unwrap();
         println!("{} | {:.3}", date, dividend.amount);
     }
 }
@@ -1,5 +1,3 @@
-#[cfg(not(feature = "blocking"))]
-use tokio_test;
 use yahoo_finance_api as yahoo;
 
 #[cfg(not(feature = "blocking"))]
@@ -28,7 +28,7 @@ async fn main() {
     // Get any splits that occured during the requested period
     println!("SPLITS");
     for split in hist.splits().unwrap() {
-        let date = OffsetDateTime::from(UNIX_EPOCH + Duration::from_secs(split.date));
+        let date = split.date.to_datetime().unwrap();
         println!("{} | {} : {}", date, split.numerator, split.denominator);
     }
 }
@@ -53,7 +53,7 @@ fn main() {
     // Get any splits that occured during the requested period
     println!("SPLITS");
     for split in hist.splits().unwrap() {
-        let date = OffsetDateTime::from(UNIX_EPOCH + Duration::from_secs(split.date));
+        let date = split.date.to_datetime().unwrap();
         println!("{} | {} : {}", date, split.numerator, split.denominator);
     }
 }
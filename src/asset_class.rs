@@ -0,0 +1,127 @@
+//! Classification of symbols by the type of the quoted instrument.
+use std::{convert::Infallible, fmt, str::FromStr};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use super::{QuoteType, YMetaData};
+
+/// Type of the instrument quoted by a symbol, as reported by yahoo! finance
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum AssetClass {
+    Equity,
+    Etf,
+    MutualFund,
+    Index,
+    Currency,
+    Crypto,
+    Future,
+    Option,
+    /// Instrument type not known to this crate, e.g. `MONEYMARKET`
+    Other(String),
+}
+
+impl AssetClass {
+    /// Map the instrument type used by yahoo! finance (e.g. `EQUITY` or `CRYPTOCURRENCY`)
+    pub fn from_instrument_type(instrument_type: &str) -> AssetClass {
+        match instrument_type.to_uppercase().as_str() {
+            "EQUITY" => AssetClass::Equity,
+            "ETF" => AssetClass::Etf,
+            "MUTUALFUND" => AssetClass::MutualFund,
+            "INDEX" => AssetClass::Index,
+            "CURRENCY" => AssetClass::Currency,
+            "CRYPTOCURRENCY" => AssetClass::Crypto,
+            "FUTURE" => AssetClass::Future,
+            "OPTION" => AssetClass::Option,
+            other => AssetClass::Other(other.to_string()),
+        }
+    }
+
+    /// Instrument type as used by yahoo! finance
+    pub fn as_str(&self) -> &str {
+        match self {
+            AssetClass::Equity => "EQUITY",
+            AssetClass::Etf => "ETF",
+            AssetClass::MutualFund => "MUTUALFUND",
+            AssetClass::Index => "INDEX",
+            AssetClass::Currency => "CURRENCY",
+            AssetClass::Crypto => "CRYPTOCURRENCY",
+            AssetClass::Future => "FUTURE",
+            AssetClass::Option => "OPTION",
+            AssetClass::Other(other) => other,
+        }
+    }
+}
+
+impl fmt::Display for AssetClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for AssetClass {
+    type Err = Infallible;
+
+    fn from_str(instrument_type: &str) -> Result<Self, Self::Err> {
+        Ok(AssetClass::from_instrument_type(instrument_type))
+    }
+}
+
+impl PartialEq<str> for AssetClass {
+    fn eq(&self, instrument_type: &str) -> bool {
+        self.as_str() == instrument_type
+    }
+}
+
+impl PartialEq<&str> for AssetClass {
+    fn eq(&self, instrument_type: &&str) -> bool {
+        self.as_str() == *instrument_type
+    }
+}
+
+impl<'de> Deserialize<'de> for AssetClass {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let instrument_type = <std::borrow::Cow<'de, str>>::deserialize(deserializer)?;
+        Ok(AssetClass::from_instrument_type(&instrument_type))
+    }
+}
+
+impl Serialize for AssetClass {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl YMetaData {
+    pub fn asset_class(&self) -> AssetClass {
+        self.instrument_type.clone()
+    }
+}
+
+impl QuoteType {
+    pub fn asset_class(&self) -> AssetClass {
+        AssetClass::from_instrument_type(&self.quote_type)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::chart_fixture;
+
+    #[test]
+    fn test_asset_class() {
+        for (name, asset_class) in [
+            ("equity_aapl_1d", AssetClass::Equity),
+            ("mutual_fund_amagx_1d", AssetClass::MutualFund),
+            ("fx_eurusd_1d", AssetClass::Currency),
+        ] {
+            let response = chart_fixture(name).unwrap().response().unwrap();
+            assert_eq!(response.metadata().unwrap().asset_class(), asset_class);
+        }
+        assert_eq!(
+            AssetClass::from_instrument_type("MONEYMARKET"),
+            AssetClass::Other("MONEYMARKET".to_string())
+        );
+        assert_eq!(AssetClass::Crypto.to_string(), "CRYPTOCURRENCY");
+    }
+}
@@ -1,4 +1,16 @@
-use search_result::YOptionChain;
+#[cfg(feature = "schema_monitor")]
+use std::collections::BTreeMap;
+use std::{collections::HashMap, io::Write, path::Path, sync::Arc};
+
+use bytes::Bytes;
+use futures_util::Stream;
+use reqwest::header::HeaderMap;
+
+use search_result::{YNewsResult, YOptionChain};
+use session::{
+    parse_consent_form, parse_crumb, parse_crumb_from_html, YCONSENT_HOST, YCOOKIE_URL, YCRUMB_URL,
+    YQUOTE_PAGE_URL,
+};
 
 use super::*;
 
@@ -12,6 +24,188 @@ impl YahooConnector {
         self.get_quote_range(ticker, interval, "1mo").await
     }
 
+    /// Retrieve the most recent price of the given ticker, requesting as little data as possible
+    pub async fn get_latest_price(&self, ticker: &str) -> Result<LatestPrice, YahooError> {
+        self.get_quote_range(ticker, "1d", "1d")
+            .await?
+            .latest_price()
+    }
+
+    /// Retrieve the type of instrument quoted by the given symbol, e.g. to distinguish
+    /// equities from funds or indices
+    pub async fn get_asset_class(&self, symbol: &str) -> Result<AssetClass, YahooError> {
+        Ok(self
+            .get_quote_range(symbol, "1d", "1d")
+            .await?
+            .metadata()?
+            .asset_class())
+    }
+
+    /// Retrieve the ranges, intervals and sessions available for the given symbol with a
+    /// minimal chart request, e.g. to check a query before downloading a long history
+    pub async fn get_symbol_capabilities(
+        &self,
+        symbol: &str,
+    ) -> Result<SymbolCapabilities, YahooError> {
+        Ok(self
+            .get_quote_range(symbol, "1d", "1d")
+            .await?
+            .metadata()?
+            .capabilities())
+    }
+
+    /// Infer the dividend frequency of the given ticker from its recent dividends and project
+    /// the dividends of the next year, see `estimate_dividend_schedule`
+    pub async fn estimate_dividend_schedule(
+        &self,
+        ticker: &str,
+    ) -> Result<DividendForecast, YahooError> {
+        let response = self.get_quote_range(ticker, "1mo", "5y").await?;
+        let price = response.metadata()?.regular_market_price;
+        estimate_dividend_schedule(&response.dividends()?, price, OffsetDateTime::now_utc())
+    }
+
+    /// Retrieve the 52-week high and low of the given ticker and the distance of the current
+    /// price from both, requesting as little data as possible
+    pub async fn get_52_week_stats(&self, ticker: &str) -> Result<FiftyTwoWeekStats, YahooError> {
+        self.get_quote_range(ticker, "1d", "1d")
+            .await?
+            .metadata()?
+            .fifty_two_week_stats()
+    }
+
+    /// Retrieve the most recent prices of the given tickers, requesting up to
+    /// `SPARK_MAX_SYMBOLS` of them at once. Tickers for which yahoo! finance reports no price
+    /// are missing in the returned map.
+    pub async fn get_latest_prices(
+        &self,
+        tickers: &[&str],
+    ) -> Result<HashMap<String, LatestPrice>, YahooError> {
+        let spark = self.get_spark(tickers).await?;
+        Ok(spark.latest_prices(OffsetDateTime::now_utc().unix_timestamp()))
+    }
+
+    /// Retrieve the most recent prices of the given tickers, requesting up to
+    /// `SPARK_MAX_SYMBOLS` of them at once, and report tickers without price as failed with
+    /// `YahooError::SymbolNotFound`. If one of the requests fails, the whole batch fails.
+    pub async fn get_latest_prices_batch(
+        &self,
+        tickers: &[&str],
+        policy: BatchPolicy,
+    ) -> Result<BatchResult<LatestPrice>, YahooError> {
+        let spark = self.get_spark(tickers).await?;
+        let mut prices = spark.latest_prices(OffsetDateTime::now_utc().unix_timestamp());
+        let mut batch = BatchResult::new();
+        for ticker in tickers {
+            let price = prices
+                .remove(*ticker)
+                .ok_or_else(|| YahooError::SymbolNotFound(ticker.to_string()));
+            batch.push(ticker, price, policy)?;
+        }
+        Ok(batch)
+    }
+
+    /// Retrieve the meta data of the daily charts of the given tickers, requesting up to
+    /// `SPARK_MAX_SYMBOLS` of them at once; no request is sent for an empty list
+    pub(crate) async fn get_spark(&self, tickers: &[&str]) -> Result<YSpark, YahooError> {
+        let mut spark = YSpark::default();
+        // yahoo! finance rejects requests for too many symbols at once
+        for chunk in tickers.chunks(SPARK_MAX_SYMBOLS) {
+            let symbols = chunk.join(",");
+            let params = [
+                ("symbols", symbols.as_str()),
+                ("range", "1d"),
+                ("interval", "1d"),
+            ];
+            let json = self.send_query(&YEndpoint::Spark, &params).await?;
+            let chunk = self
+                .dump_policy
+                .deserialize("spark", json, |json| YSpark::from_json(json, &symbols))?;
+            spark.spark.result.extend(chunk.spark.result);
+        }
+        Ok(spark)
+    }
+
+    /// Retrieve the exchange rate for converting one unit of currency `from` into currency `to`.
+    /// Minor units as used by yahoo! finance (e.g. "GBp") are converted via their major currency.
+    #[cfg(feature = "money")]
+    pub async fn get_fx_rate(&self, from: &Currency, to: &Currency) -> Result<Decimal, YahooError> {
+        let (from, from_units) = from.major_unit().unwrap_or((from.clone(), 1));
+        let (to, to_units) = to.major_unit().unwrap_or((to.clone(), 1));
+        let rate = if from == to {
+            Decimal::from(1)
+        } else {
+            self.get_latest_price(&format!("{from}{to}=X")).await?.price
+        };
+        Ok(rate * Decimal::from(to_units) / Decimal::from(from_units))
+    }
+
+    /// Convert an amount into another currency using the latest exchange rate
+    #[cfg(feature = "money")]
+    pub async fn convert_money(&self, money: &Money, to: &Currency) -> Result<Money, YahooError> {
+        let rate = self.get_fx_rate(&money.currency, to).await?;
+        Ok(money.convert(rate, to.clone()))
+    }
+
+    /// Retrieve the revenue and cash metrics of the given symbol converted from the currency
+    /// of its financial statements into the given currency using the latest exchange rate
+    #[cfg(feature = "money")]
+    pub async fn get_financials_in(
+        &self,
+        symbol: &str,
+        currency: &Currency,
+    ) -> Result<ConvertedFinancials, YahooError> {
+        let summary = self
+            .get_quote_summary_modules(symbol, &["financialData"], false)
+            .await?;
+        let data = summary.financial_data().ok_or(YahooError::EmptyDataSet)?;
+        let from = data.currency().ok_or(YahooError::MissingCurrency)?;
+        let rate = self.get_fx_rate(&from, currency).await?;
+        data.convert(rate, currency)
+    }
+
+    /// Retrieve the current trading state of the market of the given ticker together with the next opening and closing time
+    pub async fn get_market_state(&self, ticker: &str) -> Result<MarketStatus, YahooError> {
+        self.get_quote_range(ticker, "1d", "1d")
+            .await?
+            .metadata()?
+            .market_status_at(OffsetDateTime::now_utc())
+            .ok_or(YahooError::EmptyDataSet)
+    }
+
+    /// Poll the latest quote of the given ticker every `interval`, as measured by the clock of
+    /// the connector. Quotes with the same timestamp as the previously returned one are skipped,
+    /// errors are returned without ending the stream.
+    /// Polling stops as soon as the stream is dropped, e.g. by combining it with
+    /// `StreamExt::take_until` for a graceful shutdown.
+    pub fn poll_quotes<'a>(
+        &'a self,
+        ticker: &'a str,
+        interval: Duration,
+    ) -> impl Stream<Item = Result<Quote, YahooError>> + 'a {
+        futures_util::stream::unfold((None, true), move |(last, first)| async move {
+            let mut first = first;
+            loop {
+                if !first {
+                    self.clock.sleep(interval).await;
+                }
+                first = false;
+                let quote = self
+                    .get_quote_range(ticker, "1m", "1d")
+                    .await
+                    .and_then(|response| response.last_quote());
+                match quote {
+                    Ok(quote) if Some(quote.timestamp) == last => continue,
+                    Ok(quote) => {
+                        let timestamp = quote.timestamp;
+                        return Some((Ok(quote), (Some(timestamp), false)));
+                    }
+                    Err(e) => return Some((Err(e), (last, false))),
+                }
+            }
+        })
+    }
+
     /// Retrieve the quote history for the given ticker form date start to end (inclusive), if available
     pub async fn get_quote_history(
         &self,
@@ -23,24 +217,277 @@ impl YahooConnector {
             .await
     }
 
+    /// Retrieve the daily quote history for the given ticker over a range of dates resolved in
+    /// the time zone of its exchange, see `DateRange`. The meta data of the ticker is requested
+    /// first to determine the time zone and trading hours.
+    pub async fn get_quote_history_dates(
+        &self,
+        ticker: &str,
+        range: &DateRange,
+    ) -> Result<YResponse, YahooError> {
+        let meta = self.get_quote_range(ticker, "1d", "1d").await?.metadata()?;
+        let (start, end) = range.period(&meta)?;
+        self.get_quote_history(ticker, start, end).await
+    }
+
+    /// Retrieve the daily quote histories of the given tickers from date start to end
+    /// (inclusive), one request per ticker. Failing tickers abort the batch or are collected
+    /// depending on the policy.
+    pub async fn get_quote_histories(
+        &self,
+        tickers: &[&str],
+        start: OffsetDateTime,
+        end: OffsetDateTime,
+        policy: BatchPolicy,
+    ) -> Result<BatchResult<YResponse>, YahooError> {
+        let mut batch = BatchResult::new();
+        for ticker in tickers {
+            let response = self.get_quote_history(ticker, start, end).await;
+            batch.push(ticker, response, policy)?;
+        }
+        Ok(batch)
+    }
+
+    /// Retrieve the daily quote histories of the given tickers from date start to end
+    /// (inclusive) and write each to a JSON Lines file `<ticker>.jsonl` in the given directory,
+    /// see `YResponse::write_jsonl`. Returns the number of lines written per ticker.
+    pub async fn download_jsonl(
+        &self,
+        tickers: &[&str],
+        start: OffsetDateTime,
+        end: OffsetDateTime,
+        dir: &Path,
+        policy: BatchPolicy,
+    ) -> Result<BatchResult<usize>, YahooError> {
+        let mut batch = BatchResult::new();
+        for ticker in tickers {
+            let written = self
+                .get_quote_history(ticker, start, end)
+                .await
+                .and_then(|response| {
+                    jsonl::write_jsonl_file(&response, &dir.join(format!("{ticker}.jsonl")))
+                });
+            batch.push(ticker, written, policy)?;
+        }
+        Ok(batch)
+    }
+
+    /// Retrieve the daily quote histories of the given tickers from date start to end
+    /// (inclusive) and write them to a single JSON Lines stream, one ticker after another.
+    /// Each line is tagged with its symbol, see `YResponse::write_jsonl`.
+    pub async fn download_jsonl_to<W: Write>(
+        &self,
+        tickers: &[&str],
+        start: OffsetDateTime,
+        end: OffsetDateTime,
+        mut writer: W,
+        policy: BatchPolicy,
+    ) -> Result<BatchResult<usize>, YahooError> {
+        let mut batch = BatchResult::new();
+        for ticker in tickers {
+            let written = self
+                .get_quote_history(ticker, start, end)
+                .await
+                .and_then(|response| response.write_jsonl(&mut writer));
+            batch.push(ticker, written, policy)?;
+        }
+        Ok(batch)
+    }
+
+    /// Retrieve the quote histories of the given tickers and interval from start to end in
+    /// chunks and append them to JSON Lines files `<ticker>.jsonl` in the given directory,
+    /// recording the progress in a `DownloadManifest`. Calling it again after an interruption
+    /// skips the tickers and chunks already completed. Returns the number of lines written per
+    /// ticker by this call.
+    pub async fn download_jsonl_resumable(
+        &self,
+        tickers: &[&str],
+        start: OffsetDateTime,
+        end: OffsetDateTime,
+        interval: &str,
+        dir: &Path,
+        policy: BatchPolicy,
+    ) -> Result<BatchResult<usize>, YahooError> {
+        let mut manifest = DownloadManifest::open(dir, start, end, interval)?;
+        let mut batch = BatchResult::new();
+        for ticker in tickers {
+            let written = self.resume_download(ticker, &mut manifest, dir).await;
+            batch.push(ticker, written, policy)?;
+        }
+        Ok(batch)
+    }
+
+    async fn resume_download(
+        &self,
+        ticker: &str,
+        manifest: &mut DownloadManifest,
+        dir: &Path,
+    ) -> Result<usize, YahooError> {
+        let mut download = manifest::SymbolDownload::open(manifest, dir, ticker)?;
+        let mut written = 0;
+        for (chunk_start, chunk_end) in download.chunks(manifest)? {
+            let quotes = self
+                .get_quote_history_interval(ticker, chunk_start, chunk_end, &manifest.interval)
+                .await
+                .and_then(|response| response.quotes());
+            written += download.append(manifest, chunk_end, quotes)?;
+        }
+        download.complete(manifest)?;
+        Ok(written)
+    }
+
+    /// Retrieve the daily quote history from date start to end (inclusive) of a security
+    /// traded under several symbols over time, each given with the time from which on it
+    /// applies, e.g. `&[("FB", start), ("META", datetime!(2022-06-09 0:00 UTC))]`. Prices are
+    /// not rescaled at the splice points.
+    pub async fn get_quote_history_merged(
+        &self,
+        symbols: &[(&str, OffsetDateTime)],
+        start: OffsetDateTime,
+        end: OffsetDateTime,
+    ) -> Result<MergedHistory, YahooError> {
+        let mut merged = MergedHistory::default();
+        for period in history::symbol_periods(symbols, start, end) {
+            let quotes = self
+                .get_quote_history(&period.symbol, period.from, period.until)
+                .await?
+                .quotes()?;
+            merged.append(&period, quotes);
+        }
+        Ok(merged)
+    }
+
+    /// Retrieve the price reactions to the latest `n_events` of the given earnings
+    /// announcements of the symbol which lie in the past, see `earnings_reactions`. The
+    /// announcement times are required, since yahoo! finance does not report past ones.
+    pub async fn get_earnings_reactions(
+        &self,
+        symbol: &str,
+        announcements: &[OffsetDateTime],
+        n_events: usize,
+    ) -> Result<Vec<EarningsReaction>, YahooError> {
+        let now = OffsetDateTime::now_utc();
+        let announcements = earnings::latest_announcements(announcements, n_events, now);
+        let (Some(first), Some(last)) = (announcements.first(), announcements.last()) else {
+            return Ok(Vec::new());
+        };
+        // cover sessions before and after the announcements across weekends and holidays
+        let margin = time::Duration::days(7);
+        let quotes = self
+            .get_quote_history(symbol, *first - margin, (*last + margin).min(now))
+            .await?
+            .quotes()?;
+        Ok(earnings_reactions(&quotes, &announcements))
+    }
+
+    /// Retrieve the daily quote history of the front month future of the given commodity from
+    /// date start to end (inclusive), see `symbols` for further symbols
+    pub async fn get_commodity_history(
+        &self,
+        commodity: Commodity,
+        start: OffsetDateTime,
+        end: OffsetDateTime,
+    ) -> Result<YResponse, YahooError> {
+        self.get_quote_history(commodity.symbol(), start, end).await
+    }
+
+    /// Retrieve the intraday bars of the given ticker for the last `days_back` days. The days
+    /// are limited to those available for the interval (e.g. a week of one-minute bars), and
+    /// quotes outside of regular trading hours are included for intervals below an hour, see
+    /// `HistoryQuery::intraday`.
+    pub async fn get_intraday(
+        &self,
+        ticker: &str,
+        interval: IntradayInterval,
+        days_back: u32,
+    ) -> Result<YResponse, YahooError> {
+        self.get_history(&HistoryQuery::intraday(ticker, interval, days_back))
+            .await
+    }
+
+    /// Retrieve quotes and events as specified by the given query. This is the most general
+    /// way to request the chart endpoint, the other history methods are shortcuts for it.
+    pub async fn get_history(&self, query: &HistoryQuery) -> Result<YResponse, YahooError> {
+        match self.get_history_if_modified(query, None).await? {
+            Conditional::Modified(response, _) => Ok(response),
+            // only requests with a tag are answered as not modified
+            Conditional::NotModified => Err(YahooError::FetchFailed(format!(
+                "{}",
+                StatusCode::NOT_MODIFIED
+            ))),
+        }
+    }
+
+    /// Retrieve quotes and events as specified by the given query, unless the response is the
+    /// same as the one identified by `tag`, which has been returned by a previous call. This
+    /// allows to poll the same range repeatedly without processing unchanged responses.
+    pub async fn get_history_if_modified(
+        &self,
+        query: &HistoryQuery,
+        tag: Option<&ResponseTag>,
+    ) -> Result<Conditional<YResponse>, YahooError> {
+        let params = query.params();
+        let params: Vec<(&str, &str)> = params.iter().map(|(k, v)| (*k, v.as_str())).collect();
+        let mut response = self
+            .send_chart_request(query.ticker(), &params, tag)
+            .await?;
+        if let Conditional::Modified(response, _) = &mut response {
+            query.trim(response);
+        }
+        Ok(response)
+    }
+
+    /// Retrieve the dividends, splits and capital gains of the ticker from start to end,
+    /// restricted to the given events. The quotes are requested at a quarterly interval and
+    /// dropped, which keeps the response small for maintaining databases of corporate actions.
+    pub async fn get_events(
+        &self,
+        ticker: &str,
+        start: OffsetDateTime,
+        end: OffsetDateTime,
+        events: EventsFilter,
+    ) -> Result<CorporateActions, YahooError> {
+        let query = HistoryQuery::new(ticker)
+            .period(start, end)
+            .interval("3mo")
+            .events(events);
+        CorporateActions::from_response(&self.get_history(&query).await?)
+    }
+
     /// Retrieve quotes for the given ticker for an arbitrary range
+    ///
+    /// Shortcut for `get_history`, which may replace this method in a future release.
     pub async fn get_quote_range(
         &self,
         ticker: &str,
         interval: &str,
         range: &str,
     ) -> Result<YResponse, YahooError> {
-        let url: String = format!(
-            YCHART_RANGE_QUERY!(),
-            url = self.url,
-            symbol = ticker,
-            interval = interval,
-            range = range
-        );
-        YResponse::from_json(self.send_request(&url).await?)
+        let query = HistoryQuery::new(ticker).range(range).interval(interval);
+        self.get_history(&query).await
+    }
+
+    /// Retrieve quotes for the given ticker for an arbitrary range, together with the close prices
+    /// of additional symbols for comparison (see `YResponse::comparisons`)
+    ///
+    /// Shortcut for `get_history`, which may replace this method in a future release.
+    pub async fn get_quote_range_with_comparisons(
+        &self,
+        ticker: &str,
+        interval: &str,
+        range: &str,
+        comparisons: &[&str],
+    ) -> Result<YResponse, YahooError> {
+        let query = HistoryQuery::new(ticker)
+            .range(range)
+            .interval(interval)
+            .comparisons(comparisons);
+        self.get_history(&query).await
     }
 
     /// Retrieve the quote history for the given ticker form date start to end (inclusive), if available; specifying the interval of the ticker.
+    ///
+    /// Shortcut for `get_history`, which may replace this method in a future release.
     pub async fn get_quote_history_interval(
         &self,
         ticker: &str,
@@ -48,18 +495,32 @@ impl YahooConnector {
         end: OffsetDateTime,
         interval: &str,
     ) -> Result<YResponse, YahooError> {
-        let url = format!(
-            YCHART_PERIOD_QUERY!(),
-            url = self.url,
-            symbol = ticker,
-            start = start.unix_timestamp(),
-            end = end.unix_timestamp(),
-            interval = interval,
-        );
-        YResponse::from_json(self.send_request(&url).await?)
+        let query = HistoryQuery::new(ticker)
+            .period(start, end)
+            .interval(interval);
+        self.get_history(&query).await
+    }
+
+    /// Stream the quotes of the given ticker and interval from start to end, requesting the
+    /// history in chunks and yielding the quotes of each chunk as soon as it has been received,
+    /// see `QuoteStream`
+    pub fn stream_quote_history<'a>(
+        &'a self,
+        ticker: &'a str,
+        start: OffsetDateTime,
+        end: OffsetDateTime,
+        interval: &'a str,
+    ) -> QuoteStream<'a> {
+        QuoteStream::new(start, end, interval, move |start, end| async move {
+            self.get_quote_history_interval(ticker, start, end, interval)
+                .await?
+                .quotes()
+        })
     }
 
     /// Retrieve the quote history for the given ticker for a given period and ticker interval and optionally before and after regular trading hours
+    ///
+    /// Shortcut for `get_history`, which may replace this method in a future release.
     pub async fn get_quote_period_interval(
         &self,
         ticker: &str,
@@ -67,21 +528,18 @@ impl YahooConnector {
         interval: &str,
         prepost: bool,
     ) -> Result<YResponse, YahooError> {
-        let url = format!(
-            YCHART_PERIOD_INTERVAL_QUERY!(),
-            url = self.url,
-            symbol = ticker,
-            period = period,
-            interval = interval,
-            prepost = prepost,
-        );
-        YResponse::from_json(self.send_request(&url).await?)
+        let query = HistoryQuery::new(ticker)
+            .range(period)
+            .interval(interval)
+            .prepost(prepost);
+        self.get_history(&query).await
     }
 
     /// Retrieve the list of quotes found searching a given name
     pub async fn search_ticker_opt(&self, name: &str) -> Result<YSearchResultOpt, YahooError> {
-        let url = format!(YTICKER_QUERY!(), url = self.search_url, name = name);
-        YSearchResultOpt::from_json(self.send_request(&url).await?)
+        let json = self.send_query(&YEndpoint::Search, &[("q", name)]).await?;
+        self.dump_policy
+            .deserialize("search", json, YSearchResultOpt::from_json)
     }
 
     /// Retrieve the list of quotes found searching a given name
@@ -90,29 +548,631 @@ impl YahooConnector {
         Ok(YSearchResult::from_opt(&result))
     }
 
+    /// Search for the given name (e.g. `Volkswagen`) and return the best matching symbol
+    /// together with the other results, ranked by the default `ResolvePreference`
+    pub async fn resolve_symbol(&self, name: &str) -> Result<ResolvedSymbol, YahooError> {
+        self.resolve_symbol_with(name, &ResolvePreference::default())
+            .await
+    }
+
+    /// Search for the given name and return the best matching symbol together with the other
+    /// results, ranked by the given preference
+    pub async fn resolve_symbol_with(
+        &self,
+        name: &str,
+        preference: &ResolvePreference,
+    ) -> Result<ResolvedSymbol, YahooError> {
+        let result = self.search_ticker(name).await?;
+        ResolvedSymbol::from_quotes(result.quotes, preference)
+            .ok_or_else(|| YahooError::SymbolNotFound(name.to_string()))
+    }
+
+    /// Retrieve news articles found searching a given query, newest first. Pages through the
+    /// results by skipping the first `start` articles and returning at most `count` articles.
+    /// yahoo! finance has no offset parameter for news, so all articles up to `start + count`
+    /// are requested.
+    pub async fn search_news(
+        &self,
+        query: &str,
+        count: usize,
+        start: usize,
+    ) -> Result<Vec<YNewsItem>, YahooError> {
+        let news_count = start.checked_add(count).ok_or_else(|| {
+            YahooError::FetchFailed(format!("cannot request {count} articles after {start}"))
+        })?;
+        let news_count = news_count.to_string();
+        let params = [
+            ("q", query),
+            ("quotesCount", "0"),
+            ("newsCount", news_count.as_str()),
+        ];
+        let json = self.send_query(&YEndpoint::Search, &params).await?;
+        let result = self
+            .dump_policy
+            .deserialize("search", json, YNewsResult::from_json)?;
+        Ok(result.news.into_iter().skip(start).take(count).collect())
+    }
+
+    /// Stream over the latest `count` news articles found searching a given query, newest
+    /// first. Since yahoo! finance has no offset parameter for news, further pages would repeat
+    /// all articles before them, so the articles are requested at once when the stream is first
+    /// consumed.
+    pub fn news_pages<'a>(&'a self, query: &'a str, count: usize) -> PagedStream<'a, YNewsItem> {
+        PagedStream::new(count, move |start, count| async move {
+            // all articles are requested with the first page
+            if start > 0 {
+                return Ok(Vec::new());
+            }
+            self.search_news(query, count, 0).await
+        })
+    }
+
     /// Get list for options for a given name
     pub async fn search_options(&self, name: &str) -> Result<YOptionChain, YahooError> {
-        let url = format!("https://query2.finance.yahoo.com/v6/finance/options/{name}");
-        let resp = self.client.get(url).send().await?;
-        let resp = resp.json::<YOptionChain>().await?;
+        let json = self
+            .send_query(&YEndpoint::Options(name.to_string()), &[])
+            .await?;
+        self.dump_policy.deserialize("options", json, |json| {
+            Ok(serde_json::from_value::<YOptionChain>(json)?)
+        })
+    }
+
+    /// Retrieve detailed information about the given ticker from the quoteSummary endpoint.
+    /// Cookie and crumb required by the endpoint are fetched on first use, see `warm_up`.
+    pub async fn get_ticker_info(&self, ticker: &str) -> Result<YQuoteSummary, YahooError> {
+        self.get_quote_summary(ticker, false).await
+    }
+
+    /// Retrieve detailed information about the given ticker from the quoteSummary endpoint.
+    /// If `formatted` is set, numbers are returned together with formatted strings as used
+    /// on the yahoo! finance web pages, e.g. `{"raw": 1.5e9, "fmt": "1.5B"}`.
+    pub async fn get_quote_summary(
+        &self,
+        ticker: &str,
+        formatted: bool,
+    ) -> Result<YQuoteSummary, YahooError> {
+        self.get_quote_summary_modules(ticker, YQUOTE_SUMMARY_MODULES, formatted)
+            .await
+    }
+
+    /// Retrieve the trends of the page views of the given symbol on the yahoo! finance web
+    /// pages, which may serve as a proxy of the attention the symbol gets
+    pub async fn get_popularity(&self, symbol: &str) -> Result<PageViews, YahooError> {
+        self.get_quote_summary_modules(symbol, &["pageViews"], false)
+            .await?
+            .page_views()
+            .cloned()
+            .ok_or(YahooError::EmptyDataSet)
+    }
 
-        Ok(resp)
+    /// Retrieve the reported earnings per share of the last quarters of the given symbol
+    /// compared to the estimates of the analysts, oldest quarter first
+    pub async fn get_earnings_history(
+        &self,
+        symbol: &str,
+    ) -> Result<Vec<EarningsSurprise>, YahooError> {
+        Ok(self
+            .get_quote_summary_modules(symbol, &["earningsHistory"], false)
+            .await?
+            .earnings_history()
+            .ok_or(YahooError::EmptyDataSet)?
+            .surprises())
+    }
+
+    /// Retrieve the type, exchange and names of the given symbol
+    pub async fn get_quote_type(&self, symbol: &str) -> Result<QuoteType, YahooError> {
+        self.get_quote_summary_modules(symbol, &["quoteType"], false)
+            .await?
+            .quote_type()
+            .cloned()
+            .ok_or(YahooError::EmptyDataSet)
     }
 
-    /// Send request to yahoo! finance server and transform response to JSON value
-    async fn send_request(&self, url: &str) -> Result<serde_json::Value, YahooError> {
-        let resp = self.client.get(url).send().await?;
+    /// Retrieve the listing of research reports on the given symbol, newest first. The content
+    /// of the reports requires a premium subscription, see `ResearchReport::requires_premium`.
+    pub async fn get_research_reports(
+        &self,
+        symbol: &str,
+    ) -> Result<Vec<ResearchReport>, YahooError> {
+        let endpoint = YEndpoint::Insights(symbol.to_string());
+        let json = self.send_query(&endpoint, &[]).await?;
+        research::from_insights_json(json, symbol)
+    }
+
+    /// Retrieve the fundamentals of the given types (e.g. `annualBasicAverageShares`) reported
+    /// for fiscal periods ending from date start to end (inclusive). Types without any reported
+    /// values are omitted.
+    pub async fn get_fundamentals(
+        &self,
+        symbol: &str,
+        types: &[&str],
+        start: OffsetDateTime,
+        end: OffsetDateTime,
+    ) -> Result<Vec<FundamentalSeries>, YahooError> {
+        let types = types.join(",");
+        let period1 = start.unix_timestamp().to_string();
+        let period2 = end.unix_timestamp().to_string();
+        let params = [
+            ("symbol", symbol),
+            ("type", types.as_str()),
+            ("period1", period1.as_str()),
+            ("period2", period2.as_str()),
+        ];
+        let endpoint = YEndpoint::Timeseries(symbol.to_string());
+        let json = self.send_query(&endpoint, &params).await?;
+        timeseries::from_timeseries_json(json, symbol)
+    }
+
+    /// Retrieve the numbers of shares reported for fiscal periods ending from date start to
+    /// end (inclusive), e.g. to derive the market capitalization with `market_cap_history`
+    pub async fn get_shares_history(
+        &self,
+        symbol: &str,
+        start: OffsetDateTime,
+        end: OffsetDateTime,
+    ) -> Result<Vec<SharesOutstanding>, YahooError> {
+        let series = self
+            .get_fundamentals(symbol, timeseries::SHARES_TIMESERIES_TYPES, start, end)
+            .await?;
+        Ok(timeseries::shares_history(series))
+    }
+
+    /// Retrieve the annual balance sheets and income statements of fiscal years ending from
+    /// date start to end (inclusive) and derive ratios of the financial health of the company,
+    /// e.g. the trend of its current ratio or its Altman Z'-score
+    pub async fn get_financial_health(
+        &self,
+        symbol: &str,
+        start: OffsetDateTime,
+        end: OffsetDateTime,
+    ) -> Result<FinancialHealth, YahooError> {
+        let series = self
+            .get_fundamentals(
+                symbol,
+                financial_health::HEALTH_TIMESERIES_TYPES,
+                start,
+                end,
+            )
+            .await?;
+        Ok(FinancialHealth::from_series(&series))
+    }
+
+    /// Store the quoteSummary responses of the given symbols as snapshots and return the
+    /// changes of their schema compared to the previous snapshots, for symbols which had one.
+    /// This allows to detect changes of the response format before they break deserialization.
+    #[cfg(feature = "schema_monitor")]
+    pub async fn monitor_quote_summaries(
+        &self,
+        symbols: &[&str],
+        snapshots: &SnapshotDir,
+    ) -> Result<BTreeMap<String, SchemaDiff>, YahooError> {
+        let mut diffs = BTreeMap::new();
+        for symbol in symbols {
+            let json = match self
+                .fetch_quote_summary_json(symbol, YQUOTE_SUMMARY_MODULES, false)
+                .await
+            {
+                Err(YahooError::InvalidCrumb) => {
+                    self.crumb_rejected();
+                    self.fetch_quote_summary_json(symbol, YQUOTE_SUMMARY_MODULES, false)
+                        .await?
+                }
+                result => result?,
+            };
+            if let Some(diff) = snapshots.update("quote_summary", symbol, &json)? {
+                diffs.insert(symbol.to_string(), diff);
+            }
+        }
+        Ok(diffs)
+    }
+
+    /// Check whether the chart, search and crumb endpoints are reachable by a lightweight
+    /// request to each, e.g. for a readiness probe. Fresh credentials are fetched unless they
+    /// are disabled by `AuthMode::None`. Failures are reported, not returned as error.
+    pub async fn health_check(&self) -> HealthReport {
+        let started = Instant::now();
+        let result = self.get_quote_range("AAPL", "1d", "1d").await;
+        let chart = EndpointHealth::new("chart", started, result);
+        let started = Instant::now();
+        let result = self.search_ticker_opt("AAPL").await;
+        let search = EndpointHealth::new("search", started, result);
+        let crumb = if self.auth_mode == AuthMode::None {
+            None
+        } else {
+            let started = Instant::now();
+            let result = self.fetch_credentials_with_fallback().await;
+            Some(EndpointHealth::new("crumb", started, result))
+        };
+        HealthReport {
+            chart,
+            search,
+            crumb,
+            authenticated: self.session.crumb().is_some(),
+        }
+    }
+
+    /// Fetch cookie and crumb required by authenticated endpoints (e.g. `get_ticker_info`), so
+    /// the first call of such an endpoint does not pay for the additional round trips. Fetching
+    /// is retried up to `CREDENTIAL_ATTEMPTS` times with exponential backoff, extracting the crumb
+    /// from a quote page if the crumb endpoint failed `CRUMB_ENDPOINT_FAILURE_LIMIT` times in a row.
+    /// Fails with `YahooError::AuthDisabled` if credentials are disabled by `AuthMode::None`.
+    pub async fn warm_up(&self) -> Result<(), YahooError> {
+        if self.auth_mode == AuthMode::None {
+            return Err(YahooError::AuthDisabled);
+        }
+        let mut delay = CREDENTIAL_RETRY_DELAY;
+        let mut attempt = 1;
+        loop {
+            match self.fetch_credentials_with_fallback().await {
+                Err(_) if attempt < CREDENTIAL_ATTEMPTS => {
+                    #[cfg(feature = "metrics")]
+                    crate::telemetry::record_retry("credentials");
+                    self.clock.sleep(delay).await;
+                    delay *= 2;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Spawn a task refreshing cookie and crumb in the background before they expire, checking
+    /// every `check_interval` whether a refresh is due. Failed refreshes are retried at the next
+    /// check. The task ends once the connector has been dropped.
+    pub fn spawn_credential_refresh(
+        self: Arc<Self>,
+        check_interval: Duration,
+    ) -> tokio::task::JoinHandle<()> {
+        let connector = Arc::downgrade(&self);
+        drop(self);
+        tokio::spawn(async move {
+            while let Some(connector) = connector.upgrade() {
+                if connector.session.needs_refresh() {
+                    let _ = connector.warm_up().await;
+                }
+                // the connector is not kept alive while waiting
+                let sleep = connector.clock.sleep(check_interval);
+                drop(connector);
+                sleep.await;
+            }
+        })
+    }
+
+    /// Send a request with the given query parameters to an endpoint of the yahoo! finance API
+    /// and return the response as unprocessed JSON value. This allows to use endpoints or
+    /// parameters not (yet) supported by this crate. Requests to endpoints requiring a crumb
+    /// (i.e. quoteSummary) carry it and are retried once with fresh credentials if rejected.
+    /// Responses with an error status fail with `YahooError::FetchFailed`.
+    pub async fn query_json(
+        &self,
+        endpoint: YEndpoint,
+        params: &[(&str, &str)],
+    ) -> Result<serde_json::Value, YahooError> {
+        if !endpoint.requires_crumb() {
+            return self.send_query(&endpoint, params).await;
+        }
+        let response = match self.send_authenticated_query(&endpoint, params).await {
+            Err(YahooError::InvalidCrumb) => {
+                self.crumb_rejected();
+                self.send_authenticated_query(&endpoint, params).await
+            }
+            response => response,
+        };
+        match response? {
+            (status, json) if status.is_success() => Ok(json?),
+            (status, _) => Err(YahooError::FetchFailed(format!("{}", status))),
+        }
+    }
+
+    /// Fetch credentials in a background task, if called within a tokio runtime; otherwise
+    /// credentials are fetched on first use
+    pub(crate) fn spawn_warm_up(&self) {
+        if let Ok(runtime) = tokio::runtime::Handle::try_current() {
+            let connector = self.clone();
+            runtime.spawn(async move {
+                let _ = connector.crumb().await;
+            });
+        }
+    }
+
+    /// Return the current crumb, fetching new credentials if required
+    async fn crumb(&self) -> Result<String, YahooError> {
+        if self.auth_mode == AuthMode::None {
+            return Err(YahooError::AuthDisabled);
+        }
+        if let Some(crumb) = self.session.crumb() {
+            return Ok(crumb);
+        }
+        let _guard = self.session.fetch_lock.lock().await;
+        // credentials may have been fetched while waiting for the lock
+        if let Some(crumb) = self.session.crumb() {
+            return Ok(crumb);
+        }
+        self.warm_up().await?;
+        self.session.crumb().ok_or(YahooError::NoCrumb)
+    }
+
+    /// Fetch credentials from the crumb endpoint, or from a quote page if the endpoint failed
+    /// repeatedly
+    async fn fetch_credentials_with_fallback(&self) -> Result<(), YahooError> {
+        if let Some(pool) = &self.user_agents {
+            pool.new_session();
+        }
+        if self.session.use_quote_page() {
+            let result = self.fetch_credentials_from_quote_page().await;
+            if result.is_err() {
+                self.session.reset_strategy();
+            }
+            result
+        } else {
+            let result = self.fetch_credentials().await;
+            self.session.record_endpoint_result(result.is_ok());
+            result
+        }
+    }
+
+    /// Fetch a session cookie and extract the crumb from the page state of a quote page
+    async fn fetch_credentials_from_quote_page(&self) -> Result<(), YahooError> {
+        let resp = self.get_with_consent(YQUOTE_PAGE_URL).await?;
+        self.session.check_cookies()?;
+        self.report_auth(AuthEvent::CookieFetched);
+        match resp.status() {
+            StatusCode::OK => {
+                let crumb = parse_crumb_from_html(&resp.text().await?)?;
+                self.session.set_crumb(crumb);
+                self.report_auth(AuthEvent::CrumbRefreshed);
+                Ok(())
+            }
+            status => Err(YahooError::FetchFailed(format!("{}", status))),
+        }
+    }
+
+    /// Send a request to a page of yahoo! finance. If yahoo! finance redirects to the consent
+    /// page (as it does for users from EU countries), all cookies are accepted, which sets the
+    /// session cookies, and the request is repeated.
+    async fn get_with_consent(&self, url: &str) -> Result<reqwest::Response, YahooError> {
+        let resp = self.auth_request(self.client.get(url)).send().await?;
+        if resp.url().host_str() != Some(YCONSENT_HOST) {
+            return Ok(resp);
+        }
+        let consent_url = resp.url().clone();
+        let form = parse_consent_form(&resp.text().await?)?;
+        self.auth_request(self.client.post(consent_url))
+            .form(&form)
+            .send()
+            .await?;
+        Ok(self.auth_request(self.client.get(url)).send().await?)
+    }
 
+    /// Fetch a session cookie and the corresponding crumb
+    async fn fetch_credentials(&self) -> Result<(), YahooError> {
+        // the response itself does not matter, only the cookie set
+        self.get_with_consent(YCOOKIE_URL).await?;
+        self.session.check_cookies()?;
+        self.report_auth(AuthEvent::CookieFetched);
+        let resp = self
+            .auth_request(self.client.get(YCRUMB_URL))
+            .send()
+            .await?;
         match resp.status() {
-            StatusCode::OK => Ok(resp.json().await?),
+            StatusCode::OK => {
+                let crumb = parse_crumb(&resp.text().await?)?;
+                self.session.set_crumb(crumb);
+                self.report_auth(AuthEvent::CrumbRefreshed);
+                Ok(())
+            }
             status => Err(YahooError::FetchFailed(format!("{}", status))),
         }
     }
+
+    /// Request the given modules from the quoteSummary endpoint, retrying once with fresh
+    /// credentials if the crumb has been rejected
+    async fn get_quote_summary_modules(
+        &self,
+        ticker: &str,
+        modules: &[&str],
+        formatted: bool,
+    ) -> Result<YQuoteSummary, YahooError> {
+        match self
+            .send_quote_summary_request(ticker, modules, formatted)
+            .await
+        {
+            Err(YahooError::InvalidCrumb) => {
+                self.crumb_rejected();
+                self.send_quote_summary_request(ticker, modules, formatted)
+                    .await
+            }
+            result => result,
+        }
+    }
+
+    async fn send_quote_summary_request(
+        &self,
+        ticker: &str,
+        modules: &[&str],
+        formatted: bool,
+    ) -> Result<YQuoteSummary, YahooError> {
+        let json = self
+            .fetch_quote_summary_json(ticker, modules, formatted)
+            .await?;
+        self.dump_policy.deserialize("quote_summary", json, |json| {
+            YQuoteSummary::from_json(json, ticker)
+        })
+    }
+
+    /// Request the given modules from the quoteSummary endpoint and return the unprocessed
+    /// response
+    async fn fetch_quote_summary_json(
+        &self,
+        ticker: &str,
+        modules: &[&str],
+        formatted: bool,
+    ) -> Result<serde_json::Value, YahooError> {
+        let modules = modules.join(",");
+        let params = [
+            ("modules", modules.as_str()),
+            ("formatted", if formatted { "true" } else { "false" }),
+            ("corsDomain", "finance.yahoo.com"),
+        ];
+        let endpoint = YEndpoint::QuoteSummary(ticker.to_string());
+        // error responses are passed on, the deserializer maps them to the reported error
+        let (status, json) = self.send_authenticated_query(&endpoint, &params).await?;
+        json.map_err(|_| YahooError::FetchFailed(format!("{}", status)))
+    }
+
+    /// Send request with the crumb appended to the query parameters, failing with
+    /// `YahooError::InvalidCrumb` if yahoo! finance rejects it
+    async fn send_authenticated_query(
+        &self,
+        endpoint: &YEndpoint,
+        params: &[(&str, &str)],
+    ) -> Result<(StatusCode, Result<serde_json::Value, serde_json::Error>), YahooError> {
+        let crumb = self.crumb().await?;
+        let mut params = params.to_vec();
+        params.push(("crumb", crumb.as_str()));
+        match self.fetch_json(endpoint, &params).await? {
+            (StatusCode::UNAUTHORIZED, _) => Err(YahooError::InvalidCrumb),
+            response => Ok(response),
+        }
+    }
+
+    /// Send request to the chart endpoint and deserialize the response, mapping errors reported
+    /// for the requested ticker (e.g. unknown symbols) to the corresponding `YahooError`. Unknown
+    /// symbols are requested again with the default exchange suffix, if set. The response is
+    /// not modified if its content is the one identified by `tag`.
+    async fn send_chart_request(
+        &self,
+        ticker: &str,
+        params: &[(&str, &str)],
+        tag: Option<&ResponseTag>,
+    ) -> Result<Conditional<YResponse>, YahooError> {
+        let result = self.fetch_chart(ticker, params, tag).await;
+        let suffixed = match (&result, self.suffixed(ticker)) {
+            (Err(YahooError::SymbolNotFound(_)), Some(suffixed)) => suffixed,
+            _ => return result,
+        };
+        let params: Vec<(&str, &str)> = params
+            .iter()
+            .map(|&(key, value)| match key {
+                "symbol" => (key, suffixed.as_str()),
+                _ => (key, value),
+            })
+            .collect();
+        match self.fetch_chart(&suffixed, &params, tag).await {
+            Err(YahooError::SymbolNotFound(_)) => result,
+            suffixed_result => suffixed_result,
+        }
+    }
+
+    async fn fetch_chart(
+        &self,
+        ticker: &str,
+        params: &[(&str, &str)],
+        tag: Option<&ResponseTag>,
+    ) -> Result<Conditional<YResponse>, YahooError> {
+        #[cfg(feature = "cache")]
+        let cache_key = self.cache.as_ref().and_then(|cache| {
+            let key = cache::chart_key(ticker, params, OffsetDateTime::now_utc().unix_timestamp())?;
+            Some((cache, key))
+        });
+        #[cfg(feature = "cache")]
+        if let Some((cache, key)) = &cache_key {
+            // the cache is best effort, unreadable responses are requested again
+            if let Ok(Some(json)) = cache.get(key) {
+                let new_tag = ResponseTag::new(&HeaderMap::new(), json.to_string().as_bytes());
+                return self.chart_response(json, ticker, tag, new_tag);
+            }
+        }
+        let endpoint = YEndpoint::Chart(ticker.to_string());
+        let mut request = self.request(&endpoint, params);
+        if let Some(tag) = tag {
+            request = tag.apply(request);
+        }
+        let (status, headers, body) = self.fetch(&endpoint, request).await?;
+        if status == StatusCode::NOT_MODIFIED && tag.is_some() {
+            return Ok(Conditional::NotModified);
+        }
+        match (status, self.parse_json(status, &headers, &body)?) {
+            (StatusCode::OK, json) => {
+                let json = json?;
+                #[cfg(feature = "cache")]
+                let new_tag = match &cache_key {
+                    Some((cache, key)) => {
+                        // failing to store the response must not fail the request
+                        let _ = cache.put(key, &json);
+                        // identify the content the same way as when it is read from the cache
+                        ResponseTag::new(&headers, json.to_string().as_bytes())
+                    }
+                    None => ResponseTag::new(&headers, &body),
+                };
+                #[cfg(not(feature = "cache"))]
+                let new_tag = ResponseTag::new(&headers, &body);
+                self.chart_response(json, ticker, tag, new_tag)
+            }
+            (_, Ok(json)) if json["chart"]["error"].is_object() => {
+                let response = YResponse::from_chart_json(json, ticker)?;
+                Ok(Conditional::Modified(
+                    response,
+                    ResponseTag::new(&headers, &body),
+                ))
+            }
+            (status, _) => Err(YahooError::FetchFailed(format!("{}", status))),
+        }
+    }
+
+    /// Send request with additional query parameters to yahoo! finance server and transform response to JSON value
+    async fn send_query(
+        &self,
+        endpoint: &YEndpoint,
+        params: &[(&str, &str)],
+    ) -> Result<serde_json::Value, YahooError> {
+        match self.fetch_json(endpoint, params).await? {
+            (StatusCode::OK, json) => Ok(json?),
+            (status, _) => Err(YahooError::FetchFailed(format!("{}", status))),
+        }
+    }
+
+    /// Send request to an endpoint and parse the response body as JSON, see `parse_json`
+    async fn fetch_json(
+        &self,
+        endpoint: &YEndpoint,
+        params: &[(&str, &str)],
+    ) -> Result<(StatusCode, Result<serde_json::Value, serde_json::Error>), YahooError> {
+        let (status, headers, body) = self.fetch(endpoint, self.request(endpoint, params)).await?;
+        Ok((status, self.parse_json(status, &headers, &body)?))
+    }
+
+    /// Send a request to an endpoint and return status, headers and body of the response,
+    /// reporting status, size and duration of the request to circuit breaker and observer
+    async fn fetch(
+        &self,
+        endpoint: &YEndpoint,
+        request: RequestBuilder,
+    ) -> Result<(StatusCode, HeaderMap, Bytes), YahooError> {
+        self.check_circuit(endpoint)?;
+        if let Some(scheduler) = &self.scheduler {
+            scheduler.acquire(self.priority).await;
+        }
+        let started = Instant::now();
+        let resp = match request.send().await {
+            Ok(resp) => resp,
+            Err(e) => {
+                self.report(endpoint, None, 0, started);
+                return Err(e.into());
+            }
+        };
+        let status = resp.status();
+        let headers = resp.headers().clone();
+        let body = resp.bytes().await;
+        let bytes = body.as_ref().map_or(0, |body| body.len());
+        self.report(endpoint, Some(status), bytes, started);
+        Ok((status, headers, body?))
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use time::macros::datetime;
+    use time::macros::{date, datetime};
 
     use super::*;
 
@@ -139,7 +1199,7 @@ mod tests {
         assert_eq!(&resp.chart.result[0].meta.data_granularity, "1d");
         assert_eq!(
             &resp.chart.result[0].meta.first_trade_date,
-            &Some(-252322200)
+            &Some(YTimestamp::from_seconds(-252322200))
         );
 
         let _ = resp.last_quote().unwrap();
@@ -165,8 +1225,7 @@ mod tests {
         let end = datetime!(2020-01-31 23:59:59.99 UTC);
 
         let resp = tokio_test::block_on(provider.get_quote_history("AAPL", start, end));
-        if resp.is_ok() {
-            let resp = resp.unwrap();
+        if let Ok(resp) = resp {
             assert_eq!(resp.chart.result[0].timestamp.len(), 21);
             let quotes = resp.quotes().unwrap();
             assert_eq!(quotes.len(), 21);
@@ -209,13 +1268,48 @@ mod tests {
         assert_eq!(quotes.len(), 13usize);
     }
 
+    #[test]
+    fn test_get_quote_range_with_comparisons() {
+        let provider = YahooConnector::new().unwrap();
+        let response = tokio_test::block_on(provider.get_quote_range_with_comparisons(
+            "AAPL",
+            "1d",
+            "1mo",
+            &["MSFT", "^GSPC"],
+        ))
+        .unwrap();
+        let comparisons = response.comparisons().unwrap();
+        assert_eq!(comparisons.len(), 2);
+        assert!(!response.comparison_closes("MSFT").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_query_json() {
+        let provider = YahooConnector::new().unwrap();
+        let json = tokio_test::block_on(provider.query_json(
+            YEndpoint::Chart("AAPL".to_string()),
+            &[("interval", "1d"), ("range", "5d")],
+        ))
+        .unwrap();
+        let response = YResponse::from_json(json).unwrap();
+        assert_eq!(response.metadata().unwrap().symbol, "AAPL");
+    }
+
+    #[test]
+    fn test_get_latest_price() {
+        let provider = YahooConnector::new().unwrap();
+        let latest = tokio_test::block_on(provider.get_latest_price("AAPL")).unwrap();
+        assert_eq!(latest.currency.as_deref(), Some("USD"));
+        assert!(latest.market_state.is_some());
+    }
+
     #[test]
     fn test_large_volume() {
         let provider = YahooConnector::new().unwrap();
         let response =
             tokio_test::block_on(provider.get_quote_range("BTC-USD", "1d", "5d")).unwrap();
         let quotes = response.quotes().unwrap();
-        assert!(quotes.len() > 0usize);
+        assert!(!quotes.is_empty());
     }
 
     #[test]
@@ -234,6 +1328,27 @@ mod tests {
         assert!(apple_found)
     }
 
+    #[test]
+    fn test_resolve_symbol() {
+        let provider = YahooConnector::new().unwrap();
+        let preference = ResolvePreference::default().exchanges(&["GER"]);
+        let resolved =
+            tokio_test::block_on(provider.resolve_symbol_with("Volkswagen", &preference)).unwrap();
+        assert!(resolved.symbol().ends_with(".DE"));
+    }
+
+    #[test]
+    fn test_search_news() {
+        let provider = YahooConnector::new().unwrap();
+        let news = tokio_test::block_on(provider.search_news("Apple", 5, 0)).unwrap();
+        assert!(!news.is_empty() && news.len() <= 5);
+        let page = tokio_test::block_on(provider.search_news("Apple", 2, 3)).unwrap();
+        assert!(page.len() <= 2);
+        if news.len() == 5 && !page.is_empty() {
+            assert_eq!(page[0].uuid, news[3].uuid);
+        }
+    }
+
     #[test]
     fn search_options() {
         let provider = YahooConnector::new().unwrap();
@@ -250,8 +1365,7 @@ mod tests {
         let end = datetime!(2020-01-31 23:59:59.99 UTC);
 
         let resp = tokio_test::block_on(provider.get_quote_history("VTSAX", start, end));
-        if resp.is_ok() {
-            let resp = resp.unwrap();
+        if let Ok(resp) = resp {
             assert_eq!(resp.chart.result[0].timestamp.len(), 21);
             let quotes = resp.quotes().unwrap();
             assert_eq!(quotes.len(), 21);
@@ -299,6 +1413,350 @@ mod tests {
         assert_eq!(&response.chart.result[0].meta.range, "5y");
         assert_eq!(&response.chart.result[0].meta.data_granularity, "1d");
         let capital_gains = response.capital_gains().unwrap();
-        assert!(capital_gains.len() > 0usize);
+        assert!(!capital_gains.is_empty());
+    }
+
+    #[cfg(feature = "money")]
+    #[test]
+    fn test_get_financials_in() {
+        let provider = YahooConnector::new().unwrap();
+        let financials =
+            tokio_test::block_on(provider.get_financials_in("SONY", &Currency::new("USD")))
+                .unwrap();
+        let revenue = financials.total_revenue.unwrap();
+        assert_eq!(revenue.converted.currency.code(), "USD");
+    }
+
+    #[cfg(feature = "money")]
+    #[test]
+    fn test_get_fx_rate() {
+        let provider = YahooConnector::new().unwrap();
+        let rate = tokio_test::block_on(
+            provider.get_fx_rate(&Currency::new("GBp"), &Currency::new("GBP")),
+        )
+        .unwrap();
+        assert_eq!(rate * Decimal::from(100), Decimal::from(1));
+    }
+
+    #[test]
+    fn test_symbol_not_found() {
+        let provider = YahooConnector::new().unwrap();
+        let result = tokio_test::block_on(provider.get_latest_quotes("XXXXXX", "1d"));
+        assert!(matches!(result, Err(YahooError::SymbolNotFound(symbol)) if symbol == "XXXXXX"));
+    }
+
+    #[test]
+    fn test_get_latest_prices() {
+        let provider = YahooConnector::new().unwrap();
+        let prices =
+            tokio_test::block_on(provider.get_latest_prices(&["AAPL", "MSFT", "XXXXXX"])).unwrap();
+        assert!(prices.contains_key("AAPL") && prices.contains_key("MSFT"));
+        assert!(!prices.contains_key("XXXXXX"));
+    }
+
+    #[cfg(feature = "money")]
+    #[test]
+    fn test_portfolio_value() {
+        let provider = YahooConnector::new().unwrap();
+        let mut portfolio = Portfolio::new(Currency::new("EUR"));
+        let cost = Money::new(Decimal::from(1000), Currency::new("USD"));
+        portfolio.add_position("AAPL", Decimal::from(10), cost);
+        let valuation = tokio_test::block_on(portfolio.value(&provider)).unwrap();
+        assert_eq!(valuation.market_value.currency, Currency::new("EUR"));
+        assert_eq!(valuation.positions[0].weight, Decimal::from(1));
+    }
+
+    #[test]
+    fn test_estimate_dividend_schedule() {
+        let provider = YahooConnector::new().unwrap();
+        let forecast = tokio_test::block_on(provider.estimate_dividend_schedule("AAPL")).unwrap();
+        assert_eq!(forecast.frequency, DividendFrequency::Quarterly);
+        assert!(forecast.annualized_yield.is_some());
+    }
+
+    #[test]
+    fn test_get_52_week_stats() {
+        let provider = YahooConnector::new().unwrap();
+        let stats = tokio_test::block_on(provider.get_52_week_stats("AAPL")).unwrap();
+        assert!(stats.low <= stats.high);
+        assert!(stats.from_high_percent <= Decimal::default());
+    }
+
+    #[test]
+    fn test_get_ticker_info() {
+        let provider = YahooConnector::new().unwrap();
+        let summary = tokio_test::block_on(provider.get_ticker_info("AAPL")).unwrap();
+        assert!(summary.financial_data().is_some());
+    }
+
+    #[test]
+    fn test_get_quote_history_merged() {
+        let provider = YahooConnector::new().unwrap();
+        let start = datetime!(2022-6-1 0:00:00.00 UTC);
+        let end = datetime!(2022-6-30 23:59:59.99 UTC);
+        let splice = datetime!(2022-6-15 0:00:00.00 UTC);
+        let merged = tokio_test::block_on(provider.get_quote_history_merged(
+            &[("AAPL", start), ("MSFT", splice)],
+            start,
+            end,
+        ))
+        .unwrap();
+        assert_eq!(merged.splices.len(), 2);
+        assert_eq!(merged.symbol_at(merged.quotes[0].timestamp), Some("AAPL"));
+        assert!(merged.splices[1].timestamp >= splice.unix_timestamp() as u64);
+    }
+
+    #[test]
+    fn test_get_earnings_reactions() {
+        let provider = YahooConnector::new().unwrap();
+        let announcements = [
+            datetime!(2024-1-1 0:00:00.00 UTC),
+            datetime!(2024-2-1 21:30:00.00 UTC),
+        ];
+        let reactions =
+            tokio_test::block_on(provider.get_earnings_reactions("AAPL", &announcements, 1))
+                .unwrap();
+        assert_eq!(reactions.len(), 1);
+        assert_eq!(reactions[0].announcement, announcements[1]);
+        assert!(reactions[0].timestamp > announcements[1].unix_timestamp() as u64);
+    }
+
+    #[test]
+    fn test_get_quote_histories() {
+        let provider = YahooConnector::new().unwrap();
+        let start = datetime!(2020-01-01 0:00:00.00 UTC);
+        let end = datetime!(2020-01-31 23:59:59.99 UTC);
+        let batch = tokio_test::block_on(provider.get_quote_histories(
+            &["AAPL", "XXXXXXXX"],
+            start,
+            end,
+            BatchPolicy::CollectAll,
+        ))
+        .unwrap();
+        assert_eq!(batch.succeeded.len(), 1);
+        assert_eq!(batch.succeeded[0].0, "AAPL");
+        assert_eq!(batch.failed.len(), 1);
+        assert_eq!(batch.failed[0].0, "XXXXXXXX");
+    }
+
+    #[test]
+    fn test_health_check() {
+        let provider = YahooConnector::new().unwrap();
+        let report = tokio_test::block_on(provider.health_check());
+        assert!(report.chart.reachable);
+        assert!(report.search.reachable);
+        assert!(report.is_healthy(), "{:?}", report.unreachable());
+    }
+
+    #[test]
+    fn test_get_quote_history_dates() {
+        let provider = YahooConnector::new().unwrap();
+        let range = DateRange::new(date!(2024 - 03 - 08), date!(2024 - 03 - 12));
+        let quotes = tokio_test::block_on(provider.get_quote_history_dates("AAPL", &range))
+            .unwrap()
+            .quotes()
+            .unwrap();
+        // Friday, Monday and Tuesday across the change to daylight saving time
+        assert_eq!(quotes.len(), 3);
+    }
+
+    #[test]
+    fn test_get_events() {
+        let provider = YahooConnector::new().unwrap();
+        let actions = tokio_test::block_on(provider.get_events(
+            "AAPL",
+            datetime!(2020-01-01 0:00:00.00 UTC),
+            datetime!(2021-01-01 0:00:00.00 UTC),
+            EventsFilter::DIVIDENDS | EventsFilter::SPLITS,
+        ))
+        .unwrap();
+        assert_eq!(actions.dividends.len(), 4);
+        assert_eq!(actions.splits.len(), 1);
+        assert_eq!(actions.splits[0].split_ratio, "4:1");
+    }
+
+    #[test]
+    fn test_news_pages() {
+        use futures_util::StreamExt;
+        let provider = YahooConnector::new().unwrap();
+        let news: Vec<_> = tokio_test::block_on(provider.news_pages("Apple", 7).collect());
+        assert_eq!(news.len(), 7);
+        assert!(news.iter().all(|item| item.is_ok()));
+    }
+
+    #[test]
+    fn test_stream_quote_history() {
+        use futures_util::StreamExt;
+        let provider = YahooConnector::new().unwrap();
+        let start = datetime!(1990-01-01 0:00:00.00 UTC);
+        let end = datetime!(2020-01-01 0:00:00.00 UTC);
+        let quotes: Vec<_> = tokio_test::block_on(
+            provider
+                .stream_quote_history("AAPL", start, end, "1mo")
+                .collect(),
+        );
+        assert_eq!(quotes.len(), 360);
+        assert!(quotes.iter().all(|quote| quote.is_ok()));
+    }
+
+    #[test]
+    fn test_get_intraday() {
+        let provider = YahooConnector::new().unwrap();
+        let response =
+            tokio_test::block_on(provider.get_intraday("AAPL", IntradayInterval::OneMinute, 30))
+                .unwrap();
+        assert_eq!(&response.chart.result[0].meta.data_granularity, "1m");
+        assert_eq!(&response.chart.result[0].meta.range, "7d");
+    }
+
+    #[test]
+    fn test_default_exchange_suffix() {
+        let provider = YahooConnector::builder()
+            .default_exchange_suffix(".DE")
+            .build()
+            .unwrap();
+        let response = tokio_test::block_on(provider.get_latest_quotes("VOW3", "1d")).unwrap();
+        assert_eq!(response.metadata().unwrap().symbol, "VOW3.DE");
+    }
+
+    #[test]
+    fn test_get_commodity_history() {
+        let provider = YahooConnector::new().unwrap();
+        let start = datetime!(2024-01-02 0:00 UTC);
+        let end = datetime!(2024-01-31 0:00 UTC);
+        let response =
+            tokio_test::block_on(provider.get_commodity_history(Commodity::Gold, start, end))
+                .unwrap();
+        assert_eq!(response.metadata().unwrap().symbol, "GC=F");
+        assert!(!response.quotes().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_get_history_if_modified() {
+        let provider = YahooConnector::new().unwrap();
+        let query = HistoryQuery::new("AAPL")
+            .period(
+                datetime!(2024-01-02 0:00 UTC),
+                datetime!(2024-01-31 0:00 UTC),
+            )
+            .interval("1d");
+        let Conditional::Modified(response, tag) =
+            tokio_test::block_on(provider.get_history_if_modified(&query, None)).unwrap()
+        else {
+            panic!("first request must return data");
+        };
+        assert!(!response.quotes().unwrap().is_empty());
+        // the meta data contains the latest price, so the response may differ nevertheless
+        tokio_test::block_on(provider.get_history_if_modified(&query, Some(&tag))).unwrap();
+    }
+
+    #[test]
+    fn test_get_symbol_capabilities() {
+        let provider = YahooConnector::new().unwrap();
+        let capabilities = tokio_test::block_on(provider.get_symbol_capabilities("AAPL")).unwrap();
+        assert!(capabilities.supports_range("max"));
+        assert_eq!(capabilities.min_interval, "1m");
+    }
+
+    #[test]
+    fn test_get_asset_class() {
+        let provider = YahooConnector::new().unwrap();
+        assert_eq!(
+            tokio_test::block_on(provider.get_asset_class("AAPL")).unwrap(),
+            AssetClass::Equity
+        );
+        assert_eq!(
+            tokio_test::block_on(provider.get_asset_class("^GSPC")).unwrap(),
+            AssetClass::Index
+        );
+        assert_eq!(
+            tokio_test::block_on(provider.get_asset_class("BTC-USD")).unwrap(),
+            AssetClass::Crypto
+        );
+    }
+
+    #[test]
+    fn test_get_earnings_history() {
+        let provider = YahooConnector::new().unwrap();
+        let surprises = tokio_test::block_on(provider.get_earnings_history("AAPL")).unwrap();
+        assert!(!surprises.is_empty());
+        assert!(surprises.windows(2).all(|w| w[0].quarter < w[1].quarter));
+    }
+
+    #[test]
+    fn test_get_popularity() {
+        let provider = YahooConnector::new().unwrap();
+        let page_views = tokio_test::block_on(provider.get_popularity("AAPL")).unwrap();
+        assert!(page_views.short_term_trend.is_some());
+    }
+
+    #[test]
+    fn test_get_research_reports() {
+        let provider = YahooConnector::new().unwrap();
+        let reports = tokio_test::block_on(provider.get_research_reports("AAPL")).unwrap();
+        assert!(reports.iter().all(|report| !report.title.is_empty()));
+    }
+
+    #[test]
+    fn test_get_financial_health() {
+        let provider = YahooConnector::new().unwrap();
+        let start = datetime!(2020-1-1 0:00:00.00 UTC);
+        let end = datetime!(2024-1-1 0:00:00.00 UTC);
+        let health =
+            tokio_test::block_on(provider.get_financial_health("AAPL", start, end)).unwrap();
+        assert!(!health.trend(HealthRatio::CurrentRatio).is_empty());
+        assert!(health.latest().unwrap().ratios.altman_z.is_some());
+    }
+
+    #[test]
+    fn test_get_shares_history() {
+        let provider = YahooConnector::new().unwrap();
+        let start = datetime!(2022-1-1 0:00:00.00 UTC);
+        let end = datetime!(2024-1-1 0:00:00.00 UTC);
+        let shares = tokio_test::block_on(provider.get_shares_history("AAPL", start, end)).unwrap();
+        assert!(shares
+            .iter()
+            .any(|s| s.measure == SharesMeasure::PeriodEnd && s.shares > 10_000_000_000));
+        let quotes = tokio_test::block_on(provider.get_quote_history("AAPL", start, end))
+            .unwrap()
+            .quotes()
+            .unwrap();
+        assert!(!market_cap_history(&shares, &quotes).is_empty());
+    }
+
+    #[test]
+    fn test_get_quote_summary_formatted() {
+        let provider = YahooConnector::new().unwrap();
+        let summary = tokio_test::block_on(provider.get_quote_summary("AAPL", true)).unwrap();
+        let financial_data = summary.financial_data().unwrap();
+        assert!(financial_data.current_price.as_ref().unwrap().fmt.is_some());
+    }
+
+    #[test]
+    fn test_concurrent_get_ticker_info() {
+        // clones share cookie and crumb, which are fetched only once
+        let provider = YahooConnector::new().unwrap();
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let tasks: Vec<_> = ["AAPL", "MSFT", "GOOG", "AMZN"]
+            .into_iter()
+            .map(|ticker| {
+                let provider = provider.clone();
+                runtime.spawn(async move { provider.get_ticker_info(ticker).await })
+            })
+            .collect();
+        for task in tasks {
+            let summary = runtime.block_on(task).unwrap().unwrap();
+            assert!(summary.quote_type().is_some());
+        }
+    }
+
+    #[test]
+    fn test_poll_quotes() {
+        use futures_util::StreamExt;
+
+        let provider = YahooConnector::new().unwrap();
+        let quotes = provider.poll_quotes("BTC-USD", Duration::from_secs(1));
+        let quotes: Vec<_> = tokio_test::block_on(quotes.take(2).collect());
+        assert!(quotes[0].as_ref().unwrap().timestamp < quotes[1].as_ref().unwrap().timestamp);
     }
 }
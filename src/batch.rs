@@ -0,0 +1,115 @@
+//! Results of requests for many symbols, which do not fail as a whole because a single
+//! symbol failed unless requested so.
+//!
+//! ```rust,ignore
+//! let batch = provider
+//!     .get_quote_histories(&["AAPL", "XXXXXX"], start, end, BatchPolicy::CollectAll)
+//!     .await?;
+//! for (symbol, error) in &batch.failed {
+//!     eprintln!("skipping {symbol}: {error}");
+//! }
+//! ```
+use super::YahooError;
+
+/// Handling of symbols failing within a batch
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BatchPolicy {
+    /// Abort the batch and return the error of the first failing symbol
+    FailFast,
+    /// Process all symbols and collect the failures alongside the results
+    #[default]
+    CollectAll,
+}
+
+/// Results of a batch, in the order of the requested symbols
+#[derive(Debug)]
+pub struct BatchResult<T> {
+    pub succeeded: Vec<(String, T)>,
+    pub failed: Vec<(String, YahooError)>,
+}
+
+impl<T> Default for BatchResult<T> {
+    fn default() -> Self {
+        BatchResult {
+            succeeded: Vec::new(),
+            failed: Vec::new(),
+        }
+    }
+}
+
+impl<T> BatchResult<T> {
+    pub fn new() -> BatchResult<T> {
+        BatchResult::default()
+    }
+
+    /// Add the result for a symbol. Returns the error if the symbol failed and the policy is
+    /// `BatchPolicy::FailFast`, so the batch can be aborted with `?`.
+    pub fn push(
+        &mut self,
+        symbol: &str,
+        result: Result<T, YahooError>,
+        policy: BatchPolicy,
+    ) -> Result<(), YahooError> {
+        match result {
+            Ok(value) => self.succeeded.push((symbol.to_string(), value)),
+            Err(e) if policy == BatchPolicy::FailFast => return Err(e),
+            Err(e) => self.failed.push((symbol.to_string(), e)),
+        }
+        Ok(())
+    }
+
+    /// Return whether all symbols succeeded
+    pub fn is_complete(&self) -> bool {
+        self.failed.is_empty()
+    }
+
+    /// Return the results of the symbols, or the error of the first failed symbol if any
+    pub fn into_result(self) -> Result<Vec<(String, T)>, YahooError> {
+        match self.failed.into_iter().next() {
+            Some((_, e)) => Err(e),
+            None => Ok(self.succeeded),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_batch_result() {
+        let results = || {
+            vec![
+                ("AAPL", Ok(1)),
+                (
+                    "XXXXXX",
+                    Err(YahooError::SymbolNotFound("XXXXXX".to_string())),
+                ),
+                ("MSFT", Ok(2)),
+            ]
+        };
+        let mut batch = BatchResult::new();
+        for (symbol, result) in results() {
+            batch.push(symbol, result, BatchPolicy::CollectAll).unwrap();
+        }
+        assert_eq!(
+            batch.succeeded,
+            vec![("AAPL".to_string(), 1), ("MSFT".to_string(), 2)]
+        );
+        assert_eq!(batch.failed.len(), 1);
+        assert_eq!(batch.failed[0].0, "XXXXXX");
+        assert!(!batch.is_complete());
+        assert!(matches!(
+            batch.into_result(),
+            Err(YahooError::SymbolNotFound(_))
+        ));
+
+        let mut batch = BatchResult::new();
+        let aborted = results()
+            .into_iter()
+            .try_for_each(|(symbol, result)| batch.push(symbol, result, BatchPolicy::FailFast));
+        assert!(aborted.is_err());
+        assert_eq!(batch.succeeded.len(), 1);
+        assert!(batch.is_complete());
+    }
+}
@@ -0,0 +1,213 @@
+//! Board of the latest quotes of several symbols, refreshed in batched requests and compared
+//! against the previous snapshot, e.g. for terminal tickers and dashboards.
+//!
+//! ```rust,ignore
+//! let mut board = QuoteBoard::new(&["AAPL", "MSFT", "^GSPC"]);
+//! loop {
+//!     for change in board.refresh(&connector).await? {
+//!         println!("{}: {:?}", change.symbol, change.current.map(|entry| entry.price));
+//!     }
+//!     tokio::time::sleep(Duration::from_secs(30)).await;
+//! }
+//! ```
+use std::collections::HashMap;
+
+use time::OffsetDateTime;
+
+use super::quotes::ZERO;
+use super::{Decimal, MarketState, YMetaData, YahooConnector, YahooError};
+
+/// Latest quote of a symbol on the board
+#[derive(Debug, Clone, PartialEq)]
+pub struct BoardEntry {
+    pub symbol: String,
+    pub price: Decimal,
+    /// Change against the previous close, if reported
+    pub change: Option<Decimal>,
+    /// Change against the previous close in percent, if reported
+    pub change_percent: Option<Decimal>,
+    /// Volume of the day, if reported
+    pub volume: Option<u64>,
+    /// State of the market at the time of the snapshot, if trading periods are known
+    pub market_state: Option<MarketState>,
+    /// Time of the price (as UNIX timestamp), if known
+    pub time: Option<i64>,
+}
+
+impl BoardEntry {
+    fn from_meta(symbol: &str, meta: &YMetaData, now: i64) -> Option<BoardEntry> {
+        let price = meta.regular_market_price?;
+        let previous = meta
+            .previous_close
+            .or(meta.chart_previous_close)
+            .filter(|previous| *previous != ZERO);
+        Some(BoardEntry {
+            symbol: symbol.to_string(),
+            price,
+            change: previous.map(|previous| price - previous),
+            change_percent: previous
+                .map(|previous| (price - previous) / previous * Decimal::from(100)),
+            volume: meta.regular_market_volume,
+            market_state: meta.market_state_at(now),
+            time: meta.regular_market_time.map(|time| time.seconds()),
+        })
+    }
+}
+
+/// Quotes of all symbols of a board at one point in time
+#[derive(Debug, Clone, PartialEq)]
+pub struct BoardSnapshot {
+    /// Entries in the order of the symbols of the board; symbols without price are missing
+    pub entries: Vec<BoardEntry>,
+    /// Time the snapshot has been taken (as UNIX timestamp)
+    pub taken_at: i64,
+}
+
+impl BoardSnapshot {
+    pub fn get(&self, symbol: &str) -> Option<&BoardEntry> {
+        self.entries.iter().find(|entry| entry.symbol == symbol)
+    }
+}
+
+/// Difference of the entry of a symbol between two snapshots
+#[derive(Debug, Clone, PartialEq)]
+pub struct BoardChange {
+    pub symbol: String,
+    /// Entry of the previous snapshot, `None` if the symbol had no price
+    pub previous: Option<BoardEntry>,
+    /// Entry of the current snapshot, `None` if the symbol has no price anymore
+    pub current: Option<BoardEntry>,
+}
+
+/// Symbols whose latest quotes are retrieved together, keeping the most recent snapshot
+#[derive(Debug, Clone, Default)]
+pub struct QuoteBoard {
+    symbols: Vec<String>,
+    snapshot: Option<BoardSnapshot>,
+}
+
+impl QuoteBoard {
+    pub fn new(symbols: &[&str]) -> QuoteBoard {
+        QuoteBoard {
+            symbols: symbols.iter().map(|s| s.to_string()).collect(),
+            snapshot: None,
+        }
+    }
+
+    pub fn symbols(&self) -> Vec<&str> {
+        self.symbols.iter().map(|s| s.as_str()).collect()
+    }
+
+    /// The most recent snapshot, `None` before the first update
+    pub fn snapshot(&self) -> Option<&BoardSnapshot> {
+        self.snapshot.as_ref()
+    }
+
+    /// Take a snapshot of the given meta data of the symbols at the given time (as UNIX
+    /// timestamp) and return the entries which differ from the previous snapshot, in the order
+    /// of the symbols. On the first update, all entries are reported as changed.
+    pub fn update(&mut self, meta: &HashMap<String, YMetaData>, now: i64) -> Vec<BoardChange> {
+        let snapshot = BoardSnapshot {
+            entries: self
+                .symbols
+                .iter()
+                .filter_map(|symbol| BoardEntry::from_meta(symbol, meta.get(symbol)?, now))
+                .collect(),
+            taken_at: now,
+        };
+        let changes = self
+            .symbols
+            .iter()
+            .filter_map(|symbol| {
+                let previous = self.snapshot.as_ref().and_then(|s| s.get(symbol)).cloned();
+                let current = snapshot.get(symbol).cloned();
+                (previous != current).then(|| BoardChange {
+                    symbol: symbol.clone(),
+                    previous,
+                    current,
+                })
+            })
+            .collect();
+        self.snapshot = Some(snapshot);
+        changes
+    }
+
+    /// Retrieve the latest quotes of all symbols in batched requests and return the changes
+    /// against the previous snapshot, see `QuoteBoard::update`
+    #[cfg(not(feature = "blocking"))]
+    pub async fn refresh(
+        &mut self,
+        connector: &YahooConnector,
+    ) -> Result<Vec<BoardChange>, YahooError> {
+        if self.symbols.is_empty() {
+            return Ok(Vec::new());
+        }
+        let spark = connector.get_spark(&self.symbols()).await?;
+        let now = OffsetDateTime::now_utc().unix_timestamp();
+        Ok(self.update(&spark.into_meta(), now))
+    }
+
+    /// Retrieve the latest quotes of all symbols in batched requests and return the changes
+    /// against the previous snapshot, see `QuoteBoard::update`
+    #[cfg(feature = "blocking")]
+    pub fn refresh(&mut self, connector: &YahooConnector) -> Result<Vec<BoardChange>, YahooError> {
+        if self.symbols.is_empty() {
+            return Ok(Vec::new());
+        }
+        let spark = connector.get_spark(&self.symbols())?;
+        let now = OffsetDateTime::now_utc().unix_timestamp();
+        Ok(self.update(&spark.into_meta(), now))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::chart_fixture;
+
+    fn meta(name: &str) -> YMetaData {
+        chart_fixture(name)
+            .unwrap()
+            .response()
+            .unwrap()
+            .metadata()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_quote_board() {
+        let mut aapl = meta("equity_aapl_1d");
+        aapl.regular_market_price = Some(Decimal::from(200));
+        aapl.previous_close = Some(Decimal::from(250));
+        let mut quotes = HashMap::new();
+        quotes.insert("AAPL".to_string(), aapl.clone());
+        quotes.insert("EURUSD=X".to_string(), meta("fx_eurusd_1d"));
+
+        let mut board = QuoteBoard::new(&["AAPL", "EURUSD=X", "XXXXXX"]);
+        let changes = board.update(&quotes, 1707512401);
+        assert_eq!(changes.len(), 2);
+        assert!(changes.iter().all(|change| change.previous.is_none()));
+        let entry = board.snapshot().unwrap().get("AAPL").unwrap();
+        assert_eq!(entry.change, Some(Decimal::from(-50)));
+        assert_eq!(entry.change_percent, Some(Decimal::from(-20)));
+        assert_eq!(board.snapshot().unwrap().taken_at, 1707512401);
+
+        assert!(board.update(&quotes, 1707512401).is_empty());
+
+        aapl.regular_market_price = Some(Decimal::from(210));
+        quotes.insert("AAPL".to_string(), aapl);
+        quotes.remove("EURUSD=X");
+        let changes = board.update(&quotes, 1707512461);
+        let symbols: Vec<&str> = changes.iter().map(|c| c.symbol.as_str()).collect();
+        assert_eq!(symbols, vec!["AAPL", "EURUSD=X"]);
+        assert_eq!(
+            changes[0].previous.as_ref().unwrap().price,
+            Decimal::from(200)
+        );
+        assert_eq!(
+            changes[0].current.as_ref().unwrap().price,
+            Decimal::from(210)
+        );
+        assert!(changes[1].current.is_none());
+    }
+}
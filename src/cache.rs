@@ -0,0 +1,236 @@
+//! Cache of responses on disk, compressed with zstd, so repeated backtests over the same
+//! symbols read their histories from disk instead of requesting them again.
+//!
+//! Each response is stored in a file of its own, which is written atomically and read via a
+//! memory map. If a budget is set, the least recently written files are evicted as soon as
+//! the total size of the cache exceeds it.
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::SystemTime;
+
+use memmap2::Mmap;
+
+use super::YahooError;
+
+const EXTENSION: &str = "json.zst";
+
+/// Chart responses are only cached if the requested period ended at least this long ago,
+/// since the latest quotes may still change
+const MIN_AGE_SECONDS: i64 = 86400;
+
+/// Distinguishes temporary files written concurrently within this process
+static TEMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Directory of compressed responses
+#[derive(Debug, Clone)]
+pub struct ResponseCache {
+    dir: PathBuf,
+    budget: Option<u64>,
+    level: i32,
+}
+
+impl ResponseCache {
+    /// Open (or create) a cache in the given directory, without budget and with the default
+    /// compression level of zstd
+    pub fn open<P: AsRef<Path>>(dir: P) -> Result<ResponseCache, YahooError> {
+        fs::create_dir_all(dir.as_ref()).map_err(YahooError::CacheFailed)?;
+        Ok(ResponseCache {
+            dir: dir.as_ref().to_path_buf(),
+            budget: None,
+            level: zstd::DEFAULT_COMPRESSION_LEVEL,
+        })
+    }
+
+    /// Limit the total size of the compressed responses to the given number of bytes
+    pub fn budget(mut self, bytes: u64) -> Self {
+        self.budget = Some(bytes);
+        self
+    }
+
+    /// Set the zstd compression level, from 1 (fastest) to 22 (smallest)
+    pub fn compression_level(mut self, level: i32) -> Self {
+        self.level = level;
+        self
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        let name: String = key
+            .chars()
+            .take(64)
+            .map(|c| match c {
+                'A'..='Z' | 'a'..='z' | '0'..='9' | '-' | '.' => c,
+                _ => '_',
+            })
+            .collect();
+        // the hash distinguishes keys which differ only in replaced or truncated characters
+        self.dir
+            .join(format!("{name}-{:016x}.{EXTENSION}", fnv1a(key)))
+    }
+
+    /// Return the response stored under the given key, if any
+    pub fn get(&self, key: &str) -> Result<Option<serde_json::Value>, YahooError> {
+        let file = match File::open(self.path(key)) {
+            Ok(file) => file,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(YahooError::CacheFailed(e)),
+        };
+        // SAFETY: files are never modified after being written, they are only replaced by
+        // renaming a new file over them or removed, which leaves the mapped file intact
+        let map = unsafe { Mmap::map(&file) }.map_err(YahooError::CacheFailed)?;
+        let json = zstd::decode_all(&map[..]).map_err(YahooError::CacheFailed)?;
+        Ok(Some(serde_json::from_slice(&json)?))
+    }
+
+    /// Store the response under the given key, replacing a previously stored one, and evict
+    /// responses exceeding the budget
+    pub fn put(&self, key: &str, json: &serde_json::Value) -> Result<(), YahooError> {
+        let compressed = zstd::encode_all(serde_json::to_vec(json)?.as_slice(), self.level)
+            .map_err(YahooError::CacheFailed)?;
+        let path = self.path(key);
+        // concurrent writers of the same key, also from other processes, use temporary files
+        // of their own
+        let temp = self.dir.join(format!(
+            "{}.{}-{}.tmp",
+            path.file_name().unwrap_or_default().to_string_lossy(),
+            std::process::id(),
+            TEMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        let write = || -> io::Result<()> {
+            let mut file = File::create(&temp)?;
+            file.write_all(&compressed)?;
+            file.sync_all()?;
+            fs::rename(&temp, &path)
+        };
+        if let Err(e) = write() {
+            let _ = fs::remove_file(&temp);
+            return Err(YahooError::CacheFailed(e));
+        }
+        self.evict()?;
+        Ok(())
+    }
+
+    /// Files of the cache with their size and time of writing, oldest first
+    fn entries(&self) -> Result<Vec<(PathBuf, u64, SystemTime)>, YahooError> {
+        let mut entries = Vec::new();
+        for entry in fs::read_dir(&self.dir).map_err(YahooError::CacheFailed)? {
+            let entry = entry.map_err(YahooError::CacheFailed)?;
+            let path = entry.path();
+            if !path.to_string_lossy().ends_with(EXTENSION) {
+                continue;
+            }
+            let meta = entry.metadata().map_err(YahooError::CacheFailed)?;
+            let modified = meta.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+            entries.push((path, meta.len(), modified));
+        }
+        entries.sort_by_key(|(_, _, modified)| *modified);
+        Ok(entries)
+    }
+
+    /// Total size of the compressed responses in bytes
+    pub fn size(&self) -> Result<u64, YahooError> {
+        Ok(self.entries()?.iter().map(|(_, size, _)| size).sum())
+    }
+
+    /// Remove the least recently written responses until the cache fits its budget. Returns
+    /// the number of removed responses.
+    pub fn evict(&self) -> Result<usize, YahooError> {
+        let Some(budget) = self.budget else {
+            return Ok(0);
+        };
+        let entries = self.entries()?;
+        let mut size: u64 = entries.iter().map(|(_, size, _)| size).sum();
+        let mut removed = 0;
+        for (path, file_size, _) in entries {
+            if size <= budget {
+                break;
+            }
+            match fs::remove_file(path) {
+                Ok(()) => removed += 1,
+                // already removed by another process sharing the cache
+                Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+                Err(e) => return Err(YahooError::CacheFailed(e)),
+            }
+            size -= file_size;
+        }
+        Ok(removed)
+    }
+
+    /// Remove all responses
+    pub fn clear(&self) -> Result<(), YahooError> {
+        for (path, _, _) in self.entries()? {
+            fs::remove_file(path).map_err(YahooError::CacheFailed)?;
+        }
+        Ok(())
+    }
+}
+
+/// FNV-1a hash, which unlike the hasher of the standard library is stable across releases
+fn fnv1a(key: &str) -> u64 {
+    key.bytes().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+/// Key of a chart request, or `None` if the response may still change, i.e. unless a period
+/// ending at least a day before `now` (unix timestamp) has been requested
+pub(crate) fn chart_key(ticker: &str, params: &[(&str, &str)], now: i64) -> Option<String> {
+    let (_, end) = params.iter().find(|(key, _)| *key == "period2")?;
+    if end.parse::<i64>().ok()? > now - MIN_AGE_SECONDS {
+        return None;
+    }
+    let mut params = params.to_vec();
+    params.sort();
+    let params: Vec<String> = params.iter().map(|(k, v)| format!("{k}={v}")).collect();
+    Some(format!("chart-{ticker}-{}", params.join("&")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_response_cache() {
+        let dir = std::env::temp_dir().join(format!("yahoo_cache_test_{}", std::process::id()));
+        let cache = ResponseCache::open(&dir).unwrap();
+        cache.clear().unwrap();
+        let json = serde_json::json!({"chart": {"result": [{"timestamp": [1, 2, 3]}]}});
+        assert_eq!(cache.get("chart-^GSPC").unwrap(), None);
+        cache.put("chart-^GSPC", &json).unwrap();
+        cache.put("chart-_GSPC", &serde_json::json!({})).unwrap();
+        assert_eq!(cache.get("chart-^GSPC").unwrap(), Some(json.clone()));
+        assert_eq!(
+            cache.get("chart-_GSPC").unwrap(),
+            Some(serde_json::json!({}))
+        );
+
+        // a budget below the size of two responses evicts the older one
+        let size = cache.size().unwrap();
+        let cache = cache.budget(size - 1);
+        assert_eq!(cache.evict().unwrap(), 1);
+        assert!(cache.size().unwrap() < size);
+        cache.clear().unwrap();
+        assert_eq!(cache.size().unwrap(), 0);
+        fs::remove_dir(dir).unwrap();
+    }
+
+    #[test]
+    fn test_chart_key() {
+        let params = [
+            ("symbol", "AAPL"),
+            ("period2", "1706745600"),
+            ("period1", "1704067200"),
+            ("interval", "1d"),
+        ];
+        assert_eq!(
+            chart_key("AAPL", &params, 1706745600 + 86400).as_deref(),
+            Some("chart-AAPL-interval=1d&period1=1704067200&period2=1706745600&symbol=AAPL")
+        );
+        assert_eq!(chart_key("AAPL", &params, 1706745600 + 3600), None);
+        assert_eq!(
+            chart_key("AAPL", &[("symbol", "AAPL"), ("range", "1mo")], 1706745600),
+            None
+        );
+    }
+}
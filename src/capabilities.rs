@@ -0,0 +1,85 @@
+//! Data available for a symbol, as derived from the meta data of a chart response.
+use time::OffsetDateTime;
+
+use super::{AssetClass, InternedStr, YMetaData};
+
+/// Ranges, intervals and sessions supported by the chart endpoint for a symbol
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SymbolCapabilities {
+    pub symbol: String,
+    pub asset_class: AssetClass,
+    /// Ranges accepted by the chart endpoint, e.g. `5d` or `max`
+    pub valid_ranges: Vec<InternedStr>,
+    /// Finest interval quotes are available for. Note that yahoo! finance returns intraday
+    /// quotes for recent periods only, e.g. quotes of one minute for the last seven days.
+    pub min_interval: &'static str,
+    /// Whether quotes of pre and post market sessions are available, see `HistoryQuery::prepost`
+    pub prepost: bool,
+    pub first_trade_date: Option<OffsetDateTime>,
+}
+
+impl SymbolCapabilities {
+    pub fn supports_range(&self, range: &str) -> bool {
+        self.valid_ranges.iter().any(|r| r == range)
+    }
+}
+
+impl YMetaData {
+    /// Return the capabilities of the symbol, which are the same for all chart responses
+    pub fn capabilities(&self) -> SymbolCapabilities {
+        let asset_class = self.asset_class();
+        // mutual funds are priced once a day, all other symbols are quoted intraday
+        let min_interval = if asset_class == AssetClass::MutualFund
+            || !self.valid_ranges.iter().any(|r| r == "1d")
+        {
+            "1d"
+        } else {
+            "1m"
+        };
+        // symbols traded around the clock report empty pre and post market sessions
+        let prepost = self.current_trading_period.as_ref().is_some_and(|period| {
+            period.pre.start < period.pre.end || period.post.start < period.post.end
+        });
+        SymbolCapabilities {
+            symbol: self.symbol.clone(),
+            asset_class,
+            valid_ranges: self.valid_ranges.clone(),
+            min_interval,
+            prepost,
+            first_trade_date: self.first_trade_date.and_then(|date| date.to_datetime()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::chart_fixture;
+
+    fn capabilities(name: &str) -> SymbolCapabilities {
+        chart_fixture(name)
+            .unwrap()
+            .response()
+            .unwrap()
+            .metadata()
+            .unwrap()
+            .capabilities()
+    }
+
+    #[test]
+    fn test_capabilities() {
+        let aapl = capabilities("equity_aapl_1d");
+        assert_eq!(aapl.min_interval, "1m");
+        assert!(aapl.prepost);
+        assert!(aapl.supports_range("max"));
+        assert!(!aapl.supports_range("3d"));
+        assert!(aapl.first_trade_date.is_some());
+
+        let fund = capabilities("mutual_fund_amagx_1d");
+        assert_eq!(fund.min_interval, "1d");
+
+        let bitcoin = capabilities("crypto_btcusd_1d");
+        assert_eq!(bitcoin.min_interval, "1m");
+        assert!(!bitcoin.prepost);
+    }
+}
@@ -0,0 +1,128 @@
+//! Circuit breaker stopping requests to a symbol or endpoint which failed repeatedly.
+//!
+//! Each endpoint and symbol (e.g. `chart/AAPL`) has its own circuit. After `threshold`
+//! consecutive failures, the circuit opens and requests fail immediately with
+//! `YahooError::CircuitOpen` until the cooldown has passed. Then a single trial request is
+//! let through: if it succeeds, the circuit closes, otherwise it opens for another cooldown.
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use super::{Clock, SystemClock, YahooError};
+
+#[derive(Debug, Default)]
+struct Circuit {
+    failures: u32,
+    open_until: Option<Instant>,
+}
+
+pub struct CircuitBreaker {
+    threshold: u32,
+    cooldown: Duration,
+    circuits: Mutex<HashMap<String, Circuit>>,
+    clock: Arc<dyn Clock>,
+}
+
+impl std::fmt::Debug for CircuitBreaker {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CircuitBreaker")
+            .field("threshold", &self.threshold)
+            .field("cooldown", &self.cooldown)
+            .field("circuits", &self.circuits)
+            .finish_non_exhaustive()
+    }
+}
+
+impl CircuitBreaker {
+    /// Open a circuit after `threshold` consecutive failures for the given cooldown
+    pub fn new(threshold: u32, cooldown: Duration) -> CircuitBreaker {
+        CircuitBreaker::with_clock(threshold, cooldown, Arc::new(SystemClock))
+    }
+
+    /// Circuit breaker measuring the cooldown by the given clock, see `MockClock`
+    pub fn with_clock(threshold: u32, cooldown: Duration, clock: Arc<dyn Clock>) -> CircuitBreaker {
+        CircuitBreaker {
+            threshold: threshold.max(1),
+            cooldown,
+            circuits: Mutex::new(HashMap::new()),
+            clock,
+        }
+    }
+
+    /// Return whether the circuit with the given key (e.g. `chart/AAPL`) is open
+    pub fn is_open(&self, key: &str) -> bool {
+        self.circuits
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(key)
+            .and_then(|circuit| circuit.open_until)
+            .is_some_and(|until| self.clock.now() < until)
+    }
+
+    /// Fail if the circuit is open; after the cooldown, one trial request is let through
+    pub(crate) fn check(&self, key: &str) -> Result<(), YahooError> {
+        let mut circuits = self.circuits.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(circuit) = circuits.get_mut(key) {
+            match circuit.open_until {
+                Some(until) if self.clock.now() < until => {
+                    return Err(YahooError::CircuitOpen(key.to_string()))
+                }
+                // half open: keep the circuit open for other requests during the trial
+                Some(_) => circuit.open_until = Some(self.clock.now() + self.cooldown),
+                None => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Record the outcome of a request
+    pub(crate) fn record(&self, key: &str, success: bool) {
+        let mut circuits = self.circuits.lock().unwrap_or_else(|e| e.into_inner());
+        if success {
+            circuits.remove(key);
+            return;
+        }
+        let circuit = circuits.entry(key.to_string()).or_default();
+        circuit.failures += 1;
+        if circuit.failures >= self.threshold {
+            circuit.open_until = Some(self.clock.now() + self.cooldown);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MockClock;
+
+    #[test]
+    fn test_circuit_breaker() {
+        let clock = Arc::new(MockClock::new());
+        let breaker = CircuitBreaker::with_clock(2, Duration::from_secs(20), clock.clone());
+        let key = "chart/XXXXXX";
+        breaker.record(key, false);
+        assert!(breaker.check(key).is_ok());
+        breaker.record(key, false);
+        assert!(matches!(
+            breaker.check(key),
+            Err(YahooError::CircuitOpen(k)) if k == key
+        ));
+        // other symbols are not affected
+        assert!(breaker.check("chart/AAPL").is_ok());
+
+        clock.advance(Duration::from_secs(25));
+        // single trial after the cooldown
+        assert!(breaker.check(key).is_ok());
+        assert!(breaker.check(key).is_err());
+        breaker.record(key, false);
+        assert!(breaker.is_open(key));
+
+        clock.advance(Duration::from_secs(25));
+        assert!(breaker.check(key).is_ok());
+        breaker.record(key, true);
+        assert!(!breaker.is_open(key));
+        assert!(breaker.check(key).is_ok());
+    }
+}
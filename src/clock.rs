@@ -0,0 +1,130 @@
+//! Source of time for the rate limit, the backoff of credential retries, the expiry of
+//! credentials and the circuit breaker.
+//!
+//! Connectors use the system clock by default. Tests can set a `MockClock` instead, which
+//! only moves when advanced explicitly or slept on, so timing dependent behavior can be tested
+//! deterministically and without waiting:
+//!
+//! ```rust,ignore
+//! let clock = Arc::new(MockClock::new());
+//! let provider = YahooConnector::builder()
+//!     .clock(clock.clone())
+//!     .circuit_breaker(3, Duration::from_secs(60))
+//!     .build()?;
+//! // ... let requests fail until the circuit opens
+//! clock.advance(Duration::from_secs(61));
+//! ```
+#[cfg(not(feature = "blocking"))]
+use std::{future::Future, pin::Pin};
+use std::{
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+/// Future returned by `Clock::sleep`
+#[cfg(not(feature = "blocking"))]
+pub type Sleep = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+/// Monotonic clock which can be waited on
+pub trait Clock: Send + Sync {
+    /// Current point in time
+    fn now(&self) -> Instant;
+
+    /// Wait until the given duration has passed on this clock
+    #[cfg(not(feature = "blocking"))]
+    fn sleep(&self, duration: Duration) -> Sleep;
+
+    /// Wait until the given duration has passed on this clock
+    #[cfg(feature = "blocking")]
+    fn sleep(&self, duration: Duration);
+}
+
+/// Clock of the operating system, waiting by tokio timers (or by blocking the thread with the
+/// `blocking` feature)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    #[cfg(not(feature = "blocking"))]
+    fn sleep(&self, duration: Duration) -> Sleep {
+        Box::pin(tokio::time::sleep(duration))
+    }
+
+    #[cfg(feature = "blocking")]
+    fn sleep(&self, duration: Duration) {
+        std::thread::sleep(duration);
+    }
+}
+
+/// Clock for tests, standing still until advanced. Sleeping advances the clock by the given
+/// duration and returns immediately.
+#[derive(Debug)]
+pub struct MockClock {
+    start: Instant,
+    elapsed: Mutex<Duration>,
+}
+
+impl Default for MockClock {
+    fn default() -> Self {
+        MockClock {
+            start: Instant::now(),
+            elapsed: Mutex::new(Duration::ZERO),
+        }
+    }
+}
+
+impl MockClock {
+    pub fn new() -> MockClock {
+        MockClock::default()
+    }
+
+    /// Move the clock forward
+    pub fn advance(&self, duration: Duration) {
+        *self.elapsed.lock().unwrap_or_else(|e| e.into_inner()) += duration;
+    }
+
+    /// Time the clock has been advanced by since it was created
+    pub fn elapsed(&self) -> Duration {
+        *self.elapsed.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Clock for MockClock {
+    fn now(&self) -> Instant {
+        self.start + self.elapsed()
+    }
+
+    #[cfg(not(feature = "blocking"))]
+    fn sleep(&self, duration: Duration) -> Sleep {
+        self.advance(duration);
+        Box::pin(std::future::ready(()))
+    }
+
+    #[cfg(feature = "blocking")]
+    fn sleep(&self, duration: Duration) {
+        self.advance(duration);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mock_clock() {
+        let clock = MockClock::new();
+        let start = clock.now();
+        assert_eq!(clock.now(), start);
+        clock.advance(Duration::from_secs(60));
+        #[cfg(not(feature = "blocking"))]
+        tokio_test::block_on(clock.sleep(Duration::from_secs(3600)));
+        #[cfg(feature = "blocking")]
+        clock.sleep(Duration::from_secs(3600));
+        assert_eq!(clock.elapsed(), Duration::from_secs(3660));
+        assert_eq!(clock.now() - start, Duration::from_secs(3660));
+    }
+}
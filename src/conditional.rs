@@ -0,0 +1,100 @@
+//! Conditional requests, to poll the same data repeatedly without processing unchanged responses.
+//!
+//! yahoo! finance does not send `ETag` or `Last-Modified` headers for most endpoints, so the
+//! content of a response is identified by a hash of its body as well. Validators sent by
+//! yahoo! finance are passed on with the next request, allowing the server to answer with
+//! `304 Not Modified`.
+use std::{
+    collections::hash_map::DefaultHasher,
+    hash::{Hash, Hasher},
+};
+
+use reqwest::header::{HeaderMap, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED};
+
+use super::RequestBuilder;
+
+/// Identifies the content of a response. Tags are only valid within the running process,
+/// since the hash function may change with the version of Rust.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResponseTag {
+    etag: Option<String>,
+    last_modified: Option<String>,
+    hash: u64,
+}
+
+impl ResponseTag {
+    pub(crate) fn new(headers: &HeaderMap, body: &[u8]) -> ResponseTag {
+        let header = |name| {
+            headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string)
+        };
+        let mut hasher = DefaultHasher::new();
+        body.hash(&mut hasher);
+        ResponseTag {
+            etag: header(ETAG),
+            last_modified: header(LAST_MODIFIED),
+            hash: hasher.finish(),
+        }
+    }
+
+    /// Add the validators sent by yahoo! finance, if any, to a request
+    pub(crate) fn apply(&self, mut request: RequestBuilder) -> RequestBuilder {
+        if let Some(etag) = &self.etag {
+            request = request.header(IF_NONE_MATCH, etag);
+        }
+        if let Some(last_modified) = &self.last_modified {
+            request = request.header(IF_MODIFIED_SINCE, last_modified);
+        }
+        request
+    }
+
+    /// Return whether both tags identify the same content
+    pub fn matches(&self, other: &ResponseTag) -> bool {
+        self.hash == other.hash
+    }
+}
+
+/// Result of a conditional request
+#[derive(Debug)]
+pub enum Conditional<T> {
+    /// The content changed, the tag identifies the new content
+    Modified(T, ResponseTag),
+    /// The content is the same as identified by the tag given with the request
+    NotModified,
+}
+
+impl<T> Conditional<T> {
+    pub fn is_modified(&self) -> bool {
+        matches!(self, Conditional::Modified(..))
+    }
+
+    /// Return the new content, if modified
+    pub fn modified(self) -> Option<T> {
+        match self {
+            Conditional::Modified(content, _) => Some(content),
+            Conditional::NotModified => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use reqwest::header::HeaderValue;
+
+    use super::*;
+
+    #[test]
+    fn test_response_tag() {
+        let mut headers = HeaderMap::new();
+        let tag = ResponseTag::new(&headers, br#"{"chart": {}}"#);
+        assert_eq!(tag.etag, None);
+        assert!(tag.matches(&ResponseTag::new(&headers, br#"{"chart": {}}"#)));
+        assert!(!tag.matches(&ResponseTag::new(&headers, br#"{"chart": []}"#)));
+
+        headers.insert(ETAG, HeaderValue::from_static("\"abc\""));
+        let tag = ResponseTag::new(&headers, b"{}");
+        assert_eq!(tag.etag.as_deref(), Some("\"abc\""));
+    }
+}
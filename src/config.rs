@@ -0,0 +1,197 @@
+//! Configuration of a connector as plain data, read from a JSON file by `Config::load` and
+//! turned into a connector by `YahooConnector::from_config`. Since `Config` implements
+//! `Deserialize`, services using other formats (e.g. TOML or YAML) deserialize it from their
+//! own configuration files themselves.
+//!
+//! Durations are given in (fractional) seconds and must be positive. Unknown fields are
+//! rejected, so misspelled settings are not silently ignored.
+//!
+//! ```json
+//! {
+//!     "timeout_secs": 10,
+//!     "endpoint_timeouts": { "auth_secs": 2, "chart_secs": 60 },
+//!     "proxy": "http://proxy.example.com:8080",
+//!     "default_exchange_suffix": ".DE",
+//!     "rate_limit": { "requests": 5, "period_secs": 1 }
+//! }
+//! ```
+use std::fs;
+use std::path::Path;
+#[cfg(feature = "cache")]
+use std::path::PathBuf;
+use std::time::Duration;
+
+use serde::Deserialize;
+
+use super::{
+    AuthMode, EndpointTimeouts, UserAgentRotation, YahooConnector, YahooConnectorBuilder,
+    YahooError,
+};
+
+/// Settings of a connector; settings not given keep the defaults of `YahooConnector::builder`
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Timeout of each request
+    pub timeout_secs: Option<f64>,
+    /// Timeouts of particular endpoints, overriding `timeout_secs`
+    pub endpoint_timeouts: EndpointTimeoutsConfig,
+    /// User agent of all requests, unless a pool of user agents is given
+    pub user_agent: Option<String>,
+    /// User agents sent in rotation, see `YahooConnectorBuilder::user_agent_pool`
+    pub user_agents: Vec<String>,
+    pub user_agent_rotation: UserAgentRotation,
+    /// Proxy of all requests, see `YahooConnectorBuilder::proxy`
+    pub proxy: Option<String>,
+    pub ignore_proxy_env: bool,
+    pub auth_mode: AuthMode,
+    pub rate_limit: Option<RateLimitConfig>,
+    pub circuit_breaker: Option<CircuitBreakerConfig>,
+    /// Suffix of the preferred exchange, e.g. `.DE`
+    pub default_exchange_suffix: Option<String>,
+    /// Directory of the response cache, see `ResponseCache`
+    #[cfg(feature = "cache")]
+    pub cache_dir: Option<PathBuf>,
+}
+
+/// Settings of `YahooConnectorBuilder::endpoint_timeouts`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct EndpointTimeoutsConfig {
+    pub chart_secs: Option<f64>,
+    pub search_secs: Option<f64>,
+    pub quote_summary_secs: Option<f64>,
+    pub auth_secs: Option<f64>,
+}
+
+/// Settings of `YahooConnectorBuilder::rate_limit`
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RateLimitConfig {
+    pub requests: u32,
+    pub period_secs: f64,
+}
+
+/// Settings of `YahooConnectorBuilder::circuit_breaker`
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CircuitBreakerConfig {
+    pub threshold: u32,
+    pub cooldown_secs: f64,
+}
+
+fn duration(name: &str, secs: f64) -> Result<Duration, YahooError> {
+    match Duration::try_from_secs_f64(secs) {
+        Ok(duration) if !duration.is_zero() => Ok(duration),
+        _ => Err(YahooError::InvalidConfig(format!(
+            "{name} must be a positive number"
+        ))),
+    }
+}
+
+impl Config {
+    /// Read the configuration from a JSON file
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Config, YahooError> {
+        let json = fs::read(path).map_err(YahooError::ReadFailed)?;
+        serde_json::from_slice(&json).map_err(|e| YahooError::InvalidConfig(e.to_string()))
+    }
+
+    /// Builder of a connector with these settings, e.g. to add an observer before building it
+    pub fn builder(&self) -> Result<YahooConnectorBuilder, YahooError> {
+        let mut builder = YahooConnector::builder()
+            .ignore_proxy_env(self.ignore_proxy_env)
+            .auth_mode(self.auth_mode)
+            .user_agent_pool(self.user_agents.clone())
+            .user_agent_rotation(self.user_agent_rotation);
+        if let Some(secs) = self.timeout_secs {
+            builder = builder.timeout(duration("timeout_secs", secs)?);
+        }
+        let timeouts = &self.endpoint_timeouts;
+        let timeout = |name, secs: Option<f64>| secs.map(|secs| duration(name, secs)).transpose();
+        builder = builder.endpoint_timeouts(EndpointTimeouts {
+            chart: timeout("endpoint_timeouts.chart_secs", timeouts.chart_secs)?,
+            search: timeout("endpoint_timeouts.search_secs", timeouts.search_secs)?,
+            quote_summary: timeout(
+                "endpoint_timeouts.quote_summary_secs",
+                timeouts.quote_summary_secs,
+            )?,
+            auth: timeout("endpoint_timeouts.auth_secs", timeouts.auth_secs)?,
+        });
+        if let Some(proxy) = &self.proxy {
+            builder = builder.proxy(proxy);
+        }
+        if let Some(limit) = &self.rate_limit {
+            let period = duration("rate_limit.period_secs", limit.period_secs)?;
+            builder = builder.rate_limit(limit.requests, period);
+        }
+        if let Some(breaker) = &self.circuit_breaker {
+            let cooldown = duration("circuit_breaker.cooldown_secs", breaker.cooldown_secs)?;
+            builder = builder.circuit_breaker(breaker.threshold, cooldown);
+        }
+        if let Some(suffix) = &self.default_exchange_suffix {
+            builder = builder.default_exchange_suffix(suffix);
+        }
+        #[cfg(feature = "cache")]
+        if let Some(dir) = &self.cache_dir {
+            builder = builder.cache(super::ResponseCache::open(dir)?);
+        }
+        Ok(builder)
+    }
+}
+
+impl YahooConnector {
+    /// Build a connector with the given settings
+    pub fn from_config(config: &Config) -> Result<YahooConnector, YahooError> {
+        let builder = config.builder()?;
+        match &config.user_agent {
+            Some(user_agent) => builder.build_with_agent(user_agent),
+            None => builder.build(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config() {
+        let json = serde_json::json!({
+            "timeout_secs": 2.5,
+            "auth_mode": "none",
+            "user_agent_rotation": "per_session",
+            "endpoint_timeouts": {"auth_secs": 2},
+            "rate_limit": {"requests": 5, "period_secs": 1},
+            "default_exchange_suffix": ".DE"
+        });
+        let config: Config = serde_json::from_value(json).unwrap();
+        assert_eq!(config.auth_mode, AuthMode::None);
+        assert_eq!(config.user_agent_rotation, UserAgentRotation::PerSession);
+        assert_eq!(config.endpoint_timeouts.auth_secs, Some(2.0));
+        assert_eq!(
+            config.rate_limit,
+            Some(RateLimitConfig {
+                requests: 5,
+                period_secs: 1.0
+            })
+        );
+        assert!(YahooConnector::from_config(&config).is_ok());
+
+        let path = std::env::temp_dir().join(format!("config_{}.json", std::process::id()));
+        fs::write(&path, r#"{"timeout": 10}"#).unwrap();
+        let result = Config::load(&path);
+        fs::remove_file(&path).unwrap();
+        assert!(matches!(result, Err(YahooError::InvalidConfig(_))));
+
+        for secs in [-1.0, 0.0, f64::NAN] {
+            let config = Config {
+                timeout_secs: Some(secs),
+                ..Config::default()
+            };
+            assert!(matches!(
+                YahooConnector::from_config(&config),
+                Err(YahooError::InvalidConfig(_))
+            ));
+        }
+    }
+}
@@ -0,0 +1,274 @@
+//! Ranges of calendar dates resolved in the time zone of the exchange of a symbol.
+//!
+//! yahoo! finance returns the bars whose timestamps lie between two UNIX timestamps. Passing
+//! midnight UTC as boundaries includes or drops the first or last day for exchanges far from
+//! UTC, e.g. a daily bar of Tokyo is time stamped 00:00 UTC. A `DateRange` instead takes plain
+//! dates and computes the boundaries from the offset of the exchange on each date, including
+//! daylight saving time.
+//!
+//! Offsets are derived from the current offset of the meta data and the daylight saving time
+//! rules of the region of the exchange time zone (United States and Canada, Europe,
+//! south-eastern Australia); other time zones are assumed to have a constant offset.
+//! Transitions are applied at the start of the day, which only matters on Sundays.
+use time::{Date, Duration, Month, OffsetDateTime, Time, UtcOffset};
+
+use super::{YMetaData, YahooError};
+
+/// Time of day of the boundaries of a `DateRange`, in the time zone of the exchange
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum At {
+    /// From the start of regular trading on the first date until the start of regular trading
+    /// on the last date (inclusive)
+    MarketOpen,
+    /// From the end of regular trading on the first date until the end of regular trading on
+    /// the last date (inclusive)
+    MarketClose,
+    /// From midnight before the first date until midnight after the last date, i.e. all bars
+    /// dated within the range in exchange-local terms
+    #[default]
+    ExchangeLocal,
+}
+
+/// Dates from `start` to `end` (inclusive) with the policy for the time of day of the
+/// boundaries
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DateRange {
+    pub start: Date,
+    pub end: Date,
+    pub at: At,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DstRule {
+    None,
+    /// Second Sunday of March until first Sunday of November
+    NorthAmerica,
+    /// Last Sunday of March until last Sunday of October
+    Europe,
+    /// First Sunday of October until first Sunday of April
+    SouthEastAustralia,
+}
+
+impl DstRule {
+    fn for_timezone(name: &str) -> DstRule {
+        const NO_DST: &[&str] = &[
+            "America/Sao_Paulo",
+            "America/Mexico_City",
+            "America/Argentina/Buenos_Aires",
+            "America/Bogota",
+            "America/Lima",
+            "America/Santiago",
+            "America/Caracas",
+            "America/Phoenix",
+            "America/Regina",
+            "Europe/Moscow",
+            "Europe/Istanbul",
+            "Europe/Minsk",
+        ];
+        const SOUTH_EAST_AUSTRALIA: &[&str] = &[
+            "Australia/Sydney",
+            "Australia/Melbourne",
+            "Australia/Hobart",
+            "Australia/Canberra",
+            "Australia/Adelaide",
+        ];
+        if NO_DST.contains(&name) {
+            DstRule::None
+        } else if SOUTH_EAST_AUSTRALIA.contains(&name) {
+            DstRule::SouthEastAustralia
+        } else if name.starts_with("America/") || name.starts_with("Canada/") {
+            DstRule::NorthAmerica
+        } else if name.starts_with("Europe/") {
+            DstRule::Europe
+        } else {
+            DstRule::None
+        }
+    }
+
+    /// Return whether daylight saving time is in effect on the date
+    fn is_dst(self, date: Date) -> bool {
+        let year = date.year();
+        match self {
+            DstRule::None => false,
+            DstRule::NorthAmerica => {
+                nth_sunday(year, Month::March, 2) <= date
+                    && date < nth_sunday(year, Month::November, 1)
+            }
+            DstRule::Europe => {
+                last_sunday(year, Month::March) <= date && date < last_sunday(year, Month::October)
+            }
+            DstRule::SouthEastAustralia => {
+                date < nth_sunday(year, Month::April, 1)
+                    || nth_sunday(year, Month::October, 1) <= date
+            }
+        }
+    }
+}
+
+fn nth_sunday(year: i32, month: Month, n: u8) -> Date {
+    let first = Date::from_calendar_date(year, month, 1).expect("valid date");
+    let days = (7 - first.weekday().number_days_from_sunday()) % 7;
+    first + Duration::days(days as i64 + 7 * (n as i64 - 1))
+}
+
+fn last_sunday(year: i32, month: Month) -> Date {
+    let last = Date::from_calendar_date(year, month, time::util::days_in_year_month(year, month))
+        .expect("valid date");
+    last - Duration::days(last.weekday().number_days_from_sunday() as i64)
+}
+
+/// Offsets of the exchange of a symbol over time
+struct ExchangeTimezone {
+    standard_offset: i32,
+    rule: DstRule,
+}
+
+impl ExchangeTimezone {
+    fn from_meta(meta: &YMetaData) -> Result<ExchangeTimezone, YahooError> {
+        let rule = DstRule::for_timezone(&meta.exchange_timezone_name);
+        let reference = meta
+            .current_trading_period
+            .as_ref()
+            .map(|period| period.regular.start)
+            .or_else(|| meta.regular_market_time.map(|time| time.seconds()))
+            .ok_or(YahooError::DataInconsistency)?;
+        let reference = OffsetDateTime::from_unix_timestamp(reference + meta.gmtoffset as i64)
+            .map_err(|_| YahooError::DataInconsistency)?;
+        let dst = if rule.is_dst(reference.date()) {
+            3600
+        } else {
+            0
+        };
+        Ok(ExchangeTimezone {
+            standard_offset: meta.gmtoffset - dst,
+            rule,
+        })
+    }
+
+    fn offset_on(&self, date: Date) -> Result<UtcOffset, YahooError> {
+        let dst = if self.rule.is_dst(date) { 3600 } else { 0 };
+        UtcOffset::from_whole_seconds(self.standard_offset + dst)
+            .map_err(|_| YahooError::DataInconsistency)
+    }
+
+    fn timestamp(&self, date: Date, time: Time) -> Result<i64, YahooError> {
+        Ok(date
+            .with_time(time)
+            .assume_offset(self.offset_on(date)?)
+            .unix_timestamp())
+    }
+}
+
+impl DateRange {
+    /// Range of the given dates (inclusive), with boundaries at midnight in the time zone of
+    /// the exchange
+    pub fn new(start: Date, end: Date) -> DateRange {
+        DateRange {
+            start,
+            end,
+            at: At::default(),
+        }
+    }
+
+    pub fn at(mut self, at: At) -> Self {
+        self.at = at;
+        self
+    }
+
+    /// UNIX timestamps of the start (inclusive) and end (exclusive) of the range in the time
+    /// zone of the exchange described by the meta data. Market open and close are taken from
+    /// the current trading period of the meta data.
+    pub fn bounds(&self, meta: &YMetaData) -> Result<(i64, i64), YahooError> {
+        let timezone = ExchangeTimezone::from_meta(meta)?;
+        let local_time = |timestamp: i64| -> Result<Time, YahooError> {
+            let seconds = (timestamp + meta.gmtoffset as i64).rem_euclid(86400);
+            Time::from_hms((seconds / 3600) as u8, (seconds / 60 % 60) as u8, 0)
+                .map_err(|_| YahooError::DataInconsistency)
+        };
+        match self.at {
+            At::ExchangeLocal => Ok((
+                timezone.timestamp(self.start, Time::MIDNIGHT)?,
+                timezone.timestamp(
+                    self.end.next_day().ok_or(YahooError::DataInconsistency)?,
+                    Time::MIDNIGHT,
+                )?,
+            )),
+            At::MarketOpen | At::MarketClose => {
+                let regular = &meta
+                    .current_trading_period
+                    .as_ref()
+                    .ok_or(YahooError::DataInconsistency)?
+                    .regular;
+                let time = local_time(if self.at == At::MarketOpen {
+                    regular.start
+                } else {
+                    regular.end
+                })?;
+                Ok((
+                    timezone.timestamp(self.start, time)?,
+                    timezone.timestamp(self.end, time)? + 1,
+                ))
+            }
+        }
+    }
+
+    /// Start and end of the range as times for requesting histories, see `DateRange::bounds`
+    pub(crate) fn period(
+        &self,
+        meta: &YMetaData,
+    ) -> Result<(OffsetDateTime, OffsetDateTime), YahooError> {
+        let (start, end) = self.bounds(meta)?;
+        let to_datetime = |timestamp| {
+            OffsetDateTime::from_unix_timestamp(timestamp)
+                .map_err(|_| YahooError::DataInconsistency)
+        };
+        Ok((to_datetime(start)?, to_datetime(end)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::date;
+
+    use super::*;
+    use crate::testing::chart_fixture;
+
+    #[test]
+    fn test_dst_rules() {
+        assert_eq!(nth_sunday(2024, Month::March, 2), date!(2024 - 03 - 10));
+        assert_eq!(nth_sunday(2024, Month::November, 1), date!(2024 - 11 - 03));
+        assert_eq!(last_sunday(2024, Month::October), date!(2024 - 10 - 27));
+        assert!(DstRule::Europe.is_dst(date!(2024 - 07 - 01)));
+        assert!(!DstRule::Europe.is_dst(date!(2024 - 03 - 29)));
+        assert!(DstRule::SouthEastAustralia.is_dst(date!(2024 - 01 - 15)));
+        assert!(!DstRule::SouthEastAustralia.is_dst(date!(2024 - 06 - 15)));
+        assert_eq!(
+            DstRule::for_timezone("America/New_York"),
+            DstRule::NorthAmerica
+        );
+        assert_eq!(DstRule::for_timezone("Asia/Tokyo"), DstRule::None);
+    }
+
+    #[test]
+    fn test_date_range_bounds() {
+        // AAPL in February 2024, i.e. during standard time (UTC-5)
+        let meta = chart_fixture("equity_aapl_1d")
+            .unwrap()
+            .response()
+            .unwrap()
+            .metadata()
+            .unwrap();
+        let range = DateRange::new(date!(2024 - 03 - 08), date!(2024 - 03 - 11));
+        let (start, end) = range.bounds(&meta).unwrap();
+        // midnight EST before the first date, midnight EDT after the last date
+        assert_eq!(start, 1709874000);
+        assert_eq!(end, 1710216000);
+
+        // the open at 9:30 local time is 14:30 UTC in winter and 13:30 UTC in summer
+        let (start, end) = range.at(At::MarketOpen).bounds(&meta).unwrap();
+        assert_eq!(start, 1709908200);
+        assert_eq!(end, 1710163800 + 1);
+        let (start, _) = range.at(At::MarketClose).bounds(&meta).unwrap();
+        assert_eq!(start, 1709931600);
+    }
+}
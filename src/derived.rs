@@ -0,0 +1,242 @@
+//! Values commonly derived from quote series, such as price changes and VWAP.
+use super::quotes::{decimal::from_volume, ZERO};
+use super::{Decimal, Dividend, Quote, YMetaData, YResponse, YahooError};
+
+impl Quote {
+    /// Typical price of the bar, i.e. the average of high, low and close
+    pub fn typical_price(&self) -> Decimal {
+        (self.high + self.low + self.close) / Decimal::from(3)
+    }
+}
+
+/// Volume weighted average of the typical prices of the quotes,
+/// or `None` if the total volume is zero
+pub fn vwap(quotes: &[Quote]) -> Option<Decimal> {
+    let (weighted, volume) = quotes.iter().fold((ZERO, ZERO), |(weighted, volume), q| {
+        let v = from_volume(q.volume);
+        (weighted + q.typical_price() * v, volume + v)
+    });
+    if volume == ZERO {
+        None
+    } else {
+        Some(weighted / volume)
+    }
+}
+
+/// Value of a total return index at the time of a quote
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TotalReturn {
+    pub timestamp: u64,
+    pub value: Decimal,
+}
+
+/// Total return index of the quotes, starting at 100 with the first quote. Dividends are
+/// reinvested at the close of their ex-date, i.e. a dividend adds to the return of the first
+/// quote at or after its ex-date. Quotes with zero close carry the previous value forward.
+pub fn total_return_index(quotes: &[Quote], dividends: &[Dividend]) -> Vec<TotalReturn> {
+    let mut index = Vec::with_capacity(quotes.len());
+    let mut value = Decimal::from(100);
+    let mut previous: Option<&Quote> = None;
+    for quote in quotes {
+        if let Some(prev) = previous {
+            let paid = dividends
+                .iter()
+                .filter(|d| {
+                    let date = d.date.seconds();
+                    prev.timestamp < date as u64 && date as u64 <= quote.timestamp
+                })
+                .fold(ZERO, |sum, d| sum + d.amount);
+            if prev.close != ZERO && quote.close != ZERO {
+                value *= (quote.close + paid) / prev.close;
+            }
+        }
+        if quote.close != ZERO {
+            previous = Some(quote);
+        }
+        index.push(TotalReturn {
+            timestamp: quote.timestamp,
+            value,
+        });
+    }
+    index
+}
+
+impl YResponse {
+    /// Return the total return index of the quotes with dividends reinvested on their
+    /// ex-date, see `total_return_index`. Unlike the close prices, the index is comparable
+    /// between symbols paying dividends and those which do not.
+    pub fn total_return_series(&self) -> Result<Vec<TotalReturn>, YahooError> {
+        Ok(total_return_index(&self.quotes()?, &self.dividends()?))
+    }
+
+    /// Return the close the change of the latest quote is measured against: the previous
+    /// close as reported for a range of one day, otherwise the close before the start of the
+    /// requested range
+    pub fn reference_close(&self) -> Result<Decimal, YahooError> {
+        let meta = &self.result(0)?.meta;
+        meta.previous_close
+            .or(meta.chart_previous_close)
+            .ok_or(YahooError::EmptyDataSet)
+    }
+
+    /// Return the absolute change of the close of the latest quote against the reference close
+    pub fn change(&self) -> Result<Decimal, YahooError> {
+        Ok(self.last_quote()?.close - self.reference_close()?)
+    }
+
+    /// Return the change of the close of the latest quote against the reference close in percent
+    pub fn change_percent(&self) -> Result<Decimal, YahooError> {
+        let reference = self.reference_close()?;
+        if reference == ZERO {
+            return Err(YahooError::DataInconsistency);
+        }
+        Ok((self.last_quote()?.close - reference) / reference * Decimal::from(100))
+    }
+
+    /// Return the volume weighted average price over all quotes of the response,
+    /// e.g. the intraday VWAP if one day of intraday quotes was requested
+    pub fn vwap(&self) -> Result<Decimal, YahooError> {
+        vwap(&self.quotes()?).ok_or(YahooError::EmptyDataSet)
+    }
+}
+
+/// Position of the current price within the range of the last 52 weeks
+#[derive(Debug, Clone, PartialEq)]
+pub struct FiftyTwoWeekStats {
+    pub high: Decimal,
+    pub low: Decimal,
+    pub price: Decimal,
+    /// Distance of the price from the 52-week high in percent, zero or negative
+    pub from_high_percent: Decimal,
+    /// Distance of the price from the 52-week low in percent, zero or positive
+    pub from_low_percent: Decimal,
+    /// The range formatted as on yahoo! finance, e.g. "143.90 - 199.62"
+    pub range: String,
+}
+
+impl YMetaData {
+    /// Return the 52-week statistics of the current price, if reported by yahoo! finance
+    pub fn fifty_two_week_stats(&self) -> Result<FiftyTwoWeekStats, YahooError> {
+        let (high, low, price) = match (
+            self.fifty_two_week_high,
+            self.fifty_two_week_low,
+            self.regular_market_price,
+        ) {
+            (Some(high), Some(low), Some(price)) => (high, low, price),
+            _ => return Err(YahooError::EmptyDataSet),
+        };
+        if high == ZERO || low == ZERO {
+            return Err(YahooError::DataInconsistency);
+        }
+        let hundred = Decimal::from(100);
+        let decimals = self.price_decimals();
+        Ok(FiftyTwoWeekStats {
+            high,
+            low,
+            price,
+            from_high_percent: (price - high) / high * hundred,
+            from_low_percent: (price - low) / low * hundred,
+            range: format!("{low:.decimals$} - {high:.decimals$}"),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::YTimestamp;
+
+    fn quote(timestamp: u64, high: u32, low: u32, close: u32, volume: u64) -> Quote {
+        Quote {
+            timestamp,
+            open: Decimal::from(close),
+            high: Decimal::from(high),
+            low: Decimal::from(low),
+            volume,
+            close: Decimal::from(close),
+            adjclose: Decimal::from(close),
+        }
+    }
+
+    #[test]
+    fn test_typical_price_and_vwap() {
+        let quotes = vec![quote(1, 12, 6, 9, 100), quote(2, 21, 15, 18, 200)];
+        assert_eq!(quotes[0].typical_price(), Decimal::from(9));
+        assert_eq!(vwap(&quotes), Some(Decimal::from(15)));
+        assert_eq!(vwap(&[quote(1, 12, 6, 9, 0)]), None);
+        assert_eq!(vwap(&[]), None);
+    }
+
+    #[test]
+    fn test_total_return_index() {
+        let quotes = vec![
+            quote(1, 8, 8, 8, 0),
+            quote(2, 16, 16, 16, 0),
+            quote(3, 0, 0, 0, 0),
+            quote(4, 8, 8, 8, 0),
+        ];
+        let dividend = Dividend {
+            amount: Decimal::from(8),
+            date: YTimestamp::from_seconds(3),
+        };
+        let index = total_return_index(&quotes, &[dividend]);
+        let values: Vec<Decimal> = index.iter().map(|point| point.value).collect();
+        // the dividend paid with the missing quote is reinvested at the next close
+        assert_eq!(
+            values,
+            [
+                Decimal::from(100),
+                Decimal::from(200),
+                Decimal::from(200),
+                Decimal::from(200)
+            ]
+        );
+        assert_eq!(index[3].timestamp, 4);
+    }
+
+    #[test]
+    fn test_change() {
+        use crate::testing::chart_fixture;
+
+        let mut json = chart_fixture("equity_aapl_1d").unwrap().json_value();
+        let meta = &mut json["chart"]["result"][0]["meta"];
+        meta["previousClose"] = serde_json::Value::Null;
+        meta["chartPreviousClose"] = serde_json::json!(100.0);
+        let response = YResponse::from_json(json).unwrap();
+        let close = response.last_quote().unwrap().close;
+        assert_eq!(response.change().unwrap(), close - Decimal::from(100));
+        let hundred = Decimal::from(100);
+        assert_eq!(
+            response.change_percent().unwrap(),
+            (close - hundred) / hundred * hundred
+        );
+
+        let mut json = chart_fixture("equity_aapl_1d").unwrap().json_value();
+        let meta = &mut json["chart"]["result"][0]["meta"];
+        meta["previousClose"] = serde_json::Value::Null;
+        meta["chartPreviousClose"] = serde_json::Value::Null;
+        let response = YResponse::from_json(json).unwrap();
+        assert!(matches!(response.change(), Err(YahooError::EmptyDataSet)));
+    }
+
+    #[test]
+    fn test_fifty_two_week_stats() {
+        use crate::testing::chart_fixture;
+
+        let response = chart_fixture("equity_aapl_1d").unwrap().response().unwrap();
+        let mut meta = response.metadata().unwrap();
+        meta.fifty_two_week_high = Some(Decimal::from(200));
+        meta.fifty_two_week_low = Some(Decimal::from(150));
+        meta.regular_market_price = Some(Decimal::from(180));
+        let stats = meta.fifty_two_week_stats().unwrap();
+        assert_eq!(stats.from_high_percent, Decimal::from(-10));
+        assert_eq!(stats.from_low_percent, Decimal::from(20));
+        assert_eq!(stats.range, "150.00 - 200.00");
+
+        meta.fifty_two_week_low = None;
+        assert!(matches!(
+            meta.fifty_two_week_stats(),
+            Err(YahooError::EmptyDataSet)
+        ));
+    }
+}
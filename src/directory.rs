@@ -0,0 +1,355 @@
+//! Cache of the reference data of symbols, i.e. the meta data of the chart and the quote type,
+//! detecting changes of currency, exchange, name or type, e.g. after a renaming, relisting or
+//! reclassification. yahoo! finance does not publish a history of such changes, so the
+//! directory records the changes it detects, see `SymbolDirectory::history`.
+//!
+//! ```rust,ignore
+//! let mut directory = SymbolDirectory::new(Duration::from_secs(24 * 3600));
+//! directory.lookup(&connector, "AAPL").await?;
+//! // ... a day later
+//! for change in directory.refresh_changed(&connector).await? {
+//!     println!("{}: {:?} changed from {:?} to {:?}", change.symbol, change.field, change.old, change.new);
+//! }
+//! ```
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use time::OffsetDateTime;
+
+use super::{Clock, Exchange, QuoteType, SystemClock, YMetaData, YahooConnector, YahooError};
+
+/// Reference data of a symbol
+#[derive(Debug, Clone)]
+pub struct SymbolInfo {
+    pub meta: YMetaData,
+    pub quote_type: QuoteType,
+}
+
+impl SymbolInfo {
+    pub fn currency(&self) -> Option<&str> {
+        self.meta.currency.as_deref()
+    }
+
+    pub fn exchange(&self) -> &Exchange {
+        &self.meta.exchange_name
+    }
+
+    pub fn long_name(&self) -> Option<&str> {
+        self.quote_type.long_name.as_deref()
+    }
+
+    /// Retrieve the meta data of the latest daily chart and the quote type of the symbol
+    #[cfg(not(feature = "blocking"))]
+    pub async fn fetch(connector: &YahooConnector, symbol: &str) -> Result<SymbolInfo, YahooError> {
+        Ok(SymbolInfo {
+            meta: connector
+                .get_quote_range(symbol, "1d", "1d")
+                .await?
+                .metadata()?,
+            quote_type: connector.get_quote_type(symbol).await?,
+        })
+    }
+
+    /// Retrieve the meta data of the latest daily chart and the quote type of the symbol
+    #[cfg(feature = "blocking")]
+    pub fn fetch(connector: &YahooConnector, symbol: &str) -> Result<SymbolInfo, YahooError> {
+        Ok(SymbolInfo {
+            meta: connector.get_quote_range(symbol, "1d", "1d")?.metadata()?,
+            quote_type: connector.get_quote_type(symbol)?,
+        })
+    }
+}
+
+/// Field of the reference data watched for changes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ReferenceField {
+    /// Symbol reported for the requested one, which changes if yahoo! finance redirects it to
+    /// a new ticker
+    Symbol,
+    Currency,
+    Exchange,
+    LongName,
+    /// Type of the instrument, e.g. `EQUITY` or `ETF`
+    QuoteType,
+}
+
+/// Change of a field of the reference data of a symbol between two retrievals
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SymbolChange {
+    pub symbol: String,
+    pub field: ReferenceField,
+    pub old: Option<String>,
+    pub new: Option<String>,
+    /// Time the change has been detected, i.e. of the retrieval reporting the new value
+    pub detected_at: OffsetDateTime,
+}
+
+/// Reference data of symbols, each kept until it is older than the maximum age
+pub struct SymbolDirectory {
+    max_age: Duration,
+    clock: Arc<dyn Clock>,
+    entries: HashMap<String, (SymbolInfo, Instant)>,
+    history: Vec<SymbolChange>,
+}
+
+impl fmt::Debug for SymbolDirectory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SymbolDirectory")
+            .field("max_age", &self.max_age)
+            .field("symbols", &self.entries.keys().collect::<Vec<_>>())
+            .field("history", &self.history)
+            .finish()
+    }
+}
+
+impl SymbolDirectory {
+    pub fn new(max_age: Duration) -> SymbolDirectory {
+        SymbolDirectory::with_clock(max_age, Arc::new(SystemClock))
+    }
+
+    /// Directory measuring the age of its entries by the given clock, see `MockClock`
+    pub fn with_clock(max_age: Duration, clock: Arc<dyn Clock>) -> SymbolDirectory {
+        SymbolDirectory {
+            max_age,
+            clock,
+            entries: HashMap::new(),
+            history: Vec::new(),
+        }
+    }
+
+    /// Return the cached reference data of the symbol, even if it is stale
+    pub fn get(&self, symbol: &str) -> Option<&SymbolInfo> {
+        self.entries.get(symbol).map(|(info, _)| info)
+    }
+
+    pub fn symbols(&self) -> Vec<&str> {
+        self.entries.keys().map(|s| s.as_str()).collect()
+    }
+
+    pub fn remove(&mut self, symbol: &str) -> Option<SymbolInfo> {
+        self.entries.remove(symbol).map(|(info, _)| info)
+    }
+
+    /// Changes detected for the symbol since the directory has been created, oldest first
+    pub fn history(&self, symbol: &str) -> Vec<&SymbolChange> {
+        self.history
+            .iter()
+            .filter(|change| change.symbol == symbol)
+            .collect()
+    }
+
+    /// Changes of all symbols detected at or after the given time, oldest first
+    pub fn changes_since(&self, since: OffsetDateTime) -> Vec<&SymbolChange> {
+        self.history
+            .iter()
+            .filter(|change| change.detected_at >= since)
+            .collect()
+    }
+
+    /// Symbols whose reference data is older than the maximum age
+    pub fn stale(&self) -> Vec<&str> {
+        let now = self.clock.now();
+        self.entries
+            .iter()
+            .filter(|(_, (_, fetched))| now.duration_since(*fetched) >= self.max_age)
+            .map(|(symbol, _)| symbol.as_str())
+            .collect()
+    }
+
+    /// Store the reference data of its symbol and return the changes against the data cached
+    /// before, if any
+    pub fn insert(&mut self, info: SymbolInfo) -> Vec<SymbolChange> {
+        let symbol = info.meta.symbol.clone();
+        self.refresh(&symbol, info)
+    }
+
+    fn fresh(&self, symbol: &str) -> Option<&SymbolInfo> {
+        let (info, fetched) = self.entries.get(symbol)?;
+        (self.clock.now().duration_since(*fetched) < self.max_age).then_some(info)
+    }
+
+    /// Return the reference data of the symbol, retrieving it unless a fresh copy is cached
+    #[cfg(not(feature = "blocking"))]
+    pub async fn lookup(
+        &mut self,
+        connector: &YahooConnector,
+        symbol: &str,
+    ) -> Result<&SymbolInfo, YahooError> {
+        if self.fresh(symbol).is_none() {
+            let info = SymbolInfo::fetch(connector, symbol).await?;
+            self.entries
+                .insert(symbol.to_string(), (info, self.clock.now()));
+        }
+        Ok(&self.entries[symbol].0)
+    }
+
+    /// Return the reference data of the symbol, retrieving it unless a fresh copy is cached
+    #[cfg(feature = "blocking")]
+    pub fn lookup(
+        &mut self,
+        connector: &YahooConnector,
+        symbol: &str,
+    ) -> Result<&SymbolInfo, YahooError> {
+        if self.fresh(symbol).is_none() {
+            let info = SymbolInfo::fetch(connector, symbol)?;
+            self.entries
+                .insert(symbol.to_string(), (info, self.clock.now()));
+        }
+        Ok(&self.entries[symbol].0)
+    }
+
+    /// Retrieve the reference data of all stale symbols again and return the changes of their
+    /// reference fields, which are also added to the history
+    #[cfg(not(feature = "blocking"))]
+    pub async fn refresh_changed(
+        &mut self,
+        connector: &YahooConnector,
+    ) -> Result<Vec<SymbolChange>, YahooError> {
+        let stale: Vec<String> = self.stale().into_iter().map(String::from).collect();
+        let mut changes = Vec::new();
+        for symbol in stale {
+            let info = SymbolInfo::fetch(connector, &symbol).await?;
+            changes.extend(self.refresh(&symbol, info));
+        }
+        Ok(changes)
+    }
+
+    /// Retrieve the reference data of all stale symbols again and return the changes of their
+    /// reference fields, which are also added to the history
+    #[cfg(feature = "blocking")]
+    pub fn refresh_changed(
+        &mut self,
+        connector: &YahooConnector,
+    ) -> Result<Vec<SymbolChange>, YahooError> {
+        let stale: Vec<String> = self.stale().into_iter().map(String::from).collect();
+        let mut changes = Vec::new();
+        for symbol in stale {
+            let info = SymbolInfo::fetch(connector, &symbol)?;
+            changes.extend(self.refresh(&symbol, info));
+        }
+        Ok(changes)
+    }
+
+    /// Replace the reference data cached under the symbol, which may differ from the symbol
+    /// of the data, e.g. if yahoo! finance redirects to a new ticker
+    fn refresh(&mut self, symbol: &str, info: SymbolInfo) -> Vec<SymbolChange> {
+        let changes = match self.entries.get(symbol) {
+            Some((old, _)) => changes(symbol, old, &info, OffsetDateTime::now_utc()),
+            None => Vec::new(),
+        };
+        self.history.extend(changes.iter().cloned());
+        self.entries
+            .insert(symbol.to_string(), (info, self.clock.now()));
+        changes
+    }
+}
+
+fn changes(
+    symbol: &str,
+    old: &SymbolInfo,
+    new: &SymbolInfo,
+    detected_at: OffsetDateTime,
+) -> Vec<SymbolChange> {
+    [
+        (
+            ReferenceField::Symbol,
+            Some(old.meta.symbol.clone()),
+            Some(new.meta.symbol.clone()),
+        ),
+        (
+            ReferenceField::Currency,
+            old.currency().map(String::from),
+            new.currency().map(String::from),
+        ),
+        (
+            ReferenceField::Exchange,
+            Some(old.exchange().to_string()),
+            Some(new.exchange().to_string()),
+        ),
+        (
+            ReferenceField::LongName,
+            old.long_name().map(String::from),
+            new.long_name().map(String::from),
+        ),
+        (
+            ReferenceField::QuoteType,
+            Some(old.quote_type.quote_type.to_string()),
+            Some(new.quote_type.quote_type.to_string()),
+        ),
+    ]
+    .into_iter()
+    .filter(|(_, old, new)| old != new)
+    .map(|(field, old, new)| SymbolChange {
+        symbol: symbol.to_string(),
+        field,
+        old,
+        new,
+        detected_at,
+    })
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{chart_fixture, quote_summary_fixture};
+    use crate::MockClock;
+
+    fn info() -> SymbolInfo {
+        SymbolInfo {
+            meta: chart_fixture("equity_aapl_1d")
+                .unwrap()
+                .response()
+                .unwrap()
+                .metadata()
+                .unwrap(),
+            quote_type: quote_summary_fixture("quote_summary_aapl")
+                .unwrap()
+                .quote_summary()
+                .unwrap()
+                .quote_type()
+                .unwrap()
+                .clone(),
+        }
+    }
+
+    #[test]
+    fn test_symbol_directory() {
+        let clock = Arc::new(MockClock::new());
+        let mut directory = SymbolDirectory::with_clock(Duration::from_secs(3600), clock.clone());
+        assert!(directory.insert(info()).is_empty());
+        assert!(directory.get("AAPL").is_some());
+        assert!(directory.stale().is_empty());
+        clock.advance(Duration::from_secs(3600));
+        assert_eq!(directory.stale(), vec!["AAPL"]);
+
+        let mut renamed = info();
+        renamed.quote_type.long_name = Some("Apple Computer, Inc.".to_string());
+        renamed.meta.exchange_name = Exchange::NewYork;
+        let changes = directory.insert(renamed);
+        assert_eq!(changes.len(), 2);
+        assert_eq!(changes[0].field, ReferenceField::Exchange);
+        assert_eq!(changes[0].old.as_deref(), Some("NMS"));
+        assert_eq!(changes[0].new.as_deref(), Some("NYQ"));
+        assert_eq!(changes[1].field, ReferenceField::LongName);
+        assert_eq!(changes[1].new.as_deref(), Some("Apple Computer, Inc."));
+        assert!(directory.stale().is_empty());
+
+        let mut reclassified = info();
+        reclassified.quote_type.quote_type = "ETF".into();
+        let since = OffsetDateTime::now_utc();
+        let changes = directory.insert(reclassified);
+        assert_eq!(changes.len(), 3);
+        assert_eq!(changes[2].field, ReferenceField::QuoteType);
+        assert_eq!(changes[2].old.as_deref(), Some("EQUITY"));
+        let history = directory.history("AAPL");
+        assert_eq!(history.len(), 5);
+        assert!(history
+            .windows(2)
+            .all(|w| w[0].detected_at <= w[1].detected_at));
+        assert_eq!(directory.changes_since(since).len(), 3);
+        assert!(directory.history("MSFT").is_empty());
+    }
+}
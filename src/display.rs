@@ -0,0 +1,289 @@
+//! Formatting of prices, volumes and market capitalizations for display, e.g. "1.2B" or
+//! "3.421,50 €".
+use super::quotes::decimal::{from_volume, round_to};
+use super::{decimal_to_f64, Decimal, Quote, YMetaData, YResponse, YahooError};
+
+/// Separators and placement of currency symbols used to format numbers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NumberFormat {
+    /// Separator of groups of thousands, if any
+    pub thousands: Option<char>,
+    pub decimal: char,
+    /// Whether the currency symbol follows the amount, e.g. "3.421,50 €"
+    pub symbol_after: bool,
+}
+
+impl NumberFormat {
+    /// E.g. "€3,421.50"
+    pub const ENGLISH: NumberFormat = NumberFormat {
+        thousands: Some(','),
+        decimal: '.',
+        symbol_after: false,
+    };
+    /// E.g. "3.421,50 €"
+    pub const GERMAN: NumberFormat = NumberFormat {
+        thousands: Some('.'),
+        decimal: ',',
+        symbol_after: true,
+    };
+    /// E.g. "3 421,50 €"
+    pub const FRENCH: NumberFormat = NumberFormat {
+        thousands: Some('\u{202f}'),
+        decimal: ',',
+        symbol_after: true,
+    };
+    /// E.g. "3421.50", as used in data files
+    pub const PLAIN: NumberFormat = NumberFormat {
+        thousands: None,
+        decimal: '.',
+        symbol_after: false,
+    };
+}
+
+impl Default for NumberFormat {
+    fn default() -> Self {
+        NumberFormat::ENGLISH
+    }
+}
+
+/// Format a number with the given number of decimals, e.g. "3,421.50"
+pub fn format_number(value: Decimal, decimals: usize, format: &NumberFormat) -> String {
+    let formatted = format!("{:.*}", decimals, round_to(value, decimals as u32));
+    let (sign, digits) = match formatted.strip_prefix('-') {
+        Some(digits) => ("-", digits),
+        None => ("", formatted.as_str()),
+    };
+    let (integer, fraction) = match digits.split_once('.') {
+        Some((integer, fraction)) => (integer, Some(fraction)),
+        None => (digits, None),
+    };
+    let mut result = sign.to_string();
+    for (i, digit) in integer.chars().enumerate() {
+        if i > 0 && (integer.len() - i) % 3 == 0 {
+            if let Some(separator) = format.thousands {
+                result.push(separator);
+            }
+        }
+        result.push(digit);
+    }
+    if let Some(fraction) = fraction {
+        result.push(format.decimal);
+        result.push_str(fraction);
+    }
+    result
+}
+
+/// Format a large number with a suffix for thousands (K), millions (M), billions (B) or
+/// trillions (T) and at most two decimals, e.g. "1.2B" as shown on yahoo! finance
+pub fn format_compact(value: Decimal, format: &NumberFormat) -> String {
+    let value = decimal_to_f64(value);
+    let (scaled, suffix) = [(1e12, "T"), (1e9, "B"), (1e6, "M"), (1e3, "K")]
+        .into_iter()
+        .find(|(scale, _)| value.abs() >= *scale)
+        .map_or((value, ""), |(scale, suffix)| (value / scale, suffix));
+    let mut number = format!("{scaled:.2}");
+    if number.contains('.') {
+        number = number
+            .trim_end_matches('0')
+            .trim_end_matches('.')
+            .to_string();
+    }
+    format!(
+        "{}{suffix}",
+        number.replace('.', &format.decimal.to_string())
+    )
+}
+
+/// Symbol of the currency with the given ISO code, or `None` if it is commonly written as code
+pub fn currency_symbol(currency: &str) -> Option<&'static str> {
+    Some(match currency {
+        "USD" => "$",
+        "EUR" => "€",
+        "GBP" => "£",
+        // prices of the London Stock Exchange are quoted in pence
+        "GBp" | "GBX" => "p",
+        "JPY" => "¥",
+        "CNY" => "CN¥",
+        "INR" => "₹",
+        "KRW" => "₩",
+        "ILS" => "₪",
+        "BTC" => "₿",
+        _ => return None,
+    })
+}
+
+/// Format an amount of the given currency, e.g. "€3,421.50" or "3.421,50 €"
+pub fn format_money(
+    value: Decimal,
+    currency: &str,
+    decimals: usize,
+    format: &NumberFormat,
+) -> String {
+    with_currency(format_number(value, decimals, format), currency, format)
+}
+
+fn with_currency(number: String, currency: &str, format: &NumberFormat) -> String {
+    match (currency_symbol(currency), format.symbol_after) {
+        (Some(symbol), false) => match number.strip_prefix('-') {
+            Some(number) => format!("-{symbol}{number}"),
+            None => format!("{symbol}{number}"),
+        },
+        (Some(symbol), true) => format!("{number} {symbol}"),
+        (None, false) => format!("{currency} {number}"),
+        (None, true) => format!("{number} {currency}"),
+    }
+}
+
+impl YMetaData {
+    /// Number of decimals prices of the symbol are shown with on yahoo! finance, as given
+    /// by the price hint (defaults to 2)
+    pub fn price_decimals(&self) -> usize {
+        self.price_hint.unwrap_or(2).max(0) as usize
+    }
+
+    /// Format a price of the symbol with the decimals of the price hint and the currency of
+    /// the symbol, if known
+    pub fn format_price(&self, price: Decimal, format: &NumberFormat) -> String {
+        match &self.currency {
+            Some(currency) => format_money(price, currency, self.price_decimals(), format),
+            None => format_number(price, self.price_decimals(), format),
+        }
+    }
+
+    /// Format a market capitalization (or another large amount) in the currency of the
+    /// symbol, e.g. "$2.87T"
+    pub fn format_market_cap(&self, market_cap: Decimal, format: &NumberFormat) -> String {
+        let number = format_compact(market_cap, format);
+        match &self.currency {
+            Some(currency) => with_currency(number, currency, format),
+            None => number,
+        }
+    }
+}
+
+impl Quote {
+    /// Return the quote with its prices rounded to the decimals yahoo! finance shows for the
+    /// symbol of the meta data, see `YMetaData::price_decimals`
+    pub fn rounded(&self, meta: &YMetaData) -> Quote {
+        let decimals = meta.price_decimals() as u32;
+        Quote {
+            timestamp: self.timestamp,
+            open: round_to(self.open, decimals),
+            high: round_to(self.high, decimals),
+            low: round_to(self.low, decimals),
+            volume: self.volume,
+            close: round_to(self.close, decimals),
+            adjclose: round_to(self.adjclose, decimals),
+        }
+    }
+}
+
+impl YResponse {
+    /// Return the quotes with prices rounded as shown on yahoo! finance, see `Quote::rounded`
+    pub fn quotes_display(&self) -> Result<Vec<Quote>, YahooError> {
+        let meta = &self.result(0)?.meta;
+        Ok(self.quotes()?.iter().map(|q| q.rounded(meta)).collect())
+    }
+}
+
+/// Format a volume, e.g. "52.3M"
+pub fn format_volume(volume: u64, format: &NumberFormat) -> String {
+    format_compact(from_volume(volume), format)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decimal_from_f64;
+
+    fn decimal(value: f64) -> Decimal {
+        decimal_from_f64(value).unwrap()
+    }
+
+    #[test]
+    fn test_format_number() {
+        let value = decimal(3421.5);
+        assert_eq!(format_number(value, 2, &NumberFormat::ENGLISH), "3,421.50");
+        assert_eq!(format_number(value, 2, &NumberFormat::GERMAN), "3.421,50");
+        assert_eq!(format_number(value, 0, &NumberFormat::PLAIN), "3422");
+        assert_eq!(
+            format_number(decimal(-1234567.0), 1, &NumberFormat::ENGLISH),
+            "-1,234,567.0"
+        );
+        assert_eq!(
+            format_number(decimal(0.25), 3, &NumberFormat::ENGLISH),
+            "0.250"
+        );
+    }
+
+    #[test]
+    fn test_round_to() {
+        assert_eq!(round_to(decimal(12.3456), 2), decimal(12.35));
+        assert_eq!(round_to(decimal(-2.5), 0), decimal(-3.0));
+    }
+
+    #[test]
+    fn test_format_compact() {
+        let english = NumberFormat::ENGLISH;
+        assert_eq!(format_compact(decimal(1.2e9), &english), "1.2B");
+        assert_eq!(
+            format_compact(decimal(2_873_000_000_000.0), &english),
+            "2.87T"
+        );
+        assert_eq!(format_compact(decimal(3e6), &english), "3M");
+        assert_eq!(format_compact(decimal(999.0), &english), "999");
+        assert_eq!(
+            format_compact(decimal(-4500.0), &NumberFormat::GERMAN),
+            "-4,5K"
+        );
+        assert_eq!(format_volume(52_300_000, &english), "52.3M");
+    }
+
+    #[test]
+    fn test_format_money() {
+        let value = decimal(3421.5);
+        assert_eq!(
+            format_money(value, "EUR", 2, &NumberFormat::GERMAN),
+            "3.421,50 €"
+        );
+        assert_eq!(
+            format_money(value, "EUR", 2, &NumberFormat::ENGLISH),
+            "€3,421.50"
+        );
+        assert_eq!(
+            format_money(-value, "USD", 2, &NumberFormat::ENGLISH),
+            "-$3,421.50"
+        );
+        assert_eq!(
+            format_money(value, "CHF", 2, &NumberFormat::ENGLISH),
+            "CHF 3,421.50"
+        );
+    }
+
+    #[test]
+    fn test_format_price() {
+        use crate::testing::chart_fixture;
+
+        let response = chart_fixture("equity_aapl_1d").unwrap().response().unwrap();
+        let mut meta = response.metadata().unwrap();
+        meta.price_hint = Some(2);
+        let english = NumberFormat::ENGLISH;
+        assert_eq!(meta.format_price(decimal(185.0), &english), "$185.00");
+        assert_eq!(
+            meta.format_market_cap(decimal(2.87e12), &NumberFormat::GERMAN),
+            "2,87T $"
+        );
+        let quote = response.last_quote().unwrap();
+        let rounded = quote.rounded(&meta);
+        assert_eq!(rounded.close, round_to(quote.close, 2));
+        assert_eq!(rounded.volume, quote.volume);
+        assert_eq!(
+            format_number(rounded.high, 2, &english),
+            format_number(quote.high, 2, &english)
+        );
+
+        meta.price_hint = Some(4);
+        meta.currency = None;
+        assert_eq!(meta.format_price(decimal(1.08), &english), "1.0800");
+    }
+}
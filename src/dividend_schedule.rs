@@ -0,0 +1,230 @@
+//! Forecast of upcoming dividends inferred from the dividend history of a symbol.
+use time::{Duration, OffsetDateTime};
+
+use super::quotes::ZERO;
+use super::{Decimal, Dividend, YahooError};
+
+/// Number of most recent dividends used to infer the payment frequency
+pub const DIVIDEND_HISTORY_LENGTH: usize = 8;
+
+/// Frequency of dividend payments
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DividendFrequency {
+    Monthly,
+    Quarterly,
+    SemiAnnual,
+    Annual,
+    /// No regular pattern could be inferred
+    Irregular,
+}
+
+impl DividendFrequency {
+    /// Number of payments per year, or `None` for irregular payments
+    pub fn payments_per_year(&self) -> Option<u32> {
+        match self {
+            DividendFrequency::Monthly => Some(12),
+            DividendFrequency::Quarterly => Some(4),
+            DividendFrequency::SemiAnnual => Some(2),
+            DividendFrequency::Annual => Some(1),
+            DividendFrequency::Irregular => None,
+        }
+    }
+
+    fn from_interval(days: i64) -> DividendFrequency {
+        match days {
+            20..=45 => DividendFrequency::Monthly,
+            60..=135 => DividendFrequency::Quarterly,
+            150..=240 => DividendFrequency::SemiAnnual,
+            300..=450 => DividendFrequency::Annual,
+            _ => DividendFrequency::Irregular,
+        }
+    }
+}
+
+/// Caveats of a dividend forecast
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ForecastNote {
+    /// Only the given number of dividends was available to infer the frequency
+    FewDividends(usize),
+    /// The intervals between the dividends vary considerably
+    IrregularIntervals,
+    /// The latest dividend differs from the one before, the projection uses the latest amount
+    AmountChanged,
+    /// No dividend has been paid for longer than the inferred interval, payments may have
+    /// been suspended
+    Overdue,
+}
+
+/// Projected dividend payments of a symbol
+#[derive(Debug, Clone, PartialEq)]
+pub struct DividendForecast {
+    pub frequency: DividendFrequency,
+    /// Projected ex-dividend dates of the next year
+    pub next_ex_dates: Vec<OffsetDateTime>,
+    /// Expected dividends of one year; for irregular payments the dividends of the twelve
+    /// months before the latest dividend
+    pub annual_dividend: Decimal,
+    /// Annual dividend in percent of the current price, if the price is known
+    pub annualized_yield: Option<Decimal>,
+    pub notes: Vec<ForecastNote>,
+}
+
+impl DividendForecast {
+    /// Annual dividend in percent of the price paid per share
+    pub fn yield_on_cost(&self, cost_per_share: Decimal) -> Option<Decimal> {
+        percent(self.annual_dividend, cost_per_share)
+    }
+}
+
+/// Infer the payment frequency from the most recent dividends (sorted by date, as returned by
+/// `YResponse::dividends`) and project the dividends of the year following `now`
+pub fn estimate_dividend_schedule(
+    dividends: &[Dividend],
+    price: Option<Decimal>,
+    now: OffsetDateTime,
+) -> Result<DividendForecast, YahooError> {
+    let recent = &dividends[dividends.len().saturating_sub(DIVIDEND_HISTORY_LENGTH)..];
+    let dates = recent
+        .iter()
+        .map(|d| d.date.to_datetime().ok_or(YahooError::DataInconsistency))
+        .collect::<Result<Vec<_>, _>>()?;
+    let (last, last_date) = match (recent.last(), dates.last()) {
+        (Some(last), Some(date)) => (last, *date),
+        _ => return Err(YahooError::EmptyDataSet),
+    };
+
+    let mut notes = Vec::new();
+    if recent.len() < 4 {
+        notes.push(ForecastNote::FewDividends(recent.len()));
+    }
+    let mut intervals: Vec<i64> = dates
+        .windows(2)
+        .map(|w| (w[1] - w[0]).whole_days())
+        .collect();
+    intervals.sort_unstable();
+    let median = intervals.get(intervals.len() / 2).copied();
+    let frequency = median.map_or(
+        DividendFrequency::Irregular,
+        DividendFrequency::from_interval,
+    );
+    if let Some(median) = median {
+        if intervals
+            .iter()
+            .any(|days| (days - median).abs() * 10 > median * 3)
+        {
+            notes.push(ForecastNote::IrregularIntervals);
+        }
+    }
+    if recent.len() >= 2 && recent[recent.len() - 2].amount != last.amount {
+        notes.push(ForecastNote::AmountChanged);
+    }
+
+    let mut next_ex_dates = Vec::new();
+    let annual_dividend = match (frequency.payments_per_year(), median) {
+        (Some(payments), Some(median)) => {
+            let interval = Duration::days(median);
+            if now - last_date > interval + interval / 2 {
+                notes.push(ForecastNote::Overdue);
+            }
+            let mut date = last_date + interval;
+            while date <= now {
+                date += interval;
+            }
+            while date <= now + Duration::days(365) {
+                next_ex_dates.push(date);
+                date += interval;
+            }
+            last.amount * Decimal::from(payments)
+        }
+        _ => recent
+            .iter()
+            .zip(&dates)
+            .filter(|(_, date)| last_date - **date < Duration::days(365))
+            .fold(ZERO, |sum, (dividend, _)| sum + dividend.amount),
+    };
+
+    Ok(DividendForecast {
+        frequency,
+        next_ex_dates,
+        annual_dividend,
+        annualized_yield: price.and_then(|price| percent(annual_dividend, price)),
+        notes,
+    })
+}
+
+fn percent(amount: Decimal, base: Decimal) -> Option<Decimal> {
+    if base == ZERO {
+        None
+    } else {
+        Some(amount / base * Decimal::from(100))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::datetime;
+
+    use super::*;
+    use crate::YTimestamp;
+
+    fn dividend(time: OffsetDateTime, cents: u32) -> Dividend {
+        Dividend {
+            amount: Decimal::from(cents) / Decimal::from(100),
+            date: YTimestamp::from(time),
+        }
+    }
+
+    #[test]
+    fn test_quarterly_schedule() {
+        let dividends = vec![
+            dividend(datetime!(2023-02-10 0:00 UTC), 23),
+            dividend(datetime!(2023-05-12 0:00 UTC), 24),
+            dividend(datetime!(2023-08-11 0:00 UTC), 24),
+            dividend(datetime!(2023-11-10 0:00 UTC), 24),
+            dividend(datetime!(2024-02-09 0:00 UTC), 24),
+        ];
+        let now = datetime!(2024-03-01 0:00 UTC);
+        let forecast =
+            estimate_dividend_schedule(&dividends, Some(Decimal::from(192)), now).unwrap();
+        assert_eq!(forecast.frequency, DividendFrequency::Quarterly);
+        assert_eq!(
+            forecast.annual_dividend,
+            Decimal::from(96) / Decimal::from(100)
+        );
+        assert_eq!(
+            forecast.annualized_yield,
+            Some(Decimal::from(1) / Decimal::from(2))
+        );
+        assert_eq!(forecast.next_ex_dates.len(), 4);
+        assert_eq!(forecast.next_ex_dates[0], datetime!(2024-05-10 0:00 UTC));
+        assert!(forecast.notes.is_empty());
+        assert_eq!(
+            forecast.yield_on_cost(Decimal::from(96)),
+            Some(Decimal::from(1))
+        );
+
+        let later = datetime!(2024-07-01 0:00 UTC);
+        let forecast = estimate_dividend_schedule(&dividends, None, later).unwrap();
+        assert_eq!(forecast.notes, vec![ForecastNote::Overdue]);
+        assert!(forecast.next_ex_dates[0] > later);
+    }
+
+    #[test]
+    fn test_irregular_schedule() {
+        let dividends = vec![dividend(datetime!(2023-06-01 0:00 UTC), 150)];
+        let forecast =
+            estimate_dividend_schedule(&dividends, None, datetime!(2024-01-01 0:00 UTC)).unwrap();
+        assert_eq!(forecast.frequency, DividendFrequency::Irregular);
+        assert_eq!(
+            forecast.annual_dividend,
+            Decimal::from(150) / Decimal::from(100)
+        );
+        assert!(forecast.next_ex_dates.is_empty());
+        assert_eq!(forecast.notes, vec![ForecastNote::FewDividends(1)]);
+
+        assert!(matches!(
+            estimate_dividend_schedule(&[], None, datetime!(2024-01-01 0:00 UTC)),
+            Err(YahooError::EmptyDataSet)
+        ));
+    }
+}
@@ -0,0 +1,95 @@
+//! Capture of responses which could not be deserialized, e.g. after yahoo! finance changed
+//! the format of an endpoint.
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use time::OffsetDateTime;
+
+use super::YahooError;
+
+/// What to do with responses which could not be deserialized
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum DumpPolicy {
+    /// Only return the error
+    #[default]
+    Never,
+    /// Write the response to a file in the given directory and return the path as part of
+    /// the error. The directory is created if it does not exist.
+    Directory(PathBuf),
+}
+
+impl DumpPolicy {
+    /// Deserialize a response of the given kind of endpoint, dumping the payload if this fails
+    pub(crate) fn deserialize<T, F>(
+        &self,
+        kind: &str,
+        json: serde_json::Value,
+        deserialize: F,
+    ) -> Result<T, YahooError>
+    where
+        F: FnOnce(serde_json::Value) -> Result<T, YahooError>,
+    {
+        let dir = match self {
+            DumpPolicy::Never => return deserialize(json),
+            DumpPolicy::Directory(dir) => dir,
+        };
+        #[cfg(not(feature = "raw"))]
+        let payload = json.clone();
+        match deserialize(json) {
+            #[cfg(not(feature = "raw"))]
+            Err(YahooError::DeserializeFailed(e)) => match write_payload(dir, kind, &payload) {
+                Ok(path) => Err(YahooError::DeserializeFailedDumped(e, path)),
+                Err(_) => Err(YahooError::DeserializeFailed(e)),
+            },
+            #[cfg(feature = "raw")]
+            Err(YahooError::DeserializeFailedWithPayload(e, payload)) => {
+                match write_payload(dir, kind, &payload) {
+                    Ok(path) => Err(YahooError::DeserializeFailedDumped(e, path)),
+                    Err(_) => Err(YahooError::DeserializeFailedWithPayload(e, payload)),
+                }
+            }
+            result => result,
+        }
+    }
+}
+
+/// Write the payload to a file named after the kind of endpoint and the current time
+fn write_payload(
+    dir: &Path,
+    kind: &str,
+    payload: &serde_json::Value,
+) -> Result<PathBuf, io::Error> {
+    fs::create_dir_all(dir)?;
+    let now = OffsetDateTime::now_utc().unix_timestamp_nanos();
+    let path = dir.join(format!("{kind}-{now}.json"));
+    fs::write(&path, serde_json::to_vec_pretty(payload)?)?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::YResponse;
+
+    #[test]
+    fn test_dump_payload() {
+        let dir = std::env::temp_dir().join(format!("yahoo-dump-{}", std::process::id()));
+        let policy = DumpPolicy::Directory(dir.clone());
+        let json = serde_json::json!({"chart": {"result": "unexpected"}});
+        let path = match policy.deserialize("chart", json.clone(), YResponse::from_json) {
+            Err(YahooError::DeserializeFailedDumped(_, path)) => path,
+            other => panic!("unexpected result {other:?}"),
+        };
+        assert!(path.starts_with(&dir));
+        let dumped: serde_json::Value = serde_json::from_slice(&fs::read(&path).unwrap()).unwrap();
+        assert_eq!(dumped, json);
+        fs::remove_dir_all(dir).unwrap();
+
+        assert!(!matches!(
+            DumpPolicy::Never.deserialize("chart", json, YResponse::from_json),
+            Err(YahooError::DeserializeFailedDumped(_, _))
+        ));
+    }
+}
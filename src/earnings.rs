@@ -0,0 +1,131 @@
+//! Reactions of the price of a symbol to its earnings announcements.
+//!
+//! yahoo! finance time stamps daily quotes with the start of the regular session, so an
+//! announcement is attributed to the first session starting after it: announcements before
+//! the open react in that day's session, announcements during the session or after the close
+//! react in the next one.
+use time::OffsetDateTime;
+
+use super::quotes::ZERO;
+use super::{Decimal, Quote};
+
+/// Price reaction to an earnings announcement
+#[derive(Debug, Clone, PartialEq)]
+pub struct EarningsReaction {
+    pub announcement: OffsetDateTime,
+    /// Timestamp of the last session before the announcement
+    pub previous_timestamp: u64,
+    /// Close of the last session before the announcement
+    pub previous_close: Decimal,
+    /// Timestamp of the first session after the announcement
+    pub timestamp: u64,
+    /// Open of the first session after the announcement
+    pub open: Decimal,
+    /// Close of the first session after the announcement
+    pub close: Decimal,
+    /// Change of the open against the previous close in percent
+    pub gap_percent: Decimal,
+    /// Change of the close against the previous close in percent
+    pub return_percent: Decimal,
+}
+
+/// Derive the reactions to the given announcements from daily quotes, sorted by announcement.
+/// Announcements without a session before and after them in the quotes are skipped.
+pub fn earnings_reactions(
+    quotes: &[Quote],
+    announcements: &[OffsetDateTime],
+) -> Vec<EarningsReaction> {
+    let mut announcements = announcements.to_vec();
+    announcements.sort();
+    announcements
+        .into_iter()
+        .filter_map(|announcement| {
+            let time = announcement.unix_timestamp();
+            let after = quotes.partition_point(|q| q.timestamp as i64 <= time);
+            let (previous, reaction) = (&quotes[after.checked_sub(1)?], quotes.get(after)?);
+            if previous.close == ZERO {
+                return None;
+            }
+            let percent =
+                |price: Decimal| (price - previous.close) / previous.close * Decimal::from(100);
+            Some(EarningsReaction {
+                announcement,
+                previous_timestamp: previous.timestamp,
+                previous_close: previous.close,
+                timestamp: reaction.timestamp,
+                open: reaction.open,
+                close: reaction.close,
+                gap_percent: percent(reaction.open),
+                return_percent: percent(reaction.close),
+            })
+        })
+        .collect()
+}
+
+/// Return the latest `n_events` announcements before `now`, oldest first
+pub(crate) fn latest_announcements(
+    announcements: &[OffsetDateTime],
+    n_events: usize,
+    now: OffsetDateTime,
+) -> Vec<OffsetDateTime> {
+    let mut past: Vec<OffsetDateTime> =
+        announcements.iter().copied().filter(|a| *a < now).collect();
+    past.sort();
+    past.split_off(past.len().saturating_sub(n_events))
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::datetime;
+
+    use super::*;
+
+    fn quote(timestamp: u64, open: u32, close: u32) -> Quote {
+        Quote {
+            timestamp,
+            open: Decimal::from(open),
+            high: Decimal::from(open.max(close)),
+            low: Decimal::from(open.min(close)),
+            volume: 1000,
+            close: Decimal::from(close),
+            adjclose: Decimal::from(close),
+        }
+    }
+
+    #[test]
+    fn test_earnings_reactions() {
+        // sessions opening at 14:30 UTC on 2024-01-30, 2024-01-31 and 2024-02-01
+        let quotes = [
+            quote(1706625000, 100, 100),
+            quote(1706711400, 104, 110),
+            quote(1706797800, 108, 99),
+        ];
+        let announcements = [
+            // after the close of 2024-01-31
+            datetime!(2024-01-31 21:30 UTC),
+            // before the open of 2024-01-31
+            datetime!(2024-01-31 12:00 UTC),
+            // after the last session
+            datetime!(2024-02-01 21:30 UTC),
+        ];
+        let reactions = earnings_reactions(&quotes, &announcements);
+        assert_eq!(reactions.len(), 2);
+        assert_eq!(reactions[0].announcement, datetime!(2024-01-31 12:00 UTC));
+        assert_eq!(reactions[0].previous_timestamp, 1706625000);
+        assert_eq!(reactions[0].timestamp, 1706711400);
+        assert_eq!(reactions[0].gap_percent, Decimal::from(4));
+        assert_eq!(reactions[0].return_percent, Decimal::from(10));
+        assert_eq!(reactions[1].previous_close, Decimal::from(110));
+        assert_eq!(reactions[1].timestamp, 1706797800);
+        assert_eq!(reactions[1].return_percent, Decimal::from(-10));
+
+        let latest = latest_announcements(&announcements, 2, datetime!(2024-02-01 0:00 UTC));
+        assert_eq!(
+            latest,
+            vec![
+                datetime!(2024-01-31 12:00 UTC),
+                datetime!(2024-01-31 21:30 UTC)
+            ]
+        );
+    }
+}
@@ -0,0 +1,203 @@
+//! Codes of exchanges and markets as used by yahoo! finance, e.g. `NMS` for the Nasdaq Global
+//! Select Market or `de_market` for the German market.
+//!
+//! Codes not known to this crate are kept as `Other`, so new codes reported by yahoo! finance
+//! never fail deserialization. Both types compare equal to their code, e.g.
+//! `meta.exchange_name == "NMS"`.
+use std::{convert::Infallible, fmt, str::FromStr};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Define an enum of codes with a fallback variant `Other(String)`, conversions from and to
+/// the codes and (de)serialization as code
+macro_rules! codes {
+    (
+        $(#[$meta:meta])*
+        $name:ident {
+            $($(#[$variant_meta:meta])* $variant:ident => $code:literal,)*
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+        #[non_exhaustive]
+        pub enum $name {
+            $($(#[$variant_meta])* $variant,)*
+            /// Code not known to this crate
+            Other(String),
+        }
+
+        impl $name {
+            /// Map a code as used by yahoo! finance
+            pub fn from_code(code: &str) -> $name {
+                match code {
+                    $($code => $name::$variant,)*
+                    other => $name::Other(other.to_string()),
+                }
+            }
+
+            /// Code as used by yahoo! finance
+            pub fn as_str(&self) -> &str {
+                match self {
+                    $($name::$variant => $code,)*
+                    $name::Other(other) => other,
+                }
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(self.as_str())
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = Infallible;
+
+            fn from_str(code: &str) -> Result<Self, Self::Err> {
+                Ok($name::from_code(code))
+            }
+        }
+
+        impl PartialEq<str> for $name {
+            fn eq(&self, code: &str) -> bool {
+                self.as_str() == code
+            }
+        }
+
+        impl PartialEq<&str> for $name {
+            fn eq(&self, code: &&str) -> bool {
+                self.as_str() == *code
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let code = <std::borrow::Cow<'de, str>>::deserialize(deserializer)?;
+                Ok($name::from_code(&code))
+            }
+        }
+
+        impl Serialize for $name {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.serialize_str(self.as_str())
+            }
+        }
+    };
+}
+
+codes! {
+    /// Exchange (or source of quotes) of a symbol
+    Exchange {
+        /// Nasdaq Global Select Market
+        NasdaqGs => "NMS",
+        /// Nasdaq Global Market
+        NasdaqGm => "NGM",
+        /// Nasdaq Capital Market
+        NasdaqCm => "NCM",
+        /// Nasdaq, e.g. mutual funds
+        Nasdaq => "NAS",
+        /// Nasdaq indices
+        NasdaqIndex => "NIM",
+        NewYork => "NYQ",
+        /// NYSE American, formerly AMEX
+        NyseAmerican => "ASE",
+        NyseArca => "PCX",
+        /// Cboe BZX, formerly BATS
+        Cboe => "BTS",
+        /// OTC markets (pink sheets)
+        OtcMarkets => "PNK",
+        /// Options on US exchanges
+        UsOptions => "OPR",
+        /// S&P indices
+        SnpIndex => "SNP",
+        Cme => "CME",
+        Cbot => "CBT",
+        Nymex => "NYM",
+        Comex => "CMX",
+        /// ICE Futures US
+        IceUs => "NYB",
+        Xetra => "GER",
+        Frankfurt => "FRA",
+        Stuttgart => "STU",
+        Munich => "MUN",
+        Berlin => "BER",
+        Dusseldorf => "DUS",
+        Hamburg => "HAM",
+        Hanover => "HAN",
+        London => "LSE",
+        Paris => "PAR",
+        Amsterdam => "AMS",
+        Brussels => "BRU",
+        Milan => "MIL",
+        Madrid => "MCE",
+        /// SIX Swiss Exchange
+        Swiss => "EBS",
+        Vienna => "VIE",
+        Copenhagen => "CPH",
+        Stockholm => "STO",
+        Helsinki => "HEL",
+        Oslo => "OSL",
+        Toronto => "TOR",
+        /// TSX Venture Exchange
+        TorontoVenture => "VAN",
+        Tokyo => "JPX",
+        HongKong => "HKG",
+        Australia => "ASX",
+        /// National Stock Exchange of India
+        India => "NSI",
+        Bombay => "BSE",
+        /// Exchange rates of currencies
+        Currency => "CCY",
+        /// Crypto currencies (CoinMarketCap)
+        Crypto => "CCC",
+    }
+}
+
+codes! {
+    /// Market of a symbol, mostly by country
+    Market {
+        Us => "us_market",
+        Canada => "ca_market",
+        Germany => "de_market",
+        UnitedKingdom => "gb_market",
+        France => "fr_market",
+        Netherlands => "nl_market",
+        Belgium => "be_market",
+        Italy => "it_market",
+        Spain => "es_market",
+        Switzerland => "ch_market",
+        Austria => "at_market",
+        Denmark => "dk_market",
+        Sweden => "se_market",
+        Finland => "fi_market",
+        Norway => "no_market",
+        Japan => "jp_market",
+        HongKong => "hk_market",
+        Australia => "au_market",
+        India => "in_market",
+        Crypto => "ccc_market",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_codes() {
+        assert_eq!(Exchange::from_code("NMS"), Exchange::NasdaqGs);
+        assert_eq!(Exchange::Stuttgart.to_string(), "STU");
+        assert_eq!(
+            "XYZ".parse::<Exchange>(),
+            Ok(Exchange::Other("XYZ".to_string()))
+        );
+        assert!(Exchange::Other("XYZ".to_string()) == "XYZ");
+        assert!(Exchange::Xetra == "GER");
+
+        let market: Market = serde_json::from_str(r#""de_market""#).unwrap();
+        assert_eq!(market, Market::Germany);
+        let market: Market = serde_json::from_str(r#""mx_market""#).unwrap();
+        assert_eq!(market.as_str(), "mx_market");
+        assert_eq!(serde_json::to_string(&market).unwrap(), r#""mx_market""#);
+    }
+}
@@ -0,0 +1,403 @@
+//! Trading calendars of major exchanges.
+//!
+//! yahoo! finance does not provide holiday calendars, therefore the calendars are
+//! computed from built-in rules for the supported exchanges.
+use time::{macros::time, Date, Duration, Month, Time, Weekday};
+
+use super::YMetaData;
+
+/// Group of exchanges sharing the same trading calendar
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CalendarRules {
+    /// NYSE, Nasdaq and other US exchanges
+    UnitedStates,
+    /// Xetra and German floor exchanges (Frankfurt, Stuttgart, ...)
+    Germany,
+    /// London Stock Exchange
+    UnitedKingdom,
+}
+
+/// A day the exchange is closed (apart from weekends)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Holiday {
+    pub date: Date,
+    pub name: &'static str,
+}
+
+/// A day the exchange closes earlier than usual
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EarlyClose {
+    pub date: Date,
+    /// Closing time in local time of the exchange
+    pub close: Time,
+}
+
+/// Trading calendar of an exchange
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExchangeCalendar {
+    rules: CalendarRules,
+}
+
+impl ExchangeCalendar {
+    pub fn new(rules: CalendarRules) -> ExchangeCalendar {
+        ExchangeCalendar { rules }
+    }
+
+    /// Return the calendar for an exchange code as used by yahoo! finance (e.g. "NMS", "GER", "LSE"),
+    /// or `None` if the exchange is not supported
+    pub fn for_exchange(exchange: &str) -> Option<ExchangeCalendar> {
+        let rules = match exchange {
+            "NMS" | "NGM" | "NCM" | "NYQ" | "NAS" | "ASE" | "PCX" | "BTS" | "NIM" => {
+                CalendarRules::UnitedStates
+            }
+            "GER" | "FRA" | "STU" | "MUN" | "BER" | "DUS" | "HAM" | "HAN" => CalendarRules::Germany,
+            "LSE" | "IOB" => CalendarRules::UnitedKingdom,
+            _ => return None,
+        };
+        Some(ExchangeCalendar::new(rules))
+    }
+
+    pub fn rules(&self) -> CalendarRules {
+        self.rules
+    }
+
+    /// Return all holidays of the given year falling on weekdays, ordered by date
+    pub fn holidays(&self, year: i32) -> Vec<Holiday> {
+        let mut holidays = match self.rules {
+            CalendarRules::UnitedStates => us_holidays(year),
+            CalendarRules::Germany => german_holidays(year),
+            CalendarRules::UnitedKingdom => uk_holidays(year),
+        };
+        holidays.retain(|h| !is_weekend(h.date));
+        holidays.sort_by_key(|h| h.date);
+        holidays
+    }
+
+    /// Return all early closes of the given year, ordered by date
+    pub fn early_closes(&self, year: i32) -> Vec<EarlyClose> {
+        let (candidates, close) = match self.rules {
+            CalendarRules::UnitedStates => {
+                let thanksgiving = nth_weekday(year, Month::November, Weekday::Thursday, 4);
+                (
+                    vec![
+                        date(year, Month::July, 3),
+                        thanksgiving + Duration::days(1),
+                        date(year, Month::December, 24),
+                    ],
+                    time!(13:00),
+                )
+            }
+            CalendarRules::UnitedKingdom => (
+                vec![
+                    date(year, Month::December, 24),
+                    date(year, Month::December, 31),
+                ],
+                time!(12:30),
+            ),
+            CalendarRules::Germany => (vec![], time!(14:00)),
+        };
+        candidates
+            .into_iter()
+            .filter(|d| self.is_trading_day(*d))
+            .map(|date| EarlyClose { date, close })
+            .collect()
+    }
+
+    /// Return the holidays on or after the given date, limited to the given number
+    pub fn upcoming_holidays(&self, from: Date, count: usize) -> Vec<Holiday> {
+        let mut year = from.year();
+        let mut holidays = Vec::new();
+        // every supported calendar has several holidays per year
+        while holidays.len() < count {
+            holidays.extend(self.holidays(year).into_iter().filter(|h| h.date >= from));
+            year += 1;
+        }
+        holidays.truncate(count);
+        holidays
+    }
+
+    /// Return whether the exchange is open on the given date
+    pub fn is_trading_day(&self, date: Date) -> bool {
+        !is_weekend(date) && !self.holidays(date.year()).iter().any(|h| h.date == date)
+    }
+
+    /// Return the first trading day after the given date
+    pub fn next_trading_day(&self, date: Date) -> Date {
+        let mut next = date + Duration::days(1);
+        while !self.is_trading_day(next) {
+            next += Duration::days(1);
+        }
+        next
+    }
+}
+
+impl YMetaData {
+    /// Return the trading calendar of the exchange of this symbol, if supported
+    pub fn exchange_calendar(&self) -> Option<ExchangeCalendar> {
+        ExchangeCalendar::for_exchange(self.exchange_name.as_str())
+    }
+}
+
+fn date(year: i32, month: Month, day: u8) -> Date {
+    Date::from_calendar_date(year, month, day).expect("valid calendar date")
+}
+
+fn is_weekend(date: Date) -> bool {
+    matches!(date.weekday(), Weekday::Saturday | Weekday::Sunday)
+}
+
+/// n-th (starting at 1) given weekday of a month
+fn nth_weekday(year: i32, month: Month, weekday: Weekday, n: u8) -> Date {
+    let first = date(year, month, 1);
+    let offset =
+        (7 + weekday.number_days_from_monday() - first.weekday().number_days_from_monday()) % 7;
+    first + Duration::days((offset + 7 * (n - 1)) as i64)
+}
+
+/// Last given weekday of a month
+fn last_weekday(year: i32, month: Month, weekday: Weekday) -> Date {
+    let last = date(year, month, time::util::days_in_year_month(year, month));
+    let offset =
+        (7 + last.weekday().number_days_from_monday() - weekday.number_days_from_monday()) % 7;
+    last - Duration::days(offset as i64)
+}
+
+/// Easter Sunday (anonymous Gregorian algorithm)
+fn easter_sunday(year: i32) -> Date {
+    let a = year % 19;
+    let b = year / 100;
+    let c = year % 100;
+    let d = b / 4;
+    let e = b % 4;
+    let f = (b + 8) / 25;
+    let g = (b - f + 1) / 3;
+    let h = (19 * a + b - d - g + 15) % 30;
+    let i = c / 4;
+    let k = c % 4;
+    let l = (32 + 2 * e + 2 * i - h - k) % 7;
+    let m = (a + 11 * h + 22 * l) / 451;
+    let month = (h + l - 7 * m + 114) / 31;
+    let day = (h + l - 7 * m + 114) % 31 + 1;
+    let month = if month == 3 {
+        Month::March
+    } else {
+        Month::April
+    };
+    date(year, month, day as u8)
+}
+
+/// Move holidays on Saturday to Friday and on Sunday to Monday
+fn observed_us(date: Date) -> Date {
+    match date.weekday() {
+        Weekday::Saturday => date - Duration::days(1),
+        Weekday::Sunday => date + Duration::days(1),
+        _ => date,
+    }
+}
+
+/// Move holidays on weekends to the next Monday
+fn observed_uk(date: Date) -> Date {
+    match date.weekday() {
+        Weekday::Saturday => date + Duration::days(2),
+        Weekday::Sunday => date + Duration::days(1),
+        _ => date,
+    }
+}
+
+fn us_holidays(year: i32) -> Vec<Holiday> {
+    let holiday = |date, name| Holiday { date, name };
+    let mut holidays = vec![
+        holiday(
+            nth_weekday(year, Month::January, Weekday::Monday, 3),
+            "Martin Luther King Jr. Day",
+        ),
+        holiday(
+            nth_weekday(year, Month::February, Weekday::Monday, 3),
+            "Washington's Birthday",
+        ),
+        holiday(easter_sunday(year) - Duration::days(2), "Good Friday"),
+        holiday(
+            last_weekday(year, Month::May, Weekday::Monday),
+            "Memorial Day",
+        ),
+        holiday(observed_us(date(year, Month::July, 4)), "Independence Day"),
+        holiday(
+            nth_weekday(year, Month::September, Weekday::Monday, 1),
+            "Labor Day",
+        ),
+        holiday(
+            nth_weekday(year, Month::November, Weekday::Thursday, 4),
+            "Thanksgiving Day",
+        ),
+        holiday(
+            observed_us(date(year, Month::December, 25)),
+            "Christmas Day",
+        ),
+    ];
+    // New Year's Day on a Saturday is not observed on the preceding Friday
+    let new_year = date(year, Month::January, 1);
+    if new_year.weekday() != Weekday::Saturday {
+        holidays.push(holiday(observed_us(new_year), "New Year's Day"));
+    }
+    if year >= 2022 {
+        holidays.push(holiday(
+            observed_us(date(year, Month::June, 19)),
+            "Juneteenth National Independence Day",
+        ));
+    }
+    holidays
+}
+
+fn german_holidays(year: i32) -> Vec<Holiday> {
+    let easter = easter_sunday(year);
+    let holiday = |date, name| Holiday { date, name };
+    vec![
+        holiday(date(year, Month::January, 1), "New Year's Day"),
+        holiday(easter - Duration::days(2), "Good Friday"),
+        holiday(easter + Duration::days(1), "Easter Monday"),
+        holiday(date(year, Month::May, 1), "Labour Day"),
+        holiday(date(year, Month::December, 24), "Christmas Eve"),
+        holiday(date(year, Month::December, 25), "Christmas Day"),
+        holiday(date(year, Month::December, 26), "Boxing Day"),
+        holiday(date(year, Month::December, 31), "New Year's Eve"),
+    ]
+}
+
+fn uk_holidays(year: i32) -> Vec<Holiday> {
+    let easter = easter_sunday(year);
+    let holiday = |date, name| Holiday { date, name };
+    let christmas = date(year, Month::December, 25);
+    let boxing_day = date(year, Month::December, 26);
+    // if Christmas falls on a weekend, both substitute days follow each other
+    let (christmas, boxing_day) = match christmas.weekday() {
+        Weekday::Saturday => (
+            christmas + Duration::days(2),
+            boxing_day + Duration::days(2),
+        ),
+        Weekday::Friday => (christmas, boxing_day + Duration::days(2)),
+        Weekday::Sunday => (christmas + Duration::days(2), boxing_day),
+        _ => (christmas, boxing_day),
+    };
+    vec![
+        holiday(observed_uk(date(year, Month::January, 1)), "New Year's Day"),
+        holiday(easter - Duration::days(2), "Good Friday"),
+        holiday(easter + Duration::days(1), "Easter Monday"),
+        holiday(
+            nth_weekday(year, Month::May, Weekday::Monday, 1),
+            "Early May Bank Holiday",
+        ),
+        holiday(
+            last_weekday(year, Month::May, Weekday::Monday),
+            "Spring Bank Holiday",
+        ),
+        holiday(
+            last_weekday(year, Month::August, Weekday::Monday),
+            "Summer Bank Holiday",
+        ),
+        holiday(christmas, "Christmas Day"),
+        holiday(boxing_day, "Boxing Day"),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::date;
+
+    use super::*;
+
+    fn dates(holidays: Vec<Holiday>) -> Vec<Date> {
+        holidays.into_iter().map(|h| h.date).collect()
+    }
+
+    #[test]
+    fn test_easter() {
+        assert_eq!(easter_sunday(2024), date!(2024 - 03 - 31));
+        assert_eq!(easter_sunday(2025), date!(2025 - 04 - 20));
+        assert_eq!(easter_sunday(2019), date!(2019 - 04 - 21));
+    }
+
+    #[test]
+    fn test_us_holidays_2024() {
+        let calendar = ExchangeCalendar::for_exchange("NMS").unwrap();
+        assert_eq!(
+            dates(calendar.holidays(2024)),
+            vec![
+                date!(2024 - 01 - 01),
+                date!(2024 - 01 - 15),
+                date!(2024 - 02 - 19),
+                date!(2024 - 03 - 29),
+                date!(2024 - 05 - 27),
+                date!(2024 - 06 - 19),
+                date!(2024 - 07 - 04),
+                date!(2024 - 09 - 02),
+                date!(2024 - 11 - 28),
+                date!(2024 - 12 - 25),
+            ]
+        );
+        let early_closes: Vec<Date> = calendar
+            .early_closes(2024)
+            .into_iter()
+            .map(|e| e.date)
+            .collect();
+        assert_eq!(
+            early_closes,
+            vec![
+                date!(2024 - 07 - 03),
+                date!(2024 - 11 - 29),
+                date!(2024 - 12 - 24)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_us_observed_holidays() {
+        let calendar = ExchangeCalendar::new(CalendarRules::UnitedStates);
+        // New Year's Day 2022 on Saturday is not observed
+        assert!(calendar.is_trading_day(date!(2021 - 12 - 31)));
+        // Independence Day 2021 on Sunday
+        assert!(!calendar.is_trading_day(date!(2021 - 07 - 05)));
+        // Independence Day 2020 on Saturday, no early close on the observed holiday
+        assert!(!calendar.is_trading_day(date!(2020 - 07 - 03)));
+        assert!(!calendar
+            .early_closes(2020)
+            .iter()
+            .any(|e| e.date == date!(2020 - 07 - 03)));
+    }
+
+    #[test]
+    fn test_german_and_uk_holidays() {
+        let xetra = ExchangeCalendar::for_exchange("GER").unwrap();
+        assert!(!xetra.is_trading_day(date!(2024 - 04 - 01)));
+        assert!(!xetra.is_trading_day(date!(2024 - 12 - 24)));
+        assert!(xetra.is_trading_day(date!(2024 - 10 - 03)));
+
+        let lse = ExchangeCalendar::for_exchange("LSE").unwrap();
+        assert_eq!(
+            dates(lse.holidays(2022))
+                .into_iter()
+                .filter(|d| d.month() == Month::December)
+                .collect::<Vec<_>>(),
+            vec![date!(2022 - 12 - 26), date!(2022 - 12 - 27)]
+        );
+        assert!(ExchangeCalendar::for_exchange("XXX").is_none());
+    }
+
+    #[test]
+    fn test_upcoming_holidays_and_next_trading_day() {
+        let calendar = ExchangeCalendar::new(CalendarRules::Germany);
+        let upcoming = dates(calendar.upcoming_holidays(date!(2024 - 12 - 20), 4));
+        assert_eq!(
+            upcoming,
+            vec![
+                date!(2024 - 12 - 24),
+                date!(2024 - 12 - 25),
+                date!(2024 - 12 - 26),
+                date!(2024 - 12 - 31),
+            ]
+        );
+        assert_eq!(
+            calendar.next_trading_day(date!(2024 - 12 - 23)),
+            date!(2024 - 12 - 27)
+        );
+    }
+}
@@ -0,0 +1,280 @@
+//! Ratios of the financial health of a company derived from the annual balance sheets and
+//! income statements of the fundamentals-timeseries endpoint, see
+//! `YahooConnector::get_financial_health`.
+//!
+//! The formulas may be refined in later releases. Results carry the `HEALTH_FORMULA_VERSION`
+//! they have been computed with, so stored values can be told apart from those of other versions.
+use std::collections::BTreeMap;
+
+use time::Date;
+
+use super::quotes::ZERO;
+use super::{Decimal, FundamentalSeries};
+
+/// Version of the formulas of `HealthRatios`, increased whenever a formula changes
+pub const HEALTH_FORMULA_VERSION: u32 = 1;
+
+/// Types requested by `get_financial_health`
+pub(crate) const HEALTH_TIMESERIES_TYPES: &[&str] = &[
+    "annualCurrentAssets",
+    "annualCurrentLiabilities",
+    "annualWorkingCapital",
+    "annualTotalAssets",
+    "annualTotalLiabilitiesNetMinorityInterest",
+    "annualTotalDebt",
+    "annualStockholdersEquity",
+    "annualRetainedEarnings",
+    "annualTotalRevenue",
+    "annualEBIT",
+    "annualInterestExpense",
+];
+
+/// Figures of the annual statements of a fiscal year the ratios are derived from; figures not
+/// reported are `None`
+#[derive(Debug, Clone, PartialEq)]
+pub struct HealthInputs {
+    /// End of the fiscal year
+    pub date: Date,
+    /// Currency of the figures, if reported
+    pub currency_code: Option<String>,
+    pub current_assets: Option<Decimal>,
+    pub current_liabilities: Option<Decimal>,
+    pub working_capital: Option<Decimal>,
+    pub total_assets: Option<Decimal>,
+    pub total_liabilities: Option<Decimal>,
+    pub total_debt: Option<Decimal>,
+    pub stockholders_equity: Option<Decimal>,
+    pub retained_earnings: Option<Decimal>,
+    pub total_revenue: Option<Decimal>,
+    pub ebit: Option<Decimal>,
+    pub interest_expense: Option<Decimal>,
+}
+
+impl HealthInputs {
+    fn new(date: Date) -> HealthInputs {
+        HealthInputs {
+            date,
+            currency_code: None,
+            current_assets: None,
+            current_liabilities: None,
+            working_capital: None,
+            total_assets: None,
+            total_liabilities: None,
+            total_debt: None,
+            stockholders_equity: None,
+            retained_earnings: None,
+            total_revenue: None,
+            ebit: None,
+            interest_expense: None,
+        }
+    }
+
+    fn set(&mut self, name: &str, value: Decimal) {
+        let field = match name.strip_prefix("annual").unwrap_or(name) {
+            "CurrentAssets" => &mut self.current_assets,
+            "CurrentLiabilities" => &mut self.current_liabilities,
+            "WorkingCapital" => &mut self.working_capital,
+            "TotalAssets" => &mut self.total_assets,
+            "TotalLiabilitiesNetMinorityInterest" => &mut self.total_liabilities,
+            "TotalDebt" => &mut self.total_debt,
+            "StockholdersEquity" => &mut self.stockholders_equity,
+            "RetainedEarnings" => &mut self.retained_earnings,
+            "TotalRevenue" => &mut self.total_revenue,
+            "EBIT" => &mut self.ebit,
+            "InterestExpense" => &mut self.interest_expense,
+            _ => return,
+        };
+        *field = Some(value);
+    }
+}
+
+/// Ratios derived from the figures of a fiscal year; ratios whose inputs are missing or whose
+/// denominator is zero are `None`
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct HealthRatios {
+    /// Current assets divided by current liabilities
+    pub current_ratio: Option<Decimal>,
+    /// Total debt divided by stockholders' equity
+    pub debt_to_equity: Option<Decimal>,
+    /// EBIT divided by the interest expense
+    pub interest_coverage: Option<Decimal>,
+    /// Altman Z'-score, i.e. the variant of the Z-score using the book value instead of the
+    /// market value of equity: `0.717 X1 + 0.847 X2 + 3.107 X3 + 0.420 X4 + 0.998 X5` with
+    /// working capital (X1), retained earnings (X2), EBIT (X3) and revenue (X5) divided by
+    /// total assets and stockholders' equity divided by total liabilities (X4). Values below
+    /// 1.23 indicate distress, values above 2.9 a safe zone.
+    pub altman_z: Option<Decimal>,
+}
+
+impl HealthRatios {
+    pub fn from_inputs(inputs: &HealthInputs) -> HealthRatios {
+        let working_capital = inputs
+            .working_capital
+            .or_else(|| Some(inputs.current_assets? - inputs.current_liabilities?));
+        let per_assets = |value: Option<Decimal>| divide(value, inputs.total_assets);
+        let terms = [
+            (717, per_assets(working_capital)),
+            (847, per_assets(inputs.retained_earnings)),
+            (3107, per_assets(inputs.ebit)),
+            (
+                420,
+                divide(inputs.stockholders_equity, inputs.total_liabilities),
+            ),
+            (998, per_assets(inputs.total_revenue)),
+        ];
+        let altman_z = terms
+            .iter()
+            .try_fold(ZERO, |z, (weight, x)| {
+                Some(z + Decimal::from(*weight) * (*x)?)
+            })
+            .map(|z| z / Decimal::from(1000));
+        HealthRatios {
+            current_ratio: divide(inputs.current_assets, inputs.current_liabilities),
+            debt_to_equity: divide(inputs.total_debt, inputs.stockholders_equity),
+            // the expense is reported as positive or negative number, depending on the company
+            interest_coverage: divide(inputs.ebit, inputs.interest_expense.map(|e| e.abs())),
+            altman_z,
+        }
+    }
+}
+
+fn divide(numerator: Option<Decimal>, denominator: Option<Decimal>) -> Option<Decimal> {
+    let denominator = denominator.filter(|d| *d != ZERO)?;
+    Some(numerator? / denominator)
+}
+
+/// Figures and ratios of a fiscal year
+#[derive(Debug, Clone, PartialEq)]
+pub struct HealthPeriod {
+    pub inputs: HealthInputs,
+    pub ratios: HealthRatios,
+}
+
+/// Ratio of `HealthRatios` selected by `FinancialHealth::trend`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HealthRatio {
+    CurrentRatio,
+    DebtToEquity,
+    InterestCoverage,
+    AltmanZ,
+}
+
+impl HealthRatio {
+    fn get(&self, ratios: &HealthRatios) -> Option<Decimal> {
+        match self {
+            HealthRatio::CurrentRatio => ratios.current_ratio,
+            HealthRatio::DebtToEquity => ratios.debt_to_equity,
+            HealthRatio::InterestCoverage => ratios.interest_coverage,
+            HealthRatio::AltmanZ => ratios.altman_z,
+        }
+    }
+}
+
+/// Financial health of a company over several fiscal years
+#[derive(Debug, Clone, PartialEq)]
+pub struct FinancialHealth {
+    /// Version of the formulas the ratios have been computed with, see `HEALTH_FORMULA_VERSION`
+    pub formula_version: u32,
+    /// Fiscal years sorted by date
+    pub periods: Vec<HealthPeriod>,
+}
+
+impl FinancialHealth {
+    /// Collect the figures of the given series by fiscal year and derive their ratios
+    pub fn from_series(series: &[FundamentalSeries]) -> FinancialHealth {
+        let mut inputs: BTreeMap<Date, HealthInputs> = BTreeMap::new();
+        for series in series {
+            for value in &series.values {
+                let period = inputs
+                    .entry(value.as_of_date)
+                    .or_insert_with(|| HealthInputs::new(value.as_of_date));
+                if period.currency_code.is_none() {
+                    period.currency_code = value.currency_code.clone();
+                }
+                period.set(&series.name, value.value);
+            }
+        }
+        FinancialHealth {
+            formula_version: HEALTH_FORMULA_VERSION,
+            periods: inputs
+                .into_values()
+                .map(|inputs| HealthPeriod {
+                    ratios: HealthRatios::from_inputs(&inputs),
+                    inputs,
+                })
+                .collect(),
+        }
+    }
+
+    /// Values of the ratio by end of fiscal year, skipping years where it is not available
+    pub fn trend(&self, ratio: HealthRatio) -> Vec<(Date, Decimal)> {
+        self.periods
+            .iter()
+            .filter_map(|period| Some((period.inputs.date, ratio.get(&period.ratios)?)))
+            .collect()
+    }
+
+    /// Figures and ratios of the most recent fiscal year
+    pub fn latest(&self) -> Option<&HealthPeriod> {
+        self.periods.last()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::date;
+
+    use super::*;
+    use crate::FundamentalValue;
+
+    fn series(name: &str, values: &[(Date, i32)]) -> FundamentalSeries {
+        FundamentalSeries {
+            name: name.to_string(),
+            values: values
+                .iter()
+                .map(|(date, value)| FundamentalValue {
+                    as_of_date: *date,
+                    period_type: "12M".to_string(),
+                    currency_code: Some("USD".to_string()),
+                    value: Decimal::from(*value),
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_financial_health() {
+        let (y1, y2) = (date!(2022 - 12 - 31), date!(2023 - 12 - 31));
+        let health = FinancialHealth::from_series(&[
+            series("annualCurrentAssets", &[(y1, 200), (y2, 300)]),
+            series("annualCurrentLiabilities", &[(y1, 100), (y2, 100)]),
+            series("annualTotalAssets", &[(y2, 1000)]),
+            series("annualTotalLiabilitiesNetMinorityInterest", &[(y2, 500)]),
+            series("annualTotalDebt", &[(y1, 50), (y2, 400)]),
+            series("annualStockholdersEquity", &[(y1, 0), (y2, 500)]),
+            series("annualRetainedEarnings", &[(y2, 100)]),
+            series("annualTotalRevenue", &[(y2, 1000)]),
+            series("annualEBIT", &[(y2, 100)]),
+            series("annualInterestExpense", &[(y2, -20)]),
+        ]);
+        assert_eq!(health.formula_version, HEALTH_FORMULA_VERSION);
+        assert_eq!(health.periods.len(), 2);
+        assert_eq!(
+            health.trend(HealthRatio::CurrentRatio),
+            vec![(y1, Decimal::from(2)), (y2, Decimal::from(3))]
+        );
+        // zero equity in the first year
+        assert_eq!(
+            health.trend(HealthRatio::DebtToEquity),
+            vec![(y2, Decimal::from(4) / Decimal::from(5))]
+        );
+
+        let latest = health.latest().unwrap();
+        assert_eq!(latest.inputs.currency_code.as_deref(), Some("USD"));
+        assert_eq!(latest.ratios.interest_coverage, Some(Decimal::from(5)));
+        // 0.717 * 0.2 + 0.847 * 0.1 + 3.107 * 0.1 + 0.420 * 1 + 0.998 * 1
+        let z = latest.ratios.altman_z.unwrap();
+        assert!((crate::decimal_to_f64(z) - 1.9568).abs() < 1e-9);
+        assert_eq!(health.trend(HealthRatio::AltmanZ).len(), 1);
+    }
+}
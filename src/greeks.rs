@@ -0,0 +1,204 @@
+//! Greeks of option contracts by the Black-Scholes model, as yahoo! finance does not provide
+//! them.
+//!
+//! The model assumes European exercise and no dividends, so the greeks are approximations for
+//! American options on dividend paying stocks.
+use super::{decimal_to_f64, Decimal, OptionKind, YOptionChain, YOptionContract, YTimestamp};
+
+const SECONDS_PER_YEAR: f64 = 365.0 * 86400.0;
+
+/// Sensitivities of the value of an option per share
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Greeks {
+    /// Change of value per unit change of the price of the underlying
+    pub delta: f64,
+    /// Change of delta per unit change of the price of the underlying
+    pub gamma: f64,
+    /// Change of value per percentage point change of the volatility
+    pub vega: f64,
+    /// Change of value per calendar day
+    pub theta: f64,
+}
+
+/// Standard normal density
+fn pdf(x: f64) -> f64 {
+    (-0.5 * x * x).exp() / (2.0 * std::f64::consts::PI).sqrt()
+}
+
+/// Standard normal distribution, with an absolute error below 1e-7
+/// (Abramowitz and Stegun, 26.2.17)
+fn cdf(x: f64) -> f64 {
+    let t = 1.0 / (1.0 + 0.2316419 * x.abs());
+    let poly = t
+        * (0.319381530
+            + t * (-0.356563782 + t * (1.781477937 + t * (-1.821255978 + t * 1.330274429))));
+    let upper = pdf(x) * poly;
+    if x >= 0.0 {
+        1.0 - upper
+    } else {
+        upper
+    }
+}
+
+impl Greeks {
+    /// Greeks by the Black-Scholes model for the given price of the underlying, strike, time
+    /// to expiration in years, volatility and continuously compounded risk-free rate (both as
+    /// fractions, e.g. `0.25`). Returns `None` unless price, strike, time and volatility are
+    /// positive.
+    pub fn black_scholes(
+        kind: OptionKind,
+        underlying_price: f64,
+        strike: f64,
+        years: f64,
+        volatility: f64,
+        rate: f64,
+    ) -> Option<Greeks> {
+        if underlying_price <= 0.0 || strike <= 0.0 || years <= 0.0 || volatility <= 0.0 {
+            return None;
+        }
+        let vol_time = volatility * years.sqrt();
+        let d1 = ((underlying_price / strike).ln()
+            + (rate + 0.5 * volatility * volatility) * years)
+            / vol_time;
+        let d2 = d1 - vol_time;
+        let discounted_strike = strike * (-rate * years).exp();
+        let decay = -underlying_price * pdf(d1) * volatility / (2.0 * years.sqrt());
+        let (delta, theta) = match kind {
+            OptionKind::Call => (cdf(d1), decay - rate * discounted_strike * cdf(d2)),
+            OptionKind::Put => (cdf(d1) - 1.0, decay + rate * discounted_strike * cdf(-d2)),
+        };
+        Some(Greeks {
+            delta,
+            gamma: pdf(d1) / (underlying_price * vol_time),
+            vega: underlying_price * pdf(d1) * years.sqrt() / 100.0,
+            theta: theta / 365.0,
+        })
+    }
+}
+
+/// Option contract together with its right and greeks
+#[derive(Debug, Clone)]
+pub struct EnrichedContract {
+    pub kind: OptionKind,
+    pub contract: YOptionContract,
+    /// `None` if the contract lacks strike, expiration or implied volatility, or has expired
+    pub greeks: Option<Greeks>,
+}
+
+impl YOptionContract {
+    /// Greeks of the contract at time `now` by the Black-Scholes model, using
+    /// the implied volatility of the contract and the given risk-free rate, see
+    /// `Greeks::black_scholes`
+    pub fn greeks(
+        &self,
+        kind: OptionKind,
+        underlying_price: Decimal,
+        rate: f64,
+        now: YTimestamp,
+    ) -> Option<Greeks> {
+        let seconds = self.expiration?.seconds().saturating_sub(now.seconds());
+        let years = seconds as f64 / SECONDS_PER_YEAR;
+        Greeks::black_scholes(
+            kind,
+            decimal_to_f64(underlying_price),
+            decimal_to_f64(self.strike?),
+            years,
+            decimal_to_f64(self.implied_volatility?),
+            rate,
+        )
+    }
+
+    /// Return the contract alongside its greeks, see `YOptionContract::greeks`
+    pub fn enriched(
+        &self,
+        kind: OptionKind,
+        underlying_price: Decimal,
+        rate: f64,
+        now: YTimestamp,
+    ) -> EnrichedContract {
+        EnrichedContract {
+            kind,
+            contract: self.clone(),
+            greeks: self.greeks(kind, underlying_price, rate, now),
+        }
+    }
+}
+
+impl YOptionChain {
+    /// Return calls and puts of all expirations in the chain alongside their greeks, using the
+    /// regular market price of the underlying, see `YOptionContract::greeks`
+    pub fn enriched(&self, rate: f64, now: YTimestamp) -> Vec<EnrichedContract> {
+        let mut contracts = Vec::new();
+        for data in &self.option_chain.result {
+            let price = data.quote.regular_market_price;
+            for details in &data.options {
+                for (kind, list) in [
+                    (OptionKind::Call, &details.calls),
+                    (OptionKind::Put, &details.puts),
+                ] {
+                    contracts.extend(list.iter().map(|c| c.enriched(kind, price, rate, now)));
+                }
+            }
+        }
+        contracts
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decimal_from_f64;
+
+    fn assert_close(value: f64, expected: f64) {
+        assert!(
+            (value - expected).abs() < 1e-4,
+            "{value} differs from {expected}"
+        );
+    }
+
+    #[test]
+    fn test_black_scholes() {
+        let call = Greeks::black_scholes(OptionKind::Call, 100.0, 100.0, 1.0, 0.2, 0.05).unwrap();
+        assert_close(call.delta, 0.6368);
+        assert_close(call.gamma, 0.018762);
+        assert_close(call.vega, 0.37524);
+        assert_close(call.theta, -6.4140 / 365.0);
+        let put = Greeks::black_scholes(OptionKind::Put, 100.0, 100.0, 1.0, 0.2, 0.05).unwrap();
+        assert_close(put.delta, -0.3632);
+        assert_close(put.gamma, call.gamma);
+        assert_close(put.theta, -1.6579 / 365.0);
+        assert!(Greeks::black_scholes(OptionKind::Put, 100.0, 100.0, 0.0, 0.2, 0.05).is_none());
+    }
+
+    #[test]
+    fn test_enriched_contract() {
+        let json = r#"{
+            "contractSymbol": "AAPL240216C00187500",
+            "strike": 187.5,
+            "currency": "USD",
+            "expiration": 1708041600,
+            "impliedVolatility": 0.1797,
+            "inTheMoney": false
+        }"#;
+        let contract: YOptionContract = serde_json::from_str(json).unwrap();
+        let price = decimal_from_f64(185.0).unwrap();
+        // one week before expiration
+        let enriched = contract.enriched(
+            OptionKind::Call,
+            price,
+            0.05,
+            YTimestamp::from_seconds(1708041600 - 7 * 86400),
+        );
+        let greeks = enriched.greeks.unwrap();
+        assert!(greeks.delta > 0.0 && greeks.delta < 0.5);
+        assert!(greeks.theta < 0.0);
+        assert!(contract
+            .greeks(
+                OptionKind::Call,
+                price,
+                0.05,
+                YTimestamp::from_seconds(1708041600)
+            )
+            .is_none());
+    }
+}
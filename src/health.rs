@@ -0,0 +1,98 @@
+//! Report on the reachability of the endpoints of yahoo! finance, e.g. for a readiness probe
+//! of a service depending on them, see `YahooConnector::health_check`.
+use std::time::{Duration, Instant};
+
+use super::YahooError;
+
+/// Outcome of a lightweight request to an endpoint
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EndpointHealth {
+    /// Name of the endpoint, e.g. `chart`
+    pub endpoint: &'static str,
+    /// Whether the request succeeded
+    pub reachable: bool,
+    /// Time until the response has been processed or the request failed
+    pub latency: Duration,
+    /// Error message if the request failed
+    pub error: Option<String>,
+}
+
+impl EndpointHealth {
+    pub(crate) fn new<T>(
+        endpoint: &'static str,
+        started: Instant,
+        result: Result<T, YahooError>,
+    ) -> EndpointHealth {
+        EndpointHealth {
+            endpoint,
+            reachable: result.is_ok(),
+            latency: started.elapsed(),
+            error: result.err().map(|e| e.to_string()),
+        }
+    }
+}
+
+/// Reachability of the chart, search and crumb endpoints
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HealthReport {
+    pub chart: EndpointHealth,
+    pub search: EndpointHealth,
+    /// `None` if credentials are disabled by `AuthMode::None`
+    pub crumb: Option<EndpointHealth>,
+    /// Whether the connector holds a valid crumb after the check
+    pub authenticated: bool,
+}
+
+impl HealthReport {
+    /// Return whether all checked endpoints are reachable and, unless credentials are
+    /// disabled, the connector is authenticated
+    pub fn is_healthy(&self) -> bool {
+        self.chart.reachable
+            && self.search.reachable
+            && self
+                .crumb
+                .as_ref()
+                .map_or(true, |crumb| crumb.reachable && self.authenticated)
+    }
+
+    /// Endpoints which could not be reached
+    pub fn unreachable(&self) -> Vec<&EndpointHealth> {
+        [Some(&self.chart), Some(&self.search), self.crumb.as_ref()]
+            .into_iter()
+            .flatten()
+            .filter(|health| !health.reachable)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_health_report() {
+        let started = Instant::now();
+        let mut report = HealthReport {
+            chart: EndpointHealth::new("chart", started, Ok(())),
+            search: EndpointHealth::new("search", started, Ok(())),
+            crumb: None,
+            authenticated: false,
+        };
+        assert!(report.is_healthy());
+        assert!(report.unreachable().is_empty());
+
+        report.crumb = Some(EndpointHealth::new::<()>(
+            "crumb",
+            started,
+            Err(YahooError::NoCrumb),
+        ));
+        assert!(!report.is_healthy());
+        let unreachable = report.unreachable();
+        assert_eq!(unreachable.len(), 1);
+        assert_eq!(unreachable[0].endpoint, "crumb");
+        assert_eq!(
+            unreachable[0].error.as_deref(),
+            Some("fetching the crumb from yahoo! finance failed")
+        );
+    }
+}
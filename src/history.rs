@@ -0,0 +1,874 @@
+//! Retrieval of quote histories, either as response of a `HistoryQuery` or as quote series
+//! with a pipeline of post-processing steps.
+//!
+//! ```rust,ignore
+//! let series = HistoryRequest::new("AAPL")
+//!     .interval("1d")
+//!     .range("1y")
+//!     .pipe(adjust_dividends)
+//!     .pipe(fill_gaps)
+//!     .fetch(&connector)
+//!     .await?;
+//! ```
+use std::collections::HashMap;
+use std::ops::{BitOr, BitOrAssign};
+
+use time::{Duration, OffsetDateTime, UtcOffset, Weekday};
+
+use super::quotes::ZERO;
+use super::{
+    AssetClass, CapitalGain, Dividend, Quote, Split, YMetaData, YResponse, YahooConnector,
+    YahooError,
+};
+
+/// Quotes of a symbol together with its events and meta data
+#[derive(Debug, Clone)]
+pub struct QuoteSeries {
+    pub meta: YMetaData,
+    pub quotes: Vec<Quote>,
+    pub splits: Vec<Split>,
+    pub dividends: Vec<Dividend>,
+}
+
+impl QuoteSeries {
+    pub fn from_response(response: &YResponse) -> Result<QuoteSeries, YahooError> {
+        Ok(QuoteSeries {
+            meta: response.metadata()?,
+            quotes: response.quotes()?,
+            splits: response.splits()?,
+            dividends: response.dividends()?,
+        })
+    }
+}
+
+/// A post-processing step of a quote series
+pub type Transformation = Box<dyn Fn(QuoteSeries) -> Result<QuoteSeries, YahooError> + Send + Sync>;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Span {
+    Range(String),
+    Period(OffsetDateTime, OffsetDateTime),
+}
+
+/// Dividends, splits and capital gains of a symbol, each sorted by date, see
+/// `YahooConnector::get_events`
+#[derive(Debug, Clone, Default)]
+pub struct CorporateActions {
+    pub dividends: Vec<Dividend>,
+    pub splits: Vec<Split>,
+    pub capital_gains: Vec<CapitalGain>,
+}
+
+impl CorporateActions {
+    /// Collect the events of the first result block, ignoring its quotes
+    pub fn from_response(response: &YResponse) -> Result<CorporateActions, YahooError> {
+        let Some(events) = &response.result(0)?.events else {
+            return Ok(CorporateActions::default());
+        };
+        fn sorted<T: Clone>(events: &Option<HashMap<i64, T>>) -> Vec<T> {
+            let mut events: Vec<(i64, T)> = events
+                .iter()
+                .flatten()
+                .map(|(date, event)| (*date, event.clone()))
+                .collect();
+            events.sort_unstable_by_key(|(date, _)| *date);
+            events.into_iter().map(|(_, event)| event).collect()
+        }
+        Ok(CorporateActions {
+            dividends: sorted(&events.dividends),
+            splits: sorted(&events.splits),
+            capital_gains: sorted(&events.capital_gains),
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.dividends.is_empty() && self.splits.is_empty() && self.capital_gains.is_empty()
+    }
+}
+
+/// Whether the end of a period includes a bar time stamped exactly at the end.
+///
+/// yahoo! finance treats `period2` differently depending on the interval, e.g. a daily bar
+/// time stamped at `period2` may or may not be returned, and the bar in progress may extend
+/// beyond it. With an explicit bound, the request is widened by a second and bars (and events)
+/// beyond the bound are dropped from the response, so the same period returns the same bars
+/// for every interval.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EndBound {
+    /// Keep bars time stamped at or before the end
+    Inclusive,
+    /// Keep bars time stamped before the end
+    Exclusive,
+}
+
+/// Close prices returned by the chart endpoint, see `HistoryQuery::close`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CloseAdjustment {
+    /// Close prices adjusted for splits and dividends
+    Adjusted,
+    /// Close prices as traded (adjusted for splits only)
+    Unadjusted,
+}
+
+impl CloseAdjustment {
+    fn param(&self) -> &'static str {
+        match self {
+            CloseAdjustment::Adjusted => "adjusted",
+            CloseAdjustment::Unadjusted => "unadjusted",
+        }
+    }
+}
+
+/// Set of events to be requested together with the quotes of a symbol, combined with `|`,
+/// e.g. `EventsFilter::DIVIDENDS | EventsFilter::SPLITS`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct EventsFilter(u8);
+
+impl EventsFilter {
+    pub const NONE: EventsFilter = EventsFilter(0);
+    pub const DIVIDENDS: EventsFilter = EventsFilter(1);
+    pub const SPLITS: EventsFilter = EventsFilter(2);
+    pub const CAPITAL_GAINS: EventsFilter = EventsFilter(4);
+    pub const ALL: EventsFilter = EventsFilter(7);
+
+    /// Return whether all events of `other` are contained in this set
+    pub fn contains(&self, other: EventsFilter) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// Return the value of the `events` query parameter, or `None` if no events are requested
+    fn param(&self) -> Option<String> {
+        let names: Vec<&str> = [
+            (EventsFilter::DIVIDENDS, "div"),
+            (EventsFilter::SPLITS, "split"),
+            (EventsFilter::CAPITAL_GAINS, "capitalGains"),
+        ]
+        .iter()
+        .filter(|(event, _)| self.contains(*event))
+        .map(|(_, name)| *name)
+        .collect();
+        if names.is_empty() {
+            None
+        } else {
+            Some(names.join("|"))
+        }
+    }
+}
+
+impl Default for EventsFilter {
+    fn default() -> Self {
+        EventsFilter::ALL
+    }
+}
+
+impl BitOr for EventsFilter {
+    type Output = EventsFilter;
+
+    fn bitor(self, rhs: EventsFilter) -> EventsFilter {
+        EventsFilter(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for EventsFilter {
+    fn bitor_assign(&mut self, rhs: EventsFilter) {
+        self.0 |= rhs.0;
+    }
+}
+
+/// Query of the chart endpoint, combining all parameters supported by this crate. By default,
+/// the daily quotes of the last month are requested together with all events.
+///
+/// ```rust,ignore
+/// let response = HistoryQuery::new("AAPL")
+///     .period(start, end)
+///     .interval("1h")
+///     .prepost(true)
+///     .fetch(&connector)
+///     .await?;
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HistoryQuery {
+    ticker: String,
+    interval: String,
+    span: Span,
+    prepost: bool,
+    events: EventsFilter,
+    comparisons: Vec<String>,
+    end_bound: Option<EndBound>,
+    close: Option<CloseAdjustment>,
+    include_adjusted_close: Option<bool>,
+}
+
+impl HistoryQuery {
+    pub fn new(ticker: &str) -> HistoryQuery {
+        HistoryQuery {
+            ticker: ticker.to_string(),
+            interval: "1d".to_string(),
+            span: Span::Range("1mo".to_string()),
+            prepost: false,
+            events: EventsFilter::ALL,
+            comparisons: Vec::new(),
+            end_bound: None,
+            close: None,
+            include_adjusted_close: None,
+        }
+    }
+
+    pub fn ticker(&self) -> &str {
+        &self.ticker
+    }
+
+    pub fn interval(mut self, interval: &str) -> Self {
+        self.interval = interval.to_string();
+        self
+    }
+
+    /// Request the quotes of a range relative to now, e.g. "1y" or "max"
+    pub fn range(mut self, range: &str) -> Self {
+        self.span = Span::Range(range.to_string());
+        self
+    }
+
+    /// Request the quotes from start to end
+    pub fn period(mut self, start: OffsetDateTime, end: OffsetDateTime) -> Self {
+        self.span = Span::Period(start, end);
+        self
+    }
+
+    /// Include quotes before and after regular trading hours (intraday intervals only)
+    pub fn prepost(mut self, prepost: bool) -> Self {
+        self.prepost = prepost;
+        self
+    }
+
+    /// Set the events to be requested
+    pub fn events(mut self, events: EventsFilter) -> Self {
+        self.events = events;
+        self
+    }
+
+    /// Request the close prices of additional symbols, see `YResponse::comparisons`
+    pub fn comparisons(mut self, symbols: &[&str]) -> Self {
+        self.comparisons = symbols.iter().map(|s| s.to_string()).collect();
+        self
+    }
+
+    /// Normalize the end of a period to the given bound, see `EndBound`. Without a bound, the
+    /// end is passed to yahoo! finance as is. Ignored for ranges.
+    pub fn end_bound(mut self, bound: EndBound) -> Self {
+        self.end_bound = Some(bound);
+        self
+    }
+
+    /// Request adjusted or unadjusted close prices. Without, the default of yahoo! finance
+    /// applies (unadjusted close prices).
+    pub fn close(mut self, close: CloseAdjustment) -> Self {
+        self.close = Some(close);
+        self
+    }
+
+    /// Set whether the adjusted close is included in the response. Excluding it shrinks the
+    /// response of long histories, but leaves the adjusted close of the quotes at zero.
+    pub fn include_adjusted_close(mut self, include: bool) -> Self {
+        self.include_adjusted_close = Some(include);
+        self
+    }
+
+    /// Timestamp before which bars are kept, if the end is normalized
+    fn until(&self) -> Option<i64> {
+        match (&self.span, self.end_bound?) {
+            (Span::Period(_, end), EndBound::Inclusive) => Some(end.unix_timestamp() + 1),
+            (Span::Period(_, end), EndBound::Exclusive) => Some(end.unix_timestamp()),
+            (Span::Range(_), _) => None,
+        }
+    }
+
+    /// Drop the bars beyond the end of the period, if the end is normalized
+    pub(crate) fn trim(&self, response: &mut YResponse) {
+        if let Some(until) = self.until() {
+            for result in &mut response.chart.result {
+                result.retain_before(until.max(0) as u64);
+            }
+        }
+    }
+
+    /// Return the query parameters of the request
+    pub(crate) fn params(&self) -> Vec<(&'static str, String)> {
+        let mut params = vec![
+            ("symbol", self.ticker.clone()),
+            ("interval", self.interval.clone()),
+        ];
+        match &self.span {
+            Span::Range(range) => params.push(("range", range.clone())),
+            Span::Period(start, end) => {
+                params.push(("period1", start.unix_timestamp().to_string()));
+                // with an explicit bound, request a second more, as yahoo! finance might drop a
+                // bar at the end; bars beyond the bound are dropped by `trim`
+                let end = match self.end_bound {
+                    Some(_) => end.unix_timestamp() + 1,
+                    None => end.unix_timestamp(),
+                };
+                params.push(("period2", end.to_string()));
+            }
+        }
+        if self.prepost {
+            params.push(("includePrePost", "true".to_string()));
+        }
+        if let Some(events) = self.events.param() {
+            params.push(("events", events));
+        }
+        if !self.comparisons.is_empty() {
+            params.push(("comparisons", self.comparisons.join(",")));
+        }
+        if let Some(close) = self.close {
+            params.push(("close", close.param().to_string()));
+        }
+        if let Some(include) = self.include_adjusted_close {
+            params.push(("includeAdjustedClose", include.to_string()));
+        }
+        params
+    }
+
+    #[cfg(not(feature = "blocking"))]
+    pub async fn fetch(&self, connector: &YahooConnector) -> Result<YResponse, YahooError> {
+        connector.get_history(self).await
+    }
+
+    #[cfg(feature = "blocking")]
+    pub fn fetch(&self, connector: &YahooConnector) -> Result<YResponse, YahooError> {
+        connector.get_history(self)
+    }
+}
+
+/// Request of a quote history, with transformations applied to the fetched series in the
+/// order they have been added
+pub struct HistoryRequest {
+    query: HistoryQuery,
+    transformations: Vec<Transformation>,
+}
+
+impl HistoryRequest {
+    /// Create a request of the daily quotes of the last month
+    pub fn new(ticker: &str) -> HistoryRequest {
+        HistoryRequest::from_query(HistoryQuery::new(ticker))
+    }
+
+    /// Create a request fetching the quotes of the given query
+    pub fn from_query(query: HistoryQuery) -> HistoryRequest {
+        HistoryRequest {
+            query,
+            transformations: Vec::new(),
+        }
+    }
+
+    pub fn interval(mut self, interval: &str) -> Self {
+        self.query = self.query.interval(interval);
+        self
+    }
+
+    /// Request the quotes of a range relative to now, e.g. "1y" or "max"
+    pub fn range(mut self, range: &str) -> Self {
+        self.query = self.query.range(range);
+        self
+    }
+
+    /// Request the quotes from start to end
+    pub fn period(mut self, start: OffsetDateTime, end: OffsetDateTime) -> Self {
+        self.query = self.query.period(start, end);
+        self
+    }
+
+    /// Set the events to be requested, all by default
+    pub fn events(mut self, events: EventsFilter) -> Self {
+        self.query = self.query.events(events);
+        self
+    }
+
+    /// Normalize the end of a period, see `HistoryQuery::end_bound`
+    pub fn end_bound(mut self, bound: EndBound) -> Self {
+        self.query = self.query.end_bound(bound);
+        self
+    }
+
+    /// Add a transformation of the fetched series
+    pub fn pipe<F>(mut self, transformation: F) -> Self
+    where
+        F: Fn(QuoteSeries) -> Result<QuoteSeries, YahooError> + Send + Sync + 'static,
+    {
+        self.transformations.push(Box::new(transformation));
+        self
+    }
+
+    /// Apply all transformations to the series
+    pub fn apply(&self, series: QuoteSeries) -> Result<QuoteSeries, YahooError> {
+        self.transformations
+            .iter()
+            .try_fold(series, |series, transformation| transformation(series))
+    }
+
+    /// Fetch the quote history and apply all transformations
+    #[cfg(not(feature = "blocking"))]
+    pub async fn fetch(&self, connector: &YahooConnector) -> Result<QuoteSeries, YahooError> {
+        let response = self.query.fetch(connector).await?;
+        self.apply(QuoteSeries::from_response(&response)?)
+    }
+
+    /// Fetch the quote history and apply all transformations
+    #[cfg(feature = "blocking")]
+    pub fn fetch(&self, connector: &YahooConnector) -> Result<QuoteSeries, YahooError> {
+        let response = self.query.fetch(connector)?;
+        self.apply(QuoteSeries::from_response(&response)?)
+    }
+}
+
+/// Adjust open, high, low and close for dividends, using the ratio of adjusted close and close
+pub fn adjust_dividends(mut series: QuoteSeries) -> Result<QuoteSeries, YahooError> {
+    for quote in &mut series.quotes {
+        if quote.close == ZERO {
+            continue;
+        }
+        let factor = quote.adjclose / quote.close;
+        quote.open *= factor;
+        quote.high *= factor;
+        quote.low *= factor;
+        quote.close = quote.adjclose;
+    }
+    Ok(series)
+}
+
+/// Insert quotes for trading days missing in a daily series, repeating the previous close
+/// with zero volume. Trading days are taken from the calendar of the exchange if supported,
+/// otherwise all weekdays are considered trading days. Other intervals are left unchanged.
+pub fn fill_gaps(mut series: QuoteSeries) -> Result<QuoteSeries, YahooError> {
+    let quotes = std::mem::take(&mut series.quotes);
+    series.quotes = carry_forward(quotes, &series.meta, false)?
+        .into_iter()
+        .map(|filled| filled.quote)
+        .collect();
+    Ok(series)
+}
+
+/// Quote of a series with gaps filled, see `YResponse::nav_quotes`
+#[derive(Debug, Clone, PartialEq)]
+pub struct FilledQuote {
+    pub quote: Quote,
+    /// Whether the quote has been carried forward from the previous one
+    pub filled: bool,
+}
+
+/// Return whether the quotes look like net asset values, i.e. open, high, low and close are
+/// equal and there is no volume, as reported once per day for mutual funds
+pub fn is_nav_quotes(quotes: &[Quote]) -> bool {
+    !quotes.is_empty()
+        && quotes
+            .iter()
+            .all(|q| q.volume == 0 && q.open == q.close && q.high == q.close && q.low == q.close)
+}
+
+impl QuoteSeries {
+    /// Return whether the series consists of net asset values, either as it is a mutual fund or
+    /// as its quotes look like net asset values, see `is_nav_quotes`
+    pub fn is_nav_series(&self) -> bool {
+        self.meta.asset_class() == AssetClass::MutualFund || is_nav_quotes(&self.quotes)
+    }
+}
+
+impl YResponse {
+    /// Return whether the response contains net asset values, see `QuoteSeries::is_nav_series`
+    pub fn is_nav_series(&self) -> Result<bool, YahooError> {
+        Ok(self.metadata()?.asset_class() == AssetClass::MutualFund
+            || is_nav_quotes(&self.quotes()?))
+    }
+
+    /// Return all valid quotes of a daily series of net asset values with gaps filled: the
+    /// previous value is carried forward to trading days without a value (e.g. when a fund
+    /// did not report on a day the exchange was open) and replaces values of zero, which
+    /// yahoo! finance occasionally reports instead of a missing value. Unlike `fill_gaps`,
+    /// carried forward quotes are flagged, as they are no valuation of the fund.
+    pub fn nav_quotes(&self) -> Result<Vec<FilledQuote>, YahooError> {
+        carry_forward(self.quotes()?, &self.metadata()?, true)
+    }
+}
+
+/// Insert the previous quote for trading days missing in a daily series and, if `replace_zero`
+/// is set, replace quotes with zero close by the previous one. Other intervals are left
+/// unchanged.
+fn carry_forward(
+    quotes: Vec<Quote>,
+    meta: &YMetaData,
+    replace_zero: bool,
+) -> Result<Vec<FilledQuote>, YahooError> {
+    let original = |quote| FilledQuote {
+        quote,
+        filled: false,
+    };
+    if meta.data_granularity != "1d" || quotes.is_empty() {
+        return Ok(quotes.into_iter().map(original).collect());
+    }
+    let offset =
+        UtcOffset::from_whole_seconds(meta.gmtoffset).map_err(|_| YahooError::DataInconsistency)?;
+    let calendar = meta.exchange_calendar();
+    let is_trading_day = |time: OffsetDateTime| match calendar {
+        Some(calendar) => calendar.is_trading_day(time.date()),
+        None => !matches!(time.weekday(), Weekday::Saturday | Weekday::Sunday),
+    };
+    let repeat = |previous: &Quote, timestamp| Quote {
+        timestamp,
+        open: previous.close,
+        high: previous.close,
+        low: previous.close,
+        volume: 0,
+        close: previous.close,
+        adjclose: previous.adjclose,
+    };
+    let mut filled: Vec<FilledQuote> = Vec::with_capacity(quotes.len());
+    for quote in quotes {
+        let Some(previous) = filled.last().map(|f| f.quote.clone()) else {
+            filled.push(original(quote));
+            continue;
+        };
+        let mut time = to_datetime(previous.timestamp, offset)? + Duration::days(1);
+        let end = to_datetime(quote.timestamp, offset)?;
+        while time.date() < end.date() {
+            if is_trading_day(time) {
+                filled.push(FilledQuote {
+                    quote: repeat(&previous, time.unix_timestamp() as u64),
+                    filled: true,
+                });
+            }
+            time += Duration::days(1);
+        }
+        if replace_zero && quote.close == ZERO {
+            filled.push(FilledQuote {
+                quote: repeat(&previous, quote.timestamp),
+                filled: true,
+            });
+        } else {
+            filled.push(original(quote));
+        }
+    }
+    Ok(filled)
+}
+
+/// Start of the quotes of a symbol within a merged history
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Splice {
+    pub symbol: String,
+    /// Timestamp of the first quote of the symbol
+    pub timestamp: u64,
+}
+
+/// Quotes of a security traded under several symbols over time, e.g. after a change of the
+/// ticker from `FB` to `META`, see `get_quote_history_merged`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MergedHistory {
+    pub quotes: Vec<Quote>,
+    /// Splice points in chronological order, one per symbol with quotes
+    pub splices: Vec<Splice>,
+}
+
+impl MergedHistory {
+    /// Return the symbol the quote with the given timestamp has been retrieved for
+    pub fn symbol_at(&self, timestamp: u64) -> Option<&str> {
+        self.splices
+            .iter()
+            .take_while(|splice| splice.timestamp <= timestamp)
+            .last()
+            .map(|splice| splice.symbol.as_str())
+    }
+
+    /// Append the quotes of the symbol within its period
+    pub(crate) fn append(&mut self, period: &SymbolPeriod, quotes: Vec<Quote>) {
+        let from = period.from.unix_timestamp();
+        let until = period.until.unix_timestamp();
+        let mut quotes = quotes
+            .into_iter()
+            .filter(|q| from <= q.timestamp as i64 && (q.timestamp as i64) < until)
+            .peekable();
+        if let Some(first) = quotes.peek() {
+            self.splices.push(Splice {
+                symbol: period.symbol.clone(),
+                timestamp: first.timestamp,
+            });
+        }
+        self.quotes.extend(quotes);
+    }
+}
+
+/// Period in which a symbol applies, from (inclusive) until (exclusive)
+pub(crate) struct SymbolPeriod {
+    pub symbol: String,
+    pub from: OffsetDateTime,
+    pub until: OffsetDateTime,
+}
+
+/// Split the period from start to end (inclusive) into the periods of the symbols, each given
+/// with the time from which on it applies
+pub(crate) fn symbol_periods(
+    symbols: &[(&str, OffsetDateTime)],
+    start: OffsetDateTime,
+    end: OffsetDateTime,
+) -> Vec<SymbolPeriod> {
+    let mut symbols = symbols.to_vec();
+    symbols.sort_by_key(|(_, from)| *from);
+    let end = end + Duration::seconds(1);
+    let mut periods = Vec::new();
+    for (i, (symbol, from)) in symbols.iter().enumerate() {
+        let from = (*from).max(start);
+        let until = symbols.get(i + 1).map_or(end, |(_, next)| (*next).min(end));
+        if from < until {
+            periods.push(SymbolPeriod {
+                symbol: symbol.to_string(),
+                from,
+                until,
+            });
+        }
+    }
+    periods
+}
+
+fn to_datetime(timestamp: u64, offset: UtcOffset) -> Result<OffsetDateTime, YahooError> {
+    OffsetDateTime::from_unix_timestamp(timestamp as i64)
+        .map(|time| time.to_offset(offset))
+        .map_err(|_| YahooError::DataInconsistency)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::chart_fixture;
+    use crate::Decimal;
+
+    fn series() -> QuoteSeries {
+        let response = chart_fixture("equity_aapl_1d").unwrap().response().unwrap();
+        QuoteSeries::from_response(&response).unwrap()
+    }
+
+    #[test]
+    fn test_history_query_params() {
+        let query = HistoryQuery::new("AAPL");
+        assert_eq!(
+            query.params(),
+            vec![
+                ("symbol", "AAPL".to_string()),
+                ("interval", "1d".to_string()),
+                ("range", "1mo".to_string()),
+                ("events", "div|split|capitalGains".to_string()),
+            ]
+        );
+        let start = OffsetDateTime::from_unix_timestamp(1704067200).unwrap();
+        let end = OffsetDateTime::from_unix_timestamp(1706745600).unwrap();
+        let query = query
+            .period(start, end)
+            .interval("1h")
+            .prepost(true)
+            .events(EventsFilter::DIVIDENDS | EventsFilter::SPLITS)
+            .comparisons(&["MSFT", "^GSPC"])
+            .close(CloseAdjustment::Adjusted)
+            .include_adjusted_close(false);
+        assert_eq!(
+            query.params(),
+            vec![
+                ("symbol", "AAPL".to_string()),
+                ("interval", "1h".to_string()),
+                ("period1", "1704067200".to_string()),
+                ("period2", "1706745600".to_string()),
+                ("includePrePost", "true".to_string()),
+                ("events", "div|split".to_string()),
+                ("comparisons", "MSFT,^GSPC".to_string()),
+                ("close", "adjusted".to_string()),
+                ("includeAdjustedClose", "false".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_end_bound() {
+        let mut response = chart_fixture("equity_aapl_1d").unwrap().response().unwrap();
+        let timestamps = response.result(0).unwrap().timestamp.clone();
+        let start = OffsetDateTime::from_unix_timestamp(timestamps[0] as i64).unwrap();
+        let end = OffsetDateTime::from_unix_timestamp(timestamps[3] as i64).unwrap();
+
+        let query = HistoryQuery::new("AAPL").period(start, end);
+        assert!(query
+            .params()
+            .contains(&("period2", timestamps[3].to_string())));
+        let query = query.end_bound(EndBound::Inclusive);
+        assert!(query
+            .params()
+            .contains(&("period2", (timestamps[3] + 1).to_string())));
+        query.trim(&mut response);
+        assert_eq!(response.quotes().unwrap().len(), 4);
+        assert_eq!(response.result(0).unwrap().timestamp, timestamps[..4]);
+
+        let query = HistoryQuery::new("AAPL")
+            .period(start, end)
+            .end_bound(EndBound::Exclusive);
+        assert!(query
+            .params()
+            .contains(&("period2", (timestamps[3] + 1).to_string())));
+        query.trim(&mut response);
+        let quotes = response.quotes().unwrap();
+        assert_eq!(quotes.len(), 3);
+        assert_eq!(quotes[2].timestamp, timestamps[2]);
+    }
+
+    #[test]
+    fn test_corporate_actions() {
+        let response = chart_fixture("equity_aapl_1d").unwrap().response().unwrap();
+        let actions = CorporateActions::from_response(&response).unwrap();
+        assert!(!actions.is_empty());
+        assert_eq!(actions.dividends.len(), response.dividends().unwrap().len());
+        assert!(actions.capital_gains.is_empty());
+
+        let mut json = chart_fixture("equity_aapl_1d").unwrap().json_value();
+        json["chart"]["result"][0]
+            .as_object_mut()
+            .unwrap()
+            .remove("events");
+        let response = YResponse::from_json(json).unwrap();
+        assert!(CorporateActions::from_response(&response)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_events_filter() {
+        let mut events = EventsFilter::NONE;
+        assert!(events.is_empty());
+        assert_eq!(events.param(), None);
+        events |= EventsFilter::CAPITAL_GAINS;
+        assert!(events.contains(EventsFilter::CAPITAL_GAINS));
+        assert!(!events.contains(EventsFilter::DIVIDENDS));
+        assert_eq!(events.param().as_deref(), Some("capitalGains"));
+        assert_eq!(
+            EventsFilter::DIVIDENDS | EventsFilter::SPLITS | EventsFilter::CAPITAL_GAINS,
+            EventsFilter::ALL
+        );
+    }
+
+    #[test]
+    fn test_pipeline() {
+        let request = HistoryRequest::new("AAPL")
+            .pipe(|mut series| {
+                series.quotes.truncate(1);
+                Ok(series)
+            })
+            .pipe(|series| {
+                assert_eq!(series.quotes.len(), 1);
+                Err(YahooError::EmptyDataSet)
+            });
+        assert!(matches!(
+            request.apply(series()),
+            Err(YahooError::EmptyDataSet)
+        ));
+    }
+
+    #[test]
+    fn test_adjust_dividends() {
+        let adjusted = adjust_dividends(series()).unwrap();
+        for (quote, original) in adjusted.quotes.iter().zip(series().quotes) {
+            assert_eq!(quote.close, original.adjclose);
+            assert!(quote.low <= quote.close && quote.close <= quote.high);
+        }
+    }
+
+    #[test]
+    fn test_merged_history() {
+        let time = |seconds| OffsetDateTime::from_unix_timestamp(seconds).unwrap();
+        let quote = |timestamp| Quote {
+            timestamp,
+            open: Decimal::from(1),
+            high: Decimal::from(1),
+            low: Decimal::from(1),
+            volume: 1000,
+            close: Decimal::from(1),
+            adjclose: Decimal::from(1),
+        };
+        let periods = symbol_periods(
+            &[("META", time(300)), ("FB", time(0)), ("XX", time(600))],
+            time(100),
+            time(500),
+        );
+        assert_eq!(periods.len(), 2);
+        assert_eq!(periods[0].symbol, "FB");
+        assert_eq!(periods[0].from, time(100));
+        assert_eq!(periods[1].until, time(501));
+
+        let mut merged = MergedHistory::default();
+        for period in &periods {
+            // overlapping responses are cut at the splice point
+            merged.append(period, (1..=5).map(|i| quote(i * 100)).collect());
+        }
+        let timestamps: Vec<u64> = merged.quotes.iter().map(|q| q.timestamp).collect();
+        assert_eq!(timestamps, vec![100, 200, 300, 400, 500]);
+        assert_eq!(
+            merged.splices[1],
+            Splice {
+                symbol: "META".to_string(),
+                timestamp: 300
+            }
+        );
+        assert_eq!(merged.symbol_at(200), Some("FB"));
+        assert_eq!(merged.symbol_at(300), Some("META"));
+        assert_eq!(merged.symbol_at(50), None);
+    }
+
+    #[test]
+    fn test_fill_gaps() {
+        let mut series = series();
+        let quote = |timestamp, close: u32| Quote {
+            timestamp,
+            open: Decimal::from(close),
+            high: Decimal::from(close),
+            low: Decimal::from(close),
+            volume: 1000,
+            close: Decimal::from(close),
+            adjclose: Decimal::from(close),
+        };
+        // Thursday 2024-02-15 and Wednesday 2024-02-21, 9:30 EST; Monday 2024-02-19 is a holiday
+        series.quotes = vec![quote(1708007400, 10), quote(1708525800, 12)];
+        let filled = fill_gaps(series).unwrap();
+        let timestamps: Vec<u64> = filled.quotes.iter().map(|q| q.timestamp).collect();
+        assert_eq!(
+            timestamps,
+            vec![1708007400, 1708093800, 1708439400, 1708525800]
+        );
+        assert_eq!(filled.quotes[1].close, Decimal::from(10));
+        assert_eq!(filled.quotes[2].volume, 0);
+    }
+
+    #[test]
+    fn test_nav_quotes() {
+        let response = chart_fixture("mutual_fund_amagx_1d")
+            .unwrap()
+            .response()
+            .unwrap();
+        assert!(response.is_nav_series().unwrap());
+        assert!(is_nav_quotes(&response.quotes().unwrap()));
+        assert!(!series().is_nav_series());
+        // consecutive trading days from Monday 2023-12-11 to Friday 2023-12-15
+        let quotes = response.nav_quotes().unwrap();
+        assert_eq!(quotes.len(), 5);
+        assert!(quotes.iter().all(|q| !q.filled));
+
+        let mut json = chart_fixture("mutual_fund_amagx_1d").unwrap().json_value();
+        let result = &mut json["chart"]["result"][0];
+        // drop Tuesday and report zero on Thursday
+        for field in ["open", "high", "low", "close"] {
+            result["indicators"]["quote"][0][field][1] = serde_json::Value::Null;
+            result["indicators"]["quote"][0][field][3] = serde_json::json!(0.0);
+        }
+        let response = YResponse::from_json(json).unwrap();
+        let quotes = response.nav_quotes().unwrap();
+        let filled: Vec<bool> = quotes.iter().map(|q| q.filled).collect();
+        assert_eq!(filled, vec![false, true, false, true, false]);
+        assert_eq!(quotes[1].quote.timestamp, 1702390800);
+        assert_eq!(quotes[1].quote.close, quotes[0].quote.close);
+        assert_eq!(quotes[3].quote.close, quotes[2].quote.close);
+    }
+}
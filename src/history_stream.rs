@@ -0,0 +1,298 @@
+//! Bars of long histories, requested in chunks of time and yielded as they arrive.
+//!
+//! Multi-decade histories or intraday histories beyond the span yahoo! finance returns per
+//! request are split into consecutive chunks, one request each: a week of one-minute bars,
+//! 30 days of other bars shorter than an hour, a year of hourly bars or five years of longer
+//! bars. Bars of a chunk are yielded as soon as it has been received, so consumers may process
+//! or store them while the remaining chunks are downloaded. Chunks without bars (e.g. before
+//! the listing of the ticker) are skipped; any other error is returned as last item.
+//!
+//! ```rust,ignore
+//! let mut bars = connector.stream_quote_history("^GSPC", start, end, "1d");
+//! while let Some(bar) = bars.next().await {
+//!     store.insert(bar?)?;
+//! }
+//! ```
+use std::collections::VecDeque;
+#[cfg(not(feature = "blocking"))]
+use std::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+#[cfg(not(feature = "blocking"))]
+use futures_util::Stream;
+use time::{Duration, OffsetDateTime};
+
+use super::{Quote, YahooError};
+
+/// Span of time requested at once for bars of the given interval
+fn chunk_span(interval: &str) -> Duration {
+    match interval {
+        "1m" => Duration::days(7),
+        "2m" | "5m" | "15m" | "30m" | "90m" => Duration::days(30),
+        "60m" | "1h" => Duration::days(365),
+        _ => Duration::days(5 * 365),
+    }
+}
+
+/// Consecutive periods covering `start` to `end`
+pub(crate) fn chunks(
+    start: OffsetDateTime,
+    end: OffsetDateTime,
+    interval: &str,
+) -> VecDeque<(OffsetDateTime, OffsetDateTime)> {
+    let span = chunk_span(interval);
+    let mut chunks = VecDeque::new();
+    let mut chunk_start = start;
+    while chunk_start < end {
+        let chunk_end = (chunk_start + span).min(end);
+        chunks.push_back((chunk_start, chunk_end));
+        chunk_start = chunk_end;
+    }
+    chunks
+}
+
+/// Periods not requested yet and the bars of the current chunk not consumed yet
+struct Bars<F> {
+    fetch: F,
+    chunks: VecDeque<(OffsetDateTime, OffsetDateTime)>,
+    quotes: VecDeque<Quote>,
+    last_timestamp: Option<u64>,
+}
+
+impl<F> Bars<F> {
+    fn new(fetch: F, start: OffsetDateTime, end: OffsetDateTime, interval: &str) -> Bars<F> {
+        Bars {
+            fetch,
+            chunks: chunks(start, end, interval),
+            quotes: VecDeque::new(),
+            last_timestamp: None,
+        }
+    }
+
+    /// Keep the bars of a fetched chunk, returning the error of a failed one
+    fn receive(&mut self, chunk: Result<Vec<Quote>, YahooError>) -> Option<YahooError> {
+        match chunk {
+            Ok(quotes) => {
+                // bars at the boundary of two chunks may be returned with both of them
+                let last = self.last_timestamp;
+                self.quotes.extend(
+                    quotes
+                        .into_iter()
+                        .filter(|quote| last.map_or(true, |last| quote.timestamp > last)),
+                );
+                if let Some(quote) = self.quotes.back() {
+                    self.last_timestamp = Some(quote.timestamp);
+                }
+                None
+            }
+            Err(YahooError::EmptyDataSet) => None,
+            Err(e) => {
+                self.chunks.clear();
+                Some(e)
+            }
+        }
+    }
+}
+
+/// Stream of the bars of a history requested in chunks, see `stream_quote_history`
+#[cfg(not(feature = "blocking"))]
+pub struct QuoteStream<'a> {
+    inner: Pin<Box<dyn Stream<Item = Result<Quote, YahooError>> + Send + 'a>>,
+}
+
+#[cfg(not(feature = "blocking"))]
+impl<'a> QuoteStream<'a> {
+    /// Stream calling `fetch(chunk_start, chunk_end)` for the chunks of the period
+    pub(crate) fn new<F, Fut>(
+        start: OffsetDateTime,
+        end: OffsetDateTime,
+        interval: &str,
+        fetch: F,
+    ) -> QuoteStream<'a>
+    where
+        F: FnMut(OffsetDateTime, OffsetDateTime) -> Fut + Send + 'a,
+        Fut: Future<Output = Result<Vec<Quote>, YahooError>> + Send + 'a,
+    {
+        let bars = Bars::new(fetch, start, end, interval);
+        let stream = futures_util::stream::unfold(bars, |mut bars| async {
+            loop {
+                if let Some(quote) = bars.quotes.pop_front() {
+                    return Some((Ok(quote), bars));
+                }
+                let (start, end) = bars.chunks.pop_front()?;
+                let chunk = (bars.fetch)(start, end).await;
+                if let Some(e) = bars.receive(chunk) {
+                    return Some((Err(e), bars));
+                }
+            }
+        });
+        QuoteStream {
+            inner: Box::pin(stream),
+        }
+    }
+}
+
+#[cfg(not(feature = "blocking"))]
+impl Stream for QuoteStream<'_> {
+    type Item = Result<Quote, YahooError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.inner.as_mut().poll_next(cx)
+    }
+}
+
+#[cfg(feature = "blocking")]
+type FetchChunk<'a> =
+    Box<dyn FnMut(OffsetDateTime, OffsetDateTime) -> Result<Vec<Quote>, YahooError> + 'a>;
+
+/// Iterator over the bars of a history requested in chunks, see `stream_quote_history`
+#[cfg(feature = "blocking")]
+pub struct QuoteStream<'a> {
+    bars: Bars<FetchChunk<'a>>,
+}
+
+#[cfg(feature = "blocking")]
+impl<'a> QuoteStream<'a> {
+    /// Iterator calling `fetch(chunk_start, chunk_end)` for the chunks of the period
+    pub(crate) fn new<F>(
+        start: OffsetDateTime,
+        end: OffsetDateTime,
+        interval: &str,
+        fetch: F,
+    ) -> QuoteStream<'a>
+    where
+        F: FnMut(OffsetDateTime, OffsetDateTime) -> Result<Vec<Quote>, YahooError> + 'a,
+    {
+        QuoteStream {
+            bars: Bars::new(Box::new(fetch), start, end, interval),
+        }
+    }
+}
+
+#[cfg(feature = "blocking")]
+impl Iterator for QuoteStream<'_> {
+    type Item = Result<Quote, YahooError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let bars = &mut self.bars;
+        loop {
+            if let Some(quote) = bars.quotes.pop_front() {
+                return Some(Ok(quote));
+            }
+            let (start, end) = bars.chunks.pop_front()?;
+            let chunk = (bars.fetch)(start, end);
+            if let Some(e) = bars.receive(chunk) {
+                return Some(Err(e));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use time::macros::datetime;
+
+    use super::*;
+    use crate::quotes::ZERO;
+
+    fn quote(timestamp: OffsetDateTime) -> Quote {
+        Quote {
+            timestamp: timestamp.unix_timestamp() as u64,
+            open: ZERO,
+            high: ZERO,
+            low: ZERO,
+            volume: 0,
+            close: ZERO,
+            adjclose: ZERO,
+        }
+    }
+
+    /// Daily bars from 2010 on including the end of each chunk, recording the requested chunks
+    fn daily(
+        requested: Arc<Mutex<Vec<(OffsetDateTime, OffsetDateTime)>>>,
+    ) -> impl FnMut(OffsetDateTime, OffsetDateTime) -> Result<Vec<Quote>, YahooError> {
+        move |start, end| {
+            requested.lock().unwrap().push((start, end));
+            let first = start.max(datetime!(2010-01-01 0:00 UTC));
+            if first > end {
+                return Err(YahooError::EmptyDataSet);
+            }
+            let days = (end - first).whole_days();
+            Ok((0..=days)
+                .map(|day| quote(first + Duration::days(day)))
+                .collect())
+        }
+    }
+
+    #[cfg(not(feature = "blocking"))]
+    fn collect(stream: QuoteStream<'_>) -> Vec<Result<Quote, YahooError>> {
+        use futures_util::StreamExt;
+        tokio_test::block_on(stream.collect())
+    }
+
+    #[cfg(not(feature = "blocking"))]
+    fn stream(
+        start: OffsetDateTime,
+        end: OffsetDateTime,
+        requested: Arc<Mutex<Vec<(OffsetDateTime, OffsetDateTime)>>>,
+    ) -> QuoteStream<'static> {
+        let mut fetch = daily(requested);
+        QuoteStream::new(start, end, "1d", move |start, end| {
+            std::future::ready(fetch(start, end))
+        })
+    }
+
+    #[cfg(feature = "blocking")]
+    fn collect(stream: QuoteStream<'_>) -> Vec<Result<Quote, YahooError>> {
+        stream.collect()
+    }
+
+    #[cfg(feature = "blocking")]
+    fn stream(
+        start: OffsetDateTime,
+        end: OffsetDateTime,
+        requested: Arc<Mutex<Vec<(OffsetDateTime, OffsetDateTime)>>>,
+    ) -> QuoteStream<'static> {
+        QuoteStream::new(start, end, "1d", daily(requested))
+    }
+
+    #[test]
+    fn test_quote_stream() {
+        let (start, end) = (
+            datetime!(2000-01-01 0:00 UTC),
+            datetime!(2020-01-01 0:00 UTC),
+        );
+        let requested = Arc::new(Mutex::new(Vec::new()));
+        let quotes: Vec<Quote> = collect(stream(start, end, requested.clone()))
+            .into_iter()
+            .map(Result::unwrap)
+            .collect();
+        let requested = requested.lock().unwrap();
+        assert_eq!(requested.len(), 5);
+        assert_eq!(requested[0].0, start);
+        assert_eq!(requested[4].1, end);
+        // first chunk is empty, bars at the chunk boundaries are not repeated
+        let expected = (end - datetime!(2010-01-01 0:00 UTC)).whole_days() + 1;
+        assert_eq!(quotes.len() as i64, expected);
+        assert!(quotes.windows(2).all(|w| w[0].timestamp < w[1].timestamp));
+    }
+
+    #[test]
+    fn test_chunks() {
+        let start = datetime!(2024-01-01 0:00 UTC);
+        let chunks = chunks(start, start + Duration::days(10), "1m");
+        assert_eq!(
+            chunks,
+            vec![
+                (start, start + Duration::days(7)),
+                (start + Duration::days(7), start + Duration::days(10))
+            ]
+        );
+        assert!(super::chunks(start, start, "1d").is_empty());
+    }
+}
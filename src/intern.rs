@@ -0,0 +1,176 @@
+//! Shared strings for values repeated in many responses, e.g. exchange names and currencies.
+//!
+//! With the feature `intern`, equal strings are deserialized into the same allocation, which
+//! cuts memory use if quotes of many symbols are held at once. Without the feature, each
+//! string is allocated on its own, as with `String`.
+use std::{borrow::Borrow, fmt, ops::Deref, sync::Arc};
+
+use serde::{
+    de::{self, Deserializer, Visitor},
+    Deserialize, Serialize, Serializer,
+};
+
+/// Immutable string, which is cheap to clone and shared between responses with the feature
+/// `intern`. Dereferences to `&str` and compares equal to string slices.
+#[derive(Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct InternedStr(Arc<str>);
+
+impl InternedStr {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Return whether both strings share the same allocation
+    pub fn ptr_eq(&self, other: &InternedStr) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+#[cfg(feature = "intern")]
+mod pool {
+    use std::{
+        collections::HashSet,
+        sync::{Arc, Mutex, OnceLock},
+    };
+
+    /// Longer strings (e.g. business summaries) are unlikely to repeat and are not interned
+    const MAX_INTERNED_LEN: usize = 64;
+    /// Maximum number of strings in the pool, further strings are not interned
+    const MAX_POOL_SIZE: usize = 10_000;
+
+    static POOL: OnceLock<Mutex<HashSet<Arc<str>>>> = OnceLock::new();
+
+    pub(super) fn intern(s: &str) -> Arc<str> {
+        if s.len() > MAX_INTERNED_LEN {
+            return Arc::from(s);
+        }
+        let mut pool = POOL
+            .get_or_init(Default::default)
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        if let Some(interned) = pool.get(s) {
+            return interned.clone();
+        }
+        let interned: Arc<str> = Arc::from(s);
+        if pool.len() < MAX_POOL_SIZE {
+            pool.insert(interned.clone());
+        }
+        interned
+    }
+}
+
+impl From<&str> for InternedStr {
+    #[cfg(feature = "intern")]
+    fn from(s: &str) -> Self {
+        InternedStr(pool::intern(s))
+    }
+
+    #[cfg(not(feature = "intern"))]
+    fn from(s: &str) -> Self {
+        InternedStr(Arc::from(s))
+    }
+}
+
+impl From<String> for InternedStr {
+    fn from(s: String) -> Self {
+        InternedStr::from(s.as_str())
+    }
+}
+
+impl From<InternedStr> for String {
+    fn from(s: InternedStr) -> Self {
+        s.0.to_string()
+    }
+}
+
+impl Deref for InternedStr {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<str> for InternedStr {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Borrow<str> for InternedStr {
+    fn borrow(&self) -> &str {
+        &self.0
+    }
+}
+
+impl PartialEq<str> for InternedStr {
+    fn eq(&self, other: &str) -> bool {
+        &*self.0 == other
+    }
+}
+
+impl PartialEq<&str> for InternedStr {
+    fn eq(&self, other: &&str) -> bool {
+        &*self.0 == *other
+    }
+}
+
+impl PartialEq<String> for InternedStr {
+    fn eq(&self, other: &String) -> bool {
+        *self.0 == **other
+    }
+}
+
+impl fmt::Debug for InternedStr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&*self.0, f)
+    }
+}
+
+impl fmt::Display for InternedStr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl Serialize for InternedStr {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.0)
+    }
+}
+
+struct InternedStrVisitor;
+
+impl<'de> Visitor<'de> for InternedStrVisitor {
+    type Value = InternedStr;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a string")
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<InternedStr, E> {
+        Ok(InternedStr::from(value))
+    }
+}
+
+impl<'de> Deserialize<'de> for InternedStr {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_str(InternedStrVisitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_interned_str() {
+        let values: Vec<InternedStr> = serde_json::from_str(r#"["USD", "USD", "EUR"]"#).unwrap();
+        assert_eq!(values[0], "USD");
+        assert_eq!(&values[2], "EUR");
+        assert_eq!(values[1], "USD".to_string());
+        assert_eq!(format!("{:?}", values[0]), r#""USD""#);
+        assert_eq!(values[0].ptr_eq(&values[1]), cfg!(feature = "intern"));
+        assert!(!values[0].ptr_eq(&values[2]));
+    }
+}
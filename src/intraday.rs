@@ -0,0 +1,92 @@
+//! Intraday intervals together with the lookback yahoo! finance accepts for each of them.
+//!
+//! The shorter the interval, the shorter the history available: one-minute bars cover the last
+//! week only, bars of 2 to 90 minutes the last 60 days and hourly bars the last two years.
+//! Requests beyond these limits are rejected by yahoo! finance, see `get_intraday`.
+use super::HistoryQuery;
+
+/// Interval of intraday bars
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum IntradayInterval {
+    OneMinute,
+    TwoMinutes,
+    FiveMinutes,
+    FifteenMinutes,
+    ThirtyMinutes,
+    SixtyMinutes,
+    NinetyMinutes,
+}
+
+impl IntradayInterval {
+    /// Interval as passed to the chart endpoint, e.g. `5m`
+    pub fn code(&self) -> &'static str {
+        match self {
+            IntradayInterval::OneMinute => "1m",
+            IntradayInterval::TwoMinutes => "2m",
+            IntradayInterval::FiveMinutes => "5m",
+            IntradayInterval::FifteenMinutes => "15m",
+            IntradayInterval::ThirtyMinutes => "30m",
+            IntradayInterval::SixtyMinutes => "60m",
+            IntradayInterval::NinetyMinutes => "90m",
+        }
+    }
+
+    /// Number of days back from today for which bars of this interval are available
+    pub fn max_days(&self) -> u32 {
+        match self {
+            IntradayInterval::OneMinute => 7,
+            IntradayInterval::SixtyMinutes => 730,
+            _ => 60,
+        }
+    }
+
+    /// Whether quotes before and after regular trading hours are included by default. Bars of
+    /// an hour or longer are aligned to the regular session, so bars outside of it would
+    /// overlap the session boundaries; they are only included for shorter intervals.
+    pub fn default_prepost(&self) -> bool {
+        !matches!(
+            self,
+            IntradayInterval::SixtyMinutes | IntradayInterval::NinetyMinutes
+        )
+    }
+}
+
+impl HistoryQuery {
+    /// Query of the intraday bars of the last `days_back` days, limited to the days available
+    /// for the interval, including quotes outside of regular trading hours as by
+    /// `IntradayInterval::default_prepost`
+    pub fn intraday(ticker: &str, interval: IntradayInterval, days_back: u32) -> HistoryQuery {
+        let days = days_back.clamp(1, interval.max_days());
+        HistoryQuery::new(ticker)
+            .interval(interval.code())
+            .range(&format!("{days}d"))
+            .prepost(interval.default_prepost())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_intraday_query() {
+        assert_eq!(
+            HistoryQuery::intraday("AAPL", IntradayInterval::OneMinute, 30),
+            HistoryQuery::new("AAPL")
+                .interval("1m")
+                .range("7d")
+                .prepost(true)
+        );
+        assert_eq!(
+            HistoryQuery::intraday("AAPL", IntradayInterval::SixtyMinutes, 0),
+            HistoryQuery::new("AAPL").interval("60m").range("1d")
+        );
+        assert_eq!(
+            HistoryQuery::intraday("AAPL", IntradayInterval::FiveMinutes, 20),
+            HistoryQuery::new("AAPL")
+                .interval("5m")
+                .range("20d")
+                .prepost(true)
+        );
+    }
+}
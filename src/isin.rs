@@ -0,0 +1,308 @@
+//! Resolution of ISINs to symbols, e.g. when importing a portfolio from a broker, with the
+//! resolutions kept in a JSON file so each ISIN is searched only once.
+//!
+//! ```rust,ignore
+//! let mut cache = IsinCache::open("isins.json")?;
+//! let batch = cache
+//!     .resolve_isins(&connector, &["US0378331005", "DE0007664039"], BatchPolicy::CollectAll)
+//!     .await?;
+//! for (isin, resolution) in batch.succeeded {
+//!     println!("{isin}: {:?}", resolution.symbol());
+//! }
+//! ```
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use super::{BatchPolicy, BatchResult, ResolvePreference, YQuoteItem, YahooConnector, YahooError};
+
+/// Symbols found for an ISIN
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IsinResolution {
+    /// A single symbol has been found
+    Unique(String),
+    /// The security is listed under several symbols, e.g. at different exchanges, ranked by
+    /// the default `ResolvePreference`
+    Ambiguous(Vec<String>),
+}
+
+impl IsinResolution {
+    /// Rank the search results, or return `None` if there are none
+    fn from_quotes(mut quotes: Vec<YQuoteItem>) -> Option<IsinResolution> {
+        ResolvePreference::default().rank(&mut quotes);
+        let mut symbols: Vec<String> = Vec::new();
+        for quote in quotes {
+            if !symbols.contains(&quote.symbol) {
+                symbols.push(quote.symbol);
+            }
+        }
+        match symbols.len() {
+            0 => None,
+            1 => symbols.pop().map(IsinResolution::Unique),
+            _ => Some(IsinResolution::Ambiguous(symbols)),
+        }
+    }
+
+    /// Return the symbol if it is unique
+    pub fn symbol(&self) -> Option<&str> {
+        match self {
+            IsinResolution::Unique(symbol) => Some(symbol),
+            IsinResolution::Ambiguous(_) => None,
+        }
+    }
+
+    /// All symbols found, the preferred one first
+    pub fn symbols(&self) -> Vec<&str> {
+        match self {
+            IsinResolution::Unique(symbol) => vec![symbol.as_str()],
+            IsinResolution::Ambiguous(symbols) => symbols.iter().map(|s| s.as_str()).collect(),
+        }
+    }
+}
+
+/// Return whether the string is a well-formed ISIN with valid check digit, e.g. `US0378331005`
+pub fn is_valid_isin(isin: &str) -> bool {
+    let bytes = isin.as_bytes();
+    if bytes.len() != 12
+        || !bytes[..2].iter().all(u8::is_ascii_uppercase)
+        || !bytes[2..11]
+            .iter()
+            .all(|b| b.is_ascii_uppercase() || b.is_ascii_digit())
+        || !bytes[11].is_ascii_digit()
+    {
+        return false;
+    }
+    // letters count as two digits (A = 10, ..., Z = 35), then the Luhn algorithm applies
+    let digits: Vec<u32> = isin
+        .chars()
+        .flat_map(|c| {
+            let value = c.to_digit(36).unwrap_or(0);
+            if value >= 10 {
+                vec![value / 10, value % 10]
+            } else {
+                vec![value]
+            }
+        })
+        .collect();
+    let sum: u32 = digits
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(i, &digit)| match (i % 2, digit * 2) {
+            (0, _) => digit,
+            (_, doubled) if doubled > 9 => doubled - 9,
+            (_, doubled) => doubled,
+        })
+        .sum();
+    sum % 10 == 0
+}
+
+/// Resolutions of ISINs, stored as JSON file if opened from a path
+#[derive(Debug, Clone, Default)]
+pub struct IsinCache {
+    path: Option<PathBuf>,
+    entries: BTreeMap<String, IsinResolution>,
+}
+
+impl IsinCache {
+    /// Open the cache stored in the given file, or an empty one if the file does not exist yet
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<IsinCache, YahooError> {
+        let path = path.as_ref().to_path_buf();
+        let entries = match fs::read(&path) {
+            Ok(json) => serde_json::from_slice(&json)?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => BTreeMap::new(),
+            Err(e) => return Err(YahooError::ReadFailed(e)),
+        };
+        Ok(IsinCache {
+            path: Some(path),
+            entries,
+        })
+    }
+
+    /// Cache which is not stored
+    pub fn in_memory() -> IsinCache {
+        IsinCache::default()
+    }
+
+    pub fn get(&self, isin: &str) -> Option<&IsinResolution> {
+        self.entries.get(isin)
+    }
+
+    pub fn insert(&mut self, isin: &str, resolution: IsinResolution) {
+        self.entries.insert(isin.to_string(), resolution);
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Write the cache to its file, replacing the previous content. Does nothing for caches
+    /// in memory.
+    pub fn save(&self) -> Result<(), YahooError> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let json = serde_json::to_vec_pretty(&self.entries)?;
+        // write to a temporary file first, so an interrupted write keeps the previous content
+        let temporary = path.with_extension("tmp");
+        fs::write(&temporary, json).map_err(YahooError::WriteFailed)?;
+        fs::rename(&temporary, path).map_err(YahooError::WriteFailed)
+    }
+
+    /// Check the ISIN and return its cached resolution, or `None` if it must be searched
+    fn lookup(&self, isin: &str) -> Result<Option<IsinResolution>, YahooError> {
+        if !is_valid_isin(isin) {
+            return Err(YahooError::InvalidSymbol(isin.to_string()));
+        }
+        Ok(self.get(isin).cloned())
+    }
+
+    /// Store the search results of the ISIN, failing if nothing has been found. ISINs without
+    /// results are not cached, as the security may be listed later.
+    fn store(
+        &mut self,
+        isin: &str,
+        quotes: Result<Vec<YQuoteItem>, YahooError>,
+    ) -> Result<IsinResolution, YahooError> {
+        let resolution = IsinResolution::from_quotes(quotes?)
+            .ok_or_else(|| YahooError::SymbolNotFound(isin.to_string()))?;
+        self.insert(isin, resolution.clone());
+        Ok(resolution)
+    }
+
+    /// Resolve the ISINs, searching those not cached one after another (subject to the rate
+    /// limit of the connector, if set), and save the cache afterwards. Malformed ISINs fail
+    /// with `YahooError::InvalidSymbol`, ISINs without results with
+    /// `YahooError::SymbolNotFound`.
+    #[cfg(not(feature = "blocking"))]
+    pub async fn resolve_isins(
+        &mut self,
+        connector: &YahooConnector,
+        isins: &[&str],
+        policy: BatchPolicy,
+    ) -> Result<BatchResult<IsinResolution>, YahooError> {
+        let mut batch = BatchResult::new();
+        for isin in isins {
+            let resolution = match self.lookup(isin) {
+                Ok(None) => {
+                    let quotes = connector.search_ticker(isin).await.map(|r| r.quotes);
+                    self.store(isin, quotes)
+                }
+                Ok(Some(resolution)) => Ok(resolution),
+                Err(e) => Err(e),
+            };
+            if let Err(e) = batch.push(isin, resolution, policy) {
+                self.save()?;
+                return Err(e);
+            }
+        }
+        self.save()?;
+        Ok(batch)
+    }
+
+    /// Resolve the ISINs, searching those not cached one after another (subject to the rate
+    /// limit of the connector, if set), and save the cache afterwards. Malformed ISINs fail
+    /// with `YahooError::InvalidSymbol`, ISINs without results with
+    /// `YahooError::SymbolNotFound`.
+    #[cfg(feature = "blocking")]
+    pub fn resolve_isins(
+        &mut self,
+        connector: &YahooConnector,
+        isins: &[&str],
+        policy: BatchPolicy,
+    ) -> Result<BatchResult<IsinResolution>, YahooError> {
+        let mut batch = BatchResult::new();
+        for isin in isins {
+            let resolution = match self.lookup(isin) {
+                Ok(None) => {
+                    let quotes = connector.search_ticker(isin).map(|r| r.quotes);
+                    self.store(isin, quotes)
+                }
+                Ok(Some(resolution)) => Ok(resolution),
+                Err(e) => Err(e),
+            };
+            if let Err(e) = batch.push(isin, resolution, policy) {
+                self.save()?;
+                return Err(e);
+            }
+        }
+        self.save()?;
+        Ok(batch)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Exchange;
+
+    fn item(symbol: &str, exchange: &str, score: f64) -> YQuoteItem {
+        YQuoteItem {
+            exchange: Exchange::from_code(exchange),
+            short_name: String::new(),
+            quote_type: "EQUITY".to_string(),
+            symbol: symbol.to_string(),
+            index: "quotes".to_string(),
+            score,
+            type_display: String::new(),
+            long_name: String::new(),
+            is_yahoo_finance: true,
+        }
+    }
+
+    #[test]
+    fn test_is_valid_isin() {
+        assert!(is_valid_isin("US0378331005"));
+        assert!(is_valid_isin("DE0007664039"));
+        assert!(is_valid_isin("GB00B03MLX29"));
+        assert!(!is_valid_isin("US0378331006"));
+        assert!(!is_valid_isin("us0378331005"));
+        assert!(!is_valid_isin("US037833100"));
+    }
+
+    #[test]
+    fn test_isin_cache() {
+        let path = std::env::temp_dir().join(format!("isin_cache_{}.json", std::process::id()));
+        let mut cache = IsinCache::open(&path).unwrap();
+        assert!(cache.is_empty());
+        assert!(matches!(
+            cache.lookup("US0378331006"),
+            Err(YahooError::InvalidSymbol(_))
+        ));
+        assert!(matches!(
+            cache.store("DE0007664039", Ok(Vec::new())),
+            Err(YahooError::SymbolNotFound(_))
+        ));
+        let resolution = cache
+            .store(
+                "DE0007664039",
+                Ok(vec![
+                    item("VOW3.F", "FRA", 1.0),
+                    item("VOW3.DE", "GER", 2.0),
+                ]),
+            )
+            .unwrap();
+        assert_eq!(resolution.symbol(), None);
+        assert_eq!(resolution.symbols(), vec!["VOW3.DE", "VOW3.F"]);
+        cache
+            .store("US0378331005", Ok(vec![item("AAPL", "NMS", 1.0)]))
+            .unwrap();
+        cache.save().unwrap();
+
+        let cache = IsinCache::open(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(cache.len(), 2);
+        assert_eq!(
+            cache.lookup("US0378331005").unwrap(),
+            Some(IsinResolution::Unique("AAPL".to_string()))
+        );
+        assert_eq!(cache.lookup("GB00B03MLX29").unwrap(), None);
+    }
+}
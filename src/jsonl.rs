@@ -0,0 +1,74 @@
+//! Export of quotes as JSON Lines, one quote per line, for feeding ingestion pipelines
+//! without building the whole document in memory.
+//!
+//! Each line holds the symbol together with the fields of `Quote`, e.g.
+//! `{"symbol":"AAPL","timestamp":1706711400,"open":187.04,...}`, so the lines of several
+//! symbols can be written to a single stream.
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use serde::Serialize;
+
+use super::{Quote, YResponse, YahooError};
+
+#[derive(Serialize)]
+struct QuoteLine<'a> {
+    symbol: &'a str,
+    #[serde(flatten)]
+    quote: &'a Quote,
+}
+
+/// Write the quotes as JSON Lines tagged with the symbol, returning the number of lines
+pub fn write_quotes_jsonl<W: Write>(
+    mut writer: W,
+    symbol: &str,
+    quotes: &[Quote],
+) -> Result<usize, YahooError> {
+    for quote in quotes {
+        serde_json::to_writer(&mut writer, &QuoteLine { symbol, quote })
+            .map_err(|e| YahooError::WriteFailed(e.into()))?;
+        writer.write_all(b"\n").map_err(YahooError::WriteFailed)?;
+    }
+    writer.flush().map_err(YahooError::WriteFailed)?;
+    Ok(quotes.len())
+}
+
+/// Write the quotes of the response to a new file at the given path (replacing an existing
+/// one), returning the number of lines
+pub(crate) fn write_jsonl_file(response: &YResponse, path: &Path) -> Result<usize, YahooError> {
+    let file = File::create(path).map_err(YahooError::WriteFailed)?;
+    response.write_jsonl(BufWriter::new(file))
+}
+
+impl YResponse {
+    /// Write all valid quotes of the response as JSON Lines, one quote per line, returning the
+    /// number of lines. Wrap unbuffered writers (e.g. files) in a `BufWriter`.
+    pub fn write_jsonl<W: Write>(&self, writer: W) -> Result<usize, YahooError> {
+        let symbol = &self.result(0)?.meta.symbol;
+        write_quotes_jsonl(writer, symbol, &self.quotes()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::chart_fixture;
+
+    #[test]
+    fn test_write_jsonl() {
+        let response = chart_fixture("equity_aapl_1d").unwrap().response().unwrap();
+        let mut buffer = Vec::new();
+        let lines = response.write_jsonl(&mut buffer).unwrap();
+        assert_eq!(lines, response.quotes().unwrap().len());
+
+        let text = String::from_utf8(buffer).unwrap();
+        assert_eq!(text.lines().count(), lines);
+        let first: serde_json::Value = serde_json::from_str(text.lines().next().unwrap()).unwrap();
+        assert_eq!(first["symbol"], "AAPL");
+        assert_eq!(
+            first["timestamp"].as_u64(),
+            Some(response.quotes().unwrap()[0].timestamp)
+        );
+        assert!(first.get("adjclose").is_some());
+    }
+}
@@ -159,67 +159,496 @@ fn main() {
 "
 )]
 
-use std::time::Duration;
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use scheduler::Scheduler;
+use session::Session;
 use time::OffsetDateTime;
+use user_agent::UserAgentPool;
 
 #[cfg(feature = "blocking")]
-use reqwest::blocking::{Client, ClientBuilder};
-use reqwest::StatusCode;
+use reqwest::blocking::{Client, ClientBuilder, RequestBuilder};
+use reqwest::{
+    header::{HeaderMap, USER_AGENT},
+    NoProxy, Proxy, StatusCode,
+};
 #[cfg(not(feature = "blocking"))]
-use reqwest::{Client, ClientBuilder};
+use reqwest::{Client, ClientBuilder, RequestBuilder};
 
 // re-export time crate
-pub use quotes::decimal::Decimal;
+pub use quotes::decimal::{decimal_from_f64, decimal_to_f64, Decimal};
 pub use time;
 
+mod asset_class;
+mod batch;
+mod board;
+#[cfg(feature = "cache")]
+mod cache;
+mod capabilities;
+mod circuit_breaker;
+mod clock;
+mod conditional;
+mod config;
+mod date_range;
+mod derived;
+mod directory;
+mod display;
+mod dividend_schedule;
+mod dump;
+mod earnings;
+mod exchange;
+mod exchange_calendar;
+mod financial_health;
+#[cfg(feature = "greeks")]
+mod greeks;
+mod health;
+mod history;
+mod history_stream;
+mod intern;
+mod intraday;
+mod isin;
+mod jsonl;
+mod manifest;
+mod market;
+#[cfg(feature = "money")]
+mod money;
+mod observer;
+mod paging;
+#[cfg(feature = "money")]
+mod portfolio;
+mod quote_summary;
 mod quotes;
+mod research;
+mod resolve;
+mod scheduler;
+#[cfg(feature = "schema_monitor")]
+mod schema_monitor;
 mod search_result;
+mod session;
+mod sniff;
+mod spark;
+pub mod stats;
+#[cfg(feature = "store")]
+mod store;
+mod strategies;
+mod symbol;
+pub mod symbols;
+#[cfg(feature = "metrics")]
+pub mod telemetry;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
+mod timeseries;
+mod timestamp;
+mod trading_day;
+mod user_agent;
+mod validation;
+mod value;
+mod vol_surface;
+mod warnings;
+mod watchlist;
 mod yahoo_error;
+pub use asset_class::AssetClass;
+pub use batch::{BatchPolicy, BatchResult};
+pub use board::{BoardChange, BoardEntry, BoardSnapshot, QuoteBoard};
+#[cfg(feature = "cache")]
+pub use cache::ResponseCache;
+pub use capabilities::SymbolCapabilities;
+pub use circuit_breaker::CircuitBreaker;
+#[cfg(not(feature = "blocking"))]
+pub use clock::Sleep;
+pub use clock::{Clock, MockClock, SystemClock};
+pub use conditional::{Conditional, ResponseTag};
+pub use config::{CircuitBreakerConfig, Config, EndpointTimeoutsConfig, RateLimitConfig};
+pub use date_range::{At, DateRange};
+pub use derived::{total_return_index, vwap, FiftyTwoWeekStats, TotalReturn};
+pub use directory::{ReferenceField, SymbolChange, SymbolDirectory, SymbolInfo};
+pub use display::{
+    currency_symbol, format_compact, format_money, format_number, format_volume, NumberFormat,
+};
+pub use dividend_schedule::{
+    estimate_dividend_schedule, DividendForecast, DividendFrequency, ForecastNote,
+    DIVIDEND_HISTORY_LENGTH,
+};
+pub use dump::DumpPolicy;
+pub use earnings::{earnings_reactions, EarningsReaction};
+pub use exchange::{Exchange, Market};
+pub use exchange_calendar::{CalendarRules, EarlyClose, ExchangeCalendar, Holiday};
+pub use financial_health::{
+    FinancialHealth, HealthInputs, HealthPeriod, HealthRatio, HealthRatios, HEALTH_FORMULA_VERSION,
+};
+#[cfg(feature = "greeks")]
+pub use greeks::{EnrichedContract, Greeks};
+pub use health::{EndpointHealth, HealthReport};
+pub use history::{
+    adjust_dividends, fill_gaps, is_nav_quotes, CloseAdjustment, CorporateActions, EndBound,
+    EventsFilter, FilledQuote, HistoryQuery, HistoryRequest, MergedHistory, QuoteSeries, Splice,
+    Transformation,
+};
+pub use history_stream::QuoteStream;
+pub use intern::InternedStr;
+pub use intraday::IntradayInterval;
+pub use isin::{is_valid_isin, IsinCache, IsinResolution};
+pub use jsonl::write_quotes_jsonl;
+pub use manifest::{DownloadManifest, SymbolProgress, MANIFEST_FILE};
+pub use market::{LatestPrice, MarketState, MarketStatus};
+#[cfg(feature = "money")]
+pub use money::{ConvertedFinancials, ConvertedMoney, Currency, Money, MoneyQuote};
+pub use observer::{AuthEvent, RequestEvent, RequestObserver, TransferStats};
+pub use paging::PagedStream;
+#[cfg(feature = "money")]
+pub use portfolio::{Portfolio, PortfolioValuation, Position, PositionValuation};
+pub use quote_summary::{
+    AssetProfile, DefaultKeyStatistics, EarningsHistory, EarningsHistoryEntry, EarningsSurprise,
+    FinancialData, PageViews, QuoteType, SummaryDetail, Trend, YFinance, YQuoteSummary,
+    YSummaryData, YQUOTE_SUMMARY_MODULES, YQUOTE_SUMMARY_NOT_MODELLED,
+};
 pub use quotes::{
-    AdjClose, CapitalGain, Dividend, PeriodInfo, Quote, QuoteBlock, QuoteList, Split,
-    TradingPeriods, YChart, YMetaData, YQuoteBlock, YResponse,
+    AdjClose, CapitalGain, Dividend, DuplicatePolicy, PeriodInfo, Quote, QuoteBlock, QuoteColumns,
+    QuoteList, Split, TradingPeriods, YChart, YChartError, YComparison, YMetaData, YQuoteBlock,
+    YResponse,
 };
+pub use research::ResearchReport;
+pub use resolve::{ResolvePreference, ResolvedSymbol, DEFAULT_QUOTE_TYPES};
+pub use scheduler::Priority;
+#[cfg(feature = "schema_monitor")]
+pub use schema_monitor::{diff_schemas, schema_of, FieldType, Schema, SchemaDiff, SnapshotDir};
 pub use search_result::{
     YNewsItem, YOptionChain, YOptionChainData, YOptionChainResult, YOptionContract, YOptionDetails,
-    YQuote, YQuoteItem, YQuoteItemOpt, YSearchResult, YSearchResultOpt,
+    YQuote, YQuoteItem, YQuoteItemOpt, YSearchResult, YSearchResultOpt, YThumbnail,
+    YThumbnailResolution,
+};
+pub use session::{
+    AuthMode, CREDENTIAL_ATTEMPTS, CREDENTIAL_REFRESH_AGE, CREDENTIAL_RETRY_DELAY,
+    CRUMB_ENDPOINT_FAILURE_LIMIT,
+};
+pub use sniff::SniffLimits;
+pub use spark::{YSpark, YSparkBody, YSparkResponse, YSparkResult, SPARK_MAX_SYMBOLS};
+#[cfg(feature = "store")]
+pub use store::QuoteStore;
+pub use strategies::{Leg, OptionKind, Strategy, StrategyKind};
+pub use symbol::Symbol;
+pub use symbols::Commodity;
+pub use timeseries::{
+    market_cap_history, FundamentalSeries, FundamentalValue, MarketCap, SharesMeasure,
+    SharesOutstanding,
 };
+pub use timestamp::{deserialize_datetime, deserialize_millis, YTimestamp};
+pub use trading_day::{SessionDay, SessionSchedule, SessionWindow, TradingSession};
+pub use user_agent::{UserAgentRotation, BROWSER_USER_AGENTS};
+pub use validation::{
+    check_splits, validate_quotes, AnomalyKind, QuoteAnomaly, SplitWarning, SplitWarningKind,
+    SPLIT_SUSPECT_FACTOR, VOLUME_SPIKE_FACTOR,
+};
+pub use value::{deserialize_raw, deserialize_value, YValue};
+pub use vol_surface::VolSurface;
+pub use warnings::{DataWarning, WarningKind};
+pub use watchlist::{Alert, AlertRule, Watchlist};
 pub use yahoo_error::YahooError;
 
 const YCHART_URL: &str = "https://query1.finance.yahoo.com/v8/finance/chart";
 const YSEARCH_URL: &str = "https://query2.finance.yahoo.com/v1/finance/search";
+const YOPTIONS_URL: &str = "https://query2.finance.yahoo.com/v6/finance/options";
+const YSPARK_URL: &str = "https://query1.finance.yahoo.com/v8/finance/spark";
+const YQUOTE_SUMMARY_URL: &str = "https://query2.finance.yahoo.com/v10/finance/quoteSummary";
+const YINSIGHTS_URL: &str = "https://query2.finance.yahoo.com/ws/insights/v2/finance/insights";
+const YTIMESERIES_URL: &str =
+    "https://query2.finance.yahoo.com/ws/fundamentals-timeseries/v1/finance/timeseries";
+
+/// Endpoints of the yahoo! finance API which can be queried directly via `query_json`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum YEndpoint {
+    /// Chart data of the given symbol
+    Chart(String),
+    /// Search for symbols and news
+    Search,
+    /// Option chain of the given symbol
+    Options(String),
+    /// Latest prices of the symbols given as query parameter `symbols`
+    Spark,
+    /// Detailed information about the given symbol, requires a crumb
+    QuoteSummary(String),
+    /// Fundamentals of the given symbol as time series, see `get_fundamentals`
+    Timeseries(String),
+    /// Insights into the given symbol, e.g. research reports; the symbol is sent as query
+    /// parameter `symbol`
+    Insights(String),
+    /// Any other URL, e.g. an endpoint not (yet) supported by this crate
+    Url(String),
+}
+
+impl YEndpoint {
+    fn url(&self, connector: &YahooConnector) -> String {
+        match self {
+            YEndpoint::Chart(symbol) => format!("{}/{}", connector.url, symbol),
+            YEndpoint::Search => connector.search_url.to_string(),
+            YEndpoint::Options(symbol) => format!("{YOPTIONS_URL}/{symbol}"),
+            YEndpoint::Spark => YSPARK_URL.to_string(),
+            YEndpoint::QuoteSummary(symbol) => {
+                format!("{}/{}", connector.quote_summary_url, symbol)
+            }
+            YEndpoint::Timeseries(symbol) => format!("{YTIMESERIES_URL}/{symbol}"),
+            YEndpoint::Insights(_) => YINSIGHTS_URL.to_string(),
+            YEndpoint::Url(url) => url.clone(),
+        }
+    }
+
+    /// Short name of the endpoint as reported to a `RequestObserver`
+    pub fn name(&self) -> &'static str {
+        match self {
+            YEndpoint::Chart(_) => "chart",
+            YEndpoint::Search => "search",
+            YEndpoint::Options(_) => "options",
+            YEndpoint::Spark => "spark",
+            YEndpoint::QuoteSummary(_) => "quote_summary",
+            YEndpoint::Timeseries(_) => "timeseries",
+            YEndpoint::Insights(_) => "insights",
+            YEndpoint::Url(_) => "url",
+        }
+    }
+
+    /// Whether requests to the endpoint must carry a crumb
+    fn requires_crumb(&self) -> bool {
+        matches!(self, YEndpoint::QuoteSummary(_))
+    }
 
-// Macros instead of constants,
-macro_rules! YCHART_PERIOD_QUERY {
-    () => {
-        "{url}/{symbol}?symbol={symbol}&period1={start}&period2={end}&interval={interval}&events=div|split|capitalGains"
-    };
+    /// Key of the circuit of the endpoint and symbol, see `CircuitBreaker`
+    fn circuit_key(&self) -> String {
+        match self {
+            YEndpoint::Chart(symbol)
+            | YEndpoint::Options(symbol)
+            | YEndpoint::QuoteSummary(symbol)
+            | YEndpoint::Timeseries(symbol)
+            | YEndpoint::Insights(symbol) => format!("{}/{symbol}", self.name()),
+            YEndpoint::Url(url) => url.clone(),
+            YEndpoint::Search | YEndpoint::Spark => self.name().to_string(),
+        }
+    }
 }
-macro_rules! YCHART_RANGE_QUERY {
-    () => {
-        "{url}/{symbol}?symbol={symbol}&interval={interval}&range={range}&events=div|split|capitalGains"
-    };
+
+/// Timeouts of requests to particular endpoints, overriding the timeout of the connector set
+/// by `YahooConnectorBuilder::timeout`, e.g. short timeouts for credentials and long ones for
+/// multi-year chart requests. Endpoints without timeout use the timeout of the connector, as do
+/// requests of option chains, spark, fundamentals time series, insights and any other URL.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EndpointTimeouts {
+    /// Quotes and events of a symbol, see `get_history`
+    pub chart: Option<Duration>,
+    /// Search for symbols and news
+    pub search: Option<Duration>,
+    /// Detailed information about a symbol, including the dates of its earnings
+    pub quote_summary: Option<Duration>,
+    /// Requests of cookie and crumb
+    pub auth: Option<Duration>,
 }
-macro_rules! YCHART_PERIOD_INTERVAL_QUERY {
-    () => {
-        "{url}/{symbol}?symbol={symbol}&period={period}&interval={interval}&includePrePost={prepost}"
-    };
+
+impl EndpointTimeouts {
+    fn get(&self, endpoint: &YEndpoint) -> Option<Duration> {
+        match endpoint {
+            YEndpoint::Chart(_) => self.chart,
+            YEndpoint::Search => self.search,
+            YEndpoint::QuoteSummary(_) => self.quote_summary,
+            YEndpoint::Options(_)
+            | YEndpoint::Spark
+            | YEndpoint::Timeseries(_)
+            | YEndpoint::Insights(_)
+            | YEndpoint::Url(_) => None,
+        }
+    }
 }
-macro_rules! YTICKER_QUERY {
-    () => {
-        "{url}?q={name}"
-    };
+
+impl YahooConnector {
+    /// Build a request of the given endpoint with additional query parameters
+    fn request(&self, endpoint: &YEndpoint, params: &[(&str, &str)]) -> RequestBuilder {
+        let mut request = self.with_user_agent(self.client.get(endpoint.url(self)));
+        if let Some(timeout) = self.timeouts.get(endpoint) {
+            request = request.timeout(timeout);
+        }
+        if let YEndpoint::Insights(symbol) = endpoint {
+            request = request.query(&[("symbol", symbol)]);
+        }
+        if params.is_empty() {
+            request
+        } else {
+            request.query(params)
+        }
+    }
+
+    /// Forget the crumb rejected by yahoo! finance, so the request is retried once with fresh
+    /// credentials
+    fn crumb_rejected(&self) {
+        self.session.invalidate();
+        self.report_auth(AuthEvent::CrumbRetried);
+        #[cfg(feature = "metrics")]
+        crate::telemetry::record_retry("crumb");
+    }
+
+    /// Prepare a request of cookie or crumb
+    fn auth_request(&self, request: RequestBuilder) -> RequestBuilder {
+        let request = self.with_user_agent(request);
+        match self.timeouts.auth {
+            Some(timeout) => request.timeout(timeout),
+            None => request,
+        }
+    }
+
+    /// Set the user agent of the request to the next one of the pool, if any
+    fn with_user_agent(&self, request: RequestBuilder) -> RequestBuilder {
+        match &self.user_agents {
+            Some(pool) => request.header(USER_AGENT, pool.next()),
+            None => request,
+        }
+    }
+
+    /// Return the symbol with the default exchange suffix appended, unless the symbol already
+    /// refers to an exchange, an index, a currency pair or a future
+    fn suffixed(&self, symbol: &str) -> Option<String> {
+        let suffix = self.default_suffix.as_deref()?;
+        if symbol.contains(['.', '^', '=']) {
+            return None;
+        }
+        Some(format!("{symbol}{suffix}"))
+    }
+
+    /// Parse the body of a response as JSON. Parsing errors are returned along with the status,
+    /// since error responses need not be JSON, unless the status reports success.
+    fn parse_json(
+        &self,
+        status: StatusCode,
+        headers: &HeaderMap,
+        body: &[u8],
+    ) -> Result<Result<serde_json::Value, serde_json::Error>, YahooError> {
+        sniff::check_json_body(status, headers, body, self.sniff_limits)?;
+        let json = serde_json::from_slice(body);
+        match &json {
+            Err(e) if status.is_success() && (e.is_syntax() || e.is_eof()) => Err(
+                sniff::unexpected_content(status, headers, body, self.sniff_limits),
+            ),
+            _ => Ok(json),
+        }
+    }
+
+    /// Deserialize the chart of the ticker, unless its content is the one identified by `tag`
+    fn chart_response(
+        &self,
+        json: serde_json::Value,
+        ticker: &str,
+        tag: Option<&ResponseTag>,
+        new_tag: ResponseTag,
+    ) -> Result<Conditional<YResponse>, YahooError> {
+        if tag.is_some_and(|tag| tag.matches(&new_tag)) {
+            return Ok(Conditional::NotModified);
+        }
+        let response = self.dump_policy.deserialize("chart", json, |json| {
+            YResponse::from_chart_json(json, ticker)
+        })?;
+        Ok(Conditional::Modified(response, new_tag))
+    }
+
+    /// Fail if requests to the endpoint are suspended by the circuit breaker
+    fn check_circuit(&self, endpoint: &YEndpoint) -> Result<(), YahooError> {
+        match &self.circuit_breaker {
+            Some(breaker) => breaker.check(&endpoint.circuit_key()),
+            None => Ok(()),
+        }
+    }
+
+    /// Report the outcome of a request to the circuit breaker, the observer, if any, and the
+    /// metrics recorder if the `metrics` feature is enabled
+    fn report(
+        &self,
+        endpoint: &YEndpoint,
+        status: Option<StatusCode>,
+        bytes: usize,
+        started: Instant,
+    ) {
+        if let Some(breaker) = &self.circuit_breaker {
+            let success = status
+                .is_some_and(|status| status.is_success() || status == StatusCode::NOT_MODIFIED);
+            breaker.record(&endpoint.circuit_key(), success);
+        }
+        if let Some(observer) = &self.observer {
+            observer.on_request(&RequestEvent {
+                endpoint: endpoint.name(),
+                status: status.map(|status| status.as_u16()),
+                bytes: bytes as u64,
+                duration: started.elapsed(),
+            });
+        }
+        if status == Some(StatusCode::TOO_MANY_REQUESTS) {
+            if let Some(pool) = &self.user_agents {
+                pool.rotate();
+            }
+        }
+        #[cfg(feature = "metrics")]
+        telemetry::record_request(
+            endpoint.name(),
+            status.map(|status| status.as_u16()),
+            started.elapsed(),
+        );
+        if status == Some(StatusCode::UNAUTHORIZED) {
+            self.report_auth(AuthEvent::Unauthorized);
+        }
+    }
+
+    /// Report an event of the authentication to the observer, if any, and the metrics recorder
+    /// if the `metrics` feature is enabled
+    fn report_auth(&self, event: AuthEvent) {
+        if let Some(observer) = &self.observer {
+            observer.on_auth(event);
+        }
+        #[cfg(feature = "metrics")]
+        telemetry::record_auth(event);
+    }
 }
 
 /// Container for connection parameters to yahoo! finance server
+///
+/// Cloning a connector is cheap: all clones share the HTTP client with its connection pool as
+/// well as cookie and crumb, so credentials are fetched only once. A connector can therefore be
+/// used as shared state of a web application, e.g. by cloning it into each request handler.
+#[derive(Clone)]
 pub struct YahooConnector {
     client: Client,
     url: &'static str,
     search_url: &'static str,
+    quote_summary_url: &'static str,
+    session: Arc<Session>,
+    dump_policy: DumpPolicy,
+    auth_mode: AuthMode,
+    observer: Option<Arc<dyn RequestObserver>>,
+    circuit_breaker: Option<Arc<CircuitBreaker>>,
+    scheduler: Option<Arc<Scheduler>>,
+    clock: Arc<dyn Clock>,
+    priority: Priority,
+    default_suffix: Option<Arc<str>>,
+    user_agents: Option<Arc<UserAgentPool>>,
+    sniff_limits: SniffLimits,
+    timeouts: EndpointTimeouts,
+    #[cfg(feature = "cache")]
+    cache: Option<Arc<ResponseCache>>,
 }
 
 #[derive(Default)]
 pub struct YahooConnectorBuilder {
     inner: ClientBuilder,
+    dump_policy: DumpPolicy,
+    auth_mode: AuthMode,
+    observer: Option<Arc<dyn RequestObserver>>,
+    circuit_breaker: Option<(u32, Duration)>,
+    rate_limit: Option<(u32, Duration)>,
+    clock: Option<Arc<dyn Clock>>,
+    default_suffix: Option<Arc<str>>,
+    user_agent_pool: Vec<String>,
+    user_agent_rotation: UserAgentRotation,
+    proxy: Option<String>,
+    ignore_proxy_env: bool,
+    sniff_limits: SniffLimits,
+    timeouts: EndpointTimeouts,
+    #[cfg(feature = "cache")]
+    cache: Option<Arc<ResponseCache>>,
 }
 
 impl YahooConnector {
@@ -228,19 +657,66 @@ impl YahooConnector {
         Self::builder().build()
     }
 
+    /// Return a clone of the connector sending its requests with the given priority. If a rate
+    /// limit is set, waiting requests of higher priority are sent first, otherwise the
+    /// priority has no effect.
+    pub fn with_priority(&self, priority: Priority) -> YahooConnector {
+        YahooConnector {
+            priority,
+            ..self.clone()
+        }
+    }
+
     pub fn builder() -> YahooConnectorBuilder {
         YahooConnectorBuilder {
             inner: Client::builder(),
+            dump_policy: DumpPolicy::default(),
+            auth_mode: AuthMode::default(),
+            observer: None,
+            circuit_breaker: None,
+            rate_limit: None,
+            clock: None,
+            default_suffix: None,
+            user_agent_pool: Vec::new(),
+            user_agent_rotation: UserAgentRotation::default(),
+            proxy: None,
+            ignore_proxy_env: false,
+            sniff_limits: SniffLimits::default(),
+            timeouts: EndpointTimeouts::default(),
+            #[cfg(feature = "cache")]
+            cache: None,
         }
     }
 }
 
 impl Default for YahooConnector {
     fn default() -> Self {
+        let clock: Arc<dyn Clock> = Arc::new(SystemClock);
+        let session = Arc::new(Session::new(clock.clone()));
         YahooConnector {
-            client: Client::default(),
+            client: Client::builder()
+                .gzip(true)
+                .deflate(true)
+                .cookie_provider(session.jar.clone())
+                .build()
+                .expect("default client"),
             url: YCHART_URL,
             search_url: YSEARCH_URL,
+            quote_summary_url: YQUOTE_SUMMARY_URL,
+            session,
+            dump_policy: DumpPolicy::default(),
+            auth_mode: AuthMode::default(),
+            observer: None,
+            circuit_breaker: None,
+            scheduler: None,
+            clock,
+            priority: Priority::default(),
+            default_suffix: None,
+            user_agents: None,
+            sniff_limits: SniffLimits::default(),
+            timeouts: EndpointTimeouts::default(),
+            #[cfg(feature = "cache")]
+            cache: None,
         }
     }
 }
@@ -251,13 +727,61 @@ impl YahooConnectorBuilder {
     }
 
     pub fn build_with_agent(self, user_agent: &str) -> Result<YahooConnector, YahooError> {
-        let client = Client::builder().user_agent(user_agent).build()?;
+        let clock = self.clock.unwrap_or_else(|| Arc::new(SystemClock));
+        let session = Arc::new(Session::new(clock.clone()));
+        let mut inner = self.inner;
+        match (self.proxy, self.ignore_proxy_env) {
+            (Some(proxy), ignore_env) => {
+                let mut proxy = Proxy::all(proxy)?;
+                if !ignore_env {
+                    proxy = proxy.no_proxy(NoProxy::from_env());
+                }
+                inner = inner.proxy(proxy);
+            }
+            (None, true) => inner = inner.no_proxy(),
+            (None, false) => {}
+        }
+        let client = inner
+            .user_agent(user_agent)
+            // compressed responses are much smaller, especially for long intraday histories
+            .gzip(true)
+            .deflate(true)
+            .cookie_provider(session.jar.clone())
+            .build()?;
 
-        Ok(YahooConnector {
+        let connector = YahooConnector {
             client,
             url: YCHART_URL,
             search_url: YSEARCH_URL,
-        })
+            quote_summary_url: YQUOTE_SUMMARY_URL,
+            session,
+            dump_policy: self.dump_policy,
+            auth_mode: self.auth_mode,
+            observer: self.observer,
+            circuit_breaker: self.circuit_breaker.map(|(threshold, cooldown)| {
+                Arc::new(CircuitBreaker::with_clock(
+                    threshold,
+                    cooldown,
+                    clock.clone(),
+                ))
+            }),
+            scheduler: self.rate_limit.map(|(requests, period)| {
+                Arc::new(Scheduler::new(requests, period, clock.clone()))
+            }),
+            clock,
+            priority: Priority::default(),
+            default_suffix: self.default_suffix,
+            user_agents: UserAgentPool::new(self.user_agent_pool, self.user_agent_rotation)
+                .map(Arc::new),
+            sniff_limits: self.sniff_limits,
+            timeouts: self.timeouts,
+            #[cfg(feature = "cache")]
+            cache: self.cache,
+        };
+        if connector.auth_mode == AuthMode::Eager {
+            connector.spawn_warm_up();
+        }
+        Ok(connector)
     }
 
     pub fn timeout(mut self, timeout: Duration) -> Self {
@@ -265,6 +789,484 @@ impl YahooConnectorBuilder {
 
         self
     }
+
+    /// Set what to do with responses which could not be deserialized, e.g. write them to a
+    /// directory to reproduce problems caused by changes of the response format
+    pub fn on_deserialize_error(mut self, policy: DumpPolicy) -> Self {
+        self.dump_policy = policy;
+
+        self
+    }
+
+    /// Set whether and when cookie and crumb are fetched. With `AuthMode::None`, only endpoints
+    /// not requiring them can be used (e.g. chart and search), but yahoo! finance is never
+    /// contacted for credentials.
+    pub fn auth_mode(mut self, mode: AuthMode) -> Self {
+        self.auth_mode = mode;
+
+        self
+    }
+
+    /// Set an observer receiving the status, size and duration of each request sent to
+    /// yahoo! finance, e.g. `TransferStats` to count the transferred bytes
+    pub fn observer(mut self, observer: Arc<dyn RequestObserver>) -> Self {
+        self.observer = Some(observer);
+
+        self
+    }
+
+    /// Suspend requests to symbols or endpoints failing repeatedly, e.g. delisted symbols in a
+    /// batch job, see `CircuitBreaker`
+    pub fn circuit_breaker(mut self, threshold: u32, cooldown: Duration) -> Self {
+        self.circuit_breaker = Some((threshold, cooldown));
+
+        self
+    }
+
+    /// Send at most `requests` requests per `period`, shared by all clones of the connector.
+    /// Waiting requests are sent by priority, see `YahooConnector::with_priority`.
+    pub fn rate_limit(mut self, requests: u32, period: Duration) -> Self {
+        self.rate_limit = Some((requests, period));
+
+        self
+    }
+
+    /// Set the clock used by rate limit, circuit breaker, retries of fetching credentials and
+    /// their expiry, e.g. a `MockClock` in tests
+    pub fn clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = Some(clock);
+
+        self
+    }
+
+    /// Set the suffix of the preferred exchange, e.g. `.DE` for XETRA or `.L` for London. If
+    /// yahoo! finance does not know a symbol requested from the chart endpoint, it is requested
+    /// again with the suffix appended. The symbol which matched is the one of the meta data.
+    pub fn default_exchange_suffix(mut self, suffix: &str) -> Self {
+        self.default_suffix = Some(Arc::from(suffix));
+
+        self
+    }
+
+    /// Send requests with the given user agents in rotation instead of the user agent of
+    /// `build_with_agent`, e.g. `BROWSER_USER_AGENTS`. By default, each request is sent with
+    /// the next user agent, see `user_agent_rotation`.
+    pub fn user_agent_pool(mut self, user_agents: Vec<String>) -> Self {
+        self.user_agent_pool = user_agents;
+
+        self
+    }
+
+    /// Set when the user agent of the pool changes. After a response with status 429 (too many
+    /// requests), the next user agent is used in any case.
+    pub fn user_agent_rotation(mut self, rotation: UserAgentRotation) -> Self {
+        self.user_agent_rotation = rotation;
+
+        self
+    }
+
+    /// Send all requests, including those fetching cookie and crumb, via the given proxy, e.g.
+    /// `http://proxy.example.com:8080`, overriding the proxies of the environment. Hosts
+    /// listed in `NO_PROXY` are still contacted directly, unless `ignore_proxy_env` is set.
+    pub fn proxy(mut self, url: &str) -> Self {
+        self.proxy = Some(url.to_string());
+
+        self
+    }
+
+    /// Set whether to ignore the proxy configuration of the environment. By default, proxies
+    /// are taken from `HTTP_PROXY`, `HTTPS_PROXY` and `ALL_PROXY` (also in lower case), except
+    /// for the hosts listed in `NO_PROXY`.
+    pub fn ignore_proxy_env(mut self, ignore: bool) -> Self {
+        self.ignore_proxy_env = ignore;
+
+        self
+    }
+
+    /// Set how much of a body which is not JSON is inspected and kept as excerpt in
+    /// `YahooError::HtmlResponse` and `YahooError::UnexpectedContent`
+    pub fn sniff_limits(mut self, limits: SniffLimits) -> Self {
+        self.sniff_limits = limits;
+
+        self
+    }
+
+    /// Set timeouts of particular endpoints, overriding the timeout of all requests set by
+    /// `timeout`, see `EndpointTimeouts`
+    pub fn endpoint_timeouts(mut self, timeouts: EndpointTimeouts) -> Self {
+        self.timeouts = timeouts;
+
+        self
+    }
+
+    /// Store responses of the chart endpoint for periods which ended at least a day ago in the
+    /// given cache, and answer repeated requests for them from the cache. The cache is best
+    /// effort: unreadable responses are requested again and failing to store one does not fail
+    /// the request.
+    #[cfg(feature = "cache")]
+    pub fn cache(mut self, cache: ResponseCache) -> Self {
+        self.cache = Some(Arc::new(cache));
+
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_connector_is_shareable() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<YahooConnector>();
+    }
+
+    #[test]
+    fn test_auth_disabled() {
+        let connector = YahooConnector::builder()
+            .auth_mode(AuthMode::None)
+            .build()
+            .unwrap();
+        #[cfg(not(feature = "blocking"))]
+        let result = tokio_test::block_on(connector.get_ticker_info("AAPL"));
+        #[cfg(feature = "blocking")]
+        let result = connector.get_ticker_info("AAPL");
+        assert!(matches!(result, Err(YahooError::AuthDisabled)));
+    }
+
+    /// Local server answering a single request, returning the received request headers
+    fn serve_once() -> (String, std::thread::JoinHandle<String>) {
+        serve_once_with_status("200 OK")
+    }
+
+    /// Local server answering a single request with the given status, returning the received
+    /// request headers
+    fn serve_once_with_status(status: &'static str) -> (String, std::thread::JoinHandle<String>) {
+        serve_once_with(status, r#"{"result": []}"#)
+    }
+
+    /// Local server answering a single request with the given status and body, returning the
+    /// received request headers
+    fn serve_once_with(
+        status: &'static str,
+        body: &'static str,
+    ) -> (String, std::thread::JoinHandle<String>) {
+        use std::io::{Read, Write};
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        let server = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = Vec::new();
+            let mut buffer = [0; 1024];
+            while !request.ends_with(b"\r\n\r\n") {
+                let n = stream.read(&mut buffer).unwrap();
+                request.extend_from_slice(&buffer[..n]);
+            }
+            write!(
+                stream,
+                "HTTP/1.1 {status}\r\ncontent-type: application/json\r\ncontent-length: {}\r\n\r\n{body}",
+                body.len()
+            )
+            .unwrap();
+            String::from_utf8(request).unwrap().to_lowercase()
+        });
+        (url, server)
+    }
+
+    #[test]
+    fn test_compression_and_transfer_stats() {
+        let (url, server) = serve_once();
+        let stats = Arc::new(TransferStats::new());
+        let connector = YahooConnector::builder()
+            .observer(stats.clone())
+            .build()
+            .unwrap();
+        #[cfg(not(feature = "blocking"))]
+        let json = tokio_test::block_on(connector.query_json(YEndpoint::Url(url), &[])).unwrap();
+        #[cfg(feature = "blocking")]
+        let json = connector.query_json(YEndpoint::Url(url), &[]).unwrap();
+        assert!(json["result"].is_array());
+
+        let request = server.join().unwrap();
+        let accept_encoding = request
+            .lines()
+            .find(|line| line.starts_with("accept-encoding:"))
+            .unwrap();
+        assert!(accept_encoding.contains("gzip") && accept_encoding.contains("deflate"));
+        assert_eq!(stats.requests(), 1);
+        assert_eq!(stats.failed(), 0);
+        assert_eq!(stats.bytes_received(), 14);
+    }
+
+    #[test]
+    fn test_unauthorized_auth_event() {
+        let (url, server) = serve_once_with_status("401 Unauthorized");
+        let stats = Arc::new(TransferStats::new());
+        let connector = YahooConnector::builder()
+            .observer(stats.clone())
+            .build()
+            .unwrap();
+        #[cfg(not(feature = "blocking"))]
+        let _ = tokio_test::block_on(connector.query_json(YEndpoint::Url(url), &[]));
+        #[cfg(feature = "blocking")]
+        let _ = connector.query_json(YEndpoint::Url(url), &[]);
+        server.join().unwrap();
+        assert_eq!(stats.failed(), 1);
+        assert_eq!(stats.auth_events(AuthEvent::Unauthorized), 1);
+        assert_eq!(stats.auth_events(AuthEvent::CrumbRefreshed), 0);
+    }
+
+    #[test]
+    fn test_query_json_sends_crumb() {
+        let (url, server) = serve_once();
+        let mut connector = YahooConnector::new().unwrap();
+        connector.quote_summary_url = Box::leak(url.trim_end_matches('/').into());
+        connector.session.set_crumb("TestCrumb".to_string());
+        let endpoint = YEndpoint::QuoteSummary("AAPL".to_string());
+        let params = [("modules", "price")];
+        #[cfg(not(feature = "blocking"))]
+        let json = tokio_test::block_on(connector.query_json(endpoint, &params)).unwrap();
+        #[cfg(feature = "blocking")]
+        let json = connector.query_json(endpoint, &params).unwrap();
+        assert!(json["result"].is_array());
+        // the received request is lower case
+        let request = server.join().unwrap();
+        assert!(request.starts_with("get /aapl?modules=price&crumb=testcrumb "));
+    }
+
+    #[test]
+    fn test_query_json_error_status() {
+        let (url, server) = serve_once_with("404 Not Found", r#"{"quoteSummary":{"result":null}}"#);
+        let mut connector = YahooConnector::new().unwrap();
+        connector.quote_summary_url = Box::leak(url.trim_end_matches('/').into());
+        connector.session.set_crumb("TestCrumb".to_string());
+        let endpoint = YEndpoint::QuoteSummary("NOSUCHTICKER".to_string());
+        #[cfg(not(feature = "blocking"))]
+        let result = tokio_test::block_on(connector.query_json(endpoint, &[]));
+        #[cfg(feature = "blocking")]
+        let result = connector.query_json(endpoint, &[]);
+        assert!(
+            matches!(result, Err(YahooError::FetchFailed(status)) if status.starts_with("404"))
+        );
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn test_endpoint_timeouts() {
+        // the connection is accepted by the backlog of the listener, but never answered
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let proxy = format!("http://{}/", listener.local_addr().unwrap());
+        let connector = YahooConnector::builder()
+            .proxy(&proxy)
+            .endpoint_timeouts(EndpointTimeouts {
+                chart: Some(Duration::from_millis(100)),
+                ..EndpointTimeouts::default()
+            })
+            .build()
+            .unwrap();
+        let endpoint = YEndpoint::Chart("AAPL".to_string());
+        #[cfg(not(feature = "blocking"))]
+        let result = tokio_test::block_on(connector.query_json(endpoint, &[]));
+        #[cfg(feature = "blocking")]
+        let result = connector.query_json(endpoint, &[]);
+        assert!(matches!(result, Err(YahooError::ConnectionFailed(e)) if e.is_timeout()));
+    }
+
+    #[test]
+    fn test_proxy() {
+        let (url, server) = serve_once();
+        let connector = YahooConnector::builder().proxy(&url).build().unwrap();
+        let endpoint = YEndpoint::Url("http://finance.example.com/chart".to_string());
+        #[cfg(not(feature = "blocking"))]
+        let json = tokio_test::block_on(connector.query_json(endpoint, &[])).unwrap();
+        #[cfg(feature = "blocking")]
+        let json = connector.query_json(endpoint, &[]).unwrap();
+        assert!(json["result"].is_array());
+        // the proxy receives the absolute URL of the target
+        let request = server.join().unwrap();
+        assert!(request.starts_with("get http://finance.example.com/chart "));
+
+        assert!(YahooConnector::builder()
+            .proxy("not a url")
+            .ignore_proxy_env(true)
+            .build()
+            .is_err());
+    }
+
+    #[cfg(feature = "cache")]
+    #[test]
+    fn test_history_if_modified_from_cache() {
+        use time::macros::datetime;
+
+        let dir = std::env::temp_dir().join(format!("yahoo_conditional_{}", std::process::id()));
+        let cache = ResponseCache::open(&dir).unwrap();
+        let query = HistoryQuery::new("AAPL").period(
+            datetime!(2024-02-05 0:00 UTC),
+            datetime!(2024-02-10 0:00 UTC),
+        );
+        let params = query.params();
+        let params: Vec<(&str, &str)> = params.iter().map(|(k, v)| (*k, v.as_str())).collect();
+        let now = OffsetDateTime::now_utc().unix_timestamp();
+        let key = cache::chart_key("AAPL", &params, now).unwrap();
+        let json = include_str!("testing/fixtures/equity_aapl_1d.json");
+        cache
+            .put(&key, &serde_json::from_str(json).unwrap())
+            .unwrap();
+        let connector = YahooConnector::builder().cache(cache).build().unwrap();
+
+        // conditional requests are answered from the cache as well
+        #[cfg(not(feature = "blocking"))]
+        let response = tokio_test::block_on(connector.get_history_if_modified(&query, None));
+        #[cfg(feature = "blocking")]
+        let response = connector.get_history_if_modified(&query, None);
+        let Conditional::Modified(response, tag) = response.unwrap() else {
+            panic!("response without tag is modified");
+        };
+        assert_eq!(response.chart.result[0].meta.symbol, "AAPL");
+        #[cfg(not(feature = "blocking"))]
+        let response = tokio_test::block_on(connector.get_history_if_modified(&query, Some(&tag)));
+        #[cfg(feature = "blocking")]
+        let response = connector.get_history_if_modified(&query, Some(&tag));
+        assert!(!response.unwrap().is_modified());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(feature = "cache")]
+    #[test]
+    fn test_history_unreadable_cache() {
+        use time::macros::datetime;
+
+        let dir = std::env::temp_dir().join(format!("yahoo_unreadable_{}", std::process::id()));
+        let cache = ResponseCache::open(&dir).unwrap();
+        let query = HistoryQuery::new("AAPL").period(
+            datetime!(2024-02-05 0:00 UTC),
+            datetime!(2024-02-10 0:00 UTC),
+        );
+        let params = query.params();
+        let params: Vec<(&str, &str)> = params.iter().map(|(k, v)| (*k, v.as_str())).collect();
+        let now = OffsetDateTime::now_utc().unix_timestamp();
+        let key = cache::chart_key("AAPL", &params, now).unwrap();
+        cache.put(&key, &serde_json::json!({})).unwrap();
+        for entry in std::fs::read_dir(&dir).unwrap() {
+            std::fs::write(entry.unwrap().path(), b"not zstd").unwrap();
+        }
+        let (url, server) = serve_once_with(
+            "200 OK",
+            include_str!("testing/fixtures/equity_aapl_1d.json"),
+        );
+        let mut connector = YahooConnector::builder().cache(cache).build().unwrap();
+        connector.url = Box::leak(url.trim_end_matches('/').into());
+
+        // the response is requested again and replaces the unreadable one
+        #[cfg(not(feature = "blocking"))]
+        let response = tokio_test::block_on(connector.get_history_if_modified(&query, None));
+        #[cfg(feature = "blocking")]
+        let response = connector.get_history_if_modified(&query, None);
+        assert!(response.unwrap().is_modified());
+        server.join().unwrap();
+        let cache = ResponseCache::open(&dir).unwrap();
+        assert!(cache.get(&key).unwrap().is_some());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_latest_prices_without_tickers() {
+        // no request is sent, so the unreachable proxy is never used
+        let connector = YahooConnector::builder()
+            .proxy("http://127.0.0.1:1/")
+            .build()
+            .unwrap();
+        #[cfg(not(feature = "blocking"))]
+        let prices = tokio_test::block_on(connector.get_latest_prices(&[]));
+        #[cfg(feature = "blocking")]
+        let prices = connector.get_latest_prices(&[]);
+        assert!(prices.unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_insights_symbol_parameter() {
+        let connector = YahooConnector::new().unwrap();
+        let endpoint = YEndpoint::Insights("BRK-B".to_string());
+        let request = connector.request(&endpoint, &[("lang", "en-US")]);
+        let request = request.build().unwrap();
+        assert_eq!(request.url().query(), Some("symbol=BRK-B&lang=en-US"));
+    }
+
+    #[test]
+    fn test_search_ticker_uses_search_endpoint() {
+        let (url, server) = serve_once();
+        let mut connector = YahooConnector::new().unwrap();
+        connector.search_url = Box::leak(url.into());
+        #[cfg(not(feature = "blocking"))]
+        let _ = tokio_test::block_on(connector.search_ticker_opt("S&P 500"));
+        #[cfg(feature = "blocking")]
+        let _ = connector.search_ticker_opt("S&P 500");
+        // the query is encoded, so it is not split at the ampersand
+        let request = server.join().unwrap();
+        assert!(request.starts_with("get /?q=s%26p+500 "));
+    }
+
+    #[test]
+    fn test_news_pages_requests_once() {
+        let news = r#"{"news": [
+            {"uuid": "1", "title": "A", "publisher": "P", "link": "L",
+             "providerPublishTime": 1700000000, "type": "STORY"},
+            {"uuid": "2", "title": "B", "publisher": "P", "link": "L",
+             "providerPublishTime": 1700000000, "type": "STORY"}
+        ]}"#;
+        let (url, server) = serve_once_with("200 OK", news);
+        let mut connector = YahooConnector::new().unwrap();
+        connector.search_url = Box::leak(url.into());
+        // a second request would fail, since the server answers a single one
+        #[cfg(not(feature = "blocking"))]
+        let items: Vec<_> = {
+            use futures_util::StreamExt;
+            tokio_test::block_on(connector.news_pages("apple", 2).collect())
+        };
+        #[cfg(feature = "blocking")]
+        let items: Vec<_> = connector.news_pages("apple", 2).collect();
+        assert_eq!(items.len(), 2);
+        assert!(items.iter().all(Result::is_ok));
+        let request = server.join().unwrap();
+        assert!(request.starts_with("get /?q=apple&quotescount=0&newscount=2 "));
+    }
+
+    #[test]
+    fn test_clones_share_session() {
+        let connector = YahooConnector::new().unwrap();
+        let clone = connector.clone();
+        assert!(Arc::ptr_eq(&connector.session, &clone.session));
+        assert!(Arc::ptr_eq(&connector.session.jar, &clone.session.jar));
+    }
+
+    #[test]
+    fn test_default_exchange_suffix() {
+        let connector = YahooConnector::builder()
+            .default_exchange_suffix(".DE")
+            .build()
+            .unwrap();
+        assert_eq!(connector.suffixed("VOW3").as_deref(), Some("VOW3.DE"));
+        assert_eq!(connector.suffixed("SAP.F"), None);
+        assert_eq!(connector.suffixed("^GDAXI"), None);
+        assert_eq!(connector.suffixed("EURUSD=X"), None);
+        assert_eq!(YahooConnector::new().unwrap().suffixed("VOW3"), None);
+    }
+
+    #[test]
+    fn test_priority_shares_rate_limit() {
+        let connector = YahooConnector::builder()
+            .rate_limit(5, Duration::from_secs(1))
+            .build()
+            .unwrap();
+        let background = connector.with_priority(Priority::Low);
+        assert_eq!(connector.priority, Priority::Normal);
+        assert_eq!(background.priority, Priority::Low);
+        assert!(Arc::ptr_eq(
+            connector.scheduler.as_ref().unwrap(),
+            background.scheduler.as_ref().unwrap()
+        ));
+    }
 }
 
 #[cfg(not(feature = "blocking"))]
@@ -0,0 +1,279 @@
+//! Progress of batch downloads recorded in a manifest, so an interrupted backfill resumes
+//! where it stopped instead of requesting completed symbols again, see
+//! `YahooConnector::download_jsonl_resumable`.
+//!
+//! The manifest is stored as `manifest.json` in the download directory and updated after each
+//! chunk of a history has been appended to the file of its symbol. It records the chunks
+//! fetched and the length of the file at that point, so lines of a chunk written before an
+//! interruption but not recorded yet are truncated on resume rather than written twice.
+use std::collections::{BTreeMap, VecDeque};
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufWriter, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+
+use super::{history_stream, write_quotes_jsonl, Quote, YahooError};
+
+/// Name of the manifest file within the download directory
+pub const MANIFEST_FILE: &str = "manifest.json";
+
+/// Progress of the download of a symbol
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SymbolProgress {
+    /// End of the last chunk fetched (UNIX timestamp)
+    pub fetched_until: i64,
+    /// Timestamp of the last quote written, to skip quotes returned with two adjacent chunks
+    pub last_timestamp: Option<u64>,
+    /// Length of the output file after the last chunk
+    pub bytes: u64,
+    pub completed: bool,
+}
+
+/// Period, interval and progress per symbol of a batch download
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DownloadManifest {
+    #[serde(skip)]
+    path: PathBuf,
+    /// Start of the period (UNIX timestamp)
+    pub start: i64,
+    /// End of the period (UNIX timestamp)
+    pub end: i64,
+    pub interval: String,
+    pub symbols: BTreeMap<String, SymbolProgress>,
+}
+
+impl DownloadManifest {
+    /// Open the manifest of the download directory, or a new one if there is none yet. Fails
+    /// with `YahooError::ManifestMismatch` if the directory holds a download of another period
+    /// or interval.
+    pub fn open(
+        dir: &Path,
+        start: OffsetDateTime,
+        end: OffsetDateTime,
+        interval: &str,
+    ) -> Result<DownloadManifest, YahooError> {
+        let path = dir.join(MANIFEST_FILE);
+        let (start, end) = (start.unix_timestamp(), end.unix_timestamp());
+        let mut manifest: DownloadManifest = match fs::read(&path) {
+            Ok(json) => serde_json::from_slice(&json)?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => DownloadManifest {
+                path: PathBuf::new(),
+                start,
+                end,
+                interval: interval.to_string(),
+                symbols: BTreeMap::new(),
+            },
+            Err(e) => return Err(YahooError::ReadFailed(e)),
+        };
+        if (manifest.start, manifest.end) != (start, end) || manifest.interval != interval {
+            return Err(YahooError::ManifestMismatch(format!(
+                "{} holds interval {} from {} to {}",
+                path.display(),
+                manifest.interval,
+                manifest.start,
+                manifest.end
+            )));
+        }
+        manifest.path = path;
+        Ok(manifest)
+    }
+
+    pub fn progress(&self, symbol: &str) -> Option<&SymbolProgress> {
+        self.symbols.get(symbol)
+    }
+
+    pub fn is_completed(&self, symbol: &str) -> bool {
+        self.progress(symbol)
+            .is_some_and(|progress| progress.completed)
+    }
+
+    /// Write the manifest to its file, replacing the previous content
+    pub fn save(&self) -> Result<(), YahooError> {
+        let json = serde_json::to_vec_pretty(self)?;
+        // write to a temporary file first, so an interrupted write keeps the previous content
+        let temporary = self.path.with_extension("tmp");
+        fs::write(&temporary, json).map_err(YahooError::WriteFailed)?;
+        fs::rename(&temporary, &self.path).map_err(YahooError::WriteFailed)
+    }
+}
+
+/// Output file and progress of a symbol while it is downloaded
+pub(crate) struct SymbolDownload {
+    symbol: String,
+    file: File,
+    progress: SymbolProgress,
+}
+
+impl SymbolDownload {
+    /// Open the file `<symbol>.jsonl` in the download directory, truncated to the length
+    /// recorded in the manifest
+    pub(crate) fn open(
+        manifest: &DownloadManifest,
+        dir: &Path,
+        symbol: &str,
+    ) -> Result<SymbolDownload, YahooError> {
+        let progress = manifest
+            .progress(symbol)
+            .cloned()
+            .unwrap_or(SymbolProgress {
+                fetched_until: manifest.start,
+                last_timestamp: None,
+                bytes: 0,
+                completed: false,
+            });
+        let mut file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(false)
+            .open(dir.join(format!("{symbol}.jsonl")))
+            .map_err(YahooError::WriteFailed)?;
+        file.set_len(progress.bytes)
+            .map_err(YahooError::WriteFailed)?;
+        file.seek(SeekFrom::End(0))
+            .map_err(YahooError::WriteFailed)?;
+        Ok(SymbolDownload {
+            symbol: symbol.to_string(),
+            file,
+            progress,
+        })
+    }
+
+    /// Chunks of the period not fetched yet
+    pub(crate) fn chunks(
+        &self,
+        manifest: &DownloadManifest,
+    ) -> Result<VecDeque<(OffsetDateTime, OffsetDateTime)>, YahooError> {
+        if self.progress.completed {
+            return Ok(VecDeque::new());
+        }
+        let time = |timestamp| {
+            OffsetDateTime::from_unix_timestamp(timestamp)
+                .map_err(|e| YahooError::ManifestMismatch(e.to_string()))
+        };
+        Ok(history_stream::chunks(
+            time(self.progress.fetched_until)?,
+            time(manifest.end)?,
+            &manifest.interval,
+        ))
+    }
+
+    /// Append the quotes of a fetched chunk and record it in the manifest, returning the number
+    /// of lines written
+    pub(crate) fn append(
+        &mut self,
+        manifest: &mut DownloadManifest,
+        chunk_end: OffsetDateTime,
+        quotes: Result<Vec<Quote>, YahooError>,
+    ) -> Result<usize, YahooError> {
+        let quotes = match quotes {
+            Err(YahooError::EmptyDataSet) => Vec::new(),
+            quotes => quotes?,
+        };
+        let last = self.progress.last_timestamp;
+        let quotes: Vec<Quote> = quotes
+            .into_iter()
+            .filter(|quote| last.map_or(true, |last| quote.timestamp > last))
+            .collect();
+        let written = write_quotes_jsonl(BufWriter::new(&self.file), &self.symbol, &quotes)?;
+        if let Some(quote) = quotes.last() {
+            self.progress.last_timestamp = Some(quote.timestamp);
+        }
+        self.progress.fetched_until = chunk_end.unix_timestamp();
+        self.progress.bytes = self
+            .file
+            .stream_position()
+            .map_err(YahooError::WriteFailed)?;
+        self.record(manifest)?;
+        Ok(written)
+    }
+
+    /// Mark the symbol as completed in the manifest
+    pub(crate) fn complete(mut self, manifest: &mut DownloadManifest) -> Result<(), YahooError> {
+        if self.progress.completed {
+            return Ok(());
+        }
+        self.progress.completed = true;
+        self.record(manifest)
+    }
+
+    fn record(&self, manifest: &mut DownloadManifest) -> Result<(), YahooError> {
+        manifest
+            .symbols
+            .insert(self.symbol.clone(), self.progress.clone());
+        manifest.save()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::datetime;
+    use time::Duration;
+
+    use super::*;
+    use crate::quotes::ZERO;
+
+    fn quotes(from: OffsetDateTime, days: i64) -> Vec<Quote> {
+        (0..days)
+            .map(|day| Quote {
+                timestamp: (from + Duration::days(day)).unix_timestamp() as u64,
+                open: ZERO,
+                high: ZERO,
+                low: ZERO,
+                volume: 0,
+                close: ZERO,
+                adjclose: ZERO,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_resume_download() {
+        let dir = std::env::temp_dir().join(format!("yahoo-manifest-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let (start, end) = (
+            datetime!(2000-01-01 0:00 UTC),
+            datetime!(2020-01-01 0:00 UTC),
+        );
+
+        let mut manifest = DownloadManifest::open(&dir, start, end, "1d").unwrap();
+        let mut download = SymbolDownload::open(&manifest, &dir, "AAPL").unwrap();
+        let chunks = download.chunks(&manifest).unwrap();
+        assert_eq!(chunks.len(), 5);
+        let (_, first_end) = chunks[0];
+        let written = download
+            .append(&mut manifest, first_end, Ok(quotes(start, 3)))
+            .unwrap();
+        assert_eq!(written, 3);
+        drop(download);
+        // lines written after the last update of the manifest are dropped on resume
+        let mut file = OpenOptions::new()
+            .append(true)
+            .open(dir.join("AAPL.jsonl"))
+            .unwrap();
+        write_quotes_jsonl(&mut file, "AAPL", &quotes(start, 2)).unwrap();
+
+        let mut manifest = DownloadManifest::open(&dir, start, end, "1d").unwrap();
+        assert!(!manifest.is_completed("AAPL"));
+        let mut download = SymbolDownload::open(&manifest, &dir, "AAPL").unwrap();
+        let (_, second_end) = chunks[1];
+        assert_eq!(download.chunks(&manifest).unwrap().len(), 4);
+        // quotes already written are skipped
+        let written = download
+            .append(&mut manifest, second_end, Ok(quotes(start, 5)))
+            .unwrap();
+        assert_eq!(written, 2);
+        download.complete(&mut manifest).unwrap();
+
+        let manifest = DownloadManifest::open(&dir, start, end, "1d").unwrap();
+        assert!(manifest.is_completed("AAPL"));
+        let lines = fs::read_to_string(dir.join("AAPL.jsonl")).unwrap();
+        assert_eq!(lines.lines().count(), 5);
+        assert!(matches!(
+            DownloadManifest::open(&dir, start, end, "1wk"),
+            Err(YahooError::ManifestMismatch(_))
+        ));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,302 @@
+//! Current price and trading state of a symbol derived from the chart meta data.
+use std::{convert::Infallible, fmt, str::FromStr};
+
+use serde::{Deserialize, Deserializer};
+use time::{Duration, OffsetDateTime, UtcOffset, Weekday};
+
+use super::{Decimal, ExchangeCalendar, InternedStr, YMetaData, YResponse, YahooError};
+
+/// Trading state of the market of a symbol
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum MarketState {
+    /// Pre-market trading hours
+    Pre,
+    /// Regular trading hours
+    Regular,
+    /// Post-market trading hours
+    Post,
+    /// Market is closed
+    Closed,
+    /// State reported by yahoo! finance not known to this crate
+    Other(String),
+}
+
+impl MarketState {
+    /// Map the market state used by yahoo! finance (e.g. `REGULAR`). The states before
+    /// pre-market and after post-market trading hours (`PREPRE` and `POSTPOST`) are `Closed`.
+    pub fn from_code(code: &str) -> MarketState {
+        match code {
+            "PRE" => MarketState::Pre,
+            "REGULAR" => MarketState::Regular,
+            "POST" => MarketState::Post,
+            "CLOSED" | "PREPRE" | "POSTPOST" => MarketState::Closed,
+            other => MarketState::Other(other.to_string()),
+        }
+    }
+
+    /// Market state as used by yahoo! finance
+    pub fn as_str(&self) -> &str {
+        match self {
+            MarketState::Pre => "PRE",
+            MarketState::Regular => "REGULAR",
+            MarketState::Post => "POST",
+            MarketState::Closed => "CLOSED",
+            MarketState::Other(other) => other,
+        }
+    }
+}
+
+impl fmt::Display for MarketState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for MarketState {
+    type Err = Infallible;
+
+    fn from_str(code: &str) -> Result<Self, Self::Err> {
+        Ok(MarketState::from_code(code))
+    }
+}
+
+impl<'de> Deserialize<'de> for MarketState {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let code = <std::borrow::Cow<'de, str>>::deserialize(deserializer)?;
+        Ok(MarketState::from_code(&code))
+    }
+}
+
+/// Most recent price of a symbol
+#[derive(Debug, Clone, PartialEq)]
+pub struct LatestPrice {
+    pub price: Decimal,
+    /// Time of the price (as UNIX timestamp)
+    pub time: i64,
+    pub currency: Option<InternedStr>,
+    /// State of the market at the time of the request, if trading periods are known
+    pub market_state: Option<MarketState>,
+    /// Delay of the price in minutes, if reported by yahoo! finance
+    pub delayed_by: Option<u32>,
+}
+
+impl LatestPrice {
+    /// Return whether the price is real-time, or `None` if the delay is unknown
+    pub fn is_realtime(&self) -> Option<bool> {
+        self.delayed_by.map(|delay| delay == 0)
+    }
+}
+
+/// Trading state of a market together with the upcoming opening and closing times
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MarketStatus {
+    pub state: MarketState,
+    /// Next start of regular trading hours, in the time zone of the exchange
+    pub next_open: OffsetDateTime,
+    /// Next end of regular trading hours, in the time zone of the exchange
+    pub next_close: OffsetDateTime,
+}
+
+impl YMetaData {
+    /// Return the state of the market at the given time together with the next opening and
+    /// closing time of regular trading hours. Times beyond the current trading period are
+    /// derived assuming the exchange trades with the same hours on every trading day. Holidays
+    /// are skipped if a calendar of the exchange is available, otherwise the exchange is
+    /// assumed to trade Monday to Friday. Returns `None` if no trading period is known.
+    pub fn market_status_at(&self, now: OffsetDateTime) -> Option<MarketStatus> {
+        let state = self.market_state_at(now.unix_timestamp())?;
+        let regular = &self.current_trading_period.as_ref()?.regular;
+        let offset = UtcOffset::from_whole_seconds(self.gmtoffset).ok()?;
+        let calendar = self.exchange_calendar();
+        Some(MarketStatus {
+            state,
+            next_open: next_trading_time(regular.start, now, offset, calendar)?,
+            next_close: next_trading_time(regular.end, now, offset, calendar)?,
+        })
+    }
+
+    /// Return the regular market price of the meta data as latest price, if reported
+    pub(crate) fn latest_price_at(&self, now: i64) -> Option<LatestPrice> {
+        Some(LatestPrice {
+            price: self.regular_market_price?,
+            time: self.regular_market_time?.seconds(),
+            currency: self.currency.clone(),
+            market_state: self.market_state_at(now),
+            delayed_by: self.exchange_data_delayed_by,
+        })
+    }
+
+    /// Return the delay of the quotes, or `None` if not reported by yahoo! finance
+    pub fn data_delay(&self) -> Option<Duration> {
+        self.exchange_data_delayed_by
+            .map(|minutes| Duration::minutes(minutes.into()))
+    }
+
+    /// Return whether the quotes are real-time, or `None` if the delay is unknown. Quotes of
+    /// many exchanges are delayed by 15 or 20 minutes.
+    pub fn is_realtime(&self) -> Option<bool> {
+        self.exchange_data_delayed_by.map(|delay| delay == 0)
+    }
+
+    /// Return the state of the market at the given time based on the current trading period,
+    /// or `None` if no trading period is known
+    pub fn market_state_at(&self, timestamp: i64) -> Option<MarketState> {
+        let period = self.current_trading_period.as_ref()?;
+        let contains = |start: i64, end: i64| start <= timestamp && timestamp < end;
+        Some(if contains(period.regular.start, period.regular.end) {
+            MarketState::Regular
+        } else if contains(period.pre.start, period.pre.end) {
+            MarketState::Pre
+        } else if contains(period.post.start, period.post.end) {
+            MarketState::Post
+        } else {
+            MarketState::Closed
+        })
+    }
+}
+
+/// Return the first time after `now` which is a whole number of days after `timestamp`
+/// and falls on a trading day (or a weekday, if no calendar is given) in the given offset
+fn next_trading_time(
+    timestamp: i64,
+    now: OffsetDateTime,
+    offset: UtcOffset,
+    calendar: Option<ExchangeCalendar>,
+) -> Option<OffsetDateTime> {
+    let mut time = OffsetDateTime::from_unix_timestamp(timestamp)
+        .ok()?
+        .to_offset(offset);
+    let is_trading_day = |time: OffsetDateTime| match calendar {
+        Some(calendar) => calendar.is_trading_day(time.date()),
+        None => !matches!(time.weekday(), Weekday::Saturday | Weekday::Sunday),
+    };
+    while time <= now || !is_trading_day(time) {
+        time += Duration::days(1);
+    }
+    Some(time)
+}
+
+impl YResponse {
+    /// Return the most recent price, preferring the regular market price of the meta data
+    /// over the close of the latest quote
+    pub fn latest_price(&self) -> Result<LatestPrice, YahooError> {
+        self.latest_price_at(OffsetDateTime::now_utc().unix_timestamp())
+    }
+
+    fn latest_price_at(&self, now: i64) -> Result<LatestPrice, YahooError> {
+        let meta = &self.result(0)?.meta;
+        if let Some(price) = meta.latest_price_at(now) {
+            return Ok(price);
+        }
+        let quote = self.last_quote()?;
+        Ok(LatestPrice {
+            price: quote.close,
+            time: quote.timestamp as i64,
+            currency: meta.currency.clone(),
+            market_state: meta.market_state_at(now),
+            delayed_by: meta.exchange_data_delayed_by,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::{datetime, offset};
+
+    use super::*;
+    use crate::testing::chart_fixture;
+
+    #[test]
+    fn test_market_state() {
+        let meta = chart_fixture("equity_msft_1m_prepost")
+            .unwrap()
+            .response()
+            .unwrap()
+            .metadata()
+            .unwrap();
+        assert_eq!(meta.market_state_at(1705482000), Some(MarketState::Pre));
+        assert_eq!(meta.market_state_at(1705501800), Some(MarketState::Regular));
+        assert_eq!(meta.market_state_at(1705525200), Some(MarketState::Post));
+        assert_eq!(meta.market_state_at(1705539600), Some(MarketState::Closed));
+
+        assert_eq!(MarketState::from_code("POSTPOST"), MarketState::Closed);
+        let state: MarketState = serde_json::from_str(r#""HALTED""#).unwrap();
+        assert_eq!(state, MarketState::Other("HALTED".to_string()));
+        assert_eq!(MarketState::Regular.to_string(), "REGULAR");
+    }
+
+    #[test]
+    fn test_market_status() {
+        let meta = chart_fixture("equity_msft_1m_prepost")
+            .unwrap()
+            .response()
+            .unwrap()
+            .metadata()
+            .unwrap();
+        // Wednesday, 2024-01-17, regular trading hours 9:30 to 16:00 EST
+        let status = meta
+            .market_status_at(datetime!(2024-01-17 12:00 -5))
+            .unwrap();
+        assert_eq!(status.state, MarketState::Regular);
+        assert_eq!(status.next_open, datetime!(2024-01-18 9:30 -5));
+        assert_eq!(status.next_close, datetime!(2024-01-17 16:00 -5));
+        assert_eq!(status.next_open.offset(), offset!(-5));
+
+        let status = meta
+            .market_status_at(datetime!(2024-01-17 8:00 -5))
+            .unwrap();
+        assert_eq!(status.state, MarketState::Pre);
+        assert_eq!(status.next_open, datetime!(2024-01-17 9:30 -5));
+
+        // Friday after close, next opening on Monday
+        let status = meta
+            .market_status_at(datetime!(2024-01-19 21:00 -5))
+            .unwrap();
+        assert_eq!(status.state, MarketState::Closed);
+        assert_eq!(status.next_open, datetime!(2024-01-22 9:30 -5));
+        assert_eq!(status.next_close, datetime!(2024-01-22 16:00 -5));
+
+        // Washington's Birthday on Monday is skipped
+        let status = meta
+            .market_status_at(datetime!(2024-02-16 21:00 -5))
+            .unwrap();
+        assert_eq!(status.next_open, datetime!(2024-02-20 9:30 -5));
+    }
+
+    #[test]
+    fn test_latest_price() {
+        let response = chart_fixture("equity_aapl_1d").unwrap().response().unwrap();
+        let latest = response.latest_price_at(1707489000).unwrap();
+        assert_eq!(latest.time, 1707512401);
+        assert_eq!(latest.currency.as_deref(), Some("USD"));
+        assert_eq!(latest.market_state, Some(MarketState::Regular));
+        assert_eq!(latest.is_realtime(), None);
+
+        // fall back to latest quote if meta data lacks the market price
+        let mut json = chart_fixture("equity_aapl_1d").unwrap().json_value();
+        json["chart"]["result"][0]["meta"]["regularMarketPrice"] = serde_json::Value::Null;
+        let response = YResponse::from_json(json).unwrap();
+        let latest = response.latest_price_at(1707489000).unwrap();
+        assert_eq!(latest.time, 1707489000);
+        assert_eq!(latest.price, response.last_quote().unwrap().close);
+    }
+
+    #[test]
+    fn test_data_delay() {
+        let mut json = chart_fixture("equity_aapl_1d").unwrap().json_value();
+        json["chart"]["result"][0]["meta"]["exchangeDataDelayedBy"] = 15.into();
+        let response = YResponse::from_json(json).unwrap();
+        let meta = response.metadata().unwrap();
+        assert_eq!(meta.data_delay(), Some(Duration::minutes(15)));
+        assert_eq!(meta.is_realtime(), Some(false));
+        let latest = response.latest_price().unwrap();
+        assert_eq!(latest.delayed_by, Some(15));
+        assert_eq!(latest.is_realtime(), Some(false));
+
+        let mut json = chart_fixture("crypto_btcusd_1d").unwrap().json_value();
+        json["chart"]["result"][0]["meta"]["exchangeDataDelayedBy"] = 0.into();
+        let response = YResponse::from_json(json).unwrap();
+        assert_eq!(response.metadata().unwrap().is_realtime(), Some(true));
+    }
+}
@@ -0,0 +1,298 @@
+//! Prices tagged with their currency, to prevent mixing amounts of different currencies.
+use std::fmt;
+
+use super::{
+    Decimal, FinancialData, LatestPrice, Quote, YQuoteBlock, YResponse, YValue, YahooError,
+};
+
+/// Currency code as reported by yahoo! finance (e.g. "USD", or "GBp" for pence sterling)
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Currency(String);
+
+impl Currency {
+    pub fn new(code: &str) -> Currency {
+        Currency(code.to_string())
+    }
+
+    pub fn code(&self) -> &str {
+        &self.0
+    }
+
+    /// Return the major currency and the number of minor units per major unit if the
+    /// currency is a minor unit quoted by yahoo! finance (e.g. "GBp" for "GBP")
+    pub fn major_unit(&self) -> Option<(Currency, u32)> {
+        let major = match self.0.as_str() {
+            "GBp" | "GBX" => "GBP",
+            "ZAc" | "ZAC" => "ZAR",
+            "ILA" => "ILS",
+            _ => return None,
+        };
+        Some((Currency::new(major), 100))
+    }
+}
+
+impl fmt::Display for Currency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// An amount in a given currency
+#[derive(Debug, Clone, PartialEq)]
+pub struct Money {
+    pub amount: Decimal,
+    pub currency: Currency,
+}
+
+impl Money {
+    pub fn new(amount: Decimal, currency: Currency) -> Money {
+        Money { amount, currency }
+    }
+
+    /// Add two amounts, failing if the currencies differ
+    pub fn checked_add(&self, other: &Money) -> Result<Money, YahooError> {
+        self.check_currency(other)?;
+        Ok(Money::new(
+            self.amount + other.amount,
+            self.currency.clone(),
+        ))
+    }
+
+    /// Subtract two amounts, failing if the currencies differ
+    pub fn checked_sub(&self, other: &Money) -> Result<Money, YahooError> {
+        self.check_currency(other)?;
+        Ok(Money::new(
+            self.amount - other.amount,
+            self.currency.clone(),
+        ))
+    }
+
+    /// Multiply the amount by a factor, e.g. a number of shares
+    pub fn scale(&self, factor: Decimal) -> Money {
+        Money::new(self.amount * factor, self.currency.clone())
+    }
+
+    /// Convert the amount into another currency given the exchange rate, i.e. the
+    /// price of one unit of this currency in the target currency
+    pub fn convert(&self, rate: Decimal, currency: Currency) -> Money {
+        Money::new(self.amount * rate, currency)
+    }
+
+    fn check_currency(&self, other: &Money) -> Result<(), YahooError> {
+        if self.currency == other.currency {
+            Ok(())
+        } else {
+            Err(YahooError::CurrencyMismatch(
+                self.currency.to_string(),
+                other.currency.to_string(),
+            ))
+        }
+    }
+}
+
+impl fmt::Display for Money {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.amount, self.currency)
+    }
+}
+
+/// A quote with prices tagged by the currency of the symbol
+#[derive(Debug, Clone, PartialEq)]
+pub struct MoneyQuote {
+    pub timestamp: u64,
+    pub open: Money,
+    pub high: Money,
+    pub low: Money,
+    pub volume: u64,
+    pub close: Money,
+    pub adjclose: Money,
+}
+
+impl MoneyQuote {
+    pub fn new(quote: &Quote, currency: &Currency) -> MoneyQuote {
+        let money = |amount| Money::new(amount, currency.clone());
+        MoneyQuote {
+            timestamp: quote.timestamp,
+            open: money(quote.open),
+            high: money(quote.high),
+            low: money(quote.low),
+            volume: quote.volume,
+            close: money(quote.close),
+            adjclose: money(quote.adjclose),
+        }
+    }
+}
+
+impl YQuoteBlock {
+    /// Return the currency of the prices of this block, if reported
+    pub fn currency(&self) -> Option<Currency> {
+        self.meta.currency.as_deref().map(Currency::new)
+    }
+}
+
+impl YResponse {
+    /// Return all valid quotes with prices tagged by the currency of the symbol.
+    /// Fails with `MissingCurrency` if yahoo! finance did not report a currency.
+    pub fn money_quotes(&self) -> Result<Vec<MoneyQuote>, YahooError> {
+        let block = self.result(0)?;
+        let currency = block.currency().ok_or(YahooError::MissingCurrency)?;
+        Ok(block
+            .quotes()?
+            .iter()
+            .map(|quote| MoneyQuote::new(quote, &currency))
+            .collect())
+    }
+
+    /// Return the latest valid quote with prices tagged by the currency of the symbol
+    pub fn last_money_quote(&self) -> Result<MoneyQuote, YahooError> {
+        let block = self.result(0)?;
+        let currency = block.currency().ok_or(YahooError::MissingCurrency)?;
+        Ok(MoneyQuote::new(&block.last_quote()?, &currency))
+    }
+}
+
+impl LatestPrice {
+    /// Return the price tagged by its currency, if the currency is known
+    pub fn money(&self) -> Option<Money> {
+        let currency = self.currency.as_deref()?;
+        Some(Money::new(self.price, Currency::new(currency)))
+    }
+}
+
+/// An amount converted into another currency, keeping the original amount
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConvertedMoney {
+    pub original: Money,
+    pub converted: Money,
+}
+
+/// Revenue and cash metrics of the `financialData` module converted from the currency of the
+/// financial statements, which may differ from the trading currency (e.g. for ADRs), into a
+/// common currency. Metrics not reported are `None`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConvertedFinancials {
+    /// Exchange rate applied, i.e. the price of one unit of the financial currency in the
+    /// target currency
+    pub rate: Decimal,
+    pub total_revenue: Option<ConvertedMoney>,
+    pub gross_profits: Option<ConvertedMoney>,
+    pub ebitda: Option<ConvertedMoney>,
+    pub total_cash: Option<ConvertedMoney>,
+    pub total_debt: Option<ConvertedMoney>,
+    pub free_cashflow: Option<ConvertedMoney>,
+    pub operating_cashflow: Option<ConvertedMoney>,
+}
+
+impl FinancialData {
+    /// Return the currency of the financial statements, if reported
+    pub fn currency(&self) -> Option<Currency> {
+        self.financial_currency.as_deref().map(Currency::new)
+    }
+
+    /// Convert the revenue and cash metrics into the given currency, using the exchange rate
+    /// from the financial currency. Fails with `MissingCurrency` if yahoo! finance did not
+    /// report the financial currency.
+    pub fn convert(&self, rate: Decimal, to: &Currency) -> Result<ConvertedFinancials, YahooError> {
+        let from = self.currency().ok_or(YahooError::MissingCurrency)?;
+        let convert = |value: &Option<YValue<Decimal>>| {
+            value.as_ref().map(|value| {
+                let original = Money::new(value.raw, from.clone());
+                ConvertedMoney {
+                    converted: original.convert(rate, to.clone()),
+                    original,
+                }
+            })
+        };
+        Ok(ConvertedFinancials {
+            rate,
+            total_revenue: convert(&self.total_revenue),
+            gross_profits: convert(&self.gross_profits),
+            ebitda: convert(&self.ebitda),
+            total_cash: convert(&self.total_cash),
+            total_debt: convert(&self.total_debt),
+            free_cashflow: convert(&self.free_cashflow),
+            operating_cashflow: convert(&self.operating_cashflow),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{chart_fixture, quote_summary_fixture};
+
+    fn money(amount: u32, currency: &str) -> Money {
+        Money::new(Decimal::from(amount), Currency::new(currency))
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let sum = money(10, "USD").checked_add(&money(5, "USD")).unwrap();
+        assert_eq!(sum, money(15, "USD"));
+        assert_eq!(
+            money(10, "USD").checked_sub(&money(5, "USD")).unwrap(),
+            money(5, "USD")
+        );
+        assert!(matches!(
+            money(10, "USD").checked_add(&money(5, "EUR")),
+            Err(YahooError::CurrencyMismatch(_, _))
+        ));
+        assert_eq!(money(10, "USD").scale(Decimal::from(3)), money(30, "USD"));
+        assert_eq!(
+            money(10, "EUR").convert(Decimal::from(2), Currency::new("USD")),
+            money(20, "USD")
+        );
+        assert_eq!(
+            Currency::new("GBp").major_unit(),
+            Some((Currency::new("GBP"), 100))
+        );
+        assert_eq!(Currency::new("USD").major_unit(), None);
+    }
+
+    #[test]
+    fn test_money_quotes() {
+        let response = chart_fixture("fx_eurusd_1d").unwrap().response().unwrap();
+        let quotes = response.money_quotes().unwrap();
+        assert!(quotes.iter().all(|q| q.close.currency.code() == "USD"));
+        assert_eq!(
+            response.last_money_quote().unwrap().close.amount,
+            response.last_quote().unwrap().close
+        );
+
+        let mut json = chart_fixture("fx_eurusd_1d").unwrap().json_value();
+        json["chart"]["result"][0]["meta"]["currency"] = serde_json::Value::Null;
+        let response = YResponse::from_json(json).unwrap();
+        assert!(matches!(
+            response.money_quotes(),
+            Err(YahooError::MissingCurrency)
+        ));
+    }
+
+    #[test]
+    fn test_convert_financials() {
+        let summary = quote_summary_fixture("quote_summary_aapl")
+            .unwrap()
+            .quote_summary()
+            .unwrap();
+        let mut data = summary.financial_data().unwrap().clone();
+        let revenue = data.total_revenue.as_ref().unwrap().raw;
+        let financials = data
+            .convert(Decimal::from(2), &Currency::new("EUR"))
+            .unwrap();
+        let converted = financials.total_revenue.unwrap();
+        assert_eq!(
+            converted.original,
+            Money::new(revenue, Currency::new("USD"))
+        );
+        assert_eq!(
+            converted.converted,
+            Money::new(revenue * Decimal::from(2), Currency::new("EUR"))
+        );
+
+        data.financial_currency = None;
+        assert!(matches!(
+            data.convert(Decimal::from(1), &Currency::new("EUR")),
+            Err(YahooError::MissingCurrency)
+        ));
+    }
+}
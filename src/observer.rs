@@ -0,0 +1,116 @@
+//! Hook to monitor the requests sent to yahoo! finance and the authentication with cookie and
+//! crumb, e.g. to collect metrics. Frequent refreshes of the crumb are often the first sign of
+//! throttling by yahoo! finance.
+use std::{
+    sync::atomic::{AtomicU64, Ordering},
+    time::Duration,
+};
+
+/// Outcome of a request sent to yahoo! finance
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequestEvent {
+    /// Name of the endpoint, e.g. `chart` or `quote_summary`, see `YEndpoint::name`
+    pub endpoint: &'static str,
+    /// HTTP status of the response, or `None` if the request failed without response
+    pub status: Option<u16>,
+    /// Size of the response body after decompression
+    pub bytes: u64,
+    /// Time from sending the request until the body has been received
+    pub duration: Duration,
+}
+
+/// Event of the authentication of a connector with cookie and crumb
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AuthEvent {
+    /// A session cookie has been fetched
+    CookieFetched,
+    /// A new crumb has been received
+    CrumbRefreshed,
+    /// The crumb has been rejected and the request is retried with fresh credentials
+    CrumbRetried,
+    /// yahoo! finance responded with status 401 (unauthorized)
+    Unauthorized,
+}
+
+impl AuthEvent {
+    pub const ALL: [AuthEvent; 4] = [
+        AuthEvent::CookieFetched,
+        AuthEvent::CrumbRefreshed,
+        AuthEvent::CrumbRetried,
+        AuthEvent::Unauthorized,
+    ];
+
+    /// Name of the event, e.g. as label of metrics
+    pub fn name(&self) -> &'static str {
+        match self {
+            AuthEvent::CookieFetched => "cookie_fetched",
+            AuthEvent::CrumbRefreshed => "crumb_refreshed",
+            AuthEvent::CrumbRetried => "crumb_retried",
+            AuthEvent::Unauthorized => "unauthorized",
+        }
+    }
+
+    fn index(&self) -> usize {
+        *self as usize
+    }
+}
+
+/// Receiver of events about the requests sent by a connector, see
+/// `YahooConnectorBuilder::observer`
+pub trait RequestObserver: Send + Sync {
+    /// Called after each request, after the response body has been received
+    fn on_request(&self, event: &RequestEvent);
+
+    /// Called for each event of the authentication, in addition to `on_request` for the
+    /// requests involved
+    fn on_auth(&self, _event: AuthEvent) {}
+}
+
+/// Observer counting requests, received bytes and authentication events
+#[derive(Debug, Default)]
+pub struct TransferStats {
+    requests: AtomicU64,
+    failed: AtomicU64,
+    bytes: AtomicU64,
+    auth: [AtomicU64; 4],
+}
+
+impl TransferStats {
+    pub fn new() -> TransferStats {
+        TransferStats::default()
+    }
+
+    /// Number of requests sent
+    pub fn requests(&self) -> u64 {
+        self.requests.load(Ordering::Relaxed)
+    }
+
+    /// Number of requests which failed without response or with an HTTP error status
+    pub fn failed(&self) -> u64 {
+        self.failed.load(Ordering::Relaxed)
+    }
+
+    /// Number of bytes received in response bodies, after decompression
+    pub fn bytes_received(&self) -> u64 {
+        self.bytes.load(Ordering::Relaxed)
+    }
+
+    /// Number of authentication events of the given kind
+    pub fn auth_events(&self, event: AuthEvent) -> u64 {
+        self.auth[event.index()].load(Ordering::Relaxed)
+    }
+}
+
+impl RequestObserver for TransferStats {
+    fn on_request(&self, event: &RequestEvent) {
+        self.requests.fetch_add(1, Ordering::Relaxed);
+        if event.status.map_or(true, |status| status >= 400) {
+            self.failed.fetch_add(1, Ordering::Relaxed);
+        }
+        self.bytes.fetch_add(event.bytes, Ordering::Relaxed);
+    }
+
+    fn on_auth(&self, event: AuthEvent) {
+        self.auth[event.index()].fetch_add(1, Ordering::Relaxed);
+    }
+}
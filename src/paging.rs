@@ -0,0 +1,221 @@
+//! Items of results fetched lazily as they are consumed, as returned by `news_pages`.
+//!
+//! Since yahoo! finance has no offset parameter for news, `news_pages` requests all articles
+//! with the first page when the stream is first consumed; this is not a general pagination of
+//! the API. The results end with the first page holding fewer items than requested, or after
+//! an error, which is returned as last item.
+//!
+//! ```rust,ignore
+//! let mut news = connector.news_pages("Apple", 10);
+//! while let Some(item) = news.next().await {
+//!     println!("{}", item?.title);
+//! }
+//! ```
+use std::collections::VecDeque;
+#[cfg(not(feature = "blocking"))]
+use std::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+#[cfg(not(feature = "blocking"))]
+use futures_util::Stream;
+
+use super::YahooError;
+
+/// Position of the next page and the items of the current page not consumed yet
+struct Pages<F, T> {
+    fetch: F,
+    page_size: usize,
+    start: usize,
+    done: bool,
+    items: VecDeque<T>,
+}
+
+impl<F, T> Pages<F, T> {
+    fn new(fetch: F, page_size: usize) -> Pages<F, T> {
+        Pages {
+            fetch,
+            page_size,
+            start: 0,
+            done: false,
+            items: VecDeque::new(),
+        }
+    }
+
+    /// Keep the items of a fetched page, returning the error of a failed one
+    fn receive(&mut self, page: Result<Vec<T>, YahooError>) -> Option<YahooError> {
+        match page {
+            Ok(items) => {
+                self.done = items.is_empty() || items.len() < self.page_size;
+                self.start += items.len();
+                self.items.extend(items);
+                None
+            }
+            Err(e) => {
+                self.done = true;
+                Some(e)
+            }
+        }
+    }
+}
+
+/// Stream of items fetched when the stream is first consumed, see `news_pages`
+#[cfg(not(feature = "blocking"))]
+pub struct PagedStream<'a, T> {
+    inner: Pin<Box<dyn Stream<Item = Result<T, YahooError>> + Send + 'a>>,
+}
+
+#[cfg(not(feature = "blocking"))]
+impl<'a, T: Send + 'a> PagedStream<'a, T> {
+    /// Stream calling `fetch(start, count)` for pages of `page_size` items
+    pub(crate) fn new<F, Fut>(page_size: usize, fetch: F) -> PagedStream<'a, T>
+    where
+        F: FnMut(usize, usize) -> Fut + Send + 'a,
+        Fut: Future<Output = Result<Vec<T>, YahooError>> + Send + 'a,
+    {
+        let stream =
+            futures_util::stream::unfold(Pages::new(fetch, page_size), |mut pages| async {
+                loop {
+                    if let Some(item) = pages.items.pop_front() {
+                        return Some((Ok(item), pages));
+                    }
+                    if pages.done {
+                        return None;
+                    }
+                    let page = (pages.fetch)(pages.start, pages.page_size).await;
+                    if let Some(e) = pages.receive(page) {
+                        return Some((Err(e), pages));
+                    }
+                }
+            });
+        PagedStream {
+            inner: Box::pin(stream),
+        }
+    }
+}
+
+#[cfg(not(feature = "blocking"))]
+impl<T> Stream for PagedStream<'_, T> {
+    type Item = Result<T, YahooError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.inner.as_mut().poll_next(cx)
+    }
+}
+
+#[cfg(feature = "blocking")]
+type FetchPage<'a, T> = Box<dyn FnMut(usize, usize) -> Result<Vec<T>, YahooError> + 'a>;
+
+/// Iterator over items fetched when the iterator is first consumed, see `news_pages`
+#[cfg(feature = "blocking")]
+pub struct PagedStream<'a, T> {
+    pages: Pages<FetchPage<'a, T>, T>,
+}
+
+#[cfg(feature = "blocking")]
+impl<'a, T> PagedStream<'a, T> {
+    /// Iterator calling `fetch(start, count)` for pages of `page_size` items
+    pub(crate) fn new<F>(page_size: usize, fetch: F) -> PagedStream<'a, T>
+    where
+        F: FnMut(usize, usize) -> Result<Vec<T>, YahooError> + 'a,
+    {
+        PagedStream {
+            pages: Pages::new(Box::new(fetch), page_size),
+        }
+    }
+}
+
+#[cfg(feature = "blocking")]
+impl<T> Iterator for PagedStream<'_, T> {
+    type Item = Result<T, YahooError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let pages = &mut self.pages;
+        loop {
+            if let Some(item) = pages.items.pop_front() {
+                return Some(Ok(item));
+            }
+            if pages.done {
+                return None;
+            }
+            let page = (pages.fetch)(pages.start, pages.page_size);
+            if let Some(e) = pages.receive(page) {
+                return Some(Err(e));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+
+    /// Pages of the numbers below `total`, recording the requested starts
+    fn numbers(
+        total: usize,
+        starts: Arc<Mutex<Vec<usize>>>,
+    ) -> impl FnMut(usize, usize) -> Result<Vec<usize>, YahooError> {
+        move |start, count| {
+            starts.lock().unwrap().push(start);
+            if start >= 20 {
+                return Err(YahooError::EmptyDataSet);
+            }
+            Ok((start..total.min(start + count)).collect())
+        }
+    }
+
+    #[cfg(not(feature = "blocking"))]
+    fn collect(stream: PagedStream<'_, usize>) -> Vec<Result<usize, YahooError>> {
+        use futures_util::StreamExt;
+        tokio_test::block_on(stream.collect())
+    }
+
+    #[cfg(not(feature = "blocking"))]
+    fn paged(
+        total: usize,
+        page_size: usize,
+        starts: Arc<Mutex<Vec<usize>>>,
+    ) -> PagedStream<'static, usize> {
+        let mut fetch = numbers(total, starts);
+        PagedStream::new(page_size, move |start, count| {
+            std::future::ready(fetch(start, count))
+        })
+    }
+
+    #[cfg(feature = "blocking")]
+    fn collect(stream: PagedStream<'_, usize>) -> Vec<Result<usize, YahooError>> {
+        stream.collect()
+    }
+
+    #[cfg(feature = "blocking")]
+    fn paged(
+        total: usize,
+        page_size: usize,
+        starts: Arc<Mutex<Vec<usize>>>,
+    ) -> PagedStream<'static, usize> {
+        PagedStream::new(page_size, numbers(total, starts))
+    }
+
+    #[test]
+    fn test_paged_stream() {
+        let starts = Arc::new(Mutex::new(Vec::new()));
+        let items = collect(paged(7, 3, starts.clone()));
+        let items: Vec<usize> = items.into_iter().map(Result::unwrap).collect();
+        assert_eq!(items, (0..7).collect::<Vec<_>>());
+        assert_eq!(*starts.lock().unwrap(), vec![0, 3, 6]);
+
+        // a last full page is followed by an empty one
+        let starts = Arc::new(Mutex::new(Vec::new()));
+        assert_eq!(collect(paged(6, 3, starts.clone())).len(), 6);
+        assert_eq!(*starts.lock().unwrap(), vec![0, 3, 6]);
+
+        // errors end the stream
+        let items = collect(paged(100, 10, Arc::new(Mutex::new(Vec::new()))));
+        assert_eq!(items.len(), 21);
+        assert!(matches!(items[20], Err(YahooError::EmptyDataSet)));
+    }
+}
@@ -0,0 +1,234 @@
+//! Valuation of a portfolio of positions in a common currency.
+//!
+//! ```rust,ignore
+//! let mut portfolio = Portfolio::new(Currency::new("EUR"));
+//! portfolio.add_position("AAPL", Decimal::from(10), Money::new(cost, Currency::new("USD")));
+//! let valuation = portfolio.value(&connector).await?;
+//! println!("{} ({})", valuation.market_value, valuation.profit_loss);
+//! ```
+use std::collections::HashMap;
+
+use super::quotes::ZERO;
+use super::{Currency, Decimal, LatestPrice, Money, YahooConnector, YahooError};
+
+/// Holding of a symbol
+#[derive(Debug, Clone, PartialEq)]
+pub struct Position {
+    pub symbol: String,
+    pub quantity: Decimal,
+    /// Total amount paid for the position
+    pub cost_basis: Money,
+}
+
+/// Positions valued in a common currency
+#[derive(Debug, Clone, PartialEq)]
+pub struct Portfolio {
+    pub currency: Currency,
+    pub positions: Vec<Position>,
+}
+
+/// Value of a position, converted into the currency of the portfolio
+#[derive(Debug, Clone, PartialEq)]
+pub struct PositionValuation {
+    pub symbol: String,
+    pub quantity: Decimal,
+    /// Latest price in the currency of the symbol
+    pub price: Money,
+    pub market_value: Money,
+    pub cost_basis: Money,
+    pub profit_loss: Money,
+    /// Share of the total market value of the portfolio, between 0 and 1
+    pub weight: Decimal,
+}
+
+/// Value of all positions of a portfolio in the currency of the portfolio
+#[derive(Debug, Clone, PartialEq)]
+pub struct PortfolioValuation {
+    pub positions: Vec<PositionValuation>,
+    pub market_value: Money,
+    pub cost_basis: Money,
+    pub profit_loss: Money,
+}
+
+impl Portfolio {
+    pub fn new(currency: Currency) -> Portfolio {
+        Portfolio {
+            currency,
+            positions: Vec::new(),
+        }
+    }
+
+    pub fn add_position(&mut self, symbol: &str, quantity: Decimal, cost_basis: Money) {
+        self.positions.push(Position {
+            symbol: symbol.to_string(),
+            quantity,
+            cost_basis,
+        });
+    }
+
+    /// Return the distinct symbols of all positions
+    pub fn symbols(&self) -> Vec<&str> {
+        let mut symbols: Vec<&str> = self.positions.iter().map(|p| p.symbol.as_str()).collect();
+        symbols.sort_unstable();
+        symbols.dedup();
+        symbols
+    }
+
+    /// Value the portfolio given the latest prices by symbol and the exchange rates into the
+    /// currency of the portfolio by currency. Fails if a price or rate is missing.
+    pub fn valuation(
+        &self,
+        prices: &HashMap<String, LatestPrice>,
+        rates: &HashMap<Currency, Decimal>,
+    ) -> Result<PortfolioValuation, YahooError> {
+        let convert = |money: &Money| -> Result<Money, YahooError> {
+            if money.currency == self.currency {
+                return Ok(money.clone());
+            }
+            let rate = rates.get(&money.currency).ok_or_else(|| {
+                YahooError::CurrencyMismatch(money.currency.to_string(), self.currency.to_string())
+            })?;
+            Ok(money.convert(*rate, self.currency.clone()))
+        };
+        let zero = Money::new(ZERO, self.currency.clone());
+        let mut positions = Vec::with_capacity(self.positions.len());
+        let mut market_value = zero.clone();
+        let mut cost_basis = zero.clone();
+        for position in &self.positions {
+            let price = prices
+                .get(&position.symbol)
+                .ok_or_else(|| YahooError::SymbolNotFound(position.symbol.clone()))?
+                .money()
+                .ok_or(YahooError::MissingCurrency)?;
+            let value = convert(&price.scale(position.quantity))?;
+            let cost = convert(&position.cost_basis)?;
+            market_value = market_value.checked_add(&value)?;
+            cost_basis = cost_basis.checked_add(&cost)?;
+            positions.push(PositionValuation {
+                symbol: position.symbol.clone(),
+                quantity: position.quantity,
+                price,
+                profit_loss: value.checked_sub(&cost)?,
+                market_value: value,
+                cost_basis: cost,
+                weight: ZERO,
+            });
+        }
+        if market_value.amount != ZERO {
+            for position in &mut positions {
+                position.weight = position.market_value.amount / market_value.amount;
+            }
+        }
+        Ok(PortfolioValuation {
+            positions,
+            profit_loss: market_value.checked_sub(&cost_basis)?,
+            market_value,
+            cost_basis,
+        })
+    }
+
+    /// Return the currencies of prices and cost bases which need to be converted
+    fn foreign_currencies(&self, prices: &HashMap<String, LatestPrice>) -> Vec<Currency> {
+        let mut currencies: Vec<Currency> = prices
+            .values()
+            .filter_map(|price| price.currency.as_deref().map(Currency::new))
+            .chain(self.positions.iter().map(|p| p.cost_basis.currency.clone()))
+            .filter(|currency| *currency != self.currency)
+            .collect();
+        currencies.sort();
+        currencies.dedup();
+        currencies
+    }
+
+    /// Fetch the latest prices of all positions in batched requests and the required exchange
+    /// rates, and value the portfolio
+    #[cfg(not(feature = "blocking"))]
+    pub async fn value(
+        &self,
+        connector: &YahooConnector,
+    ) -> Result<PortfolioValuation, YahooError> {
+        let prices = connector.get_latest_prices(&self.symbols()).await?;
+        let mut rates = HashMap::new();
+        for currency in self.foreign_currencies(&prices) {
+            let rate = connector.get_fx_rate(&currency, &self.currency).await?;
+            rates.insert(currency, rate);
+        }
+        self.valuation(&prices, &rates)
+    }
+
+    /// Fetch the latest prices of all positions in batched requests and the required exchange
+    /// rates, and value the portfolio
+    #[cfg(feature = "blocking")]
+    pub fn value(&self, connector: &YahooConnector) -> Result<PortfolioValuation, YahooError> {
+        let prices = connector.get_latest_prices(&self.symbols())?;
+        let mut rates = HashMap::new();
+        for currency in self.foreign_currencies(&prices) {
+            let rate = connector.get_fx_rate(&currency, &self.currency)?;
+            rates.insert(currency, rate);
+        }
+        self.valuation(&prices, &rates)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn price(amount: u32, currency: &str) -> LatestPrice {
+        LatestPrice {
+            price: Decimal::from(amount),
+            time: 0,
+            currency: Some(currency.into()),
+            market_state: None,
+            delayed_by: None,
+        }
+    }
+
+    #[test]
+    fn test_valuation() {
+        let eur = Currency::new("EUR");
+        let usd = Currency::new("USD");
+        let mut portfolio = Portfolio::new(eur.clone());
+        portfolio.add_position(
+            "AAPL",
+            Decimal::from(10),
+            Money::new(Decimal::from(1000), usd),
+        );
+        portfolio.add_position(
+            "SAP.DE",
+            Decimal::from(5),
+            Money::new(Decimal::from(800), eur),
+        );
+        let prices = HashMap::from([
+            ("AAPL".to_string(), price(200, "USD")),
+            ("SAP.DE".to_string(), price(120, "EUR")),
+        ]);
+        assert_eq!(
+            portfolio.foreign_currencies(&prices),
+            vec![Currency::new("USD")]
+        );
+        let rates = HashMap::from([(Currency::new("USD"), "0.5".parse::<Decimal>().unwrap())]);
+        let valuation = portfolio.valuation(&prices, &rates).unwrap();
+        assert_eq!(valuation.market_value.amount, Decimal::from(1600));
+        assert_eq!(valuation.cost_basis.amount, Decimal::from(1300));
+        assert_eq!(valuation.profit_loss.amount, Decimal::from(300));
+        let aapl = &valuation.positions[0];
+        assert_eq!(
+            aapl.price,
+            Money::new(Decimal::from(200), Currency::new("USD"))
+        );
+        assert_eq!(aapl.market_value.amount, Decimal::from(1000));
+        assert_eq!(aapl.profit_loss.amount, Decimal::from(500));
+        assert_eq!(aapl.weight, "0.625".parse::<Decimal>().unwrap());
+
+        assert!(matches!(
+            portfolio.valuation(&prices, &HashMap::new()),
+            Err(YahooError::CurrencyMismatch(_, _))
+        ));
+        let prices = HashMap::from([("SAP.DE".to_string(), price(120, "EUR"))]);
+        assert!(matches!(
+            portfolio.valuation(&prices, &rates),
+            Err(YahooError::SymbolNotFound(symbol)) if symbol == "AAPL"
+        ));
+    }
+}
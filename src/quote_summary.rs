@@ -0,0 +1,695 @@
+//! Detailed information about a ticker as returned by the quoteSummary endpoint.
+//!
+//! Numbers are modelled as `YValue`, which contains the formatted representations if the
+//! summary has been requested with `formatted` set, see `get_quote_summary`.
+//!
+//! Fields are named like their camelCase counterparts in the responses. Where yahoo! finance
+//! deviates from this (e.g. `forwardPE` or `52WeekChange`) or has been seen to change the
+//! casing of a name, the field is renamed explicitly and accepts the variants as aliases.
+#[cfg(all(feature = "capture_unknown", not(feature = "deny_unknown")))]
+use std::collections::HashMap;
+
+use serde::Deserialize;
+
+use super::quotes::deserialize_null_as_empty;
+use time::OffsetDateTime;
+
+use super::{
+    deserialize_value, Decimal, Exchange, InternedStr, YChartError, YTimestamp, YValue, YahooError,
+};
+
+/// Fields of the modules returned by yahoo! finance which are intentionally not modelled, e.g.
+/// the cache lifetime `maxAge` of every module. With the `deny_unknown` feature they are ignored
+/// rather than rejected, so only fields neither modelled nor listed here fail to deserialize.
+pub const YQUOTE_SUMMARY_NOT_MODELLED: &[&str] = &[
+    "maxAge",
+    "companyOfficers",
+    "industryKey",
+    "sectorKey",
+    "fromCurrency",
+    "priceHint",
+    "gmtOffSetMilliseconds",
+    "messageBoardId",
+    "underlyingSymbol",
+    "uuid",
+];
+
+/// Modules requested from the quoteSummary endpoint by `get_ticker_info`
+pub const YQUOTE_SUMMARY_MODULES: &[&str] = &[
+    "financialData",
+    "quoteType",
+    "defaultKeyStatistics",
+    "assetProfile",
+    "summaryDetail",
+];
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct YQuoteSummary {
+    pub quote_summary: YFinance,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct YFinance {
+    /// List of results, each containing the requested modules
+    #[serde(default, deserialize_with = "deserialize_null_as_empty")]
+    pub result: Vec<YSummaryData>,
+    pub error: Option<YChartError>,
+}
+
+/// Modules of a quoteSummary result; modules not requested are `None`
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "deny_unknown", serde(deny_unknown_fields))]
+pub struct YSummaryData {
+    pub financial_data: Option<FinancialData>,
+    pub quote_type: Option<QuoteType>,
+    pub default_key_statistics: Option<DefaultKeyStatistics>,
+    pub asset_profile: Option<AssetProfile>,
+    pub summary_detail: Option<SummaryDetail>,
+    pub page_views: Option<PageViews>,
+    pub earnings_history: Option<EarningsHistory>,
+    /// Fields returned by yahoo! finance which are not (yet) modelled by this struct
+    #[cfg(all(feature = "capture_unknown", not(feature = "deny_unknown")))]
+    #[serde(flatten)]
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "deny_unknown", serde(deny_unknown_fields))]
+pub struct FinancialData {
+    pub financial_currency: Option<InternedStr>,
+    #[serde(default, deserialize_with = "deserialize_value")]
+    pub current_price: Option<YValue<Decimal>>,
+    #[serde(default, deserialize_with = "deserialize_value")]
+    pub target_high_price: Option<YValue<Decimal>>,
+    #[serde(default, deserialize_with = "deserialize_value")]
+    pub target_low_price: Option<YValue<Decimal>>,
+    #[serde(default, deserialize_with = "deserialize_value")]
+    pub target_mean_price: Option<YValue<Decimal>>,
+    #[serde(default, deserialize_with = "deserialize_value")]
+    pub recommendation_mean: Option<YValue<Decimal>>,
+    pub recommendation_key: Option<InternedStr>,
+    #[serde(default, deserialize_with = "deserialize_value")]
+    pub number_of_analyst_opinions: Option<YValue<u32>>,
+    #[serde(default, deserialize_with = "deserialize_value")]
+    pub total_cash: Option<YValue<Decimal>>,
+    #[serde(default, deserialize_with = "deserialize_value")]
+    pub total_debt: Option<YValue<Decimal>>,
+    #[serde(default, deserialize_with = "deserialize_value")]
+    pub total_revenue: Option<YValue<Decimal>>,
+    #[serde(default, alias = "EBITDA", deserialize_with = "deserialize_value")]
+    pub ebitda: Option<YValue<Decimal>>,
+    #[serde(default, deserialize_with = "deserialize_value")]
+    pub gross_profits: Option<YValue<Decimal>>,
+    #[serde(
+        default,
+        alias = "freeCashFlow",
+        deserialize_with = "deserialize_value"
+    )]
+    pub free_cashflow: Option<YValue<Decimal>>,
+    #[serde(
+        default,
+        alias = "operatingCashFlow",
+        deserialize_with = "deserialize_value"
+    )]
+    pub operating_cashflow: Option<YValue<Decimal>>,
+    #[serde(default, deserialize_with = "deserialize_value")]
+    pub debt_to_equity: Option<YValue<Decimal>>,
+    #[serde(default, deserialize_with = "deserialize_value")]
+    pub return_on_assets: Option<YValue<Decimal>>,
+    #[serde(default, deserialize_with = "deserialize_value")]
+    pub return_on_equity: Option<YValue<Decimal>>,
+    #[serde(default, deserialize_with = "deserialize_value")]
+    pub earnings_growth: Option<YValue<Decimal>>,
+    #[serde(default, deserialize_with = "deserialize_value")]
+    pub revenue_growth: Option<YValue<Decimal>>,
+    #[serde(default, deserialize_with = "deserialize_value")]
+    pub gross_margins: Option<YValue<Decimal>>,
+    #[serde(default, deserialize_with = "deserialize_value")]
+    pub operating_margins: Option<YValue<Decimal>>,
+    #[serde(default, deserialize_with = "deserialize_value")]
+    pub profit_margins: Option<YValue<Decimal>>,
+    /// Fields returned by yahoo! finance which are not (yet) modelled by this struct
+    #[cfg(all(feature = "capture_unknown", not(feature = "deny_unknown")))]
+    #[serde(flatten)]
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "deny_unknown", serde(deny_unknown_fields))]
+pub struct QuoteType {
+    pub symbol: String,
+    pub quote_type: InternedStr,
+    pub exchange: Option<Exchange>,
+    pub short_name: Option<String>,
+    pub long_name: Option<String>,
+    #[serde(
+        default,
+        alias = "firstTradeDateEpochUTC",
+        deserialize_with = "deserialize_value"
+    )]
+    pub first_trade_date_epoch_utc: Option<YValue<YTimestamp>>,
+    pub time_zone_full_name: Option<InternedStr>,
+    pub time_zone_short_name: Option<InternedStr>,
+    /// Fields returned by yahoo! finance which are not (yet) modelled by this struct
+    #[cfg(all(feature = "capture_unknown", not(feature = "deny_unknown")))]
+    #[serde(flatten)]
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "deny_unknown", serde(deny_unknown_fields))]
+pub struct DefaultKeyStatistics {
+    #[serde(default, deserialize_with = "deserialize_value")]
+    pub enterprise_value: Option<YValue<Decimal>>,
+    #[serde(
+        default,
+        rename = "forwardPE",
+        alias = "forwardPe",
+        deserialize_with = "deserialize_value"
+    )]
+    pub forward_pe: Option<YValue<Decimal>>,
+    #[serde(default, deserialize_with = "deserialize_value")]
+    pub peg_ratio: Option<YValue<Decimal>>,
+    #[serde(default, deserialize_with = "deserialize_value")]
+    pub price_to_book: Option<YValue<Decimal>>,
+    #[serde(default, deserialize_with = "deserialize_value")]
+    pub book_value: Option<YValue<Decimal>>,
+    #[serde(default, deserialize_with = "deserialize_value")]
+    pub trailing_eps: Option<YValue<Decimal>>,
+    #[serde(default, deserialize_with = "deserialize_value")]
+    pub forward_eps: Option<YValue<Decimal>>,
+    #[serde(default, deserialize_with = "deserialize_value")]
+    pub beta: Option<YValue<Decimal>>,
+    #[serde(default, deserialize_with = "deserialize_value")]
+    pub shares_outstanding: Option<YValue<u64>>,
+    #[serde(default, deserialize_with = "deserialize_value")]
+    pub float_shares: Option<YValue<u64>>,
+    #[serde(default, deserialize_with = "deserialize_value")]
+    pub shares_short: Option<YValue<u64>>,
+    #[serde(default, deserialize_with = "deserialize_value")]
+    pub short_ratio: Option<YValue<Decimal>>,
+    #[serde(default, deserialize_with = "deserialize_value")]
+    pub held_percent_insiders: Option<YValue<Decimal>>,
+    #[serde(default, deserialize_with = "deserialize_value")]
+    pub held_percent_institutions: Option<YValue<Decimal>>,
+    #[serde(default, deserialize_with = "deserialize_value")]
+    pub enterprise_to_revenue: Option<YValue<Decimal>>,
+    #[serde(
+        default,
+        alias = "enterpriseToEBITDA",
+        deserialize_with = "deserialize_value"
+    )]
+    pub enterprise_to_ebitda: Option<YValue<Decimal>>,
+    #[serde(
+        default,
+        rename = "52WeekChange",
+        alias = "fiftyTwoWeekChange",
+        deserialize_with = "deserialize_value"
+    )]
+    pub fifty_two_week_change: Option<YValue<Decimal>>,
+    pub last_split_factor: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_value")]
+    pub last_split_date: Option<YValue<YTimestamp>>,
+    #[serde(default, deserialize_with = "deserialize_value")]
+    pub last_dividend_value: Option<YValue<Decimal>>,
+    /// Fields returned by yahoo! finance which are not (yet) modelled by this struct
+    #[cfg(all(feature = "capture_unknown", not(feature = "deny_unknown")))]
+    #[serde(flatten)]
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "deny_unknown", serde(deny_unknown_fields))]
+pub struct AssetProfile {
+    pub address1: Option<String>,
+    pub city: Option<String>,
+    pub state: Option<String>,
+    pub zip: Option<String>,
+    pub country: Option<InternedStr>,
+    pub phone: Option<String>,
+    pub website: Option<String>,
+    pub industry: Option<InternedStr>,
+    pub sector: Option<InternedStr>,
+    pub long_business_summary: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_value")]
+    pub full_time_employees: Option<YValue<u64>>,
+    /// Fields returned by yahoo! finance which are not (yet) modelled by this struct
+    #[cfg(all(feature = "capture_unknown", not(feature = "deny_unknown")))]
+    #[serde(flatten)]
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "deny_unknown", serde(deny_unknown_fields))]
+pub struct SummaryDetail {
+    pub currency: Option<InternedStr>,
+    #[serde(default, deserialize_with = "deserialize_value")]
+    pub previous_close: Option<YValue<Decimal>>,
+    #[serde(default, deserialize_with = "deserialize_value")]
+    pub open: Option<YValue<Decimal>>,
+    #[serde(default, deserialize_with = "deserialize_value")]
+    pub day_low: Option<YValue<Decimal>>,
+    #[serde(default, deserialize_with = "deserialize_value")]
+    pub day_high: Option<YValue<Decimal>>,
+    #[serde(default, deserialize_with = "deserialize_value")]
+    pub bid: Option<YValue<Decimal>>,
+    #[serde(default, deserialize_with = "deserialize_value")]
+    pub ask: Option<YValue<Decimal>>,
+    #[serde(default, deserialize_with = "deserialize_value")]
+    pub volume: Option<YValue<u64>>,
+    #[serde(default, deserialize_with = "deserialize_value")]
+    pub average_volume: Option<YValue<u64>>,
+    #[serde(default, deserialize_with = "deserialize_value")]
+    pub market_cap: Option<YValue<Decimal>>,
+    #[serde(default, deserialize_with = "deserialize_value")]
+    pub beta: Option<YValue<Decimal>>,
+    #[serde(
+        default,
+        rename = "trailingPE",
+        alias = "trailingPe",
+        deserialize_with = "deserialize_value"
+    )]
+    pub trailing_pe: Option<YValue<Decimal>>,
+    #[serde(
+        default,
+        rename = "forwardPE",
+        alias = "forwardPe",
+        deserialize_with = "deserialize_value"
+    )]
+    pub forward_pe: Option<YValue<Decimal>>,
+    #[serde(default, deserialize_with = "deserialize_value")]
+    pub dividend_rate: Option<YValue<Decimal>>,
+    #[serde(default, deserialize_with = "deserialize_value")]
+    pub dividend_yield: Option<YValue<Decimal>>,
+    #[serde(default, deserialize_with = "deserialize_value")]
+    pub ex_dividend_date: Option<YValue<YTimestamp>>,
+    #[serde(default, deserialize_with = "deserialize_value")]
+    pub payout_ratio: Option<YValue<Decimal>>,
+    #[serde(default, deserialize_with = "deserialize_value")]
+    pub fifty_two_week_low: Option<YValue<Decimal>>,
+    #[serde(default, deserialize_with = "deserialize_value")]
+    pub fifty_two_week_high: Option<YValue<Decimal>>,
+    #[serde(default, deserialize_with = "deserialize_value")]
+    pub fifty_day_average: Option<YValue<Decimal>>,
+    #[serde(default, deserialize_with = "deserialize_value")]
+    pub two_hundred_day_average: Option<YValue<Decimal>>,
+    /// Fields returned by yahoo! finance which are not (yet) modelled by this struct
+    #[cfg(all(feature = "capture_unknown", not(feature = "deny_unknown")))]
+    #[serde(flatten)]
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
+/// Direction of the page views of a symbol on the yahoo! finance web pages
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum Trend {
+    Up,
+    Down,
+    Neutral,
+    /// Trend not known to this crate
+    #[serde(other)]
+    Unknown,
+}
+
+/// Trends of the page views of a symbol, a proxy of the attention the symbol gets
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "deny_unknown", serde(deny_unknown_fields))]
+pub struct PageViews {
+    pub short_term_trend: Option<Trend>,
+    pub mid_term_trend: Option<Trend>,
+    pub long_term_trend: Option<Trend>,
+    /// Fields returned by yahoo! finance which are not (yet) modelled by this struct
+    #[cfg(all(feature = "capture_unknown", not(feature = "deny_unknown")))]
+    #[serde(flatten)]
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
+/// Estimated and reported earnings per share of the last quarters
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "deny_unknown", serde(deny_unknown_fields))]
+pub struct EarningsHistory {
+    #[serde(default, deserialize_with = "deserialize_null_as_empty")]
+    pub history: Vec<EarningsHistoryEntry>,
+    /// Fields returned by yahoo! finance which are not (yet) modelled by this struct
+    #[cfg(all(feature = "capture_unknown", not(feature = "deny_unknown")))]
+    #[serde(flatten)]
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "deny_unknown", serde(deny_unknown_fields))]
+pub struct EarningsHistoryEntry {
+    #[serde(default, deserialize_with = "deserialize_value")]
+    pub eps_actual: Option<YValue<Decimal>>,
+    #[serde(default, deserialize_with = "deserialize_value")]
+    pub eps_estimate: Option<YValue<Decimal>>,
+    #[serde(default, deserialize_with = "deserialize_value")]
+    pub eps_difference: Option<YValue<Decimal>>,
+    /// Difference of actual and estimated earnings relative to the estimate, e.g. 0.05 for 5%
+    #[serde(default, deserialize_with = "deserialize_value")]
+    pub surprise_percent: Option<YValue<Decimal>>,
+    /// End of the fiscal quarter
+    #[serde(default, deserialize_with = "deserialize_value")]
+    pub quarter: Option<YValue<YTimestamp>>,
+    /// Quarter relative to the current one, e.g. `-1q`
+    pub period: Option<String>,
+    pub currency: Option<InternedStr>,
+    /// Fields returned by yahoo! finance which are not (yet) modelled by this struct
+    #[cfg(all(feature = "capture_unknown", not(feature = "deny_unknown")))]
+    #[serde(flatten)]
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
+/// Reported earnings per share of a fiscal quarter compared to the estimate of the analysts
+#[derive(Debug, Clone, PartialEq)]
+pub struct EarningsSurprise {
+    /// End of the fiscal quarter
+    pub quarter: OffsetDateTime,
+    /// Quarter relative to the current one, e.g. `-1q`
+    pub period: Option<String>,
+    pub currency: Option<String>,
+    pub eps_actual: Option<Decimal>,
+    pub eps_estimate: Option<Decimal>,
+    /// Actual minus estimated earnings per share
+    pub eps_difference: Option<Decimal>,
+    /// Difference of actual and estimated earnings in percent of the estimate
+    pub surprise_percent: Option<Decimal>,
+}
+
+impl EarningsHistory {
+    /// Quarters of the history sorted by date; entries without quarter are skipped
+    pub fn surprises(&self) -> Vec<EarningsSurprise> {
+        let raw = |value: &Option<YValue<Decimal>>| value.as_ref().map(|value| value.raw);
+        let mut surprises: Vec<EarningsSurprise> = self
+            .history
+            .iter()
+            .filter_map(|entry| {
+                Some(EarningsSurprise {
+                    quarter: entry.quarter.as_ref()?.raw.to_datetime()?,
+                    period: entry.period.clone(),
+                    currency: entry.currency.as_ref().map(|c| c.to_string()),
+                    eps_actual: raw(&entry.eps_actual),
+                    eps_estimate: raw(&entry.eps_estimate),
+                    eps_difference: raw(&entry.eps_difference),
+                    surprise_percent: raw(&entry.surprise_percent)
+                        .map(|surprise| surprise * Decimal::from(100)),
+                })
+            })
+            .collect();
+        surprises.sort_by_key(|surprise| surprise.quarter);
+        surprises
+    }
+}
+
+/// Envelope of errors returned by yahoo! finance for rejected requests, e.g. with an invalid
+/// crumb, instead of the usual quoteSummary response
+#[derive(Deserialize)]
+struct YFinanceError {
+    finance: YFinanceErrorBody,
+}
+
+#[derive(Deserialize)]
+struct YFinanceErrorBody {
+    error: YChartError,
+}
+
+impl YQuoteSummary {
+    /// Deserialize a response of the quoteSummary endpoint requested for the given symbol,
+    /// turning errors reported by yahoo! finance into the corresponding `YahooError`
+    pub(crate) fn from_json(
+        json: serde_json::Value,
+        symbol: &str,
+    ) -> Result<YQuoteSummary, YahooError> {
+        if json.get("finance").is_some() {
+            let error: YFinanceError = serde_json::from_value(json)?;
+            return Err(error.finance.error.to_error(symbol));
+        }
+        #[cfg(feature = "deny_unknown")]
+        let json = {
+            let mut json = json;
+            if let Some(result) = json.pointer_mut("/quoteSummary/result") {
+                remove_not_modelled(result);
+            }
+            json
+        };
+        let summary: YQuoteSummary = serde_json::from_value(json)?;
+        match &summary.quote_summary.error {
+            Some(error) => Err(error.to_error(symbol)),
+            None => Ok(summary),
+        }
+    }
+
+    fn first(&self) -> Option<&YSummaryData> {
+        self.quote_summary.result.first()
+    }
+
+    pub fn financial_data(&self) -> Option<&FinancialData> {
+        self.first()?.financial_data.as_ref()
+    }
+
+    pub fn quote_type(&self) -> Option<&QuoteType> {
+        self.first()?.quote_type.as_ref()
+    }
+
+    pub fn default_key_statistics(&self) -> Option<&DefaultKeyStatistics> {
+        self.first()?.default_key_statistics.as_ref()
+    }
+
+    pub fn asset_profile(&self) -> Option<&AssetProfile> {
+        self.first()?.asset_profile.as_ref()
+    }
+
+    pub fn summary_detail(&self) -> Option<&SummaryDetail> {
+        self.first()?.summary_detail.as_ref()
+    }
+
+    pub fn page_views(&self) -> Option<&PageViews> {
+        self.first()?.page_views.as_ref()
+    }
+
+    pub fn earnings_history(&self) -> Option<&EarningsHistory> {
+        self.first()?.earnings_history.as_ref()
+    }
+}
+
+/// Remove the fields listed in `YQUOTE_SUMMARY_NOT_MODELLED` from the modules of the results
+#[cfg(feature = "deny_unknown")]
+fn remove_not_modelled(json: &mut serde_json::Value) {
+    match json {
+        serde_json::Value::Object(fields) => {
+            fields.retain(|key, _| !YQUOTE_SUMMARY_NOT_MODELLED.contains(&key.as_str()));
+            fields.values_mut().for_each(remove_not_modelled);
+        }
+        serde_json::Value::Array(values) => values.iter_mut().for_each(remove_not_modelled),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::quote_summary_fixture;
+
+    /// Names (including aliases) of the fields accepted by a struct deserialized by serde
+    #[cfg(any(not(feature = "capture_unknown"), feature = "deny_unknown"))]
+    fn field_names<'de, T: Deserialize<'de>>() -> Vec<&'static str> {
+        use serde::de::{self, Deserializer, Visitor};
+
+        struct Collector<'a>(&'a mut Vec<&'static str>);
+
+        impl<'de> Deserializer<'de> for Collector<'_> {
+            type Error = de::value::Error;
+
+            fn deserialize_any<V: Visitor<'de>>(self, _: V) -> Result<V::Value, Self::Error> {
+                Err(de::Error::custom("not a struct"))
+            }
+
+            fn deserialize_struct<V: Visitor<'de>>(
+                self,
+                _: &'static str,
+                fields: &'static [&'static str],
+                _: V,
+            ) -> Result<V::Value, Self::Error> {
+                self.0.extend_from_slice(fields);
+                Err(de::Error::custom("fields collected"))
+            }
+
+            serde::forward_to_deserialize_any! {
+                bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes
+                byte_buf option unit unit_struct newtype_struct seq tuple tuple_struct map enum
+                identifier ignored_any
+            }
+        }
+
+        let mut fields = Vec::new();
+        let _ = T::deserialize(Collector(&mut fields));
+        fields
+    }
+
+    /// Every field of the recorded responses must be mapped to a field of the modules, so
+    /// renames not matching the names used by yahoo! finance are detected
+    #[test]
+    #[cfg(any(not(feature = "capture_unknown"), feature = "deny_unknown"))]
+    fn test_field_name_mapping() {
+        let json = quote_summary_fixture("quote_summary_aapl")
+            .unwrap()
+            .json_value();
+        let result = &json["quoteSummary"]["result"][0];
+        let modules = [
+            ("financialData", field_names::<FinancialData>()),
+            ("quoteType", field_names::<QuoteType>()),
+            (
+                "defaultKeyStatistics",
+                field_names::<DefaultKeyStatistics>(),
+            ),
+            ("assetProfile", field_names::<AssetProfile>()),
+            ("summaryDetail", field_names::<SummaryDetail>()),
+        ];
+        for (module, fields) in modules {
+            assert!(!fields.is_empty(), "no fields collected for {module}");
+            for key in result[module].as_object().unwrap().keys() {
+                assert!(
+                    fields.contains(&key.as_str())
+                        || YQUOTE_SUMMARY_NOT_MODELLED.contains(&key.as_str()),
+                    "{module}.{key} is not mapped to a field"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_field_aliases() {
+        let json = serde_json::json!({"quoteSummary": {"result": [{
+            "defaultKeyStatistics": {"forwardPe": 25.5, "fiftyTwoWeekChange": 0.25},
+            "summaryDetail": {"trailingPe": 29.5, "forwardPE": 25.5}
+        }], "error": null}});
+        let summary = YQuoteSummary::from_json(json, "AAPL").unwrap();
+        let statistics = summary.default_key_statistics().unwrap();
+        assert!(statistics.forward_pe.is_some());
+        assert!(statistics.fifty_two_week_change.is_some());
+        let detail = summary.summary_detail().unwrap();
+        assert!(detail.trailing_pe.is_some());
+        assert!(detail.forward_pe.is_some());
+    }
+
+    #[test]
+    fn test_quote_summary() {
+        let summary = quote_summary_fixture("quote_summary_aapl")
+            .unwrap()
+            .quote_summary()
+            .unwrap();
+        let quote_type = summary.quote_type().unwrap();
+        assert_eq!(quote_type.quote_type, "EQUITY");
+        let financial_data = summary.financial_data().unwrap();
+        assert_eq!(
+            financial_data.current_price.as_ref().unwrap().raw,
+            "188.85".parse::<Decimal>().unwrap()
+        );
+        assert_eq!(financial_data.recommendation_key.as_deref(), Some("buy"));
+        assert_eq!(
+            summary
+                .asset_profile()
+                .unwrap()
+                .full_time_employees
+                .as_ref()
+                .unwrap()
+                .raw,
+            161000
+        );
+        let statistics = summary.default_key_statistics().unwrap();
+        assert!(statistics.fifty_two_week_change.is_some());
+        assert!(statistics.forward_pe.is_some());
+        assert!(statistics.peg_ratio.is_none());
+        assert!(summary.summary_detail().unwrap().trailing_pe.is_some());
+    }
+
+    #[test]
+    fn test_formatted_quote_summary() {
+        let summary = quote_summary_fixture("quote_summary_aapl_formatted")
+            .unwrap()
+            .quote_summary()
+            .unwrap();
+        let market_cap = summary
+            .summary_detail()
+            .unwrap()
+            .market_cap
+            .clone()
+            .unwrap();
+        assert_eq!(market_cap.fmt.as_deref(), Some("2.92T"));
+        assert_eq!(market_cap.raw, "2920000000000".parse::<Decimal>().unwrap());
+    }
+
+    #[test]
+    fn test_page_views() {
+        let json = serde_json::json!({"quoteSummary": {"result": [{"pageViews": {
+            "shortTermTrend": "UP",
+            "midTermTrend": "NEUTRAL",
+            "longTermTrend": "SIDEWAYS",
+            "maxAge": 1
+        }}], "error": null}});
+        let summary = YQuoteSummary::from_json(json, "AAPL").unwrap();
+        let page_views = summary.page_views().unwrap();
+        assert_eq!(page_views.short_term_trend, Some(Trend::Up));
+        assert_eq!(page_views.mid_term_trend, Some(Trend::Neutral));
+        assert_eq!(page_views.long_term_trend, Some(Trend::Unknown));
+    }
+
+    #[test]
+    fn test_earnings_history() {
+        let json = serde_json::json!({"quoteSummary": {"result": [{"earningsHistory": {
+            "history": [
+                {"maxAge": 1, "epsActual": {"raw": 1.46, "fmt": "1.46"},
+                 "epsEstimate": {"raw": 1.39, "fmt": "1.39"},
+                 "epsDifference": {"raw": 0.07, "fmt": "0.07"},
+                 "surprisePercent": {"raw": 0.05, "fmt": "5.00%"},
+                 "quarter": {"raw": 1703980800, "fmt": "2023-12-31"}, "period": "-1q"},
+                {"maxAge": 1, "epsActual": {"raw": 1.26, "fmt": "1.26"},
+                 "epsEstimate": {"raw": 1.19, "fmt": "1.19"},
+                 "quarter": {"raw": 1696032000, "fmt": "2023-09-30"}, "period": "-2q"},
+                {"maxAge": 1, "period": "0q"}
+            ],
+            "maxAge": 86400
+        }}], "error": null}});
+        let summary = YQuoteSummary::from_json(json, "AAPL").unwrap();
+        let surprises = summary.earnings_history().unwrap().surprises();
+        assert_eq!(surprises.len(), 2);
+        assert_eq!(surprises[0].quarter.unix_timestamp(), 1696032000);
+        assert_eq!(surprises[0].surprise_percent, None);
+        assert_eq!(surprises[1].period.as_deref(), Some("-1q"));
+        assert_eq!(
+            surprises[1].surprise_percent,
+            Some("0.05".parse::<Decimal>().unwrap() * Decimal::from(100))
+        );
+    }
+
+    #[test]
+    fn test_quote_summary_errors() {
+        let json = serde_json::json!({"quoteSummary": {"result": null, "error": {
+            "code": "Not Found",
+            "description": "Quote not found for symbol: XXXXXX"
+        }}});
+        assert!(matches!(
+            YQuoteSummary::from_json(json, "XXXXXX"),
+            Err(YahooError::SymbolNotFound(symbol)) if symbol == "XXXXXX"
+        ));
+        for name in ["quote_summary_invalid_crumb", "quote_summary_unauthorized"] {
+            assert!(
+                matches!(
+                    quote_summary_fixture(name).unwrap().quote_summary(),
+                    Err(YahooError::InvalidCrumb)
+                ),
+                "fixture {name}"
+            );
+        }
+    }
+}
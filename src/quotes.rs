@@ -5,18 +5,60 @@ use serde::{
     Deserialize, Serialize,
 };
 
-use super::YahooError;
+use super::{AssetClass, Exchange, InternedStr, YTimestamp, YahooError};
 
 #[cfg(not(feature = "decimal"))]
 pub mod decimal {
     pub type Decimal = f64;
     pub const ZERO: Decimal = 0.0;
+
+    pub(crate) fn from_volume(volume: u64) -> Decimal {
+        volume as f64
+    }
+
+    /// Round to the given number of decimals, with midpoints away from zero
+    pub(crate) fn round_to(value: Decimal, decimals: u32) -> Decimal {
+        let factor = 10f64.powi(decimals as i32);
+        (value * factor).round() / factor
+    }
+
+    /// Convert an amount to a float, e.g. for statistics or plotting
+    pub fn decimal_to_f64(value: Decimal) -> f64 {
+        value
+    }
+
+    /// Convert a float to an amount, returning `None` if it is not finite
+    pub fn decimal_from_f64(value: f64) -> Option<Decimal> {
+        value.is_finite().then_some(value)
+    }
 }
 
 #[cfg(feature = "decimal")]
 pub mod decimal {
     pub type Decimal = rust_decimal::Decimal;
     pub const ZERO: Decimal = Decimal::ZERO;
+
+    pub(crate) fn from_volume(volume: u64) -> Decimal {
+        Decimal::from(volume)
+    }
+
+    /// Round to the given number of decimals, with midpoints away from zero
+    pub(crate) fn round_to(value: Decimal, decimals: u32) -> Decimal {
+        value.round_dp_with_strategy(
+            decimals,
+            rust_decimal::RoundingStrategy::MidpointAwayFromZero,
+        )
+    }
+
+    /// Convert an amount to a float, e.g. for statistics or plotting
+    pub fn decimal_to_f64(value: Decimal) -> f64 {
+        rust_decimal::prelude::ToPrimitive::to_f64(&value).unwrap_or(f64::NAN)
+    }
+
+    /// Convert a float to an amount, returning `None` if it is not finite or out of range
+    pub fn decimal_from_f64(value: f64) -> Option<Decimal> {
+        rust_decimal::prelude::FromPrimitive::from_f64(value)
+    }
 }
 
 pub use decimal::*;
@@ -24,44 +66,383 @@ pub use decimal::*;
 #[derive(Deserialize, Debug)]
 pub struct YResponse {
     pub chart: YChart,
+    /// The unprocessed JSON response as returned by yahoo! finance,
+    /// e.g. to access fields not (yet) supported by this crate
+    #[cfg(feature = "raw")]
+    #[serde(skip)]
+    pub raw: Option<serde_json::Value>,
 }
 
 impl YResponse {
-    fn check_consistency(&self) -> Result<(), YahooError> {
-        for stock in &self.chart.result {
-            let n = stock.timestamp.len();
-            if n == 0 {
-                return Err(YahooError::EmptyDataSet);
+    #[cfg(not(feature = "raw"))]
+    pub fn from_json(json: serde_json::Value) -> Result<YResponse, YahooError> {
+        Ok(serde_json::from_value(json)?)
+    }
+
+    /// Deserialize the response, keeping the raw JSON value. If deserialization fails,
+    /// the raw value is returned as part of the error.
+    #[cfg(feature = "raw")]
+    pub fn from_json(json: serde_json::Value) -> Result<YResponse, YahooError> {
+        match YResponse::deserialize(&json) {
+            Ok(mut response) => {
+                response.raw = Some(json);
+                Ok(response)
             }
-            let quote = &stock.indicators.quote[0];
-            if quote.open.len() != n
-                || quote.high.len() != n
-                || quote.low.len() != n
-                || quote.volume.len() != n
-                || quote.close.len() != n
-            {
-                return Err(YahooError::DataInconsistency);
+            Err(e) => Err(YahooError::DeserializeFailedWithPayload(e, json)),
+        }
+    }
+
+    /// Deserialize a response of the chart endpoint requested for the given symbol,
+    /// turning errors reported by yahoo! finance into the corresponding `YahooError`
+    pub(crate) fn from_chart_json(
+        json: serde_json::Value,
+        symbol: &str,
+    ) -> Result<YResponse, YahooError> {
+        let response = YResponse::from_json(json)?;
+        match &response.chart.error {
+            Some(error) => Err(error.to_error(symbol)),
+            None => Ok(response),
+        }
+    }
+
+    /// Return all result blocks contained in the response
+    pub fn results(&self) -> &[YQuoteBlock] {
+        &self.chart.result
+    }
+
+    /// Return the i-th result block of the response
+    pub fn result(&self, i: usize) -> Result<&YQuoteBlock, YahooError> {
+        self.chart.result.get(i).ok_or(YahooError::EmptyDataSet)
+    }
+
+    /// Return the latest valid quote
+    pub fn last_quote(&self) -> Result<Quote, YahooError> {
+        self.last_quote_for(0)
+    }
+
+    /// Return the latest valid quote of the i-th result block
+    pub fn last_quote_for(&self, i: usize) -> Result<Quote, YahooError> {
+        self.result(i)?.last_quote()
+    }
+
+    pub fn quotes(&self) -> Result<Vec<Quote>, YahooError> {
+        self.quotes_for(0)
+    }
+
+    /// Return all valid quotes of the i-th result block
+    pub fn quotes_for(&self, i: usize) -> Result<Vec<Quote>, YahooError> {
+        self.result(i)?.quotes()
+    }
+
+    /// Return all valid quotes sorted by timestamp, resolving duplicate timestamps
+    /// according to the given policy. Unlike `quotes`, this does not pass through
+    /// out-of-order or repeated timestamps as occasionally returned by yahoo! finance
+    /// (especially if pre- and post-market data is included).
+    pub fn sorted_quotes(&self, policy: DuplicatePolicy) -> Result<Vec<Quote>, YahooError> {
+        let mut quotes = self.quotes()?;
+        // stable sort preserves the original order of quotes with equal timestamps
+        quotes.sort_by_key(|q| q.timestamp);
+        let mut result: Vec<Quote> = Vec::with_capacity(quotes.len());
+        for quote in quotes {
+            match result.last_mut() {
+                Some(last) if last.timestamp == quote.timestamp => match policy {
+                    DuplicatePolicy::KeepFirst => {}
+                    DuplicatePolicy::KeepLast => *last = quote,
+                    DuplicatePolicy::Error => {
+                        return Err(YahooError::DuplicateTimestamp(quote.timestamp))
+                    }
+                },
+                _ => result.push(quote),
             }
-            if let Some(ref adjclose) = stock.indicators.adjclose {
-                if adjclose[0].adjclose.len() != n {
-                    return Err(YahooError::DataInconsistency);
-                }
+        }
+        Ok(result)
+    }
+
+    /// Return all valid quotes as one vector per field, as they are sent by yahoo! finance.
+    /// This avoids allocating a `Quote` per row, e.g. to pass long intraday histories to
+    /// numerical or plotting libraries.
+    pub fn columns(&self) -> Result<QuoteColumns, YahooError> {
+        self.result(0)?.columns()
+    }
+
+    pub fn metadata(&self) -> Result<YMetaData, YahooError> {
+        self.metadata_for(0)
+    }
+
+    /// Return the meta data of the i-th result block
+    pub fn metadata_for(&self, i: usize) -> Result<YMetaData, YahooError> {
+        let stock = self.result(i)?;
+        stock.check_consistency()?;
+        Ok(stock.meta.to_owned())
+    }
+
+    /// This method retrieves information about the splits that might have
+    /// occured during the considered time period
+    pub fn splits(&self) -> Result<Vec<Split>, YahooError> {
+        self.splits_for(0)
+    }
+
+    /// Return the splits of the i-th result block
+    pub fn splits_for(&self, i: usize) -> Result<Vec<Split>, YahooError> {
+        self.result(i)?.splits()
+    }
+
+    /// This method retrieves information about the dividends that have
+    /// been recorded during the considered time period.
+    ///
+    /// Note: Date is the ex-dividend date)
+    pub fn dividends(&self) -> Result<Vec<Dividend>, YahooError> {
+        self.dividends_for(0)
+    }
+
+    /// Return the dividends of the i-th result block
+    pub fn dividends_for(&self, i: usize) -> Result<Vec<Dividend>, YahooError> {
+        self.result(i)?.dividends()
+    }
+
+    /// This method retrieves information about the capital gains that might have
+    /// occured during the considered time period (available only for Mutual Funds)
+    pub fn capital_gains(&self) -> Result<Vec<CapitalGain>, YahooError> {
+        self.capital_gains_for(0)
+    }
+
+    /// Return the capital gains of the i-th result block
+    pub fn capital_gains_for(&self, i: usize) -> Result<Vec<CapitalGain>, YahooError> {
+        self.result(i)?.capital_gains()
+    }
+
+    /// Return the series of all symbols requested as comparisons
+    pub fn comparisons(&self) -> Result<Vec<YComparison>, YahooError> {
+        Ok(self.result(0)?.comparisons.clone().unwrap_or_default())
+    }
+
+    /// Return the close prices of the given comparison symbol together with their timestamps,
+    /// skipping missing values
+    pub fn comparison_closes(&self, symbol: &str) -> Result<Vec<(u64, Decimal)>, YahooError> {
+        let stock = self.result(0)?;
+        let comparison = stock
+            .comparisons
+            .iter()
+            .flatten()
+            .find(|c| c.symbol == symbol)
+            .ok_or(YahooError::EmptyDataSet)?;
+        if comparison.close.len() != stock.timestamp.len() {
+            return Err(YahooError::DataInconsistency);
+        }
+        Ok(stock
+            .timestamp
+            .iter()
+            .zip(comparison.close.iter())
+            .filter_map(|(timestamp, close)| close.map(|close| (*timestamp, close)))
+            .collect())
+    }
+}
+
+/// Policy how to treat quotes sharing the same timestamp
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DuplicatePolicy {
+    /// Keep the quote which appeared first in the response
+    #[default]
+    KeepFirst,
+    /// Keep the quote which appeared last in the response
+    KeepLast,
+    /// Fail with `YahooError::DuplicateTimestamp`
+    Error,
+}
+
+/// Struct for single quote
+#[derive(Debug, Clone, PartialEq, PartialOrd, Deserialize, Serialize)]
+pub struct Quote {
+    pub timestamp: u64,
+    pub open: Decimal,
+    pub high: Decimal,
+    pub low: Decimal,
+    pub volume: u64,
+    pub close: Decimal,
+    pub adjclose: Decimal,
+}
+
+/// Valid quotes of a response with one vector per field, all of the same length. Missing
+/// values are set to zero, as in `Quote`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct QuoteColumns {
+    pub timestamps: Vec<i64>,
+    pub open: Vec<Decimal>,
+    pub high: Vec<Decimal>,
+    pub low: Vec<Decimal>,
+    pub volume: Vec<u64>,
+    pub close: Vec<Decimal>,
+    pub adjclose: Vec<Decimal>,
+}
+
+impl QuoteColumns {
+    fn with_capacity(n: usize) -> QuoteColumns {
+        QuoteColumns {
+            timestamps: Vec::with_capacity(n),
+            open: Vec::with_capacity(n),
+            high: Vec::with_capacity(n),
+            low: Vec::with_capacity(n),
+            volume: Vec::with_capacity(n),
+            close: Vec::with_capacity(n),
+            adjclose: Vec::with_capacity(n),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.timestamps.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.timestamps.is_empty()
+    }
+}
+
+#[derive(Deserialize, Debug)]
+pub struct YChart {
+    #[serde(default, deserialize_with = "deserialize_null_as_empty")]
+    pub result: Vec<YQuoteBlock>,
+    pub error: Option<YChartError>,
+}
+
+pub(crate) fn deserialize_null_as_empty<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Ok(Option::<Vec<T>>::deserialize(deserializer)?.unwrap_or_default())
+}
+
+/// Error reported by the chart and quoteSummary endpoints
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct YChartError {
+    pub code: String,
+    #[serde(default)]
+    pub description: String,
+}
+
+impl YChartError {
+    /// Map the error to the corresponding `YahooError`, attaching the requested symbol
+    pub fn to_error(&self, symbol: &str) -> YahooError {
+        let description = self.description.to_lowercase();
+        if description.contains("symbol may be delisted")
+            || description.contains("no data found")
+            || description.contains("quote not found")
+        {
+            YahooError::SymbolNotFound(symbol.to_string())
+        } else if description.contains("data doesn't exist")
+            || description.contains("data not available")
+        {
+            YahooError::RangeUnavailable(symbol.to_string())
+        } else if self.code == "Unauthorized" {
+            // returned for invalid crumbs as well as for missing cookies
+            YahooError::InvalidCrumb
+        } else {
+            YahooError::ApiError(format!("{}: {}", self.code, self.description))
+        }
+    }
+}
+
+#[derive(Deserialize, Debug)]
+pub struct YQuoteBlock {
+    pub meta: YMetaData,
+    pub timestamp: Vec<u64>,
+    pub events: Option<EventsBlock>,
+    pub indicators: QuoteBlock,
+    #[serde(default)]
+    pub comparisons: Option<Vec<YComparison>>,
+}
+
+/// Price series of an additional symbol requested via the `comparisons` parameter,
+/// sharing the timestamps of the main symbol
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct YComparison {
+    pub symbol: String,
+    #[serde(default)]
+    pub chart_previous_close: Option<Decimal>,
+    #[serde(default)]
+    pub open: Vec<Option<Decimal>>,
+    #[serde(default)]
+    pub high: Vec<Option<Decimal>>,
+    #[serde(default)]
+    pub low: Vec<Option<Decimal>>,
+    #[serde(default)]
+    pub close: Vec<Option<Decimal>>,
+}
+
+impl YQuoteBlock {
+    /// Drop the bars and events time stamped at or after `until`
+    pub(crate) fn retain_before(&mut self, until: u64) {
+        let keep: Vec<bool> = self.timestamp.iter().map(|&t| t < until).collect();
+        if keep.iter().all(|&k| k) {
+            return;
+        }
+        retain_mask(&mut self.timestamp, &keep);
+        for quote in &mut self.indicators.quote {
+            retain_mask(&mut quote.volume, &keep);
+            retain_mask(&mut quote.high, &keep);
+            retain_mask(&mut quote.close, &keep);
+            retain_mask(&mut quote.low, &keep);
+            retain_mask(&mut quote.open, &keep);
+        }
+        for adjclose in self.indicators.adjclose.iter_mut().flatten() {
+            retain_mask(&mut adjclose.adjclose, &keep);
+        }
+        for comparison in self.comparisons.iter_mut().flatten() {
+            retain_mask(&mut comparison.open, &keep);
+            retain_mask(&mut comparison.high, &keep);
+            retain_mask(&mut comparison.low, &keep);
+            retain_mask(&mut comparison.close, &keep);
+        }
+        if let Some(events) = &mut self.events {
+            let until = until as i64;
+            if let Some(splits) = &mut events.splits {
+                splits.retain(|&t, _| t < until);
+            }
+            if let Some(dividends) = &mut events.dividends {
+                dividends.retain(|&t, _| t < until);
+            }
+            if let Some(capital_gains) = &mut events.capital_gains {
+                capital_gains.retain(|&t, _| t < until);
             }
         }
-        Ok(())
     }
 
-    pub fn from_json(json: serde_json::Value) -> Result<YResponse, YahooError> {
-        Ok(serde_json::from_value(json)?)
+    fn check_consistency(&self) -> Result<(), YahooError> {
+        let n = self.timestamp.len();
+        if n == 0 {
+            return Err(YahooError::EmptyDataSet);
+        }
+        let quote = self
+            .indicators
+            .quote
+            .first()
+            .ok_or(YahooError::DataInconsistency)?;
+        if quote.open.len() != n
+            || quote.high.len() != n
+            || quote.low.len() != n
+            || quote.volume.len() != n
+            || quote.close.len() != n
+        {
+            return Err(YahooError::DataInconsistency);
+        }
+        if let Some(ref adjclose) = self.indicators.adjclose {
+            if adjclose
+                .first()
+                .map_or(true, |adjclose| adjclose.adjclose.len() != n)
+            {
+                return Err(YahooError::DataInconsistency);
+            }
+        }
+        Ok(())
     }
 
     /// Return the latest valid quote
     pub fn last_quote(&self) -> Result<Quote, YahooError> {
         self.check_consistency()?;
-        let stock = &self.chart.result[0];
-        let n = stock.timestamp.len();
+        let n = self.timestamp.len();
         for i in (0..n).rev() {
-            let quote = stock.indicators.get_ith_quote(stock.timestamp[i], i);
+            let quote = self.indicators.get_ith_quote(self.timestamp[i], i);
             if quote.is_ok() {
                 return quote;
             }
@@ -69,14 +450,14 @@ impl YResponse {
         Err(YahooError::EmptyDataSet)
     }
 
+    /// Return all valid quotes
     pub fn quotes(&self) -> Result<Vec<Quote>, YahooError> {
         self.check_consistency()?;
-        let stock: &YQuoteBlock = &self.chart.result[0];
         let mut quotes = Vec::new();
-        let n = stock.timestamp.len();
+        let n = self.timestamp.len();
         for i in 0..n {
-            let timestamp = stock.timestamp[i];
-            let quote = stock.indicators.get_ith_quote(timestamp, i);
+            let timestamp = self.timestamp[i];
+            let quote = self.indicators.get_ith_quote(timestamp, i);
             if let Ok(q) = quote {
                 quotes.push(q);
             }
@@ -84,18 +465,40 @@ impl YResponse {
         Ok(quotes)
     }
 
-    pub fn metadata(&self) -> Result<YMetaData, YahooError> {
+    /// Return all valid quotes as columns, see `YResponse::columns`
+    pub fn columns(&self) -> Result<QuoteColumns, YahooError> {
         self.check_consistency()?;
-        let stock = &self.chart.result[0];
-        Ok(stock.meta.to_owned())
+        let quote = &self.indicators.quote[0];
+        let adjclose = self
+            .indicators
+            .adjclose
+            .as_ref()
+            .and_then(|a| a.first())
+            .map(|a| &a.adjclose);
+        let n = quote.close.iter().filter(|close| close.is_some()).count();
+        let mut columns = QuoteColumns::with_capacity(n);
+        for (i, &timestamp) in self.timestamp.iter().enumerate() {
+            // skip quotes without close, as `quotes` does
+            let Some(close) = quote.close[i] else {
+                continue;
+            };
+            columns.timestamps.push(timestamp as i64);
+            columns.open.push(quote.open[i].unwrap_or(ZERO));
+            columns.high.push(quote.high[i].unwrap_or(ZERO));
+            columns.low.push(quote.low[i].unwrap_or(ZERO));
+            columns.volume.push(quote.volume[i].unwrap_or(0));
+            columns.close.push(close);
+            columns
+                .adjclose
+                .push(adjclose.and_then(|a| a[i]).unwrap_or(ZERO));
+        }
+        Ok(columns)
     }
 
-    /// This method retrieves information about the splits that might have
-    /// occured during the considered time period
+    /// Return the splits recorded in the events block, sorted by date
     pub fn splits(&self) -> Result<Vec<Split>, YahooError> {
         self.check_consistency()?;
-        let stock = &self.chart.result[0];
-        if let Some(events) = &stock.events {
+        if let Some(events) = &self.events {
             if let Some(splits) = &events.splits {
                 let mut data = splits.values().cloned().collect::<Vec<Split>>();
                 data.sort_unstable_by_key(|d| d.date);
@@ -104,14 +507,11 @@ impl YResponse {
         }
         Ok(vec![])
     }
-    /// This method retrieves information about the dividends that have
-    /// been recorded during the considered time period.
-    ///
-    /// Note: Date is the ex-dividend date)
+
+    /// Return the dividends recorded in the events block, sorted by ex-dividend date
     pub fn dividends(&self) -> Result<Vec<Dividend>, YahooError> {
         self.check_consistency()?;
-        let stock = &self.chart.result[0];
-        if let Some(events) = &stock.events {
+        if let Some(events) = &self.events {
             if let Some(dividends) = &events.dividends {
                 let mut data = dividends.values().cloned().collect::<Vec<Dividend>>();
                 data.sort_unstable_by_key(|d| d.date);
@@ -121,12 +521,10 @@ impl YResponse {
         Ok(vec![])
     }
 
-    /// This method retrieves information about the capital gains that might have
-    /// occured during the considered time period (available only for Mutual Funds)
+    /// Return the capital gains recorded in the events block, sorted by date
     pub fn capital_gains(&self) -> Result<Vec<CapitalGain>, YahooError> {
         self.check_consistency()?;
-        let stock = &self.chart.result[0];
-        if let Some(events) = &stock.events {
+        if let Some(events) = &self.events {
             if let Some(capital_gain) = &events.capital_gains {
                 let mut data = capital_gain.values().cloned().collect::<Vec<CapitalGain>>();
                 data.sort_unstable_by_key(|d| d.date);
@@ -137,57 +535,66 @@ impl YResponse {
     }
 }
 
-/// Struct for single quote
-#[derive(Debug, Clone, PartialEq, PartialOrd, Deserialize, Serialize)]
-pub struct Quote {
-    pub timestamp: u64,
-    pub open: Decimal,
-    pub high: Decimal,
-    pub low: Decimal,
-    pub volume: u64,
-    pub close: Decimal,
-    pub adjclose: Decimal,
-}
-
-#[derive(Deserialize, Debug)]
-pub struct YChart {
-    pub result: Vec<YQuoteBlock>,
-    pub error: Option<String>,
-}
-
-#[derive(Deserialize, Debug)]
-pub struct YQuoteBlock {
-    pub meta: YMetaData,
-    pub timestamp: Vec<u64>,
-    pub events: Option<EventsBlock>,
-    pub indicators: QuoteBlock,
-}
-
 #[derive(Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "deny_unknown", serde(deny_unknown_fields))]
 pub struct YMetaData {
-    pub currency: Option<String>,
+    pub currency: Option<InternedStr>,
     pub symbol: String,
-    pub exchange_name: String,
-    pub instrument_type: String,
+    pub exchange_name: Exchange,
+    pub instrument_type: AssetClass,
+    #[serde(default)]
+    pub first_trade_date: Option<YTimestamp>,
     #[serde(default)]
-    pub first_trade_date: Option<i32>,
-    pub regular_market_time: u32,
+    pub regular_market_time: Option<YTimestamp>,
     pub gmtoffset: i32,
-    pub timezone: String,
-    pub exchange_timezone_name: String,
-    pub regular_market_price: Decimal,
-    pub chart_previous_close: Decimal,
+    pub timezone: InternedStr,
+    pub exchange_timezone_name: InternedStr,
+    #[serde(default)]
+    pub regular_market_price: Option<Decimal>,
+    #[serde(default)]
+    pub chart_previous_close: Option<Decimal>,
+    #[serde(default)]
     pub previous_close: Option<Decimal>,
     #[serde(default)]
+    pub fifty_two_week_high: Option<Decimal>,
+    #[serde(default)]
+    pub fifty_two_week_low: Option<Decimal>,
+    #[serde(default)]
+    pub regular_market_day_high: Option<Decimal>,
+    #[serde(default)]
+    pub regular_market_day_low: Option<Decimal>,
+    #[serde(default)]
+    pub regular_market_volume: Option<u64>,
+    #[serde(default)]
     pub scale: Option<i32>,
-    pub price_hint: i32,
-    pub current_trading_period: CurrentTradingPeriod,
+    #[serde(default)]
+    pub price_hint: Option<i32>,
+    #[serde(default)]
+    pub current_trading_period: Option<CurrentTradingPeriod>,
     #[serde(default)]
     pub trading_periods: TradingPeriods,
-    pub data_granularity: String,
-    pub range: String,
-    pub valid_ranges: Vec<String>,
+    pub data_granularity: InternedStr,
+    pub range: InternedStr,
+    #[serde(default)]
+    pub valid_ranges: Vec<InternedStr>,
+    /// Delay of the quotes in minutes, if reported by yahoo! finance, see `YMetaData::is_realtime`
+    #[serde(default)]
+    pub exchange_data_delayed_by: Option<u32>,
+    /// Name of the exchange as displayed by yahoo! finance, e.g. `NasdaqGS`
+    #[serde(default)]
+    pub full_exchange_name: Option<String>,
+    #[serde(default)]
+    pub long_name: Option<String>,
+    #[serde(default)]
+    pub short_name: Option<String>,
+    /// Whether quotes before and after regular trading hours are available
+    #[serde(default)]
+    pub has_pre_post_market_data: Option<bool>,
+    /// Fields returned by yahoo! finance which are not (yet) modelled by this struct
+    #[cfg(all(feature = "capture_unknown", not(feature = "deny_unknown")))]
+    #[serde(flatten)]
+    pub extra: HashMap<String, serde_json::Value>,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Eq)]
@@ -219,6 +626,13 @@ impl<'de> Deserialize<'de> for TradingPeriods {
                 formatter.write_str("struct (or array) TradingPeriods")
             }
 
+            fn visit_unit<E>(self) -> Result<TradingPeriods, E>
+            where
+                E: de::Error,
+            {
+                Ok(TradingPeriods::default())
+            }
+
             fn visit_seq<V>(self, mut seq: V) -> Result<TradingPeriods, V::Error>
             where
                 V: SeqAccess<'de>,
@@ -279,25 +693,26 @@ pub struct CurrentTradingPeriod {
 
 #[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
 pub struct PeriodInfo {
-    pub timezone: String,
-    pub start: u32,
-    pub end: u32,
+    pub timezone: InternedStr,
+    pub start: i64,
+    pub end: i64,
     pub gmtoffset: i32,
 }
 
 #[derive(Deserialize, Debug)]
 pub struct QuoteBlock {
-    quote: Vec<QuoteList>,
+    pub(crate) quote: Vec<QuoteList>,
     #[serde(default)]
-    adjclose: Option<Vec<AdjClose>>,
+    pub(crate) adjclose: Option<Vec<AdjClose>>,
 }
 
 impl QuoteBlock {
     fn get_ith_quote(&self, timestamp: u64, i: usize) -> Result<Quote, YahooError> {
-        let adjclose = match &self.adjclose {
-            Some(adjclose) => adjclose[0].adjclose[i],
-            None => None,
-        };
+        let adjclose = self
+            .adjclose
+            .as_ref()
+            .and_then(|adjclose| adjclose.first())
+            .and_then(|adjclose| adjclose.adjclose[i]);
         let quote = &self.quote[0];
         // reject if close is not set
         if quote.close[i].is_none() {
@@ -315,9 +730,18 @@ impl QuoteBlock {
     }
 }
 
+/// Keep the values whose flag is set, values beyond the flags are kept
+fn retain_mask<T>(values: &mut Vec<T>, keep: &[bool]) {
+    let mut i = 0;
+    values.retain(|_| {
+        i += 1;
+        keep.get(i - 1).copied().unwrap_or(true)
+    });
+}
+
 #[derive(Deserialize, Debug)]
 pub struct AdjClose {
-    adjclose: Vec<Option<Decimal>>,
+    pub(crate) adjclose: Vec<Option<Decimal>>,
 }
 
 #[derive(Deserialize, Debug)]
@@ -331,17 +755,17 @@ pub struct QuoteList {
 
 #[derive(Deserialize, Debug)]
 pub struct EventsBlock {
-    pub splits: Option<HashMap<u64, Split>>,
-    pub dividends: Option<HashMap<u64, Dividend>>,
+    pub splits: Option<HashMap<i64, Split>>,
+    pub dividends: Option<HashMap<i64, Dividend>>,
     #[serde(rename = "capitalGains")]
-    pub capital_gains: Option<HashMap<u64, CapitalGain>>,
+    pub capital_gains: Option<HashMap<i64, CapitalGain>>,
 }
 
 /// This structure simply models a split that has occured.
 #[derive(Deserialize, Debug, Clone)]
 pub struct Split {
     /// This is the date (timestamp) when the split occured
-    pub date: u64,
+    pub date: YTimestamp,
     /// Numerator of the split. For instance a 1:5 split means you get 5 share
     /// wherever you had one before the split. (Here the numerator is 1 and
     /// denom is 5). A reverse split is considered as nothing but a regular
@@ -363,22 +787,226 @@ pub struct Dividend {
     /// This is the price of the dividend
     pub amount: Decimal,
     /// This is the ex-dividend date
-    pub date: u64,
+    pub date: YTimestamp,
 }
 
 /// This structure simply models a capital gain which has been recorded.
 #[derive(Deserialize, Debug, Clone)]
 pub struct CapitalGain {
     /// This is the amount of capital gain distributed by the fund
-    pub amount: f64,
+    pub amount: Decimal,
     /// This is the recorded date of the capital gain
-    pub date: u64,
+    pub date: YTimestamp,
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_decimal_conversion() {
+        let value = decimal_from_f64(0.25).unwrap();
+        assert_eq!(value, Decimal::from(1) / Decimal::from(4));
+        assert_eq!(decimal_to_f64(value), 0.25);
+        assert_eq!(decimal_from_f64(f64::NAN), None);
+    }
+
+    const UNSORTED_CHART: &str = r#"
+    {
+        "chart": {
+            "result": [
+                {
+                    "meta": {
+                        "currency": "USD",
+                        "symbol": "AAPL",
+                        "exchangeName": "NMS",
+                        "instrumentType": "EQUITY",
+                        "firstTradeDate": 345479400,
+                        "regularMarketTime": 1705525201,
+                        "gmtoffset": -18000,
+                        "timezone": "EST",
+                        "exchangeTimezoneName": "America/New_York",
+                        "regularMarketPrice": 182.68,
+                        "chartPreviousClose": 185.92,
+                        "priceHint": 2,
+                        "currentTradingPeriod": {
+                            "pre": {"timezone": "EST", "start": 1705482000, "end": 1705501800, "gmtoffset": -18000},
+                            "regular": {"timezone": "EST", "start": 1705501800, "end": 1705525200, "gmtoffset": -18000},
+                            "post": {"timezone": "EST", "start": 1705525200, "end": 1705539600, "gmtoffset": -18000}
+                        },
+                        "dataGranularity": "1d",
+                        "range": "",
+                        "validRanges": ["1d", "5d", "1mo", "3mo", "6mo", "1y", "2y", "5y", "10y", "ytd", "max"]
+                    },
+                    "timestamp": [1705501800, 1705329000, 1705501800, 1705415400],
+                    "indicators": {
+                        "quote": [
+                            {
+                                "volume": [100, 200, 300, 400],
+                                "high": [10.0, 20.0, 30.0, 40.0],
+                                "close": [11.0, 21.0, 31.0, 41.0],
+                                "low": [9.0, 19.0, 29.0, 39.0],
+                                "open": [10.5, 20.5, 30.5, 40.5]
+                            }
+                        ],
+                        "adjclose": [
+                            {
+                                "adjclose": [11.0, 21.0, 31.0, 41.0]
+                            }
+                        ]
+                    }
+                }
+            ],
+            "error": null
+        }
+    }
+    "#;
+
+    #[test]
+    fn test_multiple_results() {
+        let mut json: serde_json::Value = serde_json::from_str(UNSORTED_CHART).unwrap();
+        let mut second = json["chart"]["result"][0].clone();
+        second["meta"]["symbol"] = "MSFT".into();
+        second["timestamp"] = serde_json::json!([1705329000]);
+        second["indicators"] = serde_json::json!({
+            "quote": [{"volume": [1], "high": [2.0], "close": [3.0], "low": [1.0], "open": [2.0]}]
+        });
+        json["chart"]["result"].as_array_mut().unwrap().push(second);
+        let response = YResponse::from_json(json).unwrap();
+
+        assert_eq!(response.results().len(), 2);
+        assert_eq!(response.metadata().unwrap().symbol, "AAPL");
+        assert_eq!(response.metadata_for(1).unwrap().symbol, "MSFT");
+        assert_eq!(response.quotes().unwrap().len(), 4);
+        let quotes = response.quotes_for(1).unwrap();
+        assert_eq!(quotes.len(), 1);
+        assert_eq!(quotes[0].volume, 1);
+        assert!(matches!(
+            response.quotes_for(2),
+            Err(YahooError::EmptyDataSet)
+        ));
+    }
+
+    #[test]
+    fn test_comparisons() {
+        let mut json: serde_json::Value = serde_json::from_str(UNSORTED_CHART).unwrap();
+        json["chart"]["result"][0]["comparisons"] = serde_json::json!([
+            {
+                "symbol": "MSFT",
+                "high": [1.0, 2.0, 3.0, 4.0],
+                "low": [1.0, 2.0, 3.0, 4.0],
+                "chartPreviousClose": 390.27,
+                "close": [1.5, null, 3.5, 4.5],
+                "open": [1.0, 2.0, 3.0, 4.0]
+            }
+        ]);
+        let response = YResponse::from_json(json).unwrap();
+
+        let comparisons = response.comparisons().unwrap();
+        assert_eq!(comparisons.len(), 1);
+        assert_eq!(comparisons[0].symbol, "MSFT");
+        let closes = response.comparison_closes("MSFT").unwrap();
+        assert_eq!(closes.len(), 3);
+        assert_eq!(closes[1].0, 1705501800);
+        assert!(matches!(
+            response.comparison_closes("GOOG"),
+            Err(YahooError::EmptyDataSet)
+        ));
+    }
+
+    #[cfg(feature = "raw")]
+    #[test]
+    fn test_raw_response() {
+        let json: serde_json::Value = serde_json::from_str(UNSORTED_CHART).unwrap();
+        let response = YResponse::from_json(json.clone()).unwrap();
+        assert_eq!(response.raw.as_ref(), Some(&json));
+
+        let mut invalid = json.clone();
+        invalid["chart"]["result"][0]["timestamp"] = "invalid".into();
+        match YResponse::from_json(invalid.clone()) {
+            Err(YahooError::DeserializeFailedWithPayload(_, payload)) => {
+                assert_eq!(payload, invalid)
+            }
+            _ => panic!("expected deserialization to fail"),
+        }
+    }
+
+    #[cfg(all(feature = "capture_unknown", not(feature = "deny_unknown")))]
+    #[test]
+    fn test_capture_unknown_meta_fields() {
+        let mut json: serde_json::Value = serde_json::from_str(UNSORTED_CHART).unwrap();
+        json["chart"]["result"][0]["meta"]["unmodelledField"] = true.into();
+        let response = YResponse::from_json(json).unwrap();
+        let meta = response.metadata().unwrap();
+        assert_eq!(meta.symbol, "AAPL");
+        assert_eq!(meta.extra.len(), 1);
+        assert_eq!(meta.extra["unmodelledField"], serde_json::Value::Bool(true));
+    }
+
+    #[cfg(feature = "deny_unknown")]
+    #[test]
+    fn test_deny_unknown_meta_fields() {
+        let mut json: serde_json::Value = serde_json::from_str(UNSORTED_CHART).unwrap();
+        assert!(YResponse::from_json(json.clone()).is_ok());
+        json["chart"]["result"][0]["meta"]["unmodelledField"] = true.into();
+        assert!(YResponse::from_json(json).is_err());
+    }
+
+    #[test]
+    fn test_sorted_quotes() {
+        let response: YResponse = serde_json::from_str(UNSORTED_CHART).unwrap();
+        let timestamps = |quotes: Vec<Quote>| -> Vec<(u64, u64)> {
+            quotes.iter().map(|q| (q.timestamp, q.volume)).collect()
+        };
+
+        let quotes = response.sorted_quotes(DuplicatePolicy::KeepFirst).unwrap();
+        assert_eq!(
+            timestamps(quotes),
+            vec![(1705329000, 200), (1705415400, 400), (1705501800, 100)]
+        );
+        let quotes = response.sorted_quotes(DuplicatePolicy::KeepLast).unwrap();
+        assert_eq!(
+            timestamps(quotes),
+            vec![(1705329000, 200), (1705415400, 400), (1705501800, 300)]
+        );
+        assert!(matches!(
+            response.sorted_quotes(DuplicatePolicy::Error),
+            Err(YahooError::DuplicateTimestamp(1705501800))
+        ));
+    }
+
+    #[test]
+    fn test_columns() {
+        let mut json: serde_json::Value = serde_json::from_str(UNSORTED_CHART).unwrap();
+        json["chart"]["result"][0]["indicators"]["quote"][0]["close"][1] = serde_json::Value::Null;
+        let response = YResponse::from_json(json).unwrap();
+        let quotes = response.quotes().unwrap();
+        let columns = response.columns().unwrap();
+        assert_eq!(columns.len(), quotes.len());
+        for (i, quote) in quotes.iter().enumerate() {
+            assert_eq!(columns.timestamps[i], quote.timestamp as i64);
+            assert_eq!(columns.open[i], quote.open);
+            assert_eq!(columns.volume[i], quote.volume);
+            assert_eq!(columns.close[i], quote.close);
+            assert_eq!(columns.adjclose[i], quote.adjclose);
+        }
+    }
+
+    #[test]
+    fn test_empty_adjclose() {
+        let mut json: serde_json::Value = serde_json::from_str(UNSORTED_CHART).unwrap();
+        json["chart"]["result"][0]["indicators"]["adjclose"] = serde_json::json!([]);
+        let response = YResponse::from_json(json).unwrap();
+        assert!(matches!(
+            response.last_quote(),
+            Err(YahooError::DataInconsistency)
+        ));
+        assert!(matches!(
+            response.columns(),
+            Err(YahooError::DataInconsistency)
+        ));
+    }
+
     #[test]
     fn test_deserialize_period_info() {
         let period_info_json = r#"
@@ -390,7 +1018,7 @@ mod tests {
         }
         "#;
         let period_info_expected = PeriodInfo {
-            timezone: "EST".to_string(),
+            timezone: "EST".into(),
             start: 1705501800,
             end: 1705525200,
             gmtoffset: -18000,
@@ -399,6 +1027,90 @@ mod tests {
         assert_eq!(&period_info_deserialized, &period_info_expected);
     }
 
+    #[test]
+    fn test_deserialize_trading_periods_null() {
+        let trading_periods: TradingPeriods = serde_json::from_str("null").unwrap();
+        assert_eq!(trading_periods, TradingPeriods::default());
+    }
+
+    #[test]
+    fn test_deserialize_meta_data_with_nulls() {
+        // shape of the meta data block returned for DE000SL0CZS9.SG
+        let meta_json = r#"
+        {
+            "currency": "EUR",
+            "symbol": "DE000SL0CZS9.SG",
+            "exchangeName": "STU",
+            "instrumentType": "EQUITY",
+            "firstTradeDate": null,
+            "regularMarketTime": null,
+            "gmtoffset": 3600,
+            "timezone": "CET",
+            "exchangeTimezoneName": "Europe/Berlin",
+            "regularMarketPrice": null,
+            "chartPreviousClose": null,
+            "previousClose": null,
+            "scale": 3,
+            "priceHint": null,
+            "currentTradingPeriod": null,
+            "tradingPeriods": null,
+            "dataGranularity": "1d",
+            "range": "1mo",
+            "validRanges": ["1d", "5d", "1mo", "3mo", "6mo", "1y", "2y", "5y", "10y", "ytd", "max"]
+        }
+        "#;
+        let meta: YMetaData = serde_json::from_str(meta_json).unwrap();
+        assert_eq!(meta.symbol, "DE000SL0CZS9.SG");
+        assert_eq!(meta.first_trade_date, None);
+        assert_eq!(meta.regular_market_time, None);
+        assert_eq!(meta.regular_market_price, None);
+        assert_eq!(meta.price_hint, None);
+        assert!(meta.current_trading_period.is_none());
+        assert_eq!(meta.trading_periods, TradingPeriods::default());
+    }
+
+    #[test]
+    fn test_deserialize_meta_data_pre_epoch_first_trade_date() {
+        let mut json: serde_json::Value = serde_json::from_str(UNSORTED_CHART).unwrap();
+        json["chart"]["result"][0]["meta"]["firstTradeDate"] = (-252322200i64).into();
+        let meta = YResponse::from_json(json).unwrap().metadata().unwrap();
+        assert_eq!(
+            meta.first_trade_date,
+            Some(YTimestamp::from_seconds(-252322200))
+        );
+        assert_eq!(
+            meta.regular_market_time,
+            Some(YTimestamp::from_seconds(1705525201))
+        );
+    }
+
+    #[test]
+    fn test_chart_errors() {
+        let json = serde_json::json!({"chart": {"result": null, "error": {
+            "code": "Bad Request",
+            "description": "Data doesn't exist for startDate = 1262304000, endDate = 1262563200"
+        }}});
+        assert!(matches!(
+            YResponse::from_chart_json(json, "AAPL"),
+            Err(YahooError::RangeUnavailable(symbol)) if symbol == "AAPL"
+        ));
+        let error = YChartError {
+            code: "Bad Request".to_string(),
+            description: "Invalid input - interval=2d is not supported".to_string(),
+        };
+        assert!(matches!(error.to_error("AAPL"), YahooError::ApiError(_)));
+    }
+
+    #[test]
+    fn test_deserialize_pre_epoch_events() {
+        let events: EventsBlock = serde_json::from_str(
+            r#"{"dividends": {"-63072000": {"amount": 0.25, "date": -63072000}}}"#,
+        )
+        .unwrap();
+        let dividend = &events.dividends.unwrap()[&-63072000];
+        assert_eq!(dividend.date, YTimestamp::from_seconds(-63072000));
+    }
+
     #[test]
     fn test_deserialize_trading_periods_simple() {
         let trading_periods_json = r#"
@@ -417,7 +1129,7 @@ mod tests {
         let trading_periods_expected = TradingPeriods {
             pre: None,
             regular: Some(vec![vec![PeriodInfo {
-                timezone: "EST".to_string(),
+                timezone: "EST".into(),
                 start: 1705501800,
                 end: 1705525200,
                 gmtoffset: -18000,
@@ -448,7 +1160,7 @@ mod tests {
         let trading_periods_expected = TradingPeriods {
             pre: None,
             regular: Some(vec![vec![PeriodInfo {
-                timezone: "EST".to_string(),
+                timezone: "EST".into(),
                 start: 1705501800,
                 end: 1705525200,
                 gmtoffset: -18000,
@@ -498,19 +1210,19 @@ mod tests {
        "#;
         let trading_periods_expected = TradingPeriods {
             pre: Some(vec![vec![PeriodInfo {
-                timezone: "EST".to_string(),
+                timezone: "EST".into(),
                 start: 1705482000,
                 end: 1705501800,
                 gmtoffset: -18000,
             }]]),
             regular: Some(vec![vec![PeriodInfo {
-                timezone: "EST".to_string(),
+                timezone: "EST".into(),
                 start: 1705501800,
                 end: 1705525200,
                 gmtoffset: -18000,
             }]]),
             post: Some(vec![vec![PeriodInfo {
-                timezone: "EST".to_string(),
+                timezone: "EST".into(),
                 start: 1705525200,
                 end: 1705539600,
                 gmtoffset: -18000,
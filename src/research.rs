@@ -0,0 +1,135 @@
+//! Research reports of analysts as listed by the insights endpoint.
+//!
+//! The listing of the reports is public, while their content is only available with a
+//! yahoo! finance premium subscription. Where a summary of a report is public, it is part
+//! of the listing.
+use std::cmp::Reverse;
+#[cfg(all(feature = "capture_unknown", not(feature = "deny_unknown")))]
+use std::collections::HashMap;
+
+use serde::{Deserialize, Deserializer};
+use time::{format_description::well_known::Rfc3339, OffsetDateTime};
+
+use super::quotes::deserialize_null_as_empty;
+use super::{Decimal, YChartError, YahooError};
+
+#[derive(Deserialize, Debug)]
+struct YInsightsResponse {
+    finance: YInsightsFinance,
+}
+
+#[derive(Deserialize, Debug)]
+struct YInsightsFinance {
+    result: Option<YInsights>,
+    error: Option<YChartError>,
+}
+
+#[derive(Deserialize, Debug)]
+struct YInsights {
+    #[serde(default, deserialize_with = "deserialize_null_as_empty")]
+    reports: Vec<ResearchReport>,
+}
+
+/// Listing of a research report
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "deny_unknown", serde(deny_unknown_fields))]
+pub struct ResearchReport {
+    pub id: String,
+    #[serde(rename = "reportTitle")]
+    pub title: String,
+    /// Research firm, e.g. `Argus Research` or `Morningstar`
+    pub provider: String,
+    #[serde(default, deserialize_with = "deserialize_rfc3339")]
+    pub report_date: Option<OffsetDateTime>,
+    /// Kind of report, e.g. `Analyst Report` or `Quantitative Report`
+    pub report_type: Option<String>,
+    /// Rating of the analyst, e.g. `Bullish`
+    pub investment_rating: Option<String>,
+    pub target_price: Option<Decimal>,
+    /// Change of the target price compared to the previous report, e.g. `Increased`
+    pub target_price_status: Option<String>,
+    /// Public summary of the report as HTML, if any
+    #[serde(rename = "headHtml")]
+    pub summary: Option<String>,
+    /// Symbols covered by the report
+    #[serde(default)]
+    pub tickers: Vec<String>,
+    /// Fields returned by yahoo! finance which are not (yet) modelled by this struct
+    #[cfg(all(feature = "capture_unknown", not(feature = "deny_unknown")))]
+    #[serde(flatten)]
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
+impl ResearchReport {
+    /// Return whether nothing but the listing is public, i.e. reading any of the report
+    /// requires a premium subscription. The full content always requires one.
+    pub fn requires_premium(&self) -> bool {
+        self.summary
+            .as_deref()
+            .map_or(true, |summary| summary.trim().is_empty())
+    }
+}
+
+fn deserialize_rfc3339<'de, D>(deserializer: D) -> Result<Option<OffsetDateTime>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match Option::<String>::deserialize(deserializer)? {
+        Some(date) => OffsetDateTime::parse(&date, &Rfc3339)
+            .map(Some)
+            .map_err(serde::de::Error::custom),
+        None => Ok(None),
+    }
+}
+
+/// Deserialize a response of the insights endpoint requested for the given symbol into the
+/// listed research reports, newest first
+pub(crate) fn from_insights_json(
+    json: serde_json::Value,
+    symbol: &str,
+) -> Result<Vec<ResearchReport>, YahooError> {
+    let response: YInsightsResponse = serde_json::from_value(json)?;
+    if let Some(error) = response.finance.error {
+        return Err(error.to_error(symbol));
+    }
+    let mut reports = response
+        .finance
+        .result
+        .map(|insights| insights.reports)
+        .unwrap_or_default();
+    reports.sort_by_key(|report| Reverse(report.report_date));
+    Ok(reports)
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::datetime;
+
+    use super::*;
+
+    #[test]
+    fn test_research_reports() {
+        let json = serde_json::json!({"finance": {"result": {"reports": [
+            {"id": "MS_0P000000GY_AnalystReport_1706745600000",
+             "reportTitle": "Apple: Services Growth Offsets Soft iPhone Sales",
+             "provider": "Morningstar", "reportDate": "2024-02-01T00:00:00Z",
+             "reportType": "Analyst Report", "tickers": ["AAPL"]},
+            {"id": "ARGUS_5433_AnalystReport_1706832000000",
+             "reportTitle": "Raising target price", "provider": "Argus Research",
+             "reportDate": "2024-02-02T00:00:00Z", "reportType": "Analyst Report",
+             "investmentRating": "Bullish", "targetPrice": 220.0,
+             "targetPriceStatus": "Increased",
+             "headHtml": "<p>Apple reported fiscal 1Q24 results above expectations.</p>",
+             "tickers": ["AAPL"]}
+        ]}, "error": null}});
+        let reports = from_insights_json(json, "AAPL").unwrap();
+        assert_eq!(reports.len(), 2);
+        assert_eq!(reports[0].provider, "Argus Research");
+        assert_eq!(reports[0].report_date, Some(datetime!(2024-02-02 0:00 UTC)));
+        assert_eq!(reports[0].target_price, Some(Decimal::from(220)));
+        assert!(!reports[0].requires_premium());
+        assert!(reports[1].requires_premium());
+        assert_eq!(reports[1].investment_rating, None);
+    }
+}
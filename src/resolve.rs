@@ -0,0 +1,135 @@
+//! Resolution of free-text input (e.g. a company name) to a single symbol by deterministic
+//! rules applied to the results of a search.
+use std::cmp::Ordering;
+
+use super::YQuoteItem;
+
+/// Quote types in the order they are preferred by default, see `ResolvePreference`
+pub const DEFAULT_QUOTE_TYPES: &[&str] = &["EQUITY", "ETF", "MUTUALFUND", "INDEX"];
+
+/// Rules to rank search results. Results are ordered by preferred exchange, then by quote
+/// type, then by the score of the search, and finally by symbol.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResolvePreference {
+    /// Exchange codes as returned by the search (e.g. `GER` for XETRA or `NMS` for NASDAQ) in
+    /// order of preference; other exchanges rank after these
+    pub exchanges: Vec<String>,
+    /// Quote types in order of preference; other types rank after these
+    pub quote_types: Vec<String>,
+}
+
+impl Default for ResolvePreference {
+    fn default() -> Self {
+        ResolvePreference {
+            exchanges: Vec::new(),
+            quote_types: DEFAULT_QUOTE_TYPES.iter().map(|t| t.to_string()).collect(),
+        }
+    }
+}
+
+impl ResolvePreference {
+    /// Prefer the given exchanges, in order
+    pub fn exchanges(mut self, exchanges: &[&str]) -> Self {
+        self.exchanges = exchanges.iter().map(|e| e.to_string()).collect();
+        self
+    }
+
+    /// Prefer the given quote types, in order
+    pub fn quote_types(mut self, quote_types: &[&str]) -> Self {
+        self.quote_types = quote_types.iter().map(|t| t.to_string()).collect();
+        self
+    }
+
+    fn compare(&self, a: &YQuoteItem, b: &YQuoteItem) -> Ordering {
+        let rank = |list: &[String], value: &str| {
+            list.iter().position(|v| v == value).unwrap_or(list.len())
+        };
+        rank(&self.exchanges, a.exchange.as_str())
+            .cmp(&rank(&self.exchanges, b.exchange.as_str()))
+            .then_with(|| {
+                rank(&self.quote_types, &a.quote_type).cmp(&rank(&self.quote_types, &b.quote_type))
+            })
+            .then_with(|| b.score.total_cmp(&a.score))
+            .then_with(|| a.symbol.cmp(&b.symbol))
+    }
+
+    /// Sort the search results with the best match first
+    pub fn rank(&self, quotes: &mut [YQuoteItem]) {
+        quotes.sort_by(|a, b| self.compare(a, b));
+    }
+}
+
+/// Best match of a search, together with the other results ranked after it
+#[derive(Debug)]
+pub struct ResolvedSymbol {
+    pub best: YQuoteItem,
+    pub alternatives: Vec<YQuoteItem>,
+}
+
+impl ResolvedSymbol {
+    /// Rank the search results and pick the best, or return `None` if there are none
+    pub fn from_quotes(
+        mut quotes: Vec<YQuoteItem>,
+        preference: &ResolvePreference,
+    ) -> Option<ResolvedSymbol> {
+        preference.rank(&mut quotes);
+        let mut quotes = quotes.into_iter();
+        Some(ResolvedSymbol {
+            best: quotes.next()?,
+            alternatives: quotes.collect(),
+        })
+    }
+
+    pub fn symbol(&self) -> &str {
+        &self.best.symbol
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Exchange;
+
+    fn item(symbol: &str, exchange: &str, quote_type: &str, score: f64) -> YQuoteItem {
+        YQuoteItem {
+            exchange: Exchange::from_code(exchange),
+            short_name: String::new(),
+            quote_type: quote_type.to_string(),
+            symbol: symbol.to_string(),
+            index: "quotes".to_string(),
+            score,
+            type_display: String::new(),
+            long_name: String::new(),
+            is_yahoo_finance: true,
+        }
+    }
+
+    #[test]
+    fn test_resolve_symbol() {
+        let quotes = || {
+            vec![
+                item("VWAGY", "PNK", "EQUITY", 20000.0),
+                item("VOW3.DE", "GER", "EQUITY", 30000.0),
+                item("VOW3.F", "FRA", "EQUITY", 30000.0),
+                item("VWS.CO", "CPH", "EQUITY", 10000.0),
+                item("VOWG.TI", "TLO", "FUTURE", 50000.0),
+            ]
+        };
+        let resolved =
+            ResolvedSymbol::from_quotes(quotes(), &ResolvePreference::default()).unwrap();
+        // equal scores are ordered by symbol
+        assert_eq!(resolved.symbol(), "VOW3.DE");
+        let symbols: Vec<&str> = resolved
+            .alternatives
+            .iter()
+            .map(|q| q.symbol.as_str())
+            .collect();
+        assert_eq!(symbols, vec!["VOW3.F", "VWAGY", "VWS.CO", "VOWG.TI"]);
+
+        let preference = ResolvePreference::default().exchanges(&["PNK"]);
+        let resolved = ResolvedSymbol::from_quotes(quotes(), &preference).unwrap();
+        assert_eq!(resolved.symbol(), "VWAGY");
+
+        assert!(ResolvedSymbol::from_quotes(vec![], &preference).is_none());
+    }
+}
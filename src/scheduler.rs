@@ -0,0 +1,162 @@
+//! Rate limit shared by all clones of a connector, granting requests by priority.
+//!
+//! Requests are spaced by a fixed interval. A request waits while requests of higher priority
+//! are waiting, so e.g. background downloads of histories do not delay interactive lookups.
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use super::Clock;
+
+/// Priority of the requests of a connector, see `YahooConnector::with_priority`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Priority {
+    /// E.g. backfilling histories in the background
+    Low,
+    #[default]
+    Normal,
+    /// E.g. latest quotes requested by a user
+    High,
+}
+
+impl Priority {
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+struct State {
+    next_slot: Instant,
+    /// Number of waiting requests per priority
+    waiting: [usize; 3],
+}
+
+pub(crate) struct Scheduler {
+    interval: Duration,
+    clock: Arc<dyn Clock>,
+    state: Mutex<State>,
+}
+
+/// Registration of a waiting request, removed when dropped (e.g. if a future is cancelled)
+struct Waiting<'a> {
+    scheduler: &'a Scheduler,
+    priority: Priority,
+}
+
+impl Drop for Waiting<'_> {
+    fn drop(&mut self) {
+        self.scheduler.lock().waiting[self.priority.index()] -= 1;
+    }
+}
+
+impl Scheduler {
+    /// Allow at most `requests` requests per `period`
+    pub(crate) fn new(requests: u32, period: Duration, clock: Arc<dyn Clock>) -> Scheduler {
+        Scheduler {
+            interval: period / requests.max(1),
+            state: Mutex::new(State {
+                next_slot: clock.now(),
+                waiting: [0; 3],
+            }),
+            clock,
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn register(&self, priority: Priority) -> Waiting<'_> {
+        self.lock().waiting[priority.index()] += 1;
+        Waiting {
+            scheduler: self,
+            priority,
+        }
+    }
+
+    /// Take the next slot if it is due and no request of higher priority is waiting,
+    /// otherwise return how long to wait before trying again
+    fn try_acquire(&self, priority: Priority) -> Result<(), Duration> {
+        let mut state = self.lock();
+        let now = self.clock.now();
+        let due = state.next_slot.saturating_duration_since(now);
+        if state.waiting[priority.index() + 1..].iter().any(|&n| n > 0) {
+            // let the request of higher priority take the next slot
+            return Err(due + self.interval.min(Duration::from_millis(10)));
+        }
+        if due.is_zero() {
+            state.next_slot = now + self.interval;
+            Ok(())
+        } else {
+            Err(due)
+        }
+    }
+
+    #[cfg(not(feature = "blocking"))]
+    pub(crate) async fn acquire(&self, priority: Priority) {
+        let _waiting = self.register(priority);
+        let mut acquired = self.try_acquire(priority);
+        #[cfg(feature = "metrics")]
+        if acquired.is_err() {
+            crate::telemetry::record_rate_limit_hit("local");
+        }
+        while let Err(delay) = acquired {
+            self.clock.sleep(delay).await;
+            acquired = self.try_acquire(priority);
+        }
+    }
+
+    #[cfg(feature = "blocking")]
+    pub(crate) fn acquire(&self, priority: Priority) {
+        let _waiting = self.register(priority);
+        let mut acquired = self.try_acquire(priority);
+        #[cfg(feature = "metrics")]
+        if acquired.is_err() {
+            crate::telemetry::record_rate_limit_hit("local");
+        }
+        while let Err(delay) = acquired {
+            self.clock.sleep(delay);
+            acquired = self.try_acquire(priority);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MockClock;
+
+    #[test]
+    fn test_priorities() {
+        let clock = Arc::new(MockClock::new());
+        let scheduler = Scheduler::new(10, Duration::from_secs(1), clock.clone());
+        assert!(scheduler.try_acquire(Priority::Low).is_ok());
+        let delay = scheduler.try_acquire(Priority::Low).unwrap_err();
+        assert_eq!(delay, Duration::from_millis(100));
+
+        clock.advance(delay);
+        let high = scheduler.register(Priority::High);
+        // the slot is due, but reserved for the waiting request of higher priority
+        assert!(scheduler.try_acquire(Priority::Normal).is_err());
+        assert!(scheduler.try_acquire(Priority::High).is_ok());
+        drop(high);
+        assert_eq!(scheduler.lock().waiting, [0; 3]);
+
+        clock.advance(Duration::from_millis(100));
+        assert!(scheduler.try_acquire(Priority::Normal).is_ok());
+    }
+
+    #[test]
+    fn test_acquire_waits_on_clock() {
+        let clock = Arc::new(MockClock::new());
+        let scheduler = Scheduler::new(2, Duration::from_secs(1), clock.clone());
+        for _ in 0..3 {
+            #[cfg(not(feature = "blocking"))]
+            tokio_test::block_on(scheduler.acquire(Priority::Normal));
+            #[cfg(feature = "blocking")]
+            scheduler.acquire(Priority::Normal);
+        }
+        assert_eq!(clock.elapsed(), Duration::from_secs(1));
+    }
+}
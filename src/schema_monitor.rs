@@ -0,0 +1,235 @@
+//! Monitoring of changes of the response format of yahoo! finance.
+//!
+//! Responses are stored as snapshots in a directory. The schema of a response, i.e. the paths
+//! of all fields together with the type of their values, is compared with the schema of the
+//! previous snapshot, reporting added and removed fields and fields with a changed type.
+//! Elements of arrays are merged into a single path, e.g. `quoteSummary.result[].quoteType`.
+use std::{
+    collections::BTreeMap,
+    fmt, fs, io,
+    path::{Path, PathBuf},
+};
+
+use super::YahooError;
+
+/// Type of a JSON value
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum FieldType {
+    Null,
+    Bool,
+    Number,
+    String,
+    Array,
+    Object,
+}
+
+impl FieldType {
+    fn of(value: &serde_json::Value) -> FieldType {
+        match value {
+            serde_json::Value::Null => FieldType::Null,
+            serde_json::Value::Bool(_) => FieldType::Bool,
+            serde_json::Value::Number(_) => FieldType::Number,
+            serde_json::Value::String(_) => FieldType::String,
+            serde_json::Value::Array(_) => FieldType::Array,
+            serde_json::Value::Object(_) => FieldType::Object,
+        }
+    }
+}
+
+impl fmt::Display for FieldType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self, f)
+    }
+}
+
+/// Paths of all fields of a response with the type of their values
+pub type Schema = BTreeMap<String, FieldType>;
+
+/// Return the schema of a JSON value. If array elements differ in type, the type of the
+/// last element is kept, preferring any type over `Null`.
+pub fn schema_of(json: &serde_json::Value) -> Schema {
+    let mut schema = Schema::new();
+    collect_fields(json, "", &mut schema);
+    schema
+}
+
+fn collect_fields(value: &serde_json::Value, path: &str, schema: &mut Schema) {
+    let field_type = FieldType::of(value);
+    if !path.is_empty() {
+        let entry = schema.entry(path.to_string()).or_insert(field_type);
+        if field_type != FieldType::Null {
+            *entry = field_type;
+        }
+    }
+    match value {
+        serde_json::Value::Object(fields) => {
+            for (name, value) in fields {
+                let path = if path.is_empty() {
+                    name.clone()
+                } else {
+                    format!("{path}.{name}")
+                };
+                collect_fields(value, &path, schema);
+            }
+        }
+        serde_json::Value::Array(elements) => {
+            let path = format!("{path}[]");
+            for value in elements {
+                collect_fields(value, &path, schema);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Differences between two schemas. Changes from or to `Null` are not reported, since
+/// yahoo! finance returns `null` for values missing for a symbol.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SchemaDiff {
+    pub added: Vec<(String, FieldType)>,
+    pub removed: Vec<(String, FieldType)>,
+    /// Fields with the type before and after the change
+    pub changed: Vec<(String, FieldType, FieldType)>,
+}
+
+impl SchemaDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+impl fmt::Display for SchemaDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (path, field_type) in &self.added {
+            writeln!(f, "+ {path}: {field_type}")?;
+        }
+        for (path, field_type) in &self.removed {
+            writeln!(f, "- {path}: {field_type}")?;
+        }
+        for (path, old, new) in &self.changed {
+            writeln!(f, "~ {path}: {old} -> {new}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Compare two schemas
+pub fn diff_schemas(old: &Schema, new: &Schema) -> SchemaDiff {
+    let mut diff = SchemaDiff::default();
+    for (path, &new_type) in new {
+        match old.get(path) {
+            None => diff.added.push((path.clone(), new_type)),
+            Some(&old_type)
+                if old_type != new_type
+                    && old_type != FieldType::Null
+                    && new_type != FieldType::Null =>
+            {
+                diff.changed.push((path.clone(), old_type, new_type))
+            }
+            Some(_) => {}
+        }
+    }
+    for (path, &old_type) in old {
+        if !new.contains_key(path) {
+            diff.removed.push((path.clone(), old_type));
+        }
+    }
+    diff
+}
+
+/// Directory of response snapshots, one file per endpoint and symbol
+#[derive(Debug, Clone)]
+pub struct SnapshotDir {
+    dir: PathBuf,
+}
+
+impl SnapshotDir {
+    pub fn new<P: AsRef<Path>>(dir: P) -> SnapshotDir {
+        SnapshotDir {
+            dir: dir.as_ref().to_path_buf(),
+        }
+    }
+
+    fn path(&self, kind: &str, symbol: &str) -> PathBuf {
+        self.dir.join(format!("{kind}-{symbol}.json"))
+    }
+
+    /// Return the previous snapshot of the given kind of response for a symbol, if any
+    pub fn load(&self, kind: &str, symbol: &str) -> Result<Option<serde_json::Value>, YahooError> {
+        match fs::read(self.path(kind, symbol)) {
+            Ok(bytes) => Ok(Some(serde_json::from_slice(&bytes)?)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(YahooError::SnapshotFailed(e)),
+        }
+    }
+
+    /// Store a response as new snapshot and return the differences of its schema to the
+    /// previous snapshot, or `None` if there was no previous snapshot
+    pub fn update(
+        &self,
+        kind: &str,
+        symbol: &str,
+        json: &serde_json::Value,
+    ) -> Result<Option<SchemaDiff>, YahooError> {
+        let diff = self
+            .load(kind, symbol)?
+            .map(|previous| diff_schemas(&schema_of(&previous), &schema_of(json)));
+        fs::create_dir_all(&self.dir)
+            .and_then(|_| fs::write(self.path(kind, symbol), json.to_string()))
+            .map_err(YahooError::SnapshotFailed)?;
+        Ok(diff)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_schema_diff() {
+        let old = serde_json::json!({"result": [
+            {"price": 1.5, "name": "A", "dividend": null, "removed": true},
+            {"price": 2.5, "name": "B", "dividend": 0.25, "removed": false}
+        ]});
+        let new = serde_json::json!({"result": [
+            {"price": "1.5", "name": "A", "dividend": null, "added": {"raw": 1}}
+        ]});
+        let old = schema_of(&old);
+        assert_eq!(old["result[].dividend"], FieldType::Number);
+        let diff = diff_schemas(&old, &schema_of(&new));
+        assert_eq!(
+            diff.added,
+            vec![
+                ("result[].added".to_string(), FieldType::Object),
+                ("result[].added.raw".to_string(), FieldType::Number)
+            ]
+        );
+        assert_eq!(
+            diff.removed,
+            vec![("result[].removed".to_string(), FieldType::Bool)]
+        );
+        assert_eq!(
+            diff.changed,
+            vec![(
+                "result[].price".to_string(),
+                FieldType::Number,
+                FieldType::String
+            )]
+        );
+        assert_eq!(
+            diff.to_string().lines().last(),
+            Some("~ result[].price: Number -> String")
+        );
+    }
+
+    #[test]
+    fn test_snapshot_dir() {
+        let dir = std::env::temp_dir().join(format!("yahoo-snapshots-{}", std::process::id()));
+        let snapshots = SnapshotDir::new(&dir);
+        let json = serde_json::json!({"quoteSummary": {"result": [{"price": 1}]}});
+        assert_eq!(snapshots.update("summary", "AAPL", &json).unwrap(), None);
+        let diff = snapshots.update("summary", "AAPL", &json).unwrap().unwrap();
+        assert!(diff.is_empty());
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -1,6 +1,10 @@
+#[cfg(all(feature = "capture_unknown", not(feature = "deny_unknown")))]
+use std::collections::HashMap;
+
 use serde::Deserialize;
+use time::OffsetDateTime;
 
-use super::YahooError;
+use super::{deserialize_datetime, Decimal, Exchange, Market, MarketState, YTimestamp, YahooError};
 
 #[derive(Deserialize, Debug)]
 pub struct YSearchResultOpt {
@@ -11,8 +15,9 @@ pub struct YSearchResultOpt {
 
 #[derive(Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "deny_unknown", serde(deny_unknown_fields))]
 pub struct YQuoteItemOpt {
-    pub exchange: String,
+    pub exchange: Exchange,
     #[serde(rename = "shortname")]
     pub short_name: Option<String>,
     pub quote_type: String,
@@ -24,18 +29,58 @@ pub struct YQuoteItemOpt {
     #[serde(rename = "longname")]
     pub long_name: Option<String>,
     pub is_yahoo_finance: bool,
+    /// Fields returned by yahoo! finance which are not (yet) modelled by this struct
+    #[cfg(all(feature = "capture_unknown", not(feature = "deny_unknown")))]
+    #[serde(flatten)]
+    pub extra: HashMap<String, serde_json::Value>,
 }
 
 #[derive(Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "deny_unknown", serde(deny_unknown_fields))]
 pub struct YNewsItem {
     pub uuid: String,
     pub title: String,
     pub publisher: String,
     pub link: String,
-    #[serde(rename = "providerPublishTime")]
-    pub provider_publish_time: u64,
+    #[serde(
+        rename = "providerPublishTime",
+        deserialize_with = "deserialize_datetime"
+    )]
+    pub provider_publish_time: OffsetDateTime,
     #[serde(rename = "type")]
     pub newstype: String,
+    pub thumbnail: Option<YThumbnail>,
+    /// Symbols the article refers to
+    #[serde(rename = "relatedTickers", default)]
+    pub related_tickers: Vec<String>,
+    /// Fields returned by yahoo! finance which are not (yet) modelled by this struct
+    #[cfg(all(feature = "capture_unknown", not(feature = "deny_unknown")))]
+    #[serde(flatten)]
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
+/// Thumbnail of a news article in several resolutions
+#[derive(Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "deny_unknown", serde(deny_unknown_fields))]
+pub struct YThumbnail {
+    pub resolutions: Vec<YThumbnailResolution>,
+}
+
+impl YThumbnail {
+    /// Return the largest resolution of the thumbnail
+    pub fn largest(&self) -> Option<&YThumbnailResolution> {
+        self.resolutions.iter().max_by_key(|r| r.width * r.height)
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[cfg_attr(feature = "deny_unknown", serde(deny_unknown_fields))]
+pub struct YThumbnailResolution {
+    pub url: String,
+    pub width: u32,
+    pub height: u32,
+    /// Size as given by yahoo! finance, e.g. `140x140` or `original`
+    pub tag: String,
 }
 
 impl YSearchResultOpt {
@@ -44,6 +89,18 @@ impl YSearchResultOpt {
     }
 }
 
+/// Search response when only news are requested
+#[derive(Deserialize, Debug)]
+pub(crate) struct YNewsResult {
+    pub news: Vec<YNewsItem>,
+}
+
+impl YNewsResult {
+    pub(crate) fn from_json(json: serde_json::Value) -> Result<YNewsResult, YahooError> {
+        Ok(serde_json::from_value(json)?)
+    }
+}
+
 #[derive(Debug)]
 pub struct YSearchResult {
     pub count: u32,
@@ -54,7 +111,7 @@ pub struct YSearchResult {
 #[derive(Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct YQuoteItem {
-    pub exchange: String,
+    pub exchange: Exchange,
     #[serde(rename = "shortname")]
     pub short_name: String,
     pub quote_type: String,
@@ -127,8 +184,8 @@ pub struct YOptionChainResult {
 #[serde(rename_all = "camelCase")]
 pub struct YOptionChainData {
     pub underlying_symbol: String,
-    pub expiration_dates: Vec<u64>,
-    pub strikes: Vec<f64>,
+    pub expiration_dates: Vec<YTimestamp>,
+    pub strikes: Vec<Decimal>,
     pub has_mini_options: bool,
     pub quote: YQuote,
     pub options: Vec<YOptionDetails>,
@@ -136,6 +193,7 @@ pub struct YOptionChainData {
 
 #[derive(Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "deny_unknown", serde(deny_unknown_fields))]
 pub struct YQuote {
     pub language: String,
     pub region: String,
@@ -143,52 +201,52 @@ pub struct YQuote {
     pub triggerable: bool,
     pub quote_source_name: String,
     pub currency: String,
-    pub eps_current_year: f64,
-    pub price_eps_current_year: f64,
+    pub eps_current_year: Decimal,
+    pub price_eps_current_year: Decimal,
     pub shares_outstanding: u64,
-    pub book_value: f64,
-    pub fifty_day_average: f64,
-    pub fifty_day_average_change: f64,
-    pub fifty_day_average_change_percent: f64,
-    pub two_hundred_day_average: f64,
-    pub two_hundred_day_average_change: f64,
-    pub two_hundred_day_average_change_percent: f64,
+    pub book_value: Decimal,
+    pub fifty_day_average: Decimal,
+    pub fifty_day_average_change: Decimal,
+    pub fifty_day_average_change_percent: Decimal,
+    pub two_hundred_day_average: Decimal,
+    pub two_hundred_day_average_change: Decimal,
+    pub two_hundred_day_average_change_percent: Decimal,
     pub market_cap: u64,
     #[serde(rename = "forwardPE")]
-    pub forward_pe: f64,
-    pub price_to_book: f64,
+    pub forward_pe: Decimal,
+    pub price_to_book: Decimal,
     pub source_interval: u64,
     pub exchange_timezone_name: String,
     pub exchange_timezone_short_name: String,
     pub gmt_off_set_milliseconds: i64,
     pub esg_populated: bool,
     pub tradeable: bool,
-    pub market_state: String,
+    pub market_state: MarketState,
     pub short_name: String,
-    pub fifty_two_week_high_change: f64,
-    pub fifty_two_week_high_change_percent: f64,
-    pub fifty_two_week_low: f64,
-    pub fifty_two_week_high: f64,
-    pub dividend_date: u64,
-    pub earnings_timestamp: u64,
-    pub earnings_timestamp_start: u64,
-    pub earnings_timestamp_end: u64,
-    pub trailing_annual_dividend_rate: f64,
+    pub fifty_two_week_high_change: Decimal,
+    pub fifty_two_week_high_change_percent: Decimal,
+    pub fifty_two_week_low: Decimal,
+    pub fifty_two_week_high: Decimal,
+    pub dividend_date: YTimestamp,
+    pub earnings_timestamp: YTimestamp,
+    pub earnings_timestamp_start: YTimestamp,
+    pub earnings_timestamp_end: YTimestamp,
+    pub trailing_annual_dividend_rate: Decimal,
     #[serde(rename = "trailingPE")]
-    pub trailing_pe: f64,
-    pub trailing_annual_dividend_yield: f64,
-    pub eps_trailing_twelve_months: f64,
-    pub eps_forward: f64,
+    pub trailing_pe: Decimal,
+    pub trailing_annual_dividend_yield: Decimal,
+    pub eps_trailing_twelve_months: Decimal,
+    pub eps_forward: Decimal,
     pub price_hint: u64,
-    pub post_market_change_percent: Option<f64>,
-    pub post_market_time: Option<u64>,
-    pub post_market_price: Option<f64>,
-    pub post_market_change: Option<f64>,
-    pub regular_market_change_percent: f64,
+    pub post_market_change_percent: Option<Decimal>,
+    pub post_market_time: Option<YTimestamp>,
+    pub post_market_price: Option<Decimal>,
+    pub post_market_change: Option<Decimal>,
+    pub regular_market_change_percent: Decimal,
     pub regular_market_day_range: String,
-    pub regular_market_previous_close: f64,
-    pub bid: f64,
-    pub ask: f64,
+    pub regular_market_previous_close: Decimal,
+    pub bid: Decimal,
+    pub ask: Decimal,
     pub bid_size: u64,
     pub ask_size: u64,
     pub message_board_id: String,
@@ -197,26 +255,37 @@ pub struct YQuote {
     pub financial_currency: String,
     pub average_daily_volume3_month: u64,
     pub average_daily_volume10_day: u64,
-    pub fifty_two_week_low_change: f64,
-    pub fifty_two_week_low_change_percent: f64,
+    pub fifty_two_week_low_change: Decimal,
+    pub fifty_two_week_low_change_percent: Decimal,
     pub fifty_two_week_range: String,
-    pub market: String,
+    pub market: Market,
     pub exchange_data_delayed_by: u64,
-    pub regular_market_price: f64,
-    pub regular_market_time: u64,
-    pub regular_market_change: f64,
-    pub regular_market_open: f64,
-    pub regular_market_day_high: f64,
-    pub regular_market_day_low: f64,
+    pub regular_market_price: Decimal,
+    pub regular_market_time: YTimestamp,
+    pub regular_market_change: Decimal,
+    pub regular_market_open: Decimal,
+    pub regular_market_day_high: Decimal,
+    pub regular_market_day_low: Decimal,
     pub regular_market_volume: u64,
-    pub exchange: String,
+    pub exchange: Exchange,
     pub symbol: String,
+    /// Fields returned by yahoo! finance which are not (yet) modelled by this struct
+    #[cfg(all(feature = "capture_unknown", not(feature = "deny_unknown")))]
+    #[serde(flatten)]
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
+impl YQuote {
+    /// Return whether the quote is real-time, i.e. not delayed by the exchange
+    pub fn is_realtime(&self) -> bool {
+        self.exchange_data_delayed_by == 0
+    }
 }
 
 #[derive(Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct YOptionDetails {
-    pub expiration_date: u64,
+    pub expiration_date: YTimestamp,
     pub has_mini_options: bool,
     pub calls: Vec<YOptionContract>,
     pub puts: Vec<YOptionContract>,
@@ -224,20 +293,88 @@ pub struct YOptionDetails {
 
 #[derive(Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "deny_unknown", serde(deny_unknown_fields))]
 pub struct YOptionContract {
     pub contract_symbol: Option<String>,
-    pub strike: Option<f64>,
+    pub strike: Option<Decimal>,
     pub currency: Option<String>,
-    pub last_price: Option<f64>,
-    pub change: Option<f64>,
-    pub percent_change: Option<f64>,
+    pub last_price: Option<Decimal>,
+    pub change: Option<Decimal>,
+    pub percent_change: Option<Decimal>,
     pub volume: Option<u64>,
     pub open_interest: Option<u64>,
-    pub bid: Option<f64>,
-    pub ask: Option<f64>,
+    pub bid: Option<Decimal>,
+    pub ask: Option<Decimal>,
     pub contract_size: Option<String>,
-    pub expiration: Option<u64>,
-    pub last_trade_date: Option<u64>,
-    pub implied_volatility: Option<f64>,
+    pub expiration: Option<YTimestamp>,
+    pub last_trade_date: Option<YTimestamp>,
+    pub implied_volatility: Option<Decimal>,
     pub in_the_money: Option<bool>,
+    /// Fields returned by yahoo! finance which are not (yet) modelled by this struct
+    #[cfg(all(feature = "capture_unknown", not(feature = "deny_unknown")))]
+    #[serde(flatten)]
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_news_item() {
+        let json = r#"{
+            "uuid": "0e4b2f3a-5c2e-3a5f-9a0c-6c4b8e1d2f7a",
+            "title": "Apple Stock Rises",
+            "publisher": "Reuters",
+            "link": "https://finance.yahoo.com/news/apple-stock-rises.html",
+            "providerPublishTime": 1707512401,
+            "type": "STORY",
+            "thumbnail": {"resolutions": [
+                {"url": "https://s.yimg.com/original.jpg", "width": 1200, "height": 800, "tag": "original"},
+                {"url": "https://s.yimg.com/140x140.jpg", "width": 140, "height": 140, "tag": "140x140"}
+            ]},
+            "relatedTickers": ["AAPL", "MSFT"]
+        }"#;
+        let news: YNewsItem = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            news.provider_publish_time,
+            time::macros::datetime!(2024-02-09 21:00:01 UTC)
+        );
+        assert_eq!(news.related_tickers, vec!["AAPL", "MSFT"]);
+        let thumbnail = news.thumbnail.unwrap();
+        assert_eq!(thumbnail.largest().unwrap().tag, "original");
+
+        // thumbnail and related tickers are missing for some articles
+        let json = r#"{"uuid": "1", "title": "Markets", "publisher": "AP", "link": "https://apnews.com",
+            "providerPublishTime": 1707512401, "type": "STORY"}"#;
+        let news: YNewsItem = serde_json::from_str(json).unwrap();
+        assert!(news.thumbnail.is_none());
+        assert!(news.related_tickers.is_empty());
+    }
+
+    #[test]
+    fn test_option_contract_prices() {
+        let json = r#"{
+            "contractSymbol": "AAPL240216C00187500",
+            "strike": 187.5,
+            "currency": "USD",
+            "lastPrice": 0.07,
+            "change": -0.03,
+            "percentChange": -30.0,
+            "volume": 1204,
+            "openInterest": 8417,
+            "bid": 0.06,
+            "ask": 0.08,
+            "contractSize": "REGULAR",
+            "expiration": 1708041600,
+            "lastTradeDate": 1707512399,
+            "impliedVolatility": 0.1797,
+            "inTheMoney": false
+        }"#;
+        let contract: YOptionContract = serde_json::from_str(json).unwrap();
+        let price = |s: &str| s.parse::<Decimal>().unwrap();
+        assert_eq!(contract.strike, Some(price("187.5")));
+        assert_eq!(contract.last_price, Some(price("0.07")));
+        assert_eq!(contract.implied_volatility, Some(price("0.1797")));
+    }
 }
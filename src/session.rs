@@ -0,0 +1,282 @@
+//! Cookie and crumb required by authenticated endpoints of yahoo! finance, e.g. quoteSummary.
+//!
+//! yahoo! finance sets a session cookie when visiting one of its pages, and hands out a crumb
+//! token for this cookie. Both are valid for about one year. The credentials are stored behind
+//! a mutex, so authenticated endpoints can be called on a shared connector.
+use std::{
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use reqwest::{cookie::CookieStore, cookie::Jar, Url};
+use serde::Deserialize;
+
+use super::{Clock, YahooError};
+
+pub(crate) const YCOOKIE_URL: &str = "https://fc.yahoo.com";
+pub(crate) const YCRUMB_URL: &str = "https://query1.finance.yahoo.com/v1/test/getcrumb";
+/// Host of the consent page users from EU countries are redirected to
+pub(crate) const YCONSENT_HOST: &str = "consent.yahoo.com";
+/// Quote page used as alternative source of cookie and crumb
+pub(crate) const YQUOTE_PAGE_URL: &str = "https://finance.yahoo.com/quote/AAPL";
+
+/// Number of attempts to fetch cookie and crumb before giving up
+pub const CREDENTIAL_ATTEMPTS: u32 = 3;
+/// Delay before the first retry of fetching cookie and crumb, doubled for each further retry
+pub const CREDENTIAL_RETRY_DELAY: Duration = Duration::from_millis(500);
+/// Number of consecutive failures of fetching the crumb from the crumb endpoint after which
+/// the crumb is extracted from a quote page instead
+pub const CRUMB_ENDPOINT_FAILURE_LIMIT: u32 = 2;
+/// Age after which cookie and crumb are refreshed, well before they expire after about one year
+pub const CREDENTIAL_REFRESH_AGE: Duration = Duration::from_secs(300 * 24 * 60 * 60);
+
+/// Whether and when cookie and crumb are fetched
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuthMode {
+    /// Never contact yahoo! finance for credentials; endpoints requiring them (e.g.
+    /// `get_ticker_info`) fail with `YahooError::AuthDisabled`
+    None,
+    /// Fetch credentials on first use of an endpoint requiring them
+    #[default]
+    Lazy,
+    /// Start fetching credentials in the background as soon as the connector has been built
+    Eager,
+}
+
+struct Credentials {
+    crumb: String,
+    fetched_at: Instant,
+}
+
+pub(crate) struct Session {
+    pub(crate) jar: Arc<Jar>,
+    clock: Arc<dyn Clock>,
+    credentials: Mutex<Option<Credentials>>,
+    endpoint_failures: AtomicU32,
+    /// Held while fetching credentials on demand, so concurrent requests fetch them only once
+    #[cfg(not(feature = "blocking"))]
+    pub(crate) fetch_lock: tokio::sync::Mutex<()>,
+    #[cfg(feature = "blocking")]
+    pub(crate) fetch_lock: Mutex<()>,
+}
+
+impl Session {
+    pub(crate) fn new(clock: Arc<dyn Clock>) -> Session {
+        Session {
+            jar: Arc::new(Jar::default()),
+            clock,
+            credentials: Mutex::new(None),
+            endpoint_failures: AtomicU32::new(0),
+            #[cfg(not(feature = "blocking"))]
+            fetch_lock: tokio::sync::Mutex::new(()),
+            #[cfg(feature = "blocking")]
+            fetch_lock: Mutex::new(()),
+        }
+    }
+
+    /// Return whether the crumb should be extracted from a quote page, because fetching it
+    /// from the crumb endpoint failed repeatedly
+    pub(crate) fn use_quote_page(&self) -> bool {
+        self.endpoint_failures.load(Ordering::Relaxed) >= CRUMB_ENDPOINT_FAILURE_LIMIT
+    }
+
+    /// Record the outcome of fetching the crumb from the crumb endpoint
+    pub(crate) fn record_endpoint_result(&self, success: bool) {
+        if success {
+            self.endpoint_failures.store(0, Ordering::Relaxed);
+        } else {
+            self.endpoint_failures.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Return to the crumb endpoint, e.g. after the quote page failed as well
+    pub(crate) fn reset_strategy(&self) {
+        self.endpoint_failures.store(0, Ordering::Relaxed);
+    }
+
+    /// Return the current crumb, unless it needs to be refreshed
+    pub(crate) fn crumb(&self) -> Option<String> {
+        let credentials = self.credentials.lock().unwrap();
+        credentials
+            .as_ref()
+            .filter(|c| self.clock.now().duration_since(c.fetched_at) < CREDENTIAL_REFRESH_AGE)
+            .map(|c| c.crumb.clone())
+    }
+
+    /// Return whether credentials are missing or due for a refresh
+    pub(crate) fn needs_refresh(&self) -> bool {
+        self.crumb().is_none()
+    }
+
+    pub(crate) fn set_crumb(&self, crumb: String) {
+        *self.credentials.lock().unwrap() = Some(Credentials {
+            crumb,
+            fetched_at: self.clock.now(),
+        });
+    }
+
+    /// Forget the crumb, e.g. after yahoo! finance rejected it
+    pub(crate) fn invalidate(&self) {
+        *self.credentials.lock().unwrap() = None;
+    }
+
+    /// Check that yahoo! finance has set a cookie for its domain
+    pub(crate) fn check_cookies(&self) -> Result<(), YahooError> {
+        let url: Url = YCRUMB_URL.parse().expect("valid crumb url");
+        match self.jar.cookies(&url) {
+            Some(_) => Ok(()),
+            None => Err(YahooError::NoCookies),
+        }
+    }
+}
+
+/// Check the body of a crumb response, which is plain text
+pub(crate) fn parse_crumb(body: &str) -> Result<String, YahooError> {
+    let crumb = body.trim();
+    if crumb.is_empty() || crumb.contains(['<', '{', ' ']) {
+        Err(YahooError::NoCrumb)
+    } else {
+        Ok(crumb.to_string())
+    }
+}
+
+/// Extract the crumb from the page state embedded in a quote page
+pub(crate) fn parse_crumb_from_html(html: &str) -> Result<String, YahooError> {
+    const KEY: &str = "\"crumb\":\"";
+    let start = html.find(KEY).ok_or(YahooError::NoCrumb)? + KEY.len();
+    let end = html[start..].find('"').ok_or(YahooError::NoCrumb)? + start;
+    // the page state is a JSON string, slashes are escaped as unicode
+    parse_crumb(&html[start..end].replace("\\u002F", "/"))
+}
+
+/// Return the fields of the form accepting all cookies on the consent page, i.e. the hidden
+/// fields of the page (e.g. session id and CSRF token) plus the agreement
+pub(crate) fn parse_consent_form(html: &str) -> Result<Vec<(String, String)>, YahooError> {
+    let mut fields = Vec::new();
+    for input in html.split("<input").skip(1) {
+        let tag = &input[..input.find('>').unwrap_or(input.len())];
+        if !tag.contains("type=\"hidden\"") {
+            continue;
+        }
+        if let (Some(name), Some(value)) = (attribute(tag, "name"), attribute(tag, "value")) {
+            fields.push((name.to_string(), value.to_string()));
+        }
+    }
+    if !fields.iter().any(|(name, _)| name == "sessionId") {
+        return Err(YahooError::NoCookies);
+    }
+    fields.push(("agree".to_string(), "agree".to_string()));
+    Ok(fields)
+}
+
+/// Return the value of an attribute of an HTML tag
+fn attribute<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+    let key = format!(" {name}=\"");
+    let start = tag.find(&key)? + key.len();
+    let end = tag[start..].find('"')? + start;
+    Some(&tag[start..end])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{MockClock, SystemClock};
+
+    #[test]
+    fn test_parse_crumb() {
+        assert_eq!(parse_crumb("Xw7sK1Qx.aB\n").unwrap(), "Xw7sK1Qx.aB");
+        assert!(matches!(parse_crumb(""), Err(YahooError::NoCrumb)));
+        assert!(matches!(
+            parse_crumb("<html><body>Too Many Requests</body></html>"),
+            Err(YahooError::NoCrumb)
+        ));
+        assert!(matches!(
+            parse_crumb(r#"{"finance":{"error":{"code":"Unauthorized"}}}"#),
+            Err(YahooError::NoCrumb)
+        ));
+    }
+
+    #[test]
+    fn test_parse_crumb_from_html() {
+        let html = r#"<html><script>root.App.main = {"context":{"dispatcher":{"stores":
+            {"CrumbStore":{"crumb":"Ab1\u002FcD2.eF"},"UserStore":{}}}}};</script></html>"#;
+        assert_eq!(parse_crumb_from_html(html).unwrap(), "Ab1/cD2.eF");
+        assert!(matches!(
+            parse_crumb_from_html("<html><body>Will be right back</body></html>"),
+            Err(YahooError::NoCrumb)
+        ));
+    }
+
+    #[test]
+    fn test_parse_consent_form() {
+        let html = r#"<form method="post" class="consent-form" action="">
+            <input type="hidden" name="csrfToken" value="xT3kP0">
+            <input type="hidden" name="sessionId" value="3_cc-session_1234"/>
+            <input type="hidden" name="originalDoneUrl" value="https://fc.yahoo.com/">
+            <input type="hidden" name="namespace" value="yahoo">
+            <input type="text" name="search" value="">
+            <button type="submit" class="btn secondary accept-all" name="agree" value="agree">
+            </form>"#;
+        let fields = parse_consent_form(html).unwrap();
+        let names: Vec<&str> = fields.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "csrfToken",
+                "sessionId",
+                "originalDoneUrl",
+                "namespace",
+                "agree"
+            ]
+        );
+        assert_eq!(fields[1].1, "3_cc-session_1234");
+        assert!(matches!(
+            parse_consent_form("<html></html>"),
+            Err(YahooError::NoCookies)
+        ));
+    }
+
+    #[test]
+    fn test_crumb_strategy() {
+        let session = Session::new(Arc::new(SystemClock));
+        assert!(!session.use_quote_page());
+        session.record_endpoint_result(false);
+        session.record_endpoint_result(false);
+        assert!(session.use_quote_page());
+        session.reset_strategy();
+        assert!(!session.use_quote_page());
+        session.record_endpoint_result(false);
+        session.record_endpoint_result(true);
+        session.record_endpoint_result(false);
+        assert!(!session.use_quote_page());
+    }
+
+    #[test]
+    fn test_session_credentials() {
+        let clock = Arc::new(MockClock::new());
+        let session = Session::new(clock.clone());
+        assert!(session.needs_refresh());
+        assert!(matches!(
+            session.check_cookies(),
+            Err(YahooError::NoCookies)
+        ));
+        session.set_crumb("crumb".to_string());
+        assert_eq!(session.crumb().as_deref(), Some("crumb"));
+        assert!(!session.needs_refresh());
+        clock.advance(CREDENTIAL_REFRESH_AGE);
+        assert!(session.needs_refresh());
+        session.set_crumb("crumb".to_string());
+        session.invalidate();
+        assert!(session.crumb().is_none());
+
+        let url: Url = YCOOKIE_URL.parse().unwrap();
+        session
+            .jar
+            .add_cookie_str("A3=d=AQABBK; Domain=.yahoo.com; Path=/", &url);
+        assert!(session.check_cookies().is_ok());
+    }
+}
@@ -0,0 +1,193 @@
+//! Detection of HTML pages returned instead of JSON data, e.g. the consent page shown to users
+//! from EU countries, a captcha or an outage page, and of other bodies which are not JSON,
+//! which would otherwise surface as a cryptic deserialization error.
+//!
+//! Only the start of a body is inspected, see `SniffLimits`, so large error pages are
+//! reported as cheaply as small ones. Errors carry the status and content type of the response
+//! together with an excerpt of its text.
+use reqwest::header::{HeaderMap, CONTENT_TYPE};
+use reqwest::StatusCode;
+
+use super::session::YCONSENT_HOST;
+use super::YahooError;
+
+/// Limits of inspecting bodies of responses which are not JSON, see
+/// `YahooConnectorBuilder::sniff_limits`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SniffLimits {
+    /// Number of bytes at the start of a body searched for markers of a consent page and
+    /// converted to the excerpt of the error
+    pub inspected_bytes: usize,
+    /// Maximum number of characters of the excerpt
+    pub snippet_chars: usize,
+}
+
+impl Default for SniffLimits {
+    fn default() -> Self {
+        SniffLimits {
+            inspected_bytes: 4000,
+            snippet_chars: 200,
+        }
+    }
+}
+
+fn content_type(headers: &HeaderMap) -> String {
+    headers
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default()
+        .to_string()
+}
+
+/// Fail if the body of a response expected to be JSON is an HTML page
+pub(crate) fn check_json_body(
+    status: StatusCode,
+    headers: &HeaderMap,
+    body: &[u8],
+    limits: SniffLimits,
+) -> Result<(), YahooError> {
+    let content_type = content_type(headers);
+    let starts_with_tag = body
+        .iter()
+        .find(|b| !b.is_ascii_whitespace())
+        .is_some_and(|&b| b == b'<');
+    if !content_type.contains("html") && !starts_with_tag {
+        return Ok(());
+    }
+    let page = String::from_utf8_lossy(&body[..body.len().min(limits.inspected_bytes)]);
+    if page.contains(YCONSENT_HOST) || page.contains("collectConsent") {
+        return Err(YahooError::ConsentRequired);
+    }
+    Err(YahooError::HtmlResponse {
+        status: status.as_u16(),
+        content_type,
+        snippet: snippet(&page, limits.snippet_chars),
+    })
+}
+
+/// Error of a successful response whose body is not JSON at all
+pub(crate) fn unexpected_content(
+    status: StatusCode,
+    headers: &HeaderMap,
+    body: &[u8],
+    limits: SniffLimits,
+) -> YahooError {
+    let text = String::from_utf8_lossy(&body[..body.len().min(limits.inspected_bytes)]);
+    YahooError::UnexpectedContent {
+        status: status.as_u16(),
+        content_type: content_type(headers),
+        snippet: snippet(&text, limits.snippet_chars),
+    }
+}
+
+/// Text of an HTML page without tags, scripts and styles, with whitespace collapsed and cut
+/// after the given number of characters
+fn snippet(page: &str, length: usize) -> String {
+    let mut text = String::new();
+    let mut rest = page;
+    while let Some(start) = rest.find('<') {
+        text.push_str(&rest[..start]);
+        text.push(' ');
+        rest = &rest[start + 1..];
+        // skip the content of scripts and styles up to the closing tag
+        let end = ["script", "style"]
+            .iter()
+            .find(|name| {
+                rest.as_bytes()
+                    .get(..name.len())
+                    .is_some_and(|tag| tag.eq_ignore_ascii_case(name.as_bytes()))
+            })
+            .and_then(|name| rest.to_ascii_lowercase().find(&format!("</{name}")))
+            .map_or(0, |close| close + 1);
+        match rest[end..].find('>') {
+            Some(close) => rest = &rest[end + close + 1..],
+            None => rest = "",
+        }
+    }
+    text.push_str(rest);
+    let words: Vec<&str> = text.split_whitespace().collect();
+    let text = words.join(" ");
+    match text.char_indices().nth(length) {
+        Some((cut, _)) => format!("{}...", &text[..cut]),
+        None => text,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use reqwest::header::HeaderValue;
+
+    use super::*;
+
+    fn headers(content_type: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(CONTENT_TYPE, HeaderValue::from_str(content_type).unwrap());
+        headers
+    }
+
+    #[test]
+    fn test_check_json_body() {
+        let limits = SniffLimits::default();
+        let check = |headers: &HeaderMap, body: &[u8]| {
+            check_json_body(StatusCode::OK, headers, body, limits)
+        };
+        let json = headers("application/json;charset=utf-8");
+        assert!(check(&json, br#"{"chart": {}}"#).is_ok());
+        assert!(check(&HeaderMap::new(), b"  {}").is_ok());
+
+        let page = b"<!DOCTYPE html><html><head><title>Yahoo</title>\
+            <script>var x = '<b>';</script><style>p {}</style></head>\
+            <body><p>Will be right back...</p>\n<p>Our   engineers are working quickly</p></body></html>";
+        match check(&json, page) {
+            Err(YahooError::HtmlResponse {
+                status,
+                content_type,
+                snippet,
+            }) => {
+                assert_eq!(status, 200);
+                assert_eq!(content_type, "application/json;charset=utf-8");
+                assert_eq!(
+                    snippet,
+                    "Yahoo Will be right back... Our engineers are working quickly"
+                )
+            }
+            result => panic!("unexpected result {result:?}"),
+        }
+        let consent = br#"<html><form action="https://consent.yahoo.com/v2/collectConsent">"#;
+        assert!(matches!(
+            check(&headers("text/html"), consent),
+            Err(YahooError::ConsentRequired)
+        ));
+        // the consent marker beyond the inspected bytes is not found
+        let limits = SniffLimits {
+            inspected_bytes: 20,
+            snippet_chars: 10,
+        };
+        match check_json_body(StatusCode::OK, &headers("text/html"), consent, limits) {
+            Err(YahooError::HtmlResponse { snippet, .. }) => assert_eq!(snippet, ""),
+            result => panic!("unexpected result {result:?}"),
+        }
+
+        let long = format!("<p>{}</p>", "x".repeat(500));
+        match check(&headers("text/html"), long.as_bytes()) {
+            Err(YahooError::HtmlResponse { snippet, .. }) => {
+                assert_eq!(snippet.len(), SniffLimits::default().snippet_chars + 3)
+            }
+            result => panic!("unexpected result {result:?}"),
+        }
+    }
+
+    #[test]
+    fn test_unexpected_content() {
+        let error = unexpected_content(
+            StatusCode::OK,
+            &headers("text/plain"),
+            b"Too Many Requests\r\n",
+            SniffLimits::default(),
+        );
+        assert_eq!(
+            error.to_string(),
+            "yahoo! finance returned no JSON (status 200, content type \"text/plain\"): Too Many Requests"
+        );
+    }
+}
@@ -0,0 +1,98 @@
+//! Latest prices of several symbols as returned in one response by the spark endpoint.
+use std::collections::HashMap;
+
+use serde::Deserialize;
+
+use super::quotes::deserialize_null_as_empty;
+use super::{LatestPrice, YChartError, YMetaData, YahooError};
+
+/// Maximum number of symbols requested from the spark endpoint at once; longer lists are
+/// split into several requests
+pub const SPARK_MAX_SYMBOLS: usize = 20;
+
+#[derive(Deserialize, Debug, Default)]
+pub struct YSpark {
+    pub spark: YSparkBody,
+}
+
+#[derive(Deserialize, Debug, Default)]
+pub struct YSparkBody {
+    #[serde(default, deserialize_with = "deserialize_null_as_empty")]
+    pub result: Vec<YSparkResult>,
+    pub error: Option<YChartError>,
+}
+
+/// Result for one of the requested symbols
+#[derive(Deserialize, Debug)]
+pub struct YSparkResult {
+    pub symbol: String,
+    #[serde(default, deserialize_with = "deserialize_null_as_empty")]
+    pub response: Vec<YSparkResponse>,
+}
+
+/// Chart of a symbol; only the meta data is modelled, the close prices are ignored
+#[derive(Deserialize, Debug)]
+pub struct YSparkResponse {
+    pub meta: YMetaData,
+}
+
+impl YSpark {
+    /// Deserialize a response of the spark endpoint, turning errors reported by yahoo! finance
+    /// for the requested symbols into the corresponding `YahooError`
+    pub(crate) fn from_json(json: serde_json::Value, symbols: &str) -> Result<YSpark, YahooError> {
+        let spark: YSpark = serde_json::from_value(json)?;
+        match &spark.spark.error {
+            Some(error) => Err(error.to_error(symbols)),
+            None => Ok(spark),
+        }
+    }
+
+    /// Return the meta data of each symbol for which yahoo! finance returned a chart
+    pub(crate) fn into_meta(self) -> HashMap<String, YMetaData> {
+        self.spark
+            .result
+            .into_iter()
+            .filter_map(|result| {
+                let meta = result.response.into_iter().next()?.meta;
+                Some((result.symbol, meta))
+            })
+            .collect()
+    }
+
+    /// Return the latest price of each symbol for which yahoo! finance reported a regular
+    /// market price; other symbols are missing in the map
+    pub fn latest_prices(&self, now: i64) -> HashMap<String, LatestPrice> {
+        self.spark
+            .result
+            .iter()
+            .filter_map(|result| {
+                let meta = &result.response.first()?.meta;
+                Some((result.symbol.clone(), meta.latest_price_at(now)?))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::chart_fixture;
+    use crate::Decimal;
+
+    #[test]
+    fn test_latest_prices() {
+        let meta =
+            |name| chart_fixture(name).unwrap().json_value()["chart"]["result"][0]["meta"].clone();
+        let json = serde_json::json!({"spark": {"result": [
+            {"symbol": "AAPL", "response": [{"meta": meta("equity_aapl_1d")}]},
+            {"symbol": "EURUSD=X", "response": [{"meta": meta("fx_eurusd_1d")}]},
+            {"symbol": "XXXXXX", "response": null}
+        ], "error": null}});
+        let spark = YSpark::from_json(json, "AAPL,EURUSD=X,XXXXXX").unwrap();
+        let prices = spark.latest_prices(1707512401);
+        assert_eq!(prices.len(), 2);
+        assert_eq!(prices["AAPL"].price, "188.85".parse::<Decimal>().unwrap());
+        assert_eq!(prices["AAPL"].currency.as_deref(), Some("USD"));
+        assert!(!prices.contains_key("XXXXXX"));
+    }
+}
@@ -0,0 +1,189 @@
+//! Statistics of the returns of a symbol against a benchmark, e.g. the beta of a stock to
+//! the S&P 500.
+//!
+//! ```rust,ignore
+//! let stock = provider.get_quote_history("AAPL", start, end).await?.quotes()?;
+//! let index = provider.get_quote_history(symbols::SP500, start, end).await?.quotes()?;
+//! let beta = stats::beta(&stock, &index);
+//! ```
+//!
+//! Quotes are aligned by their date in UTC, so days on which only one of the symbols traded
+//! are skipped. Returns are computed from adjusted closes between consecutive aligned days.
+use super::{decimal_to_f64, Quote};
+
+/// Returns of a symbol and its benchmark over the same day
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AlignedReturn {
+    /// Timestamp of the quote of the symbol at the end of the period
+    pub timestamp: u64,
+    pub series: f64,
+    pub benchmark: f64,
+}
+
+/// Value of a statistic over the window of returns ending at the timestamp
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RollingValue {
+    pub timestamp: u64,
+    pub value: f64,
+}
+
+fn day(quote: &Quote) -> u64 {
+    quote.timestamp / 86400
+}
+
+/// Returns of the quotes of a symbol and a benchmark on the days both traded
+pub fn aligned_returns(series: &[Quote], benchmark: &[Quote]) -> Vec<AlignedReturn> {
+    let mut pairs: Vec<(&Quote, &Quote)> = Vec::new();
+    let mut j = 0;
+    for quote in series {
+        while j < benchmark.len() && day(&benchmark[j]) < day(quote) {
+            j += 1;
+        }
+        if j < benchmark.len() && day(&benchmark[j]) == day(quote) {
+            pairs.push((quote, &benchmark[j]));
+        }
+    }
+    pairs
+        .windows(2)
+        .filter_map(|window| {
+            let ((s0, b0), (s1, b1)) = (window[0], window[1]);
+            let change = |from: &Quote, to: &Quote| {
+                let from = decimal_to_f64(from.adjclose);
+                (from != 0.0).then(|| decimal_to_f64(to.adjclose) / from - 1.0)
+            };
+            Some(AlignedReturn {
+                timestamp: s1.timestamp,
+                series: change(s0, s1)?,
+                benchmark: change(b0, b1)?,
+            })
+        })
+        .collect()
+}
+
+/// Covariance of the returns and variances of series and benchmark, or `None` for less than
+/// two returns
+fn moments(returns: &[AlignedReturn]) -> Option<(f64, f64, f64)> {
+    if returns.len() < 2 {
+        return None;
+    }
+    let n = returns.len() as f64;
+    let mean_series = returns.iter().map(|r| r.series).sum::<f64>() / n;
+    let mean_benchmark = returns.iter().map(|r| r.benchmark).sum::<f64>() / n;
+    let (mut covariance, mut var_series, mut var_benchmark) = (0.0, 0.0, 0.0);
+    for r in returns {
+        let (s, b) = (r.series - mean_series, r.benchmark - mean_benchmark);
+        covariance += s * b;
+        var_series += s * s;
+        var_benchmark += b * b;
+    }
+    Some((covariance, var_series, var_benchmark))
+}
+
+fn beta_of(returns: &[AlignedReturn]) -> Option<f64> {
+    let (covariance, _, var_benchmark) = moments(returns)?;
+    (var_benchmark > 0.0).then(|| covariance / var_benchmark)
+}
+
+fn correlation_of(returns: &[AlignedReturn]) -> Option<f64> {
+    let (covariance, var_series, var_benchmark) = moments(returns)?;
+    (var_series > 0.0 && var_benchmark > 0.0)
+        .then(|| covariance / (var_series * var_benchmark).sqrt())
+}
+
+fn rolling(
+    returns: &[AlignedReturn],
+    window: usize,
+    statistic: fn(&[AlignedReturn]) -> Option<f64>,
+) -> Vec<RollingValue> {
+    if window == 0 {
+        return Vec::new();
+    }
+    returns
+        .windows(window)
+        .filter_map(|returns| {
+            Some(RollingValue {
+                timestamp: returns[returns.len() - 1].timestamp,
+                value: statistic(returns)?,
+            })
+        })
+        .collect()
+}
+
+/// Beta of the symbol to the benchmark, i.e. the covariance of their returns divided by the
+/// variance of the returns of the benchmark. Returns `None` for less than two aligned returns
+/// or if the benchmark did not move.
+pub fn beta(series: &[Quote], benchmark: &[Quote]) -> Option<f64> {
+    beta_of(&aligned_returns(series, benchmark))
+}
+
+/// Pearson correlation of the returns of the symbol and the benchmark. Returns `None` for
+/// less than two aligned returns or if either did not move.
+pub fn correlation(series: &[Quote], benchmark: &[Quote]) -> Option<f64> {
+    correlation_of(&aligned_returns(series, benchmark))
+}
+
+/// Beta over each window of `window` consecutive aligned returns
+pub fn rolling_beta(series: &[Quote], benchmark: &[Quote], window: usize) -> Vec<RollingValue> {
+    rolling(&aligned_returns(series, benchmark), window, beta_of)
+}
+
+/// Correlation over each window of `window` consecutive aligned returns
+pub fn rolling_correlation(
+    series: &[Quote],
+    benchmark: &[Quote],
+    window: usize,
+) -> Vec<RollingValue> {
+    rolling(&aligned_returns(series, benchmark), window, correlation_of)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Decimal;
+
+    fn quotes(timestamps: &[u64], closes: &[u32]) -> Vec<Quote> {
+        timestamps
+            .iter()
+            .zip(closes)
+            .map(|(&timestamp, &close)| Quote {
+                timestamp,
+                open: Decimal::from(close),
+                high: Decimal::from(close),
+                low: Decimal::from(close),
+                volume: 0,
+                close: Decimal::from(close),
+                adjclose: Decimal::from(close),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_beta_and_correlation() {
+        let day = 86400;
+        // the stock opens later than the index; the index did not trade on day 2
+        let stock = quotes(
+            &[day + 3600, 2 * day + 3600, 3 * day + 3600, 4 * day + 3600],
+            &[100, 104, 102, 103],
+        );
+        let index = quotes(&[day, 3 * day, 4 * day], &[200, 202, 201]);
+        let returns = aligned_returns(&stock, &index);
+        assert_eq!(returns.len(), 2);
+        assert_eq!(returns[0].timestamp, 3 * day + 3600);
+        assert!((returns[0].series - 0.02).abs() < 1e-12);
+        assert!((returns[0].benchmark - 0.01).abs() < 1e-12);
+
+        let series = quotes(&[0, day, 2 * day, 3 * day], &[100, 110, 99, 108]);
+        let benchmark = quotes(&[0, day, 2 * day, 3 * day], &[100, 105, 99, 105]);
+        let beta = beta(&series, &benchmark).unwrap();
+        assert!(beta > 1.5 && beta < 2.0, "beta {beta}");
+        let correlation = correlation(&series, &benchmark).unwrap();
+        assert!(correlation > 0.99 && correlation <= 1.0);
+        assert_eq!(correlation_of(&returns[..1]), None);
+
+        let rolling = rolling_correlation(&series, &benchmark, 2);
+        assert_eq!(rolling.len(), 2);
+        assert_eq!(rolling[1].timestamp, 3 * day);
+        assert!((rolling[1].value - 1.0).abs() < 1e-12);
+        assert_eq!(rolling_beta(&series, &benchmark, 4), vec![]);
+    }
+}
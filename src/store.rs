@@ -0,0 +1,332 @@
+//! Local persistence of quote histories in a SQLite database.
+//!
+//! The store keeps one series of quotes per symbol, keyed by the quote timestamp.
+//! Writing the same bar twice replaces the previously stored values, which allows
+//! to re-fetch the latest (possibly incomplete) bar on every synchronisation.
+use std::ops::{Bound, RangeBounds};
+use std::path::Path;
+use std::sync::{Mutex, MutexGuard};
+
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use time::OffsetDateTime;
+
+use super::{BatchPolicy, BatchResult, Decimal, Quote, YahooConnector, YahooError};
+
+const CREATE_QUOTES_TABLE: &str = "CREATE TABLE IF NOT EXISTS quotes (
+    symbol TEXT NOT NULL,
+    timestamp INTEGER NOT NULL,
+    open NOT NULL,
+    high NOT NULL,
+    low NOT NULL,
+    volume INTEGER NOT NULL,
+    close NOT NULL,
+    adjclose NOT NULL,
+    PRIMARY KEY (symbol, timestamp)
+)";
+
+const UPSERT_QUOTE: &str =
+    "INSERT INTO quotes (symbol, timestamp, open, high, low, volume, close, adjclose)
+    VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+    ON CONFLICT (symbol, timestamp) DO UPDATE SET
+        open = excluded.open,
+        high = excluded.high,
+        low = excluded.low,
+        volume = excluded.volume,
+        close = excluded.close,
+        adjclose = excluded.adjclose";
+
+const LOAD_QUOTES: &str = "SELECT timestamp, open, high, low, volume, close, adjclose FROM quotes
+    WHERE symbol = ?1 AND timestamp >= ?2 AND timestamp <= ?3
+    ORDER BY timestamp";
+
+const LAST_TIMESTAMP: &str = "SELECT MAX(timestamp) FROM quotes WHERE symbol = ?1";
+
+/// SQLite backed storage for quote histories
+pub struct QuoteStore {
+    conn: Mutex<Connection>,
+}
+
+impl QuoteStore {
+    /// Open (or create) a quote store in the given database file
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<QuoteStore, YahooError> {
+        Self::init(Connection::open(path)?)
+    }
+
+    /// Create a new quote store which lives in memory only
+    pub fn open_in_memory() -> Result<QuoteStore, YahooError> {
+        Self::init(Connection::open_in_memory()?)
+    }
+
+    fn init(conn: Connection) -> Result<QuoteStore, YahooError> {
+        conn.execute(CREATE_QUOTES_TABLE, [])?;
+        Ok(QuoteStore {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    fn connection(&self) -> MutexGuard<'_, Connection> {
+        self.conn.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Insert the given quotes for a symbol, replacing already stored quotes with the same timestamp.
+    /// Returns the number of quotes written.
+    pub fn upsert_history(&self, symbol: &str, quotes: &[Quote]) -> Result<usize, YahooError> {
+        let mut conn = self.connection();
+        let tx = conn.transaction()?;
+        {
+            let mut stmt = tx.prepare_cached(UPSERT_QUOTE)?;
+            for quote in quotes {
+                stmt.execute(params![
+                    symbol,
+                    quote.timestamp as i64,
+                    to_sql(quote.open),
+                    to_sql(quote.high),
+                    to_sql(quote.low),
+                    quote.volume as i64,
+                    to_sql(quote.close),
+                    to_sql(quote.adjclose),
+                ])?;
+            }
+        }
+        tx.commit()?;
+        Ok(quotes.len())
+    }
+
+    /// Load the stored quotes of a symbol within the given time range, ordered by timestamp
+    pub fn load<R: RangeBounds<OffsetDateTime>>(
+        &self,
+        symbol: &str,
+        range: R,
+    ) -> Result<Vec<Quote>, YahooError> {
+        let start = match range.start_bound() {
+            Bound::Included(start) => start.unix_timestamp(),
+            Bound::Excluded(start) => start.unix_timestamp() + 1,
+            Bound::Unbounded => i64::MIN,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(end) => end.unix_timestamp(),
+            Bound::Excluded(end) => end.unix_timestamp() - 1,
+            Bound::Unbounded => i64::MAX,
+        };
+        let conn = self.connection();
+        let mut stmt = conn.prepare_cached(LOAD_QUOTES)?;
+        let quotes = stmt
+            .query_map(params![symbol, start, end], quote_from_row)?
+            .collect::<Result<Vec<Quote>, _>>()?;
+        Ok(quotes)
+    }
+
+    /// Return the timestamp of the most recent quote stored for the given symbol
+    pub fn last_timestamp(&self, symbol: &str) -> Result<Option<i64>, YahooError> {
+        let conn = self.connection();
+        let last = conn
+            .query_row(LAST_TIMESTAMP, params![symbol], |row| {
+                row.get::<_, Option<i64>>(0)
+            })
+            .optional()?;
+        Ok(last.flatten())
+    }
+
+    /// Fetch all daily quotes not yet stored for the given symbol and add them to the store.
+    /// The most recent stored quote is fetched again, since it might have been incomplete when
+    /// it was written. If no quotes have been stored yet, the full available history is fetched.
+    /// Returns the number of quotes written.
+    #[cfg(not(feature = "blocking"))]
+    pub async fn sync(
+        &self,
+        connector: &YahooConnector,
+        symbol: &str,
+    ) -> Result<usize, YahooError> {
+        let response = match self.last_timestamp(symbol)? {
+            Some(last) => {
+                connector
+                    .get_quote_history(symbol, from_timestamp(last)?, OffsetDateTime::now_utc())
+                    .await?
+            }
+            None => connector.get_quote_range(symbol, "1d", "max").await?,
+        };
+        self.upsert_history(symbol, &response.quotes()?)
+    }
+
+    /// Synchronize the given symbols one after another, see `QuoteStore::sync`. Returns the
+    /// number of quotes written per symbol, or the first error if the policy is
+    /// `BatchPolicy::FailFast`.
+    #[cfg(not(feature = "blocking"))]
+    pub async fn sync_all(
+        &self,
+        connector: &YahooConnector,
+        symbols: &[&str],
+        policy: BatchPolicy,
+    ) -> Result<BatchResult<usize>, YahooError> {
+        let mut batch = BatchResult::new();
+        for symbol in symbols {
+            batch.push(symbol, self.sync(connector, symbol).await, policy)?;
+        }
+        Ok(batch)
+    }
+
+    /// Fetch all daily quotes not yet stored for the given symbol and add them to the store.
+    /// The most recent stored quote is fetched again, since it might have been incomplete when
+    /// it was written. If no quotes have been stored yet, the full available history is fetched.
+    /// Returns the number of quotes written.
+    #[cfg(feature = "blocking")]
+    pub fn sync(&self, connector: &YahooConnector, symbol: &str) -> Result<usize, YahooError> {
+        let response = match self.last_timestamp(symbol)? {
+            Some(last) => connector.get_quote_history(
+                symbol,
+                from_timestamp(last)?,
+                OffsetDateTime::now_utc(),
+            )?,
+            None => connector.get_quote_range(symbol, "1d", "max")?,
+        };
+        self.upsert_history(symbol, &response.quotes()?)
+    }
+
+    /// Synchronize the given symbols one after another, see `QuoteStore::sync`. Returns the
+    /// number of quotes written per symbol, or the first error if the policy is
+    /// `BatchPolicy::FailFast`.
+    #[cfg(feature = "blocking")]
+    pub fn sync_all(
+        &self,
+        connector: &YahooConnector,
+        symbols: &[&str],
+        policy: BatchPolicy,
+    ) -> Result<BatchResult<usize>, YahooError> {
+        let mut batch = BatchResult::new();
+        for symbol in symbols {
+            batch.push(symbol, self.sync(connector, symbol), policy)?;
+        }
+        Ok(batch)
+    }
+}
+
+fn from_timestamp(timestamp: i64) -> Result<OffsetDateTime, YahooError> {
+    OffsetDateTime::from_unix_timestamp(timestamp).map_err(|_| YahooError::DataInconsistency)
+}
+
+fn quote_from_row(row: &Row) -> rusqlite::Result<Quote> {
+    Ok(Quote {
+        timestamp: row.get::<_, i64>(0)? as u64,
+        open: from_sql(row, 1)?,
+        high: from_sql(row, 2)?,
+        low: from_sql(row, 3)?,
+        volume: row.get::<_, i64>(4)? as u64,
+        close: from_sql(row, 5)?,
+        adjclose: from_sql(row, 6)?,
+    })
+}
+
+#[cfg(not(feature = "decimal"))]
+fn to_sql(value: Decimal) -> f64 {
+    value
+}
+
+#[cfg(not(feature = "decimal"))]
+fn from_sql(row: &Row, idx: usize) -> rusqlite::Result<Decimal> {
+    row.get(idx)
+}
+
+// Decimals are stored as text to preserve their exact representation
+#[cfg(feature = "decimal")]
+fn to_sql(value: Decimal) -> String {
+    value.to_string()
+}
+
+#[cfg(feature = "decimal")]
+fn from_sql(row: &Row, idx: usize) -> rusqlite::Result<Decimal> {
+    let value: String = row.get(idx)?;
+    value.parse().map_err(|e| {
+        rusqlite::Error::FromSqlConversionFailure(idx, rusqlite::types::Type::Text, Box::new(e))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::datetime;
+
+    use super::*;
+
+    fn quote(timestamp: u64, close: u32) -> Quote {
+        let close = Decimal::from(close);
+        Quote {
+            timestamp,
+            open: close,
+            high: close,
+            low: close,
+            volume: 1000,
+            close,
+            adjclose: close,
+        }
+    }
+
+    #[test]
+    fn test_upsert_and_load() {
+        let store = QuoteStore::open_in_memory().unwrap();
+        let quotes = vec![quote(1577977200, 10), quote(1578063600, 11)];
+        assert_eq!(store.upsert_history("AAPL", &quotes).unwrap(), 2);
+        assert_eq!(
+            store
+                .upsert_history("MSFT", &[quote(1577977200, 20)])
+                .unwrap(),
+            1
+        );
+
+        let loaded = store.load("AAPL", ..).unwrap();
+        assert_eq!(loaded, quotes);
+        assert_eq!(store.last_timestamp("AAPL").unwrap(), Some(1578063600));
+        assert_eq!(store.last_timestamp("IBM").unwrap(), None);
+    }
+
+    #[test]
+    fn test_upsert_replaces_existing_quotes() {
+        let store = QuoteStore::open_in_memory().unwrap();
+        store
+            .upsert_history("AAPL", &[quote(1577977200, 10), quote(1578063600, 11)])
+            .unwrap();
+        store
+            .upsert_history("AAPL", &[quote(1578063600, 12), quote(1578150000, 13)])
+            .unwrap();
+
+        let loaded = store.load("AAPL", ..).unwrap();
+        assert_eq!(
+            loaded,
+            vec![
+                quote(1577977200, 10),
+                quote(1578063600, 12),
+                quote(1578150000, 13)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_load_range() {
+        let store = QuoteStore::open_in_memory().unwrap();
+        store
+            .upsert_history(
+                "AAPL",
+                &[
+                    quote(1577977200, 10),
+                    quote(1578063600, 11),
+                    quote(1578150000, 12),
+                ],
+            )
+            .unwrap();
+
+        let start = datetime!(2020-01-03 0:00 UTC);
+        let end = datetime!(2020-01-04 15:00 UTC);
+        assert_eq!(
+            store.load("AAPL", start..end).unwrap(),
+            vec![quote(1578063600, 11)]
+        );
+        assert_eq!(
+            store.load("AAPL", start..).unwrap(),
+            vec![quote(1578063600, 11), quote(1578150000, 12)]
+        );
+        assert_eq!(
+            store
+                .load("AAPL", ..=datetime!(2020-01-03 15:00 UTC))
+                .unwrap(),
+            vec![quote(1577977200, 10), quote(1578063600, 11)]
+        );
+    }
+}
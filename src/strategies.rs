@@ -0,0 +1,283 @@
+//! Option strategies (vertical spreads, straddles and strangles) built from an option chain.
+//!
+//! Premiums are the mid prices of bid and ask. All amounts are per share, i.e. they need to
+//! be multiplied by the contract size (usually 100) to get the amount per contract.
+use super::quotes::ZERO;
+use super::{Decimal, YOptionContract, YOptionDetails, YTimestamp};
+
+/// Right of an option
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OptionKind {
+    Call,
+    Put,
+}
+
+/// Option bought (positive quantity) or written (negative quantity) as part of a strategy
+#[derive(Debug, Clone, PartialEq)]
+pub struct Leg {
+    pub kind: OptionKind,
+    pub strike: Decimal,
+    /// Mid price of bid and ask
+    pub premium: Decimal,
+    pub quantity: i32,
+    pub contract_symbol: Option<String>,
+}
+
+impl Leg {
+    fn from_contract(kind: OptionKind, contract: &YOptionContract, quantity: i32) -> Option<Leg> {
+        let (bid, ask) = (contract.bid?, contract.ask?);
+        if ask <= ZERO {
+            return None;
+        }
+        Some(Leg {
+            kind,
+            strike: contract.strike?,
+            premium: (bid + ask) / Decimal::from(2),
+            quantity,
+            contract_symbol: contract.contract_symbol.clone(),
+        })
+    }
+
+    /// Value of the leg at expiration for the given price of the underlying
+    fn payoff(&self, price: Decimal) -> Decimal {
+        let intrinsic = match self.kind {
+            OptionKind::Call => price - self.strike,
+            OptionKind::Put => self.strike - price,
+        };
+        intrinsic.max(ZERO) * Decimal::from(self.quantity)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StrategyKind {
+    /// Calls or puts of the same expiration bought and written at different strikes
+    VerticalSpread,
+    /// Call and put of the same strike and expiration
+    Straddle,
+    /// Put and call of the same expiration with the put strike below the call strike
+    Strangle,
+}
+
+/// Combination of options of the same expiration with its characteristics at expiration
+#[derive(Debug, Clone, PartialEq)]
+pub struct Strategy {
+    pub kind: StrategyKind,
+    pub expiration_date: YTimestamp,
+    pub legs: Vec<Leg>,
+    /// Premium paid (positive) or received (negative) to enter the strategy
+    pub net_premium: Decimal,
+    /// Maximum gain at expiration, or `None` if unlimited
+    pub max_gain: Option<Decimal>,
+    /// Maximum loss at expiration as positive amount, or `None` if unlimited
+    pub max_loss: Option<Decimal>,
+    /// Prices of the underlying at which the strategy breaks even at expiration, ascending
+    pub breakevens: Vec<Decimal>,
+}
+
+fn find(contracts: &[YOptionContract], strike: Decimal) -> Option<&YOptionContract> {
+    contracts.iter().find(|c| c.strike == Some(strike))
+}
+
+impl Strategy {
+    /// Vertical spread buying the option with strike `long_strike` and writing the option with
+    /// strike `short_strike`, e.g. a bull call spread if the long strike is the lower one.
+    /// Returns `None` if the chain lacks the contracts or their bid and ask.
+    pub fn vertical(
+        chain: &YOptionDetails,
+        kind: OptionKind,
+        long_strike: Decimal,
+        short_strike: Decimal,
+    ) -> Option<Strategy> {
+        if long_strike == short_strike {
+            return None;
+        }
+        let contracts = match kind {
+            OptionKind::Call => &chain.calls,
+            OptionKind::Put => &chain.puts,
+        };
+        let legs = vec![
+            Leg::from_contract(kind, find(contracts, long_strike)?, 1)?,
+            Leg::from_contract(kind, find(contracts, short_strike)?, -1)?,
+        ];
+        Some(Strategy::new(
+            StrategyKind::VerticalSpread,
+            chain.expiration_date,
+            legs,
+        ))
+    }
+
+    /// Straddle buying (`long`) or writing call and put of the given strike.
+    /// Returns `None` if the chain lacks the contracts or their bid and ask.
+    pub fn straddle(chain: &YOptionDetails, strike: Decimal, long: bool) -> Option<Strategy> {
+        Strategy::strangle_legs(chain, strike, strike, long)
+            .map(|legs| Strategy::new(StrategyKind::Straddle, chain.expiration_date, legs))
+    }
+
+    /// Strangle buying (`long`) or writing a put of strike `put_strike` and a call of the higher
+    /// strike `call_strike`. Returns `None` if the put strike is not below the call strike or
+    /// the chain lacks the contracts or their bid and ask.
+    pub fn strangle(
+        chain: &YOptionDetails,
+        put_strike: Decimal,
+        call_strike: Decimal,
+        long: bool,
+    ) -> Option<Strategy> {
+        if put_strike >= call_strike {
+            return None;
+        }
+        Strategy::strangle_legs(chain, put_strike, call_strike, long)
+            .map(|legs| Strategy::new(StrategyKind::Strangle, chain.expiration_date, legs))
+    }
+
+    fn strangle_legs(
+        chain: &YOptionDetails,
+        put_strike: Decimal,
+        call_strike: Decimal,
+        long: bool,
+    ) -> Option<Vec<Leg>> {
+        let quantity = if long { 1 } else { -1 };
+        Some(vec![
+            Leg::from_contract(OptionKind::Put, find(&chain.puts, put_strike)?, quantity)?,
+            Leg::from_contract(OptionKind::Call, find(&chain.calls, call_strike)?, quantity)?,
+        ])
+    }
+
+    /// Derive premium, maximum gain and loss and breakevens of the legs. The payoff is linear
+    /// between strikes, so it is evaluated at zero and at each strike, and its slope beyond
+    /// the highest strike is given by the calls.
+    fn new(kind: StrategyKind, expiration_date: YTimestamp, legs: Vec<Leg>) -> Strategy {
+        let net_premium = legs.iter().fold(ZERO, |sum, leg| {
+            sum + leg.premium * Decimal::from(leg.quantity)
+        });
+        let profit = |price: Decimal| {
+            legs.iter().fold(ZERO, |sum, leg| sum + leg.payoff(price)) - net_premium
+        };
+        let mut prices: Vec<Decimal> = std::iter::once(ZERO)
+            .chain(legs.iter().map(|leg| leg.strike))
+            .collect();
+        prices.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+        prices.dedup();
+        let profits: Vec<Decimal> = prices.iter().map(|&price| profit(price)).collect();
+        let slope: i32 = legs
+            .iter()
+            .filter(|leg| leg.kind == OptionKind::Call)
+            .map(|leg| leg.quantity)
+            .sum();
+        let fold_max = |a: Decimal, b: Decimal| if b > a { b } else { a };
+        let fold_min = |a: Decimal, b: Decimal| if b < a { b } else { a };
+        let max_gain = (slope <= 0).then(|| profits.iter().copied().fold(profits[0], fold_max));
+        let max_loss = (slope >= 0).then(|| -profits.iter().copied().fold(profits[0], fold_min));
+
+        let mut breakevens = Vec::new();
+        for i in 0..prices.len() {
+            if profits[i] == ZERO {
+                breakevens.push(prices[i]);
+            } else if i + 1 < prices.len()
+                && profits[i + 1] != ZERO
+                && (profits[i] < ZERO) != (profits[i + 1] < ZERO)
+            {
+                let width = prices[i + 1] - prices[i];
+                breakevens.push(prices[i] - width * profits[i] / (profits[i + 1] - profits[i]));
+            }
+        }
+        let (last_price, last_profit) = (prices[prices.len() - 1], profits[profits.len() - 1]);
+        let slope = Decimal::from(slope);
+        if last_profit != ZERO && slope != ZERO && (last_profit < ZERO) == (slope > ZERO) {
+            breakevens.push(last_price - last_profit / slope);
+        }
+
+        Strategy {
+            kind,
+            expiration_date,
+            legs,
+            net_premium,
+            max_gain,
+            max_loss,
+            breakevens,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn contract(strike: u32, bid: u32, ask: u32) -> YOptionContract {
+        YOptionContract {
+            contract_symbol: Some(format!("XYZ{strike}")),
+            strike: Some(Decimal::from(strike)),
+            currency: Some("USD".to_string()),
+            last_price: None,
+            change: None,
+            percent_change: None,
+            volume: None,
+            open_interest: None,
+            bid: Some(Decimal::from(bid)),
+            ask: Some(Decimal::from(ask)),
+            contract_size: Some("REGULAR".to_string()),
+            expiration: None,
+            last_trade_date: None,
+            implied_volatility: None,
+            in_the_money: None,
+            #[cfg(all(feature = "capture_unknown", not(feature = "deny_unknown")))]
+            extra: Default::default(),
+        }
+    }
+
+    fn chain() -> YOptionDetails {
+        YOptionDetails {
+            expiration_date: YTimestamp::from_seconds(1718928000),
+            has_mini_options: false,
+            calls: vec![
+                contract(90, 13, 15),
+                contract(100, 5, 7),
+                contract(110, 1, 3),
+            ],
+            puts: vec![
+                contract(90, 1, 3),
+                contract(100, 5, 7),
+                contract(110, 11, 13),
+            ],
+        }
+    }
+
+    fn d(value: i32) -> Decimal {
+        Decimal::from(value)
+    }
+
+    #[test]
+    fn test_vertical_spread() {
+        // bull call spread: pay 14 - 2 = 12, worth at most 20
+        let spread = Strategy::vertical(&chain(), OptionKind::Call, d(90), d(110)).unwrap();
+        assert_eq!(spread.net_premium, d(12));
+        assert_eq!(spread.max_gain, Some(d(8)));
+        assert_eq!(spread.max_loss, Some(d(12)));
+        assert_eq!(spread.breakevens, vec![d(102)]);
+
+        // bull put spread: receive 12 - 2 = 10
+        let spread = Strategy::vertical(&chain(), OptionKind::Put, d(90), d(110)).unwrap();
+        assert_eq!(spread.net_premium, d(-10));
+        assert_eq!(spread.max_gain, Some(d(10)));
+        assert_eq!(spread.max_loss, Some(d(10)));
+        assert_eq!(spread.breakevens, vec![d(100)]);
+
+        assert!(Strategy::vertical(&chain(), OptionKind::Call, d(90), d(95)).is_none());
+    }
+
+    #[test]
+    fn test_straddle_and_strangle() {
+        let straddle = Strategy::straddle(&chain(), d(100), true).unwrap();
+        assert_eq!(straddle.net_premium, d(12));
+        assert_eq!(straddle.max_gain, None);
+        assert_eq!(straddle.max_loss, Some(d(12)));
+        assert_eq!(straddle.breakevens, vec![d(88), d(112)]);
+
+        let strangle = Strategy::strangle(&chain(), d(90), d(110), false).unwrap();
+        assert_eq!(strangle.net_premium, d(-4));
+        assert_eq!(strangle.max_gain, Some(d(4)));
+        assert_eq!(strangle.max_loss, None);
+        assert_eq!(strangle.breakevens, vec![d(86), d(114)]);
+
+        assert!(Strategy::strangle(&chain(), d(110), d(90), true).is_none());
+    }
+}
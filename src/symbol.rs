@@ -0,0 +1,168 @@
+//! Validation and normalization of ticker symbols.
+use std::{fmt, ops::Deref, str::FromStr};
+
+use super::YahooError;
+
+/// Exchange suffixes used by yahoo! finance, e.g. "DE" in "SAP.DE"
+const EXCHANGE_SUFFIXES: &[&str] = &[
+    "AS", "AT", "AX", "BA", "BC", "BD", "BE", "BK", "BO", "BR", "CA", "CN", "CO", "CR", "DE", "DU",
+    "F", "HA", "HE", "HK", "HM", "IC", "IL", "IR", "IS", "JK", "JO", "KL", "KQ", "KS", "L", "LS",
+    "MC", "MI", "MU", "MX", "NE", "NS", "NZ", "OL", "PA", "PR", "QA", "RG", "SA", "SG", "SI", "SN",
+    "SR", "SS", "ST", "SW", "SZ", "T", "TA", "TI", "TL", "TO", "TW", "TWO", "V", "VI", "VS", "XC",
+];
+
+/// A ticker symbol following the conventions of yahoo! finance
+///
+/// Symbols dereference to `&str`, so they can be passed to all methods of the connector.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Symbol(String);
+
+impl Symbol {
+    /// Validate a symbol and normalize it to the conventions of yahoo! finance, e.g.
+    /// "brk.b" becomes "BRK-B" while "sap.de" becomes "SAP.DE"
+    pub fn parse(symbol: &str) -> Result<Symbol, YahooError> {
+        let invalid = || YahooError::InvalidSymbol(symbol.to_string());
+        let mut normalized = symbol.trim().to_uppercase();
+        if normalized.is_empty()
+            || !normalized
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '=' | '^' | '&'))
+            || normalized[1..].contains('^')
+        {
+            return Err(invalid());
+        }
+
+        // currency pairs and futures, e.g. "EURUSD=X" or "CL=F"
+        if let Some((base, kind)) = normalized.split_once('=') {
+            if base.is_empty()
+                || !matches!(kind, "X" | "F")
+                || base.contains(['.', '='])
+                || base.starts_with('^')
+            {
+                return Err(invalid());
+            }
+            return Ok(Symbol(normalized));
+        }
+
+        // class shares, e.g. "BRK.B" is quoted as "BRK-B"
+        if let Some((root, suffix)) = normalized.split_once('.') {
+            if suffix.len() == 1
+                && !EXCHANGE_SUFFIXES.contains(&suffix)
+                && suffix.chars().all(|c| c.is_ascii_alphabetic())
+            {
+                normalized = format!("{root}-{suffix}");
+            }
+        }
+
+        let (root, suffix) = match normalized.split_once('.') {
+            Some((root, suffix)) => (root, Some(suffix)),
+            None => (normalized.as_str(), None),
+        };
+        let root_valid = !root.is_empty()
+            && !root.starts_with('-')
+            && !root.ends_with('-')
+            && !root.contains("--");
+        let suffix_valid = suffix.map_or(true, |s| EXCHANGE_SUFFIXES.contains(&s));
+        if !root_valid || !suffix_valid {
+            return Err(invalid());
+        }
+        Ok(Symbol(normalized))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Return the exchange suffix of the symbol, if any (e.g. "DE" for "SAP.DE")
+    pub fn exchange_suffix(&self) -> Option<&str> {
+        self.0.split_once('.').map(|(_, suffix)| suffix)
+    }
+}
+
+impl Deref for Symbol {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<str> for Symbol {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl FromStr for Symbol {
+    type Err = YahooError;
+
+    fn from_str(s: &str) -> Result<Symbol, YahooError> {
+        Symbol::parse(s)
+    }
+}
+
+impl fmt::Display for Symbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(symbol: &str) -> String {
+        Symbol::parse(symbol).unwrap().to_string()
+    }
+
+    #[test]
+    fn test_normalization() {
+        assert_eq!(parse("aapl"), "AAPL");
+        assert_eq!(parse(" brk.b "), "BRK-B");
+        assert_eq!(parse("BRK-B"), "BRK-B");
+        assert_eq!(parse("sap.de"), "SAP.DE");
+        assert_eq!(parse("vow3.de"), "VOW3.DE");
+        assert_eq!(parse("DE000SL0CZS9.SG"), "DE000SL0CZS9.SG");
+        assert_eq!(parse("vod.l"), "VOD.L");
+        assert_eq!(parse("eurusd=x"), "EURUSD=X");
+        assert_eq!(parse("CL=F"), "CL=F");
+        assert_eq!(parse("btc-usd"), "BTC-USD");
+        assert_eq!(parse("^gspc"), "^GSPC");
+        assert_eq!(
+            Symbol::parse("sap.de").unwrap().exchange_suffix(),
+            Some("DE")
+        );
+    }
+
+    #[test]
+    fn test_invalid_symbols() {
+        for symbol in [
+            "",
+            "  ",
+            "AAPL US",
+            "SAP.XYZ",
+            "EUR=Y",
+            "=X",
+            "A^B",
+            "-USD",
+            "BTC-",
+            "SAP.DE.DE",
+            "BRK..B",
+            "A.B=X",
+        ] {
+            assert!(
+                matches!(Symbol::parse(symbol), Err(YahooError::InvalidSymbol(_))),
+                "{symbol}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_deref() {
+        fn takes_str(s: &str) -> usize {
+            s.len()
+        }
+        let symbol: Symbol = "brk.b".parse().unwrap();
+        assert_eq!(takes_str(&symbol), 5);
+    }
+}
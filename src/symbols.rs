@@ -0,0 +1,104 @@
+//! Symbols of common futures, indices and treasury yields.
+//!
+//! yahoo! finance marks futures with the suffix `=F`, currency pairs with `=X` and indices
+//! (including treasury yields) with the prefix `^`.
+
+/// S&P 500 index
+pub const SP500: &str = "^GSPC";
+/// Dow Jones Industrial Average
+pub const DOW_JONES: &str = "^DJI";
+/// NASDAQ Composite index
+pub const NASDAQ_COMPOSITE: &str = "^IXIC";
+/// Russell 2000 index
+pub const RUSSELL_2000: &str = "^RUT";
+/// CBOE volatility index
+pub const VIX: &str = "^VIX";
+/// German stock index DAX
+pub const DAX: &str = "^GDAXI";
+/// Euro Stoxx 50 index
+pub const EURO_STOXX_50: &str = "^STOXX50E";
+/// FTSE 100 index
+pub const FTSE_100: &str = "^FTSE";
+/// Nikkei 225 index
+pub const NIKKEI_225: &str = "^N225";
+
+/// E-mini S&P 500 future
+pub const SP500_FUTURE: &str = "ES=F";
+/// E-mini NASDAQ 100 future
+pub const NASDAQ_100_FUTURE: &str = "NQ=F";
+/// E-mini Dow future
+pub const DOW_FUTURE: &str = "YM=F";
+
+/// Yield of US treasury bills with 13 weeks maturity, in percent
+pub const TREASURY_YIELD_13W: &str = "^IRX";
+/// Yield of US treasury notes with 5 years maturity, in percent
+pub const TREASURY_YIELD_5Y: &str = "^FVX";
+/// Yield of US treasury notes with 10 years maturity, in percent
+pub const TREASURY_YIELD_10Y: &str = "^TNX";
+/// Yield of US treasury bonds with 30 years maturity, in percent
+pub const TREASURY_YIELD_30Y: &str = "^TYX";
+
+/// Commodities with futures quoted by yahoo! finance
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Commodity {
+    /// WTI crude oil
+    CrudeOil,
+    BrentCrudeOil,
+    NaturalGas,
+    Gold,
+    Silver,
+    Copper,
+    Platinum,
+    Corn,
+    Wheat,
+    Soybeans,
+}
+
+impl Commodity {
+    /// Symbol of the front month future of the commodity
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Commodity::CrudeOil => "CL=F",
+            Commodity::BrentCrudeOil => "BZ=F",
+            Commodity::NaturalGas => "NG=F",
+            Commodity::Gold => "GC=F",
+            Commodity::Silver => "SI=F",
+            Commodity::Copper => "HG=F",
+            Commodity::Platinum => "PL=F",
+            Commodity::Corn => "ZC=F",
+            Commodity::Wheat => "ZW=F",
+            Commodity::Soybeans => "ZS=F",
+        }
+    }
+}
+
+/// Symbol of the front month future with the given root symbol, e.g. `CL=F` for `CL`
+pub fn future(root: &str) -> String {
+    format!("{}=F", root.to_uppercase())
+}
+
+/// Symbol of the exchange rate of two currencies given by their ISO codes, e.g. `EURUSD=X`
+/// for the price of one euro in US dollars
+pub fn currency_pair(base: &str, quote: &str) -> String {
+    format!("{}{}=X", base.to_uppercase(), quote.to_uppercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Symbol;
+
+    #[test]
+    fn test_symbols_are_valid() {
+        assert_eq!(future("cl"), Commodity::CrudeOil.symbol());
+        assert_eq!(currency_pair("eur", "usd"), "EURUSD=X");
+        for symbol in [
+            SP500,
+            SP500_FUTURE,
+            TREASURY_YIELD_10Y,
+            Commodity::Gold.symbol(),
+        ] {
+            assert_eq!(Symbol::parse(symbol).unwrap().as_str(), symbol);
+        }
+    }
+}
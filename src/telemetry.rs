@@ -0,0 +1,141 @@
+//! Metrics emitted through the facade of the `metrics` crate, so services embedding this
+//! crate get dashboards by installing a recorder (e.g. a Prometheus exporter).
+//!
+//! | Name | Kind | Labels |
+//! |------|------|--------|
+//! | `yahoo_finance_requests_total` | counter | `endpoint`, `status` |
+//! | `yahoo_finance_request_duration_seconds` | histogram | `endpoint` |
+//! | `yahoo_finance_retries_total` | counter | `operation` |
+//! | `yahoo_finance_rate_limit_hits_total` | counter | `source` |
+//! | `yahoo_finance_auth_events_total` | counter | `event` |
+//!
+//! The status is the HTTP status code, or `error` if the request failed without response.
+//! Rate limit hits are counted for requests delayed by the local rate limit (source `local`)
+//! and for responses with status 429 (source `yahoo`). Authentication events are named as by
+//! `AuthEvent::name`, e.g. `crumb_refreshed`.
+use std::time::Duration;
+
+use super::AuthEvent;
+
+/// Counter of requests by endpoint and status
+pub const REQUESTS_TOTAL: &str = "yahoo_finance_requests_total";
+/// Histogram of the time from sending a request until its body has been received
+pub const REQUEST_DURATION_SECONDS: &str = "yahoo_finance_request_duration_seconds";
+/// Counter of retried operations, e.g. fetching credentials
+pub const RETRIES_TOTAL: &str = "yahoo_finance_retries_total";
+/// Counter of requests delayed or rejected by a rate limit
+pub const RATE_LIMIT_HITS_TOTAL: &str = "yahoo_finance_rate_limit_hits_total";
+/// Counter of fetched cookies and crumbs, rejected crumbs and unauthorized responses
+pub const AUTH_EVENTS_TOTAL: &str = "yahoo_finance_auth_events_total";
+
+pub(crate) fn record_request(endpoint: &'static str, status: Option<u16>, duration: Duration) {
+    let status = status.map_or_else(|| "error".to_string(), |status| status.to_string());
+    if status == "429" {
+        record_rate_limit_hit("yahoo");
+    }
+    metrics::counter!(REQUESTS_TOTAL, "endpoint" => endpoint, "status" => status).increment(1);
+    metrics::histogram!(REQUEST_DURATION_SECONDS, "endpoint" => endpoint)
+        .record(duration.as_secs_f64());
+}
+
+pub(crate) fn record_retry(operation: &'static str) {
+    metrics::counter!(RETRIES_TOTAL, "operation" => operation).increment(1);
+}
+
+pub(crate) fn record_rate_limit_hit(source: &'static str) {
+    metrics::counter!(RATE_LIMIT_HITS_TOTAL, "source" => source).increment(1);
+}
+
+pub(crate) fn record_auth(event: AuthEvent) {
+    metrics::counter!(AUTH_EVENTS_TOTAL, "event" => event.name()).increment(1);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::{Arc, Mutex};
+
+    use metrics::{
+        Counter, Gauge, Histogram, Key, KeyName, Metadata, Recorder, SharedString, Unit,
+    };
+
+    use super::*;
+
+    /// Recorder keeping the counters by name and labels
+    #[derive(Default)]
+    struct CountingRecorder {
+        counters: Mutex<HashMap<String, Arc<AtomicU64>>>,
+    }
+
+    impl CountingRecorder {
+        fn get(&self, key: &str) -> u64 {
+            let counters = self.counters.lock().unwrap();
+            counters
+                .get(key)
+                .map_or(0, |counter| counter.load(Ordering::Relaxed))
+        }
+    }
+
+    impl Recorder for CountingRecorder {
+        fn describe_counter(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+        fn describe_gauge(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+        fn describe_histogram(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+        fn register_counter(&self, key: &Key, _: &Metadata<'_>) -> Counter {
+            let labels: Vec<String> = key
+                .labels()
+                .map(|label| format!("{}={}", label.key(), label.value()))
+                .collect();
+            let name = format!("{}{{{}}}", key.name(), labels.join(","));
+            let mut counters = self.counters.lock().unwrap();
+            Counter::from_arc(counters.entry(name).or_default().clone())
+        }
+
+        fn register_gauge(&self, _: &Key, _: &Metadata<'_>) -> Gauge {
+            Gauge::noop()
+        }
+
+        fn register_histogram(&self, _: &Key, _: &Metadata<'_>) -> Histogram {
+            Histogram::noop()
+        }
+    }
+
+    #[test]
+    fn test_record() {
+        let recorder = CountingRecorder::default();
+        metrics::with_local_recorder(&recorder, || {
+            record_request("chart", Some(200), Duration::from_millis(120));
+            record_request("chart", Some(200), Duration::from_millis(80));
+            record_request("quote_summary", Some(429), Duration::from_millis(10));
+            record_request("quote_summary", None, Duration::from_secs(30));
+            record_retry("credentials");
+            record_rate_limit_hit("local");
+            record_auth(AuthEvent::CrumbRefreshed);
+        });
+        assert_eq!(
+            recorder.get("yahoo_finance_requests_total{endpoint=chart,status=200}"),
+            2
+        );
+        assert_eq!(
+            recorder.get("yahoo_finance_requests_total{endpoint=quote_summary,status=error}"),
+            1
+        );
+        assert_eq!(
+            recorder.get("yahoo_finance_retries_total{operation=credentials}"),
+            1
+        );
+        assert_eq!(
+            recorder.get("yahoo_finance_rate_limit_hits_total{source=yahoo}"),
+            1
+        );
+        assert_eq!(
+            recorder.get("yahoo_finance_rate_limit_hits_total{source=local}"),
+            1
+        );
+        assert_eq!(
+            recorder.get("yahoo_finance_auth_events_total{event=crumb_refreshed}"),
+            1
+        );
+    }
+}
@@ -0,0 +1,229 @@
+//! Corpus of yahoo! finance responses covering different asset classes and edge cases.
+//!
+//! The fixtures are used by the tests of this crate, but are also exposed (with the
+//! `testing` feature enabled) to allow users to check their own parsing code against
+//! the same payloads.
+use super::{YQuoteSummary, YResponse, YahooError};
+
+/// Asset class (or special case) covered by a fixture
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FixtureKind {
+    Equity,
+    MutualFund,
+    Currency,
+    Crypto,
+    Index,
+    /// Response for a symbol not known (anymore) to yahoo! finance
+    Delisted,
+    /// Response to a request with missing or invalid cookie or crumb
+    Unauthorized,
+}
+
+/// A response of an endpoint of yahoo! finance
+#[derive(Debug, Clone, Copy)]
+pub struct Fixture {
+    /// Unique name of the fixture
+    pub name: &'static str,
+    pub kind: FixtureKind,
+    /// Symbol requested
+    pub symbol: &'static str,
+    /// Response body as returned by yahoo! finance
+    pub json: &'static str,
+}
+
+impl Fixture {
+    /// Parse the response body into a JSON value
+    pub fn json_value(&self) -> serde_json::Value {
+        serde_json::from_str(self.json).expect("fixtures contain valid JSON")
+    }
+
+    /// Deserialize the fixture the same way as a response of the chart endpoint
+    pub fn response(&self) -> Result<YResponse, YahooError> {
+        YResponse::from_json(self.json_value())
+    }
+
+    /// Deserialize the fixture the same way as a response of the quoteSummary endpoint
+    pub fn quote_summary(&self) -> Result<YQuoteSummary, YahooError> {
+        YQuoteSummary::from_json(self.json_value(), self.symbol)
+    }
+}
+
+/// All responses of the chart endpoint in the corpus
+pub const CHART_FIXTURES: &[Fixture] = &[
+    Fixture {
+        name: "equity_aapl_1d",
+        kind: FixtureKind::Equity,
+        symbol: "AAPL",
+        json: include_str!("testing/fixtures/equity_aapl_1d.json"),
+    },
+    Fixture {
+        name: "equity_msft_1m_prepost",
+        kind: FixtureKind::Equity,
+        symbol: "MSFT",
+        json: include_str!("testing/fixtures/equity_msft_1m_prepost.json"),
+    },
+    Fixture {
+        name: "mutual_fund_amagx_1d",
+        kind: FixtureKind::MutualFund,
+        symbol: "AMAGX",
+        json: include_str!("testing/fixtures/mutual_fund_amagx_1d.json"),
+    },
+    Fixture {
+        name: "fx_eurusd_1d",
+        kind: FixtureKind::Currency,
+        symbol: "EURUSD=X",
+        json: include_str!("testing/fixtures/fx_eurusd_1d.json"),
+    },
+    Fixture {
+        name: "crypto_btcusd_1d",
+        kind: FixtureKind::Crypto,
+        symbol: "BTC-USD",
+        json: include_str!("testing/fixtures/crypto_btcusd_1d.json"),
+    },
+    Fixture {
+        name: "index_gspc_5m",
+        kind: FixtureKind::Index,
+        symbol: "^GSPC",
+        json: include_str!("testing/fixtures/index_gspc_5m.json"),
+    },
+    Fixture {
+        name: "delisted",
+        kind: FixtureKind::Delisted,
+        symbol: "XXXXXX",
+        json: include_str!("testing/fixtures/delisted.json"),
+    },
+];
+
+/// Look up a chart fixture by its name
+pub fn chart_fixture(name: &str) -> Option<&'static Fixture> {
+    CHART_FIXTURES.iter().find(|f| f.name == name)
+}
+
+/// All responses of the quoteSummary endpoint in the corpus
+pub const QUOTE_SUMMARY_FIXTURES: &[Fixture] = &[
+    Fixture {
+        name: "quote_summary_aapl",
+        kind: FixtureKind::Equity,
+        symbol: "AAPL",
+        json: include_str!("testing/fixtures/quote_summary_aapl.json"),
+    },
+    Fixture {
+        name: "quote_summary_aapl_formatted",
+        kind: FixtureKind::Equity,
+        symbol: "AAPL",
+        json: include_str!("testing/fixtures/quote_summary_aapl_formatted.json"),
+    },
+    Fixture {
+        name: "quote_summary_invalid_crumb",
+        kind: FixtureKind::Unauthorized,
+        symbol: "AAPL",
+        json: include_str!("testing/fixtures/quote_summary_invalid_crumb.json"),
+    },
+    Fixture {
+        name: "quote_summary_unauthorized",
+        kind: FixtureKind::Unauthorized,
+        symbol: "AAPL",
+        json: include_str!("testing/fixtures/quote_summary_unauthorized.json"),
+    },
+];
+
+/// Look up a quoteSummary fixture by its name
+pub fn quote_summary_fixture(name: &str) -> Option<&'static Fixture> {
+    QUOTE_SUMMARY_FIXTURES.iter().find(|f| f.name == name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Decimal;
+
+    #[test]
+    fn test_deserialize_chart_fixtures() {
+        for fixture in CHART_FIXTURES
+            .iter()
+            .filter(|f| f.kind != FixtureKind::Delisted)
+        {
+            let response = fixture
+                .response()
+                .unwrap_or_else(|e| panic!("fixture {} failed: {e:?}", fixture.name));
+            let meta = response.metadata().unwrap();
+            assert_eq!(meta.symbol, fixture.symbol, "fixture {}", fixture.name);
+            let quotes = response.quotes().unwrap();
+            assert!(!quotes.is_empty(), "fixture {}", fixture.name);
+            assert!(
+                response.validate().unwrap().is_empty(),
+                "fixture {}",
+                fixture.name
+            );
+        }
+    }
+
+    #[test]
+    fn test_deserialize_quote_summary_fixtures() {
+        for fixture in QUOTE_SUMMARY_FIXTURES
+            .iter()
+            .filter(|f| f.kind != FixtureKind::Unauthorized)
+        {
+            let summary = fixture
+                .quote_summary()
+                .unwrap_or_else(|e| panic!("fixture {} failed: {e:?}", fixture.name));
+            assert!(summary.quote_type().is_some(), "fixture {}", fixture.name);
+        }
+    }
+
+    /// With `deny_unknown`, fields added to the responses by yahoo! finance fail to deserialize
+    #[cfg(feature = "deny_unknown")]
+    #[test]
+    fn test_deny_unknown_fields() {
+        let mut json = chart_fixture("equity_aapl_1d").unwrap().json_value();
+        json["chart"]["result"][0]["meta"]["unmodelledField"] = true.into();
+        assert!(YResponse::from_json(json).is_err());
+
+        let mut json = quote_summary_fixture("quote_summary_aapl")
+            .unwrap()
+            .json_value();
+        json["quoteSummary"]["result"][0]["financialData"]["unmodelledField"] = 1.into();
+        assert!(YQuoteSummary::from_json(json, "AAPL").is_err());
+    }
+
+    #[test]
+    fn test_delisted_fixture() {
+        let fixture = chart_fixture("delisted").unwrap();
+        let response = fixture.response().unwrap();
+        assert!(response.results().is_empty());
+        let error = response.chart.error.unwrap().to_error(fixture.symbol);
+        assert!(matches!(error, YahooError::SymbolNotFound(symbol) if symbol == "XXXXXX"));
+    }
+
+    #[test]
+    fn test_fixture_details() {
+        let response = chart_fixture("equity_aapl_1d").unwrap().response().unwrap();
+        assert_eq!(response.dividends().unwrap().len(), 1);
+
+        let response = chart_fixture("mutual_fund_amagx_1d")
+            .unwrap()
+            .response()
+            .unwrap();
+        let capital_gains = response.capital_gains().unwrap();
+        assert_eq!(capital_gains.len(), 1);
+        assert_eq!(
+            capital_gains[0].amount,
+            "0.9128".parse::<Decimal>().unwrap()
+        );
+
+        // missing bar is skipped
+        let response = chart_fixture("equity_msft_1m_prepost")
+            .unwrap()
+            .response()
+            .unwrap();
+        assert_eq!(response.quotes().unwrap().len(), 4);
+        let periods = response.metadata().unwrap().trading_periods;
+        assert!(periods.pre.is_some() && periods.post.is_some());
+
+        let response = chart_fixture("crypto_btcusd_1d")
+            .unwrap()
+            .response()
+            .unwrap();
+        assert!(response.last_quote().unwrap().volume > u32::MAX as u64);
+    }
+}
@@ -0,0 +1,295 @@
+//! Fundamentals as time series, as returned by the fundamentals-timeseries endpoint.
+//!
+//! Each requested type (e.g. `annualBasicAverageShares`) is returned as a separate series of
+//! reported values, one per fiscal period.
+use serde::Deserialize;
+use time::{macros::format_description, Date, OffsetDateTime, Time};
+
+use super::quotes::{decimal::from_volume, deserialize_null_as_empty};
+use super::{decimal_to_f64, Decimal, Quote, YChartError, YahooError};
+
+/// Types requested by `get_shares_history`
+pub(crate) const SHARES_TIMESERIES_TYPES: &[&str] = &[
+    "quarterlyOrdinarySharesNumber",
+    "annualOrdinarySharesNumber",
+    "quarterlyBasicAverageShares",
+    "annualBasicAverageShares",
+    "quarterlyDilutedAverageShares",
+    "annualDilutedAverageShares",
+];
+
+#[derive(Deserialize, Debug)]
+struct YTimeseriesResponse {
+    timeseries: YTimeseries,
+}
+
+#[derive(Deserialize, Debug)]
+struct YTimeseries {
+    #[serde(default, deserialize_with = "deserialize_null_as_empty")]
+    result: Vec<serde_json::Value>,
+    error: Option<YChartError>,
+}
+
+#[derive(Deserialize, Debug)]
+struct YTimeseriesMeta {
+    #[serde(rename = "type")]
+    types: Vec<String>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct YTimeseriesValue {
+    as_of_date: String,
+    period_type: String,
+    currency_code: Option<String>,
+    reported_value: YReportedValue,
+}
+
+#[derive(Deserialize, Debug)]
+struct YReportedValue {
+    raw: Decimal,
+}
+
+/// Value of a fundamental reported for a fiscal period
+#[derive(Debug, Clone, PartialEq)]
+pub struct FundamentalValue {
+    /// End of the fiscal period
+    pub as_of_date: Date,
+    /// Length of the fiscal period, e.g. `3M` or `12M`
+    pub period_type: String,
+    /// Currency of monetary values, `None` e.g. for numbers of shares
+    pub currency_code: Option<String>,
+    pub value: Decimal,
+}
+
+/// Reported values of a fundamental, sorted by date
+#[derive(Debug, Clone, PartialEq)]
+pub struct FundamentalSeries {
+    /// Type of the fundamental as requested, e.g. `annualBasicAverageShares`
+    pub name: String,
+    pub values: Vec<FundamentalValue>,
+}
+
+/// Deserialize a response of the fundamentals-timeseries endpoint requested for the given
+/// symbol. Requested types without any reported values are omitted.
+pub(crate) fn from_timeseries_json(
+    json: serde_json::Value,
+    symbol: &str,
+) -> Result<Vec<FundamentalSeries>, YahooError> {
+    let response: YTimeseriesResponse = serde_json::from_value(json)?;
+    if let Some(error) = response.timeseries.error {
+        return Err(error.to_error(symbol));
+    }
+    let mut series = Vec::new();
+    for mut result in response.timeseries.result {
+        let meta: YTimeseriesMeta = serde_json::from_value(result["meta"].take())?;
+        let Some(name) = meta.types.into_iter().next() else {
+            continue;
+        };
+        let values: Vec<Option<YTimeseriesValue>> = match result.get_mut(&name) {
+            Some(values) => serde_json::from_value(values.take())?,
+            None => continue,
+        };
+        let mut values = values
+            .into_iter()
+            .flatten()
+            .map(|value| {
+                Ok(FundamentalValue {
+                    as_of_date: Date::parse(
+                        &value.as_of_date,
+                        format_description!("[year]-[month]-[day]"),
+                    )
+                    .map_err(|_| YahooError::DataInconsistency)?,
+                    period_type: value.period_type,
+                    currency_code: value.currency_code,
+                    value: value.reported_value.raw,
+                })
+            })
+            .collect::<Result<Vec<_>, YahooError>>()?;
+        values.sort_by_key(|value| value.as_of_date);
+        series.push(FundamentalSeries { name, values });
+    }
+    Ok(series)
+}
+
+/// How the number of shares of a period is measured
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SharesMeasure {
+    /// Ordinary shares outstanding at the end of the period
+    PeriodEnd,
+    /// Weighted average of the shares outstanding during the period
+    BasicAverage,
+    /// Weighted average including the dilutive effect of options and convertibles
+    DilutedAverage,
+}
+
+impl SharesMeasure {
+    fn from_type(name: &str) -> Option<SharesMeasure> {
+        if name.ends_with("OrdinarySharesNumber") {
+            Some(SharesMeasure::PeriodEnd)
+        } else if name.ends_with("BasicAverageShares") {
+            Some(SharesMeasure::BasicAverage)
+        } else if name.ends_with("DilutedAverageShares") {
+            Some(SharesMeasure::DilutedAverage)
+        } else {
+            None
+        }
+    }
+}
+
+/// Number of shares reported for a fiscal period
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SharesOutstanding {
+    /// End of the fiscal period
+    pub date: Date,
+    /// Length of the fiscal period, e.g. `3M` or `12M`
+    pub period_type: String,
+    pub measure: SharesMeasure,
+    pub shares: u64,
+}
+
+/// Collect the numbers of shares of the given series, sorted by date. Periods reported
+/// quarterly as well as annually are returned once per measure, as reported for the quarter.
+pub(crate) fn shares_history(series: Vec<FundamentalSeries>) -> Vec<SharesOutstanding> {
+    let mut shares: Vec<SharesOutstanding> = Vec::new();
+    // quarterly series are requested first
+    for series in series {
+        let Some(measure) = SharesMeasure::from_type(&series.name) else {
+            continue;
+        };
+        for value in series.values {
+            if shares
+                .iter()
+                .any(|s| s.date == value.as_of_date && s.measure == measure)
+            {
+                continue;
+            }
+            shares.push(SharesOutstanding {
+                date: value.as_of_date,
+                period_type: value.period_type,
+                measure,
+                shares: decimal_to_f64(value.value).round() as u64,
+            });
+        }
+    }
+    shares.sort_by_key(|s| s.date);
+    shares
+}
+
+/// Market capitalization at the time of a quote
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MarketCap {
+    pub timestamp: u64,
+    pub market_cap: Decimal,
+}
+
+/// Derive the market capitalization from the close prices of the quotes and the latest number
+/// of shares reported for a period ending at or before each quote. Shares outstanding at the
+/// end of the periods are used if available, otherwise the basic average shares. Quotes before
+/// the first period are skipped.
+///
+/// Note that numbers of shares are published some weeks after the end of the period, so the
+/// result contains information which was not available at the time of the quote.
+pub fn market_cap_history(shares: &[SharesOutstanding], quotes: &[Quote]) -> Vec<MarketCap> {
+    let measure = if shares.iter().any(|s| s.measure == SharesMeasure::PeriodEnd) {
+        SharesMeasure::PeriodEnd
+    } else {
+        SharesMeasure::BasicAverage
+    };
+    let shares: Vec<(i64, u64)> = shares
+        .iter()
+        .filter(|s| s.measure == measure)
+        .map(|s| {
+            let end = OffsetDateTime::new_utc(s.date, Time::MIDNIGHT);
+            (end.unix_timestamp(), s.shares)
+        })
+        .collect();
+    quotes
+        .iter()
+        .filter_map(|quote| {
+            let (_, shares) = shares
+                .iter()
+                .take_while(|(end, _)| *end <= quote.timestamp as i64)
+                .last()?;
+            Some(MarketCap {
+                timestamp: quote.timestamp,
+                market_cap: quote.close * from_volume(*shares),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::date;
+
+    use super::*;
+
+    const SHARES_JSON: &str = r#"{"timeseries": {"result": [
+        {"meta": {"symbol": ["AAPL"], "type": ["quarterlyOrdinarySharesNumber"]},
+         "timestamp": [1688083200, 1696032000],
+         "quarterlyOrdinarySharesNumber": [
+            {"dataId": 20143, "asOfDate": "2023-09-30", "periodType": "3M",
+             "currencyCode": "USD", "reportedValue": {"raw": 15550061000, "fmt": "15.55B"}},
+            {"dataId": 20143, "asOfDate": "2023-06-30", "periodType": "3M",
+             "currencyCode": "USD", "reportedValue": {"raw": 15647868000, "fmt": "15.65B"}}
+         ]},
+        {"meta": {"symbol": ["AAPL"], "type": ["annualOrdinarySharesNumber"]},
+         "timestamp": [1696032000],
+         "annualOrdinarySharesNumber": [
+            null,
+            {"dataId": 20143, "asOfDate": "2023-09-30", "periodType": "12M",
+             "currencyCode": "USD", "reportedValue": {"raw": 15550061000, "fmt": "15.55B"}}
+         ]},
+        {"meta": {"symbol": ["AAPL"], "type": ["quarterlyBasicAverageShares"]}}
+    ], "error": null}}"#;
+
+    #[test]
+    fn test_shares_history() {
+        let json = serde_json::from_str(SHARES_JSON).unwrap();
+        let series = from_timeseries_json(json, "AAPL").unwrap();
+        assert_eq!(series.len(), 2);
+        assert_eq!(series[0].name, "quarterlyOrdinarySharesNumber");
+        assert_eq!(series[0].values[0].as_of_date, date!(2023 - 06 - 30));
+        assert_eq!(series[1].values.len(), 1);
+        assert_eq!(series[1].values[0].period_type, "12M");
+
+        let shares = shares_history(series);
+        assert_eq!(shares.len(), 2);
+        assert_eq!(shares[1].date, date!(2023 - 09 - 30));
+        assert_eq!(shares[1].period_type, "3M");
+        assert_eq!(shares[1].measure, SharesMeasure::PeriodEnd);
+        assert_eq!(shares[1].shares, 15_550_061_000);
+
+        let quote = |timestamp: u64, close: u32| Quote {
+            timestamp,
+            open: Decimal::from(close),
+            high: Decimal::from(close),
+            low: Decimal::from(close),
+            volume: 0,
+            close: Decimal::from(close),
+            adjclose: Decimal::from(close),
+        };
+        // 2023-06-01, 2023-07-03 and 2023-10-02
+        let quotes = [
+            quote(1685577600, 180),
+            quote(1688342400, 190),
+            quote(1696204800, 170),
+        ];
+        let caps = market_cap_history(&shares, &quotes);
+        assert_eq!(caps.len(), 2);
+        assert_eq!(caps[0].timestamp, 1688342400);
+        assert_eq!(caps[0].market_cap, from_volume(190 * 15_647_868_000));
+        assert_eq!(caps[1].market_cap, from_volume(170 * 15_550_061_000));
+    }
+
+    #[test]
+    fn test_timeseries_error() {
+        let json = serde_json::json!({"timeseries": {"result": null, "error": {
+            "code": "Bad Request", "description": "Invalid type"}}});
+        assert!(matches!(
+            from_timeseries_json(json, "AAPL"),
+            Err(YahooError::ApiError(_))
+        ));
+    }
+}
@@ -0,0 +1,152 @@
+//! Timestamps as returned by yahoo! finance.
+//!
+//! Most timestamps are seconds since the UNIX epoch, but some fields use milliseconds instead.
+//! Timestamps before 1970 (e.g. first trade dates of long listed equities) are negative.
+use std::fmt;
+
+use serde::{
+    de::{self, Deserializer, Visitor},
+    Deserialize, Serialize, Serializer,
+};
+use time::OffsetDateTime;
+
+/// Point in time as seconds since the UNIX epoch, possibly negative
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct YTimestamp(i64);
+
+impl YTimestamp {
+    pub fn from_seconds(seconds: i64) -> YTimestamp {
+        YTimestamp(seconds)
+    }
+
+    /// Create a timestamp from milliseconds, rounding towards the past
+    pub fn from_millis(millis: i64) -> YTimestamp {
+        YTimestamp(millis.div_euclid(1000))
+    }
+
+    pub fn seconds(&self) -> i64 {
+        self.0
+    }
+
+    pub fn millis(&self) -> i64 {
+        self.0.saturating_mul(1000)
+    }
+
+    /// Convert to a date time in UTC, or `None` if out of the supported range
+    pub fn to_datetime(&self) -> Option<OffsetDateTime> {
+        OffsetDateTime::from_unix_timestamp(self.0).ok()
+    }
+}
+
+impl From<OffsetDateTime> for YTimestamp {
+    fn from(time: OffsetDateTime) -> YTimestamp {
+        YTimestamp(time.unix_timestamp())
+    }
+}
+
+impl fmt::Display for YTimestamp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Serialize for YTimestamp {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_i64(self.0)
+    }
+}
+
+/// Visitor of a number of time units, with fractional values truncated towards the past
+struct UnitsVisitor;
+
+impl<'de> Visitor<'de> for UnitsVisitor {
+    type Value = i64;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("an integer or floating point timestamp")
+    }
+
+    fn visit_i64<E: de::Error>(self, value: i64) -> Result<i64, E> {
+        Ok(value)
+    }
+
+    fn visit_u64<E: de::Error>(self, value: u64) -> Result<i64, E> {
+        i64::try_from(value).map_err(|_| E::custom(format!("timestamp {value} out of range")))
+    }
+
+    fn visit_f64<E: de::Error>(self, value: f64) -> Result<i64, E> {
+        if value.is_finite() && value.abs() < i64::MAX as f64 {
+            Ok(value.floor() as i64)
+        } else {
+            Err(E::custom(format!("timestamp {value} out of range")))
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for YTimestamp {
+    /// Deserialize a timestamp given in seconds
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(YTimestamp(deserializer.deserialize_any(UnitsVisitor)?))
+    }
+}
+
+/// Deserialize a timestamp given in milliseconds.
+/// To be used as `#[serde(deserialize_with = "deserialize_millis")]`.
+pub fn deserialize_millis<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<YTimestamp, D::Error> {
+    Ok(YTimestamp::from_millis(
+        deserializer.deserialize_any(UnitsVisitor)?,
+    ))
+}
+
+/// Deserialize a timestamp given in seconds into a date time in UTC.
+/// To be used as `#[serde(deserialize_with = "deserialize_datetime")]`.
+pub fn deserialize_datetime<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<OffsetDateTime, D::Error> {
+    let seconds = deserializer.deserialize_any(UnitsVisitor)?;
+    OffsetDateTime::from_unix_timestamp(seconds)
+        .map_err(|_| de::Error::custom(format!("timestamp {seconds} out of range")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::macros::datetime;
+
+    #[derive(Deserialize)]
+    struct Times {
+        seconds: YTimestamp,
+        #[serde(deserialize_with = "deserialize_millis")]
+        millis: YTimestamp,
+    }
+
+    #[test]
+    fn test_deserialize_seconds_and_millis() {
+        let times: Times =
+            serde_json::from_str(r#"{"seconds": 1707512401, "millis": 1707512401500}"#).unwrap();
+        assert_eq!(times.seconds, YTimestamp::from_seconds(1707512401));
+        assert_eq!(times.millis, times.seconds);
+        let times: Times =
+            serde_json::from_str(r#"{"seconds": 1707512401.9, "millis": 1.5e12}"#).unwrap();
+        assert_eq!(times.seconds.seconds(), 1707512401);
+        assert_eq!(times.millis.seconds(), 1500000000);
+    }
+
+    #[test]
+    fn test_negative_timestamps() {
+        // first trade date of IBM
+        let times: Times =
+            serde_json::from_str(r#"{"seconds": -252322200, "millis": -252322200500}"#).unwrap();
+        assert_eq!(
+            times.seconds.to_datetime(),
+            Some(datetime!(1962-01-02 14:30 UTC))
+        );
+        // milliseconds are rounded towards the past, also before 1970
+        assert_eq!(times.millis.seconds(), -252322201);
+        assert_eq!(YTimestamp::from_millis(-1).seconds(), -1);
+        assert_eq!(times.seconds.millis(), -252322200000);
+        assert!(serde_json::from_str::<YTimestamp>("18446744073709551615").is_err());
+    }
+}
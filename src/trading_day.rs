@@ -0,0 +1,292 @@
+//! Assignment of intraday quotes to the trading days of the exchange.
+//!
+//! Trading days are determined in the time zone of the exchange, using the offsets reported
+//! with the trading periods (which reflect daylight saving time of the respective day). Quotes
+//! within a trading period belong to the day the period ends, so overnight sessions of futures
+//! starting in the evening before are assigned to the following trading day.
+use std::collections::BTreeMap;
+
+use time::{Date, OffsetDateTime, UtcOffset};
+
+use super::{MarketState, PeriodInfo, Quote, TradingPeriods, YMetaData, YResponse, YahooError};
+
+/// Start and end (exclusive) of a trading session, in the time zone of the exchange
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SessionWindow {
+    pub start: OffsetDateTime,
+    pub end: OffsetDateTime,
+}
+
+impl SessionWindow {
+    fn from_period(period: &PeriodInfo) -> Option<SessionWindow> {
+        let offset = UtcOffset::from_whole_seconds(period.gmtoffset).ok()?;
+        let time = |timestamp| {
+            OffsetDateTime::from_unix_timestamp(timestamp)
+                .ok()
+                .map(|time| time.to_offset(offset))
+        };
+        Some(SessionWindow {
+            start: time(period.start)?,
+            end: time(period.end)?,
+        })
+    }
+
+    pub fn contains(&self, time: OffsetDateTime) -> bool {
+        self.start <= time && time < self.end
+    }
+}
+
+/// Sessions of a trading day; sessions not reported are `None`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SessionDay {
+    /// Trading date, i.e. the exchange-local date the regular session ends
+    pub date: Date,
+    pub pre: Option<SessionWindow>,
+    pub regular: Option<SessionWindow>,
+    pub post: Option<SessionWindow>,
+}
+
+impl SessionDay {
+    fn new(date: Date) -> SessionDay {
+        SessionDay {
+            date,
+            pre: None,
+            regular: None,
+            post: None,
+        }
+    }
+}
+
+/// Session containing a point in time, see `SessionSchedule::session_at`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TradingSession {
+    pub date: Date,
+    /// `MarketState::Pre`, `MarketState::Regular` or `MarketState::Post`
+    pub state: MarketState,
+    pub window: SessionWindow,
+}
+
+/// Trading sessions by day, as reported with the trading periods of intraday charts
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SessionSchedule {
+    /// Trading days sorted by date
+    pub days: Vec<SessionDay>,
+}
+
+impl SessionSchedule {
+    /// Collect the trading periods by the date they end (in the time zone of the exchange).
+    /// Periods with an invalid offset or time are skipped.
+    pub fn from_periods(periods: &TradingPeriods) -> SessionSchedule {
+        let mut days: BTreeMap<Date, SessionDay> = BTreeMap::new();
+        let sessions: [(&Option<Vec<Vec<PeriodInfo>>>, MarketState); 3] = [
+            (&periods.regular, MarketState::Regular),
+            (&periods.pre, MarketState::Pre),
+            (&periods.post, MarketState::Post),
+        ];
+        for (periods, state) in sessions {
+            for period in periods.iter().flatten().flatten() {
+                let Some(window) = SessionWindow::from_period(period) else {
+                    continue;
+                };
+                // pre- and post-market sessions belong to the day of the regular session
+                let date = match state {
+                    MarketState::Regular => window.end - time::Duration::NANOSECOND,
+                    _ => window.start,
+                }
+                .date();
+                let day = days.entry(date).or_insert_with(|| SessionDay::new(date));
+                let slot = match state {
+                    MarketState::Pre => &mut day.pre,
+                    MarketState::Post => &mut day.post,
+                    _ => &mut day.regular,
+                };
+                *slot = Some(window);
+            }
+        }
+        SessionSchedule {
+            days: days.into_values().collect(),
+        }
+    }
+
+    pub fn day(&self, date: Date) -> Option<&SessionDay> {
+        self.days.iter().find(|day| day.date == date)
+    }
+
+    /// Return the session containing the given UNIX timestamp, or `None` if the market is
+    /// closed at that time or the timestamp lies outside of the schedule
+    pub fn session_at(&self, timestamp: i64) -> Option<TradingSession> {
+        let time = OffsetDateTime::from_unix_timestamp(timestamp).ok()?;
+        self.days.iter().find_map(|day| {
+            [
+                (day.pre, MarketState::Pre),
+                (day.regular, MarketState::Regular),
+                (day.post, MarketState::Post),
+            ]
+            .into_iter()
+            .find_map(|(window, state)| {
+                let window = window.filter(|window| window.contains(time))?;
+                Some(TradingSession {
+                    date: day.date,
+                    state,
+                    window,
+                })
+            })
+        })
+    }
+}
+
+impl YMetaData {
+    /// Return all trading periods (pre, regular and post) reported with the meta data
+    fn periods(&self) -> impl Iterator<Item = &PeriodInfo> {
+        let periods = &self.trading_periods;
+        let current = self
+            .current_trading_period
+            .iter()
+            .flat_map(|current| [&current.pre, &current.regular, &current.post]);
+        [&periods.pre, &periods.regular, &periods.post]
+            .into_iter()
+            .flatten()
+            .flatten()
+            .flatten()
+            .chain(current)
+    }
+
+    /// Return the trading sessions reported with the meta data by day
+    pub fn session_schedule(&self) -> SessionSchedule {
+        SessionSchedule::from_periods(&self.trading_periods)
+    }
+
+    /// Return the exchange-local trading date the given UNIX timestamp belongs to: the date the
+    /// trading period containing the timestamp ends, otherwise the calendar date in the time
+    /// zone of the exchange
+    pub fn trading_date(&self, timestamp: i64) -> Option<Date> {
+        if let Some(period) = self
+            .periods()
+            .find(|period| period.start <= timestamp && timestamp < period.end)
+        {
+            return local_date(period.end - 1, period.gmtoffset);
+        }
+        // use the offset of the closest period, which accounts for daylight saving time
+        let gmtoffset = self
+            .periods()
+            .min_by_key(|period| (period.start - timestamp).abs())
+            .map_or(self.gmtoffset, |period| period.gmtoffset);
+        local_date(timestamp, gmtoffset)
+    }
+}
+
+fn local_date(timestamp: i64, gmtoffset: i32) -> Option<Date> {
+    let offset = UtcOffset::from_whole_seconds(gmtoffset).ok()?;
+    Some(
+        OffsetDateTime::from_unix_timestamp(timestamp)
+            .ok()?
+            .to_offset(offset)
+            .date(),
+    )
+}
+
+impl YResponse {
+    /// Return all valid quotes grouped by the exchange-local trading date they belong to,
+    /// e.g. to aggregate intraday quotes to daily bars
+    pub fn quotes_grouped_by_day(&self) -> Result<BTreeMap<Date, Vec<Quote>>, YahooError> {
+        let meta = &self.result(0)?.meta;
+        let mut days: BTreeMap<Date, Vec<Quote>> = BTreeMap::new();
+        for quote in self.quotes()? {
+            let date = meta
+                .trading_date(quote.timestamp as i64)
+                .ok_or(YahooError::DataInconsistency)?;
+            days.entry(date).or_default().push(quote);
+        }
+        Ok(days)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::{date, datetime};
+
+    use super::*;
+    use crate::testing::chart_fixture;
+
+    #[test]
+    fn test_quotes_grouped_by_day() {
+        let response = chart_fixture("equity_msft_1m_prepost")
+            .unwrap()
+            .response()
+            .unwrap();
+        let days = response.quotes_grouped_by_day().unwrap();
+        assert_eq!(days.len(), 1);
+        assert_eq!(
+            days.values().next().unwrap().len(),
+            response.quotes().unwrap().len()
+        );
+    }
+
+    #[test]
+    fn test_session_schedule() {
+        let response = chart_fixture("equity_msft_1m_prepost")
+            .unwrap()
+            .response()
+            .unwrap();
+        let schedule = response.metadata().unwrap().session_schedule();
+        assert_eq!(schedule.days.len(), 1);
+        let day = schedule.day(date!(2024 - 01 - 17)).unwrap();
+        let regular = day.regular.unwrap();
+        assert_eq!(regular.start, datetime!(2024-01-17 09:30 -5));
+        assert_eq!(regular.end, datetime!(2024-01-17 16:00 -5));
+        assert_eq!(day.pre.unwrap().start, datetime!(2024-01-17 04:00 -5));
+        assert_eq!(day.post.unwrap().end, datetime!(2024-01-17 20:00 -5));
+
+        let session = schedule
+            .session_at(datetime!(2024-01-17 16:30 -5).unix_timestamp())
+            .unwrap();
+        assert_eq!(session.state, MarketState::Post);
+        assert_eq!(session.date, date!(2024 - 01 - 17));
+        let open = datetime!(2024-01-17 09:30 -5).unix_timestamp();
+        assert_eq!(
+            schedule.session_at(open).unwrap().state,
+            MarketState::Regular
+        );
+        assert!(schedule
+            .session_at(datetime!(2024-01-17 20:00 -5).unix_timestamp())
+            .is_none());
+    }
+
+    #[test]
+    fn test_overnight_session() {
+        let response = chart_fixture("equity_aapl_1d").unwrap().response().unwrap();
+        let mut meta = response.metadata().unwrap();
+        meta.current_trading_period = None;
+        // session of a future from 18:00 to 17:00 EST of the following day
+        let session = |start: OffsetDateTime, end: OffsetDateTime| PeriodInfo {
+            timezone: "EST".into(),
+            start: start.unix_timestamp(),
+            end: end.unix_timestamp(),
+            gmtoffset: -18000,
+        };
+        meta.trading_periods = TradingPeriods {
+            pre: None,
+            regular: Some(vec![
+                vec![session(
+                    datetime!(2024-02-07 18:00 -5),
+                    datetime!(2024-02-08 17:00 -5),
+                )],
+                vec![session(
+                    datetime!(2024-02-08 18:00 -5),
+                    datetime!(2024-02-09 17:00 -5),
+                )],
+            ]),
+            post: None,
+        };
+        let evening = datetime!(2024-02-08 19:30 -5).unix_timestamp();
+        assert_eq!(meta.trading_date(evening), Some(date!(2024 - 02 - 09)));
+        // after midnight UTC, but still the same trading day
+        let night = datetime!(2024-02-09 01:30 UTC).unix_timestamp();
+        assert_eq!(meta.trading_date(night), Some(date!(2024 - 02 - 09)));
+        let morning = datetime!(2024-02-08 09:30 -5).unix_timestamp();
+        assert_eq!(meta.trading_date(morning), Some(date!(2024 - 02 - 08)));
+        // between sessions, the local calendar date is used
+        let pause = datetime!(2024-02-08 17:30 -5).unix_timestamp();
+        assert_eq!(meta.trading_date(pause), Some(date!(2024 - 02 - 08)));
+    }
+}
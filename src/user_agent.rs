@@ -0,0 +1,106 @@
+//! Pool of user agents sent in rotation, as yahoo! finance may throttle clients by their user
+//! agent, see `YahooConnectorBuilder::user_agent_pool`.
+//!
+//! A response with status 429 (too many requests) always moves on to the next user agent, so
+//! a retry is sent with a different one.
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use serde::Deserialize;
+
+/// User agents of current desktop browsers, e.g. as argument of `user_agent_pool`
+pub const BROWSER_USER_AGENTS: &[&str] = &[
+    "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/122.0.0.0 Safari/537.36",
+    "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/122.0.0.0 Safari/537.36",
+    "Mozilla/5.0 (X11; Linux x86_64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/122.0.0.0 Safari/537.36",
+    "Mozilla/5.0 (Windows NT 10.0; Win64; x64; rv:123.0) Gecko/20100101 Firefox/123.0",
+    "Mozilla/5.0 (Macintosh; Intel Mac OS X 14.3; rv:123.0) Gecko/20100101 Firefox/123.0",
+    "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.3 Safari/605.1.15",
+    "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/122.0.0.0 Safari/537.36 Edg/122.0.0.0",
+];
+
+/// When the user agent of a pool changes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UserAgentRotation {
+    /// Send each request with the next user agent
+    #[default]
+    PerRequest,
+    /// Keep the user agent for the lifetime of cookie and crumb, moving on to the next one
+    /// when new credentials are fetched
+    PerSession,
+}
+
+/// User agents shared by all clones of a connector
+#[derive(Debug)]
+pub(crate) struct UserAgentPool {
+    agents: Vec<String>,
+    rotation: UserAgentRotation,
+    index: AtomicUsize,
+}
+
+impl UserAgentPool {
+    /// Pool of the given user agents, or `None` if there are none
+    pub(crate) fn new(agents: Vec<String>, rotation: UserAgentRotation) -> Option<UserAgentPool> {
+        if agents.is_empty() {
+            return None;
+        }
+        Some(UserAgentPool {
+            agents,
+            rotation,
+            index: AtomicUsize::new(0),
+        })
+    }
+
+    fn get(&self, index: usize) -> &str {
+        &self.agents[index % self.agents.len()]
+    }
+
+    /// User agent of the next request
+    pub(crate) fn next(&self) -> &str {
+        match self.rotation {
+            UserAgentRotation::PerRequest => self.get(self.index.fetch_add(1, Ordering::Relaxed)),
+            UserAgentRotation::PerSession => self.get(self.index.load(Ordering::Relaxed)),
+        }
+    }
+
+    /// Move on to the next user agent
+    pub(crate) fn rotate(&self) {
+        self.index.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Move on to the next user agent if it is kept per session
+    pub(crate) fn new_session(&self) {
+        if self.rotation == UserAgentRotation::PerSession {
+            self.rotate();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pool(rotation: UserAgentRotation) -> UserAgentPool {
+        UserAgentPool::new(vec!["a".to_string(), "b".to_string()], rotation).unwrap()
+    }
+
+    #[test]
+    fn test_user_agent_rotation() {
+        assert!(UserAgentPool::new(Vec::new(), UserAgentRotation::default()).is_none());
+
+        let per_request = pool(UserAgentRotation::PerRequest);
+        let agents: Vec<&str> = (0..3).map(|_| per_request.next()).collect();
+        assert_eq!(agents, vec!["a", "b", "a"]);
+        per_request.new_session();
+        assert_eq!(per_request.next(), "b");
+
+        let per_session = pool(UserAgentRotation::PerSession);
+        assert_eq!(per_session.next(), "a");
+        assert_eq!(per_session.next(), "a");
+        per_session.new_session();
+        assert_eq!(per_session.next(), "b");
+        // e.g. after a response with status 429
+        per_session.rotate();
+        assert_eq!(per_session.next(), "a");
+    }
+}
@@ -0,0 +1,313 @@
+//! Detection of anomalies in quote series as occasionally served by yahoo! finance.
+use std::collections::HashSet;
+
+use super::quotes::ZERO;
+use super::{decimal_to_f64, PeriodInfo, Quote, Split, TradingPeriods, YResponse, YahooError};
+
+/// Volumes exceeding the median volume of a series by this factor are reported as spikes
+pub const VOLUME_SPIKE_FACTOR: u64 = 1000;
+
+/// Changes of the close between consecutive quotes by this factor or more (in either
+/// direction) are checked against the recorded splits
+pub const SPLIT_SUSPECT_FACTOR: f64 = 1.9;
+
+/// Kind of anomaly found in a quote series
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnomalyKind {
+    /// One of the prices (open, high, low, close) is zero or negative
+    NonPositivePrice,
+    /// The high price is below the low price
+    HighBelowLow,
+    /// The timestamp has already been seen earlier in the series
+    DuplicateTimestamp,
+    /// The volume exceeds the median volume of the series by a factor of `VOLUME_SPIKE_FACTOR` or more
+    VolumeSpike,
+    /// The timestamp is not within any of the trading periods reported for the series
+    OutsideTradingPeriod,
+}
+
+/// A single finding of a quote series validation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QuoteAnomaly {
+    /// Position of the affected quote within the validated series
+    pub index: usize,
+    /// Timestamp of the affected quote
+    pub timestamp: u64,
+    pub kind: AnomalyKind,
+}
+
+/// Check a series of quotes for anomalies. If trading periods are given, quotes outside
+/// of these periods are reported as well. Each quote may produce several findings, which
+/// are returned ordered by their position in the series.
+pub fn validate_quotes(
+    quotes: &[Quote],
+    trading_periods: Option<&TradingPeriods>,
+) -> Vec<QuoteAnomaly> {
+    let periods = trading_periods.map(flatten_periods).unwrap_or_default();
+    let median_volume = median_volume(quotes);
+    let mut seen = HashSet::new();
+    let mut anomalies = Vec::new();
+    for (index, quote) in quotes.iter().enumerate() {
+        let mut report = |kind| {
+            anomalies.push(QuoteAnomaly {
+                index,
+                timestamp: quote.timestamp,
+                kind,
+            })
+        };
+        if [quote.open, quote.high, quote.low, quote.close]
+            .iter()
+            .any(|price| *price <= ZERO)
+        {
+            report(AnomalyKind::NonPositivePrice);
+        }
+        if quote.high < quote.low {
+            report(AnomalyKind::HighBelowLow);
+        }
+        if !seen.insert(quote.timestamp) {
+            report(AnomalyKind::DuplicateTimestamp);
+        }
+        if let Some(median) = median_volume {
+            if quote.volume >= median.saturating_mul(VOLUME_SPIKE_FACTOR) {
+                report(AnomalyKind::VolumeSpike);
+            }
+        }
+        if !periods.is_empty() && !periods.iter().any(|p| contains(p, quote.timestamp)) {
+            report(AnomalyKind::OutsideTradingPeriod);
+        }
+    }
+    anomalies
+}
+
+impl YResponse {
+    /// Check the quotes of the response for anomalies, see `validate_quotes` for details.
+    /// Indices of the findings refer to the quotes as returned by `quotes()`.
+    pub fn validate(&self) -> Result<Vec<QuoteAnomaly>, YahooError> {
+        let quotes = self.quotes()?;
+        let meta = &self.result(0)?.meta;
+        Ok(validate_quotes(&quotes, Some(&meta.trading_periods)))
+    }
+}
+
+/// Kind of inconsistency between prices and recorded splits
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SplitWarningKind {
+    /// The close jumped without a split recorded in between, e.g. because yahoo! finance has
+    /// not recorded the split yet
+    UnrecordedSplit,
+    /// The close jumped by the ratio of a split recorded in between, i.e. prices before the
+    /// split have not been adjusted
+    UnadjustedSplit,
+}
+
+/// A price discontinuity which may be caused by a split
+#[derive(Debug, Clone, PartialEq)]
+pub struct SplitWarning {
+    /// Position of the quote after the discontinuity within the checked series
+    pub index: usize,
+    /// Timestamp of the quote after the discontinuity
+    pub timestamp: u64,
+    /// Ratio of the previous close to the close of the quote, e.g. 10 after a 10:1 split
+    pub ratio: f64,
+    pub kind: SplitWarningKind,
+}
+
+/// Cross-check discontinuities of the close prices against the recorded splits. Since prices
+/// served by yahoo! finance are adjusted for splits, changes of the close by
+/// `SPLIT_SUSPECT_FACTOR` or more are suspicious: they are reported as unadjusted if a split
+/// of about the same ratio has been recorded between both quotes, otherwise as unrecorded.
+/// Note that large moves may also be genuine, e.g. for penny stocks.
+pub fn check_splits(quotes: &[Quote], splits: &[Split]) -> Vec<SplitWarning> {
+    let mut warnings = Vec::new();
+    let mut previous: Option<&Quote> = None;
+    for (index, quote) in quotes.iter().enumerate() {
+        if quote.close <= ZERO {
+            continue;
+        }
+        if let Some(prev) = previous.replace(quote) {
+            let ratio = decimal_to_f64(prev.close) / decimal_to_f64(quote.close);
+            if ratio < SPLIT_SUSPECT_FACTOR && ratio > 1.0 / SPLIT_SUSPECT_FACTOR {
+                continue;
+            }
+            let recorded = splits.iter().any(|split| {
+                let date = split.date.seconds();
+                let split_ratio =
+                    decimal_to_f64(split.numerator) / decimal_to_f64(split.denominator);
+                prev.timestamp < date as u64
+                    && date as u64 <= quote.timestamp
+                    && (ratio / split_ratio - 1.0).abs() < 0.2
+            });
+            warnings.push(SplitWarning {
+                index,
+                timestamp: quote.timestamp,
+                ratio,
+                kind: if recorded {
+                    SplitWarningKind::UnadjustedSplit
+                } else {
+                    SplitWarningKind::UnrecordedSplit
+                },
+            });
+        }
+    }
+    warnings
+}
+
+impl YResponse {
+    /// Cross-check the quotes of the response against its splits, see `check_splits`. The
+    /// splits are only part of the response if requested, e.g. by `EventsFilter::SPLITS`.
+    pub fn check_splits(&self) -> Result<Vec<SplitWarning>, YahooError> {
+        Ok(check_splits(&self.quotes()?, &self.splits()?))
+    }
+}
+
+fn flatten_periods(periods: &TradingPeriods) -> Vec<PeriodInfo> {
+    [&periods.pre, &periods.regular, &periods.post]
+        .into_iter()
+        .flatten()
+        .flatten()
+        .flatten()
+        .cloned()
+        .collect()
+}
+
+fn contains(period: &PeriodInfo, timestamp: u64) -> bool {
+    period.start <= timestamp as i64 && (timestamp as i64) < period.end
+}
+
+/// Median of all non-zero volumes, or `None` if there are none
+fn median_volume(quotes: &[Quote]) -> Option<u64> {
+    let mut volumes: Vec<u64> = quotes.iter().map(|q| q.volume).filter(|v| *v > 0).collect();
+    if volumes.is_empty() {
+        return None;
+    }
+    volumes.sort_unstable();
+    Some(volumes[volumes.len() / 2])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Decimal, YTimestamp};
+
+    fn price(value: u32) -> Decimal {
+        Decimal::from(value)
+    }
+
+    fn quote(timestamp: u64, low: u32, high: u32, volume: u64) -> Quote {
+        Quote {
+            timestamp,
+            open: price(low),
+            high: price(high),
+            low: price(low),
+            volume,
+            close: price(high),
+            adjclose: price(high),
+        }
+    }
+
+    #[test]
+    fn test_valid_series() {
+        let quotes = vec![quote(100, 9, 10, 1000), quote(200, 10, 11, 1200)];
+        assert!(validate_quotes(&quotes, None).is_empty());
+    }
+
+    #[test]
+    fn test_price_anomalies() {
+        let quotes = vec![quote(100, 0, 10, 1000), quote(200, 12, 11, 1000)];
+        let anomalies = validate_quotes(&quotes, None);
+        assert_eq!(
+            anomalies,
+            vec![
+                QuoteAnomaly {
+                    index: 0,
+                    timestamp: 100,
+                    kind: AnomalyKind::NonPositivePrice
+                },
+                QuoteAnomaly {
+                    index: 1,
+                    timestamp: 200,
+                    kind: AnomalyKind::HighBelowLow
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_duplicates_and_volume_spikes() {
+        let quotes = vec![
+            quote(100, 9, 10, 1000),
+            quote(200, 9, 10, 1000),
+            quote(200, 9, 10, 1000),
+            quote(300, 9, 10, 1_000_000),
+        ];
+        let kinds: Vec<(usize, AnomalyKind)> = validate_quotes(&quotes, None)
+            .into_iter()
+            .map(|a| (a.index, a.kind))
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                (2, AnomalyKind::DuplicateTimestamp),
+                (3, AnomalyKind::VolumeSpike)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_check_splits() {
+        let quotes = vec![
+            quote(100, 90, 100, 1000),
+            quote(200, 90, 100, 1000),
+            quote(300, 9, 10, 1000),
+            quote(400, 9, 10, 1000),
+            quote(500, 1, 2, 1000),
+            quote(600, 2, 3, 1000),
+        ];
+        let split = Split {
+            date: YTimestamp::from_seconds(250),
+            numerator: price(10),
+            denominator: price(1),
+            split_ratio: "10:1".to_string(),
+        };
+        let warnings: Vec<(usize, SplitWarningKind)> = check_splits(&quotes, &[split])
+            .into_iter()
+            .map(|w| (w.index, w.kind))
+            .collect();
+        assert_eq!(
+            warnings,
+            vec![
+                (2, SplitWarningKind::UnadjustedSplit),
+                (4, SplitWarningKind::UnrecordedSplit)
+            ]
+        );
+        assert!(check_splits(&quotes[..2], &[]).is_empty());
+    }
+
+    #[test]
+    fn test_outside_trading_periods() {
+        let period = |start, end| PeriodInfo {
+            timezone: "EST".into(),
+            start,
+            end,
+            gmtoffset: -18000,
+        };
+        let periods = TradingPeriods {
+            pre: Some(vec![vec![period(50, 100)]]),
+            regular: Some(vec![vec![period(100, 200)], vec![period(300, 400)]]),
+            post: None,
+        };
+        let quotes = vec![
+            quote(60, 9, 10, 1000),
+            quote(150, 9, 10, 1000),
+            quote(250, 9, 10, 1000),
+            quote(300, 9, 10, 1000),
+            quote(400, 9, 10, 1000),
+        ];
+        let indices: Vec<usize> = validate_quotes(&quotes, Some(&periods))
+            .into_iter()
+            .filter(|a| a.kind == AnomalyKind::OutsideTradingPeriod)
+            .map(|a| a.index)
+            .collect();
+        assert_eq!(indices, vec![2, 4]);
+    }
+}
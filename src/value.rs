@@ -0,0 +1,124 @@
+//! Values as returned by the quoteSummary and statistics endpoints of yahoo! finance.
+//!
+//! Depending on the `formatted` query parameter, numbers are either returned as plain values
+//! or wrapped as `{ "raw": 123, "fmt": "123", "longFmt": "123" }`. Missing values are
+//! returned as `null` or as an empty object `{}`. The types and functions in this module
+//! accept all of these representations.
+use serde::{Deserialize, Deserializer};
+
+/// A value together with its formatted representations, if provided by yahoo! finance
+#[derive(Debug, Clone, PartialEq)]
+pub struct YValue<T> {
+    pub raw: T,
+    pub fmt: Option<String>,
+    pub long_fmt: Option<String>,
+}
+
+impl<T> YValue<T> {
+    pub fn new(raw: T) -> YValue<T> {
+        YValue {
+            raw,
+            fmt: None,
+            long_fmt: None,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Repr<T> {
+    Plain(T),
+    Wrapped {
+        raw: Option<T>,
+        fmt: Option<String>,
+        #[serde(rename = "longFmt")]
+        long_fmt: Option<String>,
+    },
+}
+
+impl<T> Repr<T> {
+    fn into_value(self) -> Option<YValue<T>> {
+        match self {
+            Repr::Plain(raw) => Some(YValue::new(raw)),
+            Repr::Wrapped { raw, fmt, long_fmt } => Some(YValue {
+                raw: raw?,
+                fmt,
+                long_fmt,
+            }),
+        }
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for YValue<T> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserialize_value(deserializer)?.ok_or_else(|| serde::de::Error::missing_field("raw"))
+    }
+}
+
+/// Deserialize a plain or wrapped value, mapping `null` and `{}` to `None`.
+/// To be used as `#[serde(default, deserialize_with = "deserialize_value")]`.
+pub fn deserialize_value<'de, D, T>(deserializer: D) -> Result<Option<YValue<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Ok(Option::<Repr<T>>::deserialize(deserializer)?.and_then(Repr::into_value))
+}
+
+/// Deserialize a plain or wrapped value, keeping only the raw value and mapping `null` and `{}`
+/// to `None`. To be used as `#[serde(default, deserialize_with = "deserialize_raw")]`.
+pub fn deserialize_raw<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Ok(deserialize_value(deserializer)?.map(|value| value.raw))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Decimal;
+
+    #[derive(Deserialize, Debug)]
+    struct Summary {
+        #[serde(default, deserialize_with = "deserialize_raw")]
+        price: Option<Decimal>,
+        #[serde(default, deserialize_with = "deserialize_value")]
+        volume: Option<YValue<u64>>,
+    }
+
+    #[test]
+    fn test_wrapped_values() {
+        let summary: Summary = serde_json::from_str(
+            r#"{"price": {"raw": 187.5, "fmt": "187.50"},
+                "volume": {"raw": 1200000, "fmt": "1.2M", "longFmt": "1,200,000"}}"#,
+        )
+        .unwrap();
+        assert_eq!(summary.price, Some("187.5".parse().unwrap()));
+        let volume = summary.volume.unwrap();
+        assert_eq!(volume.raw, 1200000);
+        assert_eq!(volume.fmt.as_deref(), Some("1.2M"));
+        assert_eq!(volume.long_fmt.as_deref(), Some("1,200,000"));
+    }
+
+    #[test]
+    fn test_plain_and_missing_values() {
+        let summary: Summary =
+            serde_json::from_str(r#"{"price": 187.5, "volume": 1200000}"#).unwrap();
+        assert_eq!(summary.price, Some("187.5".parse().unwrap()));
+        assert_eq!(summary.volume, Some(YValue::new(1200000)));
+
+        let summary: Summary = serde_json::from_str(r#"{"price": {}, "volume": null}"#).unwrap();
+        assert!(summary.price.is_none() && summary.volume.is_none());
+        let summary: Summary = serde_json::from_str("{}").unwrap();
+        assert!(summary.price.is_none() && summary.volume.is_none());
+
+        assert!(serde_json::from_str::<YValue<u64>>("{}").is_err());
+        let value: YValue<u64> = serde_json::from_str(r#"{"raw": 5, "fmt": "5"}"#).unwrap();
+        assert_eq!(value.raw, 5);
+    }
+}
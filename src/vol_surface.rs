@@ -0,0 +1,259 @@
+//! Implied volatility surface aggregated from option chains of several expirations.
+//!
+//! Strikes and volatilities are kept as floating point numbers, since they are only used for
+//! interpolation. Volatilities are annualized fractions, e.g. `0.25` for 25%.
+use super::{
+    decimal_to_f64, Decimal, OptionKind, YOptionChain, YOptionContract, YOptionDetails, YTimestamp,
+};
+
+/// yahoo! finance reports volatilities which could not be computed (e.g. for contracts
+/// without bid) as tiny placeholder values like `0.00001`
+const MIN_IMPLIED_VOLATILITY: f64 = 1e-3;
+
+/// Grid of implied volatilities by expiration and strike
+#[derive(Debug, Clone, PartialEq)]
+pub struct VolSurface {
+    /// Expiration dates, ascending
+    pub expirations: Vec<YTimestamp>,
+    /// Strikes, ascending
+    pub strikes: Vec<f64>,
+    /// Implied volatility by expiration (outer) and strike (inner), `None` if no contract of
+    /// the expiration and strike has a volatility
+    pub vols: Vec<Vec<Option<f64>>>,
+}
+
+fn implied_volatility(contract: &YOptionContract) -> Option<(f64, f64)> {
+    let vol = decimal_to_f64(contract.implied_volatility?);
+    let strike = decimal_to_f64(contract.strike?);
+    (vol > MIN_IMPLIED_VOLATILITY).then_some((strike, vol))
+}
+
+fn out_of_the_money(
+    chains: &[YOptionDetails],
+    underlying_price: Decimal,
+) -> impl Iterator<Item = (YTimestamp, f64, f64)> + '_ {
+    chains.iter().flat_map(move |chain| {
+        let puts = chain
+            .puts
+            .iter()
+            .filter(move |c| c.strike.is_some_and(|strike| strike < underlying_price));
+        let calls = chain
+            .calls
+            .iter()
+            .filter(move |c| c.strike.is_some_and(|strike| strike >= underlying_price));
+        puts.chain(calls)
+            .filter_map(implied_volatility)
+            .map(move |(strike, vol)| (chain.expiration_date, strike, vol))
+    })
+}
+
+fn lerp(x0: f64, y0: f64, x1: f64, y1: f64, x: f64) -> f64 {
+    y0 + (y1 - y0) * (x - x0) / (x1 - x0)
+}
+
+impl VolSurface {
+    /// Build the surface from the out-of-the-money contracts of the chains, i.e. puts with a
+    /// strike below the price of the underlying and calls with a strike at or above it, as
+    /// these are more liquid than the corresponding in-the-money contracts.
+    pub fn from_chains(chains: &[YOptionDetails], underlying_price: Decimal) -> VolSurface {
+        VolSurface::from_points(out_of_the_money(chains, underlying_price))
+    }
+
+    /// Build the surface from the calls or puts of the chains only
+    pub fn from_contracts(chains: &[YOptionDetails], kind: OptionKind) -> VolSurface {
+        let points = chains.iter().flat_map(|chain| {
+            let contracts = match kind {
+                OptionKind::Call => &chain.calls,
+                OptionKind::Put => &chain.puts,
+            };
+            contracts
+                .iter()
+                .filter_map(implied_volatility)
+                .map(move |(strike, vol)| (chain.expiration_date, strike, vol))
+        });
+        VolSurface::from_points(points)
+    }
+
+    fn from_points(points: impl Iterator<Item = (YTimestamp, f64, f64)>) -> VolSurface {
+        let points: Vec<(YTimestamp, f64, f64)> = points.collect();
+        let mut expirations: Vec<YTimestamp> = points.iter().map(|p| p.0).collect();
+        expirations.sort_unstable();
+        expirations.dedup();
+        let mut strikes: Vec<f64> = points.iter().map(|p| p.1).collect();
+        strikes.sort_by(f64::total_cmp);
+        strikes.dedup();
+        let mut vols = vec![vec![None; strikes.len()]; expirations.len()];
+        for (expiration, strike, vol) in points {
+            let row = expirations.partition_point(|e| *e < expiration);
+            let column = strikes.partition_point(|s| *s < strike);
+            vols[row][column].get_or_insert(vol);
+        }
+        VolSurface {
+            expirations,
+            strikes,
+            vols,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.vols.iter().flatten().all(Option::is_none)
+    }
+
+    /// Strikes and volatilities available for the expiration of the given row
+    fn smile(&self, row: usize) -> impl Iterator<Item = (f64, f64)> + '_ {
+        self.strikes
+            .iter()
+            .zip(&self.vols[row])
+            .filter_map(|(strike, vol)| vol.map(|vol| (*strike, vol)))
+    }
+
+    /// Rows of expirations with any volatility
+    fn rows(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.expirations.len()).filter(move |row| self.smile(*row).next().is_some())
+    }
+
+    /// Volatility of the grid point closest to the given expiration and, for that expiration,
+    /// closest to the given strike. Returns `None` if the surface is empty.
+    pub fn nearest(&self, strike: f64, expiration: YTimestamp) -> Option<f64> {
+        let row = self.rows().min_by_key(|row| {
+            self.expirations[*row]
+                .seconds()
+                .abs_diff(expiration.seconds())
+        })?;
+        self.smile(row)
+            .min_by(|a, b| (a.0 - strike).abs().total_cmp(&(b.0 - strike).abs()))
+            .map(|(_, vol)| vol)
+    }
+
+    /// Volatility interpolated linearly in strike along the smiles of the expirations before
+    /// and after the given one, then linearly in time between them. Beyond the first or last
+    /// strike or expiration the volatility is extrapolated flat. Returns `None` if the surface
+    /// is empty.
+    pub fn linear(&self, strike: f64, expiration: YTimestamp) -> Option<f64> {
+        let rows: Vec<usize> = self.rows().collect();
+        let after = rows.partition_point(|row| self.expirations[*row] < expiration);
+        let (before, after) = match (after.checked_sub(1), rows.get(after)) {
+            (_, Some(&row)) if self.expirations[row] == expiration => (row, row),
+            (Some(before), Some(&after)) => (rows[before], after),
+            (Some(before), None) => (rows[before], rows[before]),
+            (None, Some(&after)) => (after, after),
+            (None, None) => return None,
+        };
+        let vol_before = self.interpolate_smile(before, strike)?;
+        if before == after {
+            return Some(vol_before);
+        }
+        let vol_after = self.interpolate_smile(after, strike)?;
+        Some(lerp(
+            self.expirations[before].seconds() as f64,
+            vol_before,
+            self.expirations[after].seconds() as f64,
+            vol_after,
+            expiration.seconds() as f64,
+        ))
+    }
+
+    fn interpolate_smile(&self, row: usize, strike: f64) -> Option<f64> {
+        let smile: Vec<(f64, f64)> = self.smile(row).collect();
+        let after = smile.partition_point(|(s, _)| *s < strike);
+        match (after.checked_sub(1), smile.get(after)) {
+            (_, Some(&(s, vol))) if s == strike => Some(vol),
+            (Some(before), Some(&(s1, v1))) => {
+                let (s0, v0) = smile[before];
+                Some(lerp(s0, v0, s1, v1, strike))
+            }
+            (Some(before), None) => Some(smile[before].1),
+            (None, Some(&(_, vol))) => Some(vol),
+            (None, None) => None,
+        }
+    }
+}
+
+impl YOptionChain {
+    /// Implied volatility surface of the out-of-the-money contracts of all expirations in the
+    /// chain, see `VolSurface::from_chains`
+    pub fn iv_surface(&self) -> VolSurface {
+        VolSurface::from_points(
+            self.option_chain
+                .result
+                .iter()
+                .flat_map(|data| out_of_the_money(&data.options, data.quote.regular_market_price)),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decimal_from_f64;
+
+    fn contract(strike: u32, vol: f64) -> YOptionContract {
+        YOptionContract {
+            contract_symbol: None,
+            strike: Some(Decimal::from(strike)),
+            currency: Some("USD".to_string()),
+            last_price: None,
+            change: None,
+            percent_change: None,
+            volume: None,
+            open_interest: None,
+            bid: None,
+            ask: None,
+            contract_size: Some("REGULAR".to_string()),
+            expiration: None,
+            last_trade_date: None,
+            implied_volatility: decimal_from_f64(vol),
+            in_the_money: None,
+            #[cfg(all(feature = "capture_unknown", not(feature = "deny_unknown")))]
+            extra: Default::default(),
+        }
+    }
+
+    fn chain(expiration_date: i64, calls: &[(u32, f64)], puts: &[(u32, f64)]) -> YOptionDetails {
+        let contracts = |list: &[(u32, f64)]| list.iter().map(|(s, v)| contract(*s, *v)).collect();
+        YOptionDetails {
+            expiration_date: YTimestamp::from_seconds(expiration_date),
+            has_mini_options: false,
+            calls: contracts(calls),
+            puts: contracts(puts),
+        }
+    }
+
+    #[test]
+    fn test_vol_surface() {
+        let chains = [
+            chain(
+                2000,
+                &[(100, 0.5), (110, 0.25)],
+                &[(90, 0.75), (100, 0.625)],
+            ),
+            chain(
+                1000,
+                &[(100, 0.25), (110, 0.125)],
+                &[(90, 0.5), (100, 0.00001)],
+            ),
+        ];
+        let surface = VolSurface::from_chains(&chains, Decimal::from(100));
+        let at = YTimestamp::from_seconds;
+        assert_eq!(surface.expirations, vec![at(1000), at(2000)]);
+        assert_eq!(surface.strikes, vec![90.0, 100.0, 110.0]);
+        assert_eq!(surface.vols[0], vec![Some(0.5), Some(0.25), Some(0.125)]);
+        assert_eq!(surface.vols[1], vec![Some(0.75), Some(0.5), Some(0.25)]);
+
+        assert_eq!(surface.nearest(104.0, at(1400)), Some(0.25));
+        assert_eq!(surface.nearest(106.0, at(1600)), Some(0.25));
+        assert_eq!(surface.linear(105.0, at(1000)), Some(0.1875));
+        assert_eq!(surface.linear(100.0, at(1500)), Some(0.375));
+        assert_eq!(surface.linear(95.0, at(1500)), Some(0.5));
+        // flat beyond the grid
+        assert_eq!(surface.linear(80.0, at(500)), Some(0.5));
+        assert_eq!(surface.linear(120.0, at(3000)), Some(0.25));
+
+        let puts = VolSurface::from_contracts(&chains, OptionKind::Put);
+        assert_eq!(puts.vols[0], vec![Some(0.5), None]);
+        assert_eq!(puts.linear(100.0, at(1000)), Some(0.5));
+        assert!(VolSurface::from_chains(&[], Decimal::from(100))
+            .linear(100.0, at(1000))
+            .is_none());
+    }
+}
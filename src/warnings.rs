@@ -0,0 +1,227 @@
+//! Non-fatal data quality issues of responses, e.g. to log them without failing.
+//!
+//! yahoo! finance occasionally returns partially valid data: series missing the adjusted
+//! close, series shorter than the timestamps, or responses without currency. The accessors of
+//! this crate either fill in defaults (e.g. zero for a missing adjusted close) or fail for such
+//! responses; `warnings` reports them up front.
+use std::fmt;
+
+use super::{YQuoteBlock, YQuoteSummary, YResponse};
+
+/// Kind of data quality issue
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WarningKind {
+    /// Daily or longer bars are reported without adjusted close, which is set to zero in the
+    /// quotes
+    MissingAdjclose,
+    /// The series has a different length than the timestamps, so the quotes cannot be
+    /// assembled and fail with `YahooError::DataInconsistency`
+    TruncatedSeries {
+        series: &'static str,
+        expected: usize,
+        found: usize,
+    },
+    /// Number of bars without close, which are skipped in the quotes
+    IncompleteBars(usize),
+    /// No currency is reported
+    MissingCurrency,
+}
+
+/// Data quality issue of a part of a response
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DataWarning {
+    /// Part of the response affected, i.e. the symbol of a chart or the module of a quote
+    /// summary (e.g. `financialData`)
+    pub source: String,
+    pub kind: WarningKind,
+}
+
+impl DataWarning {
+    fn new(source: &str, kind: WarningKind) -> DataWarning {
+        DataWarning {
+            source: source.to_string(),
+            kind,
+        }
+    }
+}
+
+impl fmt::Display for DataWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            WarningKind::MissingAdjclose => write!(f, "{}: adjusted close missing", self.source),
+            WarningKind::TruncatedSeries {
+                series,
+                expected,
+                found,
+            } => write!(
+                f,
+                "{}: {series} has {found} values for {expected} timestamps",
+                self.source
+            ),
+            WarningKind::IncompleteBars(count) => {
+                write!(f, "{}: {count} bars without close", self.source)
+            }
+            WarningKind::MissingCurrency => write!(f, "{}: currency missing", self.source),
+        }
+    }
+}
+
+impl YQuoteBlock {
+    /// Return the data quality issues of this block
+    pub fn warnings(&self) -> Vec<DataWarning> {
+        let symbol = self.meta.symbol.as_str();
+        let mut warnings = Vec::new();
+        if self.meta.currency.is_none() {
+            warnings.push(DataWarning::new(symbol, WarningKind::MissingCurrency));
+        }
+        let expected = self.timestamp.len();
+        let Some(quote) = self.indicators.quote.first() else {
+            if expected > 0 {
+                warnings.push(DataWarning::new(
+                    symbol,
+                    WarningKind::TruncatedSeries {
+                        series: "quote",
+                        expected,
+                        found: 0,
+                    },
+                ));
+            }
+            return warnings;
+        };
+        let series = [
+            ("open", quote.open.len()),
+            ("high", quote.high.len()),
+            ("low", quote.low.len()),
+            ("close", quote.close.len()),
+            ("volume", quote.volume.len()),
+        ];
+        let adjclose = self.indicators.adjclose.as_ref().and_then(|a| a.first());
+        let adjclose_len = adjclose.map(|adjclose| ("adjclose", adjclose.adjclose.len()));
+        for (series, found) in series.into_iter().chain(adjclose_len) {
+            if found != expected {
+                warnings.push(DataWarning::new(
+                    symbol,
+                    WarningKind::TruncatedSeries {
+                        series,
+                        expected,
+                        found,
+                    },
+                ));
+            }
+        }
+        // intraday bars never have an adjusted close
+        let intraday = self.meta.data_granularity.ends_with(['m', 'h']);
+        if adjclose.is_none() && !intraday && expected > 0 {
+            warnings.push(DataWarning::new(symbol, WarningKind::MissingAdjclose));
+        }
+        let incomplete = quote.close.iter().filter(|close| close.is_none()).count();
+        if incomplete > 0 {
+            warnings.push(DataWarning::new(
+                symbol,
+                WarningKind::IncompleteBars(incomplete),
+            ));
+        }
+        warnings
+    }
+}
+
+impl YResponse {
+    /// Return the data quality issues of all result blocks, see `DataWarning`
+    pub fn warnings(&self) -> Vec<DataWarning> {
+        self.chart
+            .result
+            .iter()
+            .flat_map(|block| block.warnings())
+            .collect()
+    }
+}
+
+impl YQuoteSummary {
+    /// Return the data quality issues of the modules of the summary, see `DataWarning`
+    pub fn warnings(&self) -> Vec<DataWarning> {
+        let mut warnings = Vec::new();
+        if self
+            .financial_data()
+            .is_some_and(|data| data.financial_currency.is_none())
+        {
+            warnings.push(DataWarning::new(
+                "financialData",
+                WarningKind::MissingCurrency,
+            ));
+        }
+        if self
+            .summary_detail()
+            .is_some_and(|detail| detail.currency.is_none())
+        {
+            warnings.push(DataWarning::new(
+                "summaryDetail",
+                WarningKind::MissingCurrency,
+            ));
+        }
+        warnings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{chart_fixture, quote_summary_fixture};
+
+    #[test]
+    fn test_chart_warnings() {
+        let response = chart_fixture("equity_aapl_1d").unwrap().response().unwrap();
+        assert_eq!(response.warnings(), Vec::new());
+
+        let mut json = chart_fixture("equity_aapl_1d").unwrap().json_value();
+        let result = &mut json["chart"]["result"][0];
+        result["meta"]["currency"] = serde_json::Value::Null;
+        result["indicators"]["quote"][0]["close"][1] = serde_json::Value::Null;
+        result["indicators"]["quote"][0]["volume"]
+            .as_array_mut()
+            .unwrap()
+            .pop();
+        result["indicators"]
+            .as_object_mut()
+            .unwrap()
+            .remove("adjclose");
+        let response = YResponse::from_json(json).unwrap();
+        let warnings = response.warnings();
+        let kinds: Vec<&WarningKind> = warnings.iter().map(|w| &w.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                &WarningKind::MissingCurrency,
+                &WarningKind::TruncatedSeries {
+                    series: "volume",
+                    expected: 5,
+                    found: 4
+                },
+                &WarningKind::MissingAdjclose,
+                &WarningKind::IncompleteBars(1),
+            ]
+        );
+        assert!(warnings.iter().all(|w| w.source == "AAPL"));
+        assert_eq!(
+            warnings[1].to_string(),
+            "AAPL: volume has 4 values for 5 timestamps"
+        );
+    }
+
+    #[test]
+    fn test_quote_summary_warnings() {
+        let fixture = quote_summary_fixture("quote_summary_aapl").unwrap();
+        assert!(fixture.quote_summary().unwrap().warnings().is_empty());
+
+        let mut json = fixture.json_value();
+        json["quoteSummary"]["result"][0]["financialData"]["financialCurrency"] =
+            serde_json::Value::Null;
+        let summary = YQuoteSummary::from_json(json, "AAPL").unwrap();
+        assert_eq!(
+            summary.warnings(),
+            vec![DataWarning::new(
+                "financialData",
+                WarningKind::MissingCurrency
+            )]
+        );
+    }
+}
@@ -0,0 +1,263 @@
+//! Watchlist of symbols with alert rules, evaluated against the latest quotes of all symbols
+//! retrieved in batched requests.
+//!
+//! ```rust,ignore
+//! let mut watchlist = Watchlist::new();
+//! watchlist.add("AAPL", &[AlertRule::PriceBelow(price), AlertRule::MovePercent(five)]);
+//! watchlist.add("MSFT", &[AlertRule::FiftyTwoWeekHigh]);
+//! for alert in watchlist.check(&connector).await? {
+//!     println!("{} triggered {:?} at {}", alert.symbol, alert.rule, alert.price);
+//! }
+//! ```
+//!
+//! Delivering alerts (e.g. by mail) is left to the user.
+use std::collections::{HashMap, HashSet};
+
+use super::quotes::{decimal::from_volume, ZERO};
+use super::{Decimal, YMetaData, YahooConnector, YahooError};
+
+/// Condition on the latest quote of a symbol
+#[derive(Debug, Clone, PartialEq)]
+pub enum AlertRule {
+    /// Regular market price at or above the threshold
+    PriceAbove(Decimal),
+    /// Regular market price at or below the threshold
+    PriceBelow(Decimal),
+    /// Change against the previous close of at least the given percentage in either direction
+    MovePercent(Decimal),
+    /// Volume of the day of at least `factor` times the given average volume, e.g. the
+    /// `average_daily_volume3_month` of a quote, as yahoo! finance does not report the
+    /// average together with the latest quotes
+    VolumeSpike {
+        average_volume: u64,
+        factor: Decimal,
+    },
+    /// High of the day at or above the 52 week high, i.e. a new 52 week high has been set
+    FiftyTwoWeekHigh,
+}
+
+impl AlertRule {
+    /// Return whether the rule is triggered by the meta data of the latest chart of a symbol
+    pub fn is_triggered(&self, meta: &YMetaData) -> bool {
+        let Some(price) = meta.regular_market_price else {
+            return false;
+        };
+        match self {
+            AlertRule::PriceAbove(threshold) => price >= *threshold,
+            AlertRule::PriceBelow(threshold) => price <= *threshold,
+            AlertRule::MovePercent(percent) => {
+                match meta.previous_close.or(meta.chart_previous_close) {
+                    Some(previous) if previous != ZERO => {
+                        let change = (price - previous) / previous * Decimal::from(100);
+                        change >= *percent || -change >= *percent
+                    }
+                    _ => false,
+                }
+            }
+            AlertRule::VolumeSpike {
+                average_volume,
+                factor,
+            } => meta.regular_market_volume.is_some_and(|volume| {
+                *average_volume > 0 && from_volume(volume) >= from_volume(*average_volume) * *factor
+            }),
+            AlertRule::FiftyTwoWeekHigh => match meta.fifty_two_week_high {
+                Some(high) => meta.regular_market_day_high.unwrap_or(price) >= high,
+                None => false,
+            },
+        }
+    }
+}
+
+/// Rule of the watchlist triggered by the latest quote of a symbol
+#[derive(Debug, Clone, PartialEq)]
+pub struct Alert {
+    pub symbol: String,
+    pub rule: AlertRule,
+    pub price: Decimal,
+    /// Time of the price (as UNIX timestamp), if known
+    pub time: Option<i64>,
+}
+
+/// Symbols with alert rules. Checking the watchlist reports each alert once when its rule
+/// becomes triggered, and again only after the rule has not been triggered in between.
+#[derive(Debug, Clone, Default)]
+pub struct Watchlist {
+    entries: Vec<(String, Vec<AlertRule>)>,
+    /// Symbol and index of the rules triggered by the previous check
+    triggered: HashSet<(String, usize)>,
+}
+
+impl Watchlist {
+    pub fn new() -> Watchlist {
+        Watchlist::default()
+    }
+
+    /// Add rules for the symbol, in addition to those it already has
+    pub fn add(&mut self, symbol: &str, rules: &[AlertRule]) {
+        match self.entries.iter_mut().find(|(s, _)| s == symbol) {
+            Some((_, existing)) => existing.extend_from_slice(rules),
+            None => self.entries.push((symbol.to_string(), rules.to_vec())),
+        }
+    }
+
+    /// Remove the symbol with all its rules
+    pub fn remove(&mut self, symbol: &str) {
+        self.entries.retain(|(s, _)| s != symbol);
+        self.triggered.retain(|(s, _)| s != symbol);
+    }
+
+    pub fn symbols(&self) -> Vec<&str> {
+        self.entries.iter().map(|(s, _)| s.as_str()).collect()
+    }
+
+    /// Return the alerts of all rules triggered by the given meta data of the symbols,
+    /// regardless of previous checks. Symbols without a price are skipped.
+    pub fn evaluate(&self, meta: &HashMap<String, YMetaData>) -> Vec<Alert> {
+        self.evaluate_indexed(meta)
+            .into_iter()
+            .map(|(_, alert)| alert)
+            .collect()
+    }
+
+    fn evaluate_indexed(&self, meta: &HashMap<String, YMetaData>) -> Vec<(usize, Alert)> {
+        let mut alerts = Vec::new();
+        for (symbol, rules) in &self.entries {
+            let Some((meta, price)) = meta
+                .get(symbol)
+                .and_then(|meta| Some((meta, meta.regular_market_price?)))
+            else {
+                continue;
+            };
+            for (i, rule) in rules.iter().enumerate() {
+                if rule.is_triggered(meta) {
+                    alerts.push((
+                        i,
+                        Alert {
+                            symbol: symbol.clone(),
+                            rule: rule.clone(),
+                            price,
+                            time: meta.regular_market_time.map(|t| t.seconds()),
+                        },
+                    ));
+                }
+            }
+        }
+        alerts
+    }
+
+    /// Evaluate the rules and return the alerts which have not been triggered by the previous
+    /// call, remembering the triggered ones
+    pub fn update(&mut self, meta: &HashMap<String, YMetaData>) -> Vec<Alert> {
+        let alerts = self.evaluate_indexed(meta);
+        let triggered: HashSet<(String, usize)> = alerts
+            .iter()
+            .map(|(i, alert)| (alert.symbol.clone(), *i))
+            .collect();
+        let new = alerts
+            .into_iter()
+            .filter(|(i, alert)| !self.triggered.contains(&(alert.symbol.clone(), *i)))
+            .map(|(_, alert)| alert)
+            .collect();
+        self.triggered = triggered;
+        new
+    }
+
+    /// Retrieve the latest quotes of all symbols in batched requests and return the newly
+    /// triggered alerts, see `Watchlist::update`
+    #[cfg(not(feature = "blocking"))]
+    pub async fn check(&mut self, connector: &YahooConnector) -> Result<Vec<Alert>, YahooError> {
+        if self.entries.is_empty() {
+            return Ok(Vec::new());
+        }
+        let spark = connector.get_spark(&self.symbols()).await?;
+        Ok(self.update(&spark.into_meta()))
+    }
+
+    /// Retrieve the latest quotes of all symbols in batched requests and return the newly
+    /// triggered alerts, see `Watchlist::update`
+    #[cfg(feature = "blocking")]
+    pub fn check(&mut self, connector: &YahooConnector) -> Result<Vec<Alert>, YahooError> {
+        if self.entries.is_empty() {
+            return Ok(Vec::new());
+        }
+        let spark = connector.get_spark(&self.symbols())?;
+        Ok(self.update(&spark.into_meta()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::chart_fixture;
+
+    fn meta(name: &str) -> YMetaData {
+        chart_fixture(name)
+            .unwrap()
+            .response()
+            .unwrap()
+            .metadata()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_watchlist() {
+        let mut aapl = meta("equity_aapl_1d");
+        aapl.regular_market_price = Some(Decimal::from(180));
+        aapl.previous_close = Some(Decimal::from(250));
+        aapl.regular_market_volume = Some(30_000_000);
+        let mut quotes = HashMap::new();
+        quotes.insert("AAPL".to_string(), aapl);
+
+        let mut watchlist = Watchlist::new();
+        watchlist.add(
+            "AAPL",
+            &[
+                AlertRule::PriceAbove(Decimal::from(190)),
+                AlertRule::PriceBelow(Decimal::from(185)),
+                AlertRule::MovePercent(Decimal::from(10)),
+            ],
+        );
+        watchlist.add(
+            "AAPL",
+            &[AlertRule::VolumeSpike {
+                average_volume: 10_000_000,
+                factor: Decimal::from(3),
+            }],
+        );
+        watchlist.add("MSFT", &[AlertRule::FiftyTwoWeekHigh]);
+        assert_eq!(watchlist.symbols(), vec!["AAPL", "MSFT"]);
+
+        let alerts = watchlist.update(&quotes);
+        let rules: Vec<&AlertRule> = alerts.iter().map(|a| &a.rule).collect();
+        assert_eq!(
+            rules,
+            vec![
+                &AlertRule::PriceBelow(Decimal::from(185)),
+                &AlertRule::MovePercent(Decimal::from(10)),
+                &AlertRule::VolumeSpike {
+                    average_volume: 10_000_000,
+                    factor: Decimal::from(3)
+                },
+            ]
+        );
+        assert_eq!(alerts[0].price, Decimal::from(180));
+
+        // alerts are reported again only after their rule has not been triggered
+        assert!(watchlist.update(&quotes).is_empty());
+        assert_eq!(watchlist.evaluate(&quotes).len(), 3);
+        let aapl = quotes.get_mut("AAPL").unwrap();
+        aapl.regular_market_price = Some(Decimal::from(190));
+        assert_eq!(watchlist.update(&quotes).len(), 1);
+        let aapl = quotes.get_mut("AAPL").unwrap();
+        aapl.regular_market_price = Some(Decimal::from(180));
+        let alerts = watchlist.update(&quotes);
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].rule, AlertRule::PriceBelow(Decimal::from(185)));
+
+        let mut msft = meta("equity_msft_1m_prepost");
+        msft.fifty_two_week_high = msft.regular_market_day_high;
+        assert!(AlertRule::FiftyTwoWeekHigh.is_triggered(&msft));
+        watchlist.remove("AAPL");
+        assert_eq!(watchlist.symbols(), vec!["MSFT"]);
+    }
+}
@@ -1,19 +1,84 @@
 use thiserror::Error;
 
+/// Errors of requests to yahoo! finance. Variants may be added in minor releases, and some
+/// only exist with certain features enabled, so matches need a wildcard arm.
 #[derive(Error, Debug)]
+#[non_exhaustive]
 pub enum YahooError {
     #[error("fetching the data from yahoo! finance failed")]
     FetchFailed(String),
     #[error("deserializing response from yahoo! finance failed")]
     DeserializeFailed(#[from] serde_json::Error),
+    #[cfg(feature = "raw")]
+    #[error("deserializing response from yahoo! finance failed")]
+    DeserializeFailedWithPayload(#[source] serde_json::Error, serde_json::Value),
+    #[error("deserializing response from yahoo! finance failed, payload written to {1}")]
+    DeserializeFailedDumped(#[source] serde_json::Error, std::path::PathBuf),
     #[error("connection to yahoo! finance server failed")]
     ConnectionFailed(#[from] reqwest::Error),
     #[error("yahoo! finance return invalid JSON format")]
     InvalidJson,
+    #[error("yahoo! finance reported an error: {0}")]
+    ApiError(String),
+    #[error("yahoo! finance requires consent to its privacy policy before returning data")]
+    ConsentRequired,
+    #[error("yahoo! finance returned an HTML page instead of data (status {status}): {snippet}")]
+    HtmlResponse {
+        status: u16,
+        content_type: String,
+        snippet: String,
+    },
+    #[error("yahoo! finance returned no JSON (status {status}, content type {content_type:?}): {snippet}")]
+    UnexpectedContent {
+        status: u16,
+        content_type: String,
+        snippet: String,
+    },
+    #[error("symbol {0} not found, it may be delisted")]
+    SymbolNotFound(String),
+    #[error("no data available for symbol {0} in the requested range")]
+    RangeUnavailable(String),
     #[error("yahoo! finance returned an empty data set")]
     EmptyDataSet,
     #[error("yahoo! finance returned inconsistent data")]
     DataInconsistency,
+    #[error("yahoo! finance returned duplicate quotes for timestamp {0}")]
+    DuplicateTimestamp(u64),
+    #[error("invalid ticker symbol {0}")]
+    InvalidSymbol(String),
+    #[error("yahoo! finance did not set any cookies")]
+    NoCookies,
+    #[error("fetching the crumb from yahoo! finance failed")]
+    NoCrumb,
+    #[error("yahoo! finance rejected the crumb")]
+    InvalidCrumb,
+    #[error("cookie and crumb required by the endpoint are disabled by `AuthMode::None`")]
+    AuthDisabled,
+    #[error("requests to {0} are suspended after repeated failures")]
+    CircuitOpen(String),
     #[error("construcing yahoo! finance client failed")]
     BuilderFailed,
+    #[error("invalid configuration: {0}")]
+    InvalidConfig(String),
+    #[error("the download manifest does not match the download: {0}")]
+    ManifestMismatch(String),
+    #[error("writing the exported data failed")]
+    WriteFailed(#[source] std::io::Error),
+    #[error("reading the stored data failed")]
+    ReadFailed(#[source] std::io::Error),
+    #[cfg(feature = "money")]
+    #[error("cannot combine amounts in currencies {0} and {1}")]
+    CurrencyMismatch(String, String),
+    #[cfg(feature = "money")]
+    #[error("yahoo! finance returned no currency")]
+    MissingCurrency,
+    #[cfg(feature = "store")]
+    #[error("accessing the quote store failed")]
+    StoreFailed(#[from] rusqlite::Error),
+    #[cfg(feature = "schema_monitor")]
+    #[error("accessing response snapshots failed")]
+    SnapshotFailed(#[source] std::io::Error),
+    #[cfg(feature = "cache")]
+    #[error("accessing the response cache failed")]
+    CacheFailed(#[source] std::io::Error),
 }